@@ -0,0 +1,22 @@
+#if defined(GLES2_RENDERER)
+#define FRAG_COLOR gl_FragColor
+
+varying mediump vec2 texCoords;
+
+#else
+out vec4 FragColor;
+#define FRAG_COLOR FragColor
+
+in vec2 texCoords;
+
+#endif
+
+uniform sampler2D graphic;
+
+void main() {
+#if defined(GLES2_RENDERER)
+  FRAG_COLOR = texture2D(graphic, texCoords);
+#else
+  FRAG_COLOR = texture(graphic, texCoords);
+#endif
+}
@@ -1,10 +1,12 @@
 use std::cmp::max;
+use std::collections::HashMap;
+use std::env;
 use std::ops::{Deref, DerefMut};
 use std::path::PathBuf;
 use std::rc::Rc;
 
 use alacritty_config::SerdeReplace;
-use clap::{ArgAction, Args, Parser, Subcommand, ValueHint};
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum, ValueHint};
 use log::{error, LevelFilter};
 use serde::{Deserialize, Serialize};
 use toml::Value;
@@ -53,6 +55,10 @@ pub struct Options {
     #[clap(long, value_hint = ValueHint::FilePath)]
     pub socket: Option<PathBuf>,
 
+    /// Open the windows of a session layout file.
+    #[clap(long, value_hint = ValueHint::FilePath)]
+    pub session: Option<PathBuf>,
+
     /// Reduces the level of verbosity (the min level is -qq).
     #[clap(short, conflicts_with("verbose"), action = ArgAction::Count)]
     quiet: u8,
@@ -159,9 +165,22 @@ pub struct TerminalOptions {
     #[clap(long)]
     pub hold: bool,
 
+    /// Record the session to an asciicast file.
+    #[clap(long, value_hint = ValueHint::FilePath)]
+    pub record: Option<PathBuf>,
+
+    /// Share persisted scrollback history with windows using the same ID [default: working
+    /// directory].
+    #[clap(long)]
+    pub history_id: Option<String>,
+
     /// Command and args to execute (must be last argument).
     #[clap(short = 'e', long, allow_hyphen_values = true, num_args = 1..)]
     command: Vec<String>,
+
+    /// Extra environment variables for the shell.
+    #[clap(skip)]
+    pub env: HashMap<String, String>,
 }
 
 impl TerminalOptions {
@@ -171,6 +190,25 @@ impl TerminalOptions {
         Some(Program::WithArgs { program: program.clone(), args: args.to_vec() })
     }
 
+    /// Replace the shell with a command.
+    pub fn set_command(&mut self, command: &Program) {
+        self.command = vec![command.program().to_owned()];
+        self.command.extend_from_slice(command.args());
+    }
+
+    /// Key identifying the persisted scrollback history of the terminal.
+    pub fn history_key(&self, pty_config: &PtyOptions) -> Option<String> {
+        if let Some(history_id) = &self.history_id {
+            return Some(format!("id:{history_id}"));
+        }
+
+        let working_directory = match &pty_config.working_directory {
+            Some(working_directory) => working_directory.clone(),
+            None => env::current_dir().ok()?,
+        };
+        Some(format!("cwd:{}", working_directory.display()))
+    }
+
     /// Override the [`PtyOptions`]'s fields with the [`TerminalOptions`].
     pub fn override_pty_config(&self, pty_config: &mut PtyOptions) {
         if let Some(working_directory) = &self.working_directory {
@@ -186,6 +224,7 @@ impl TerminalOptions {
         }
 
         pty_config.hold |= self.hold;
+        pty_config.env.extend(self.env.clone());
     }
 }
 
@@ -195,6 +234,7 @@ impl From<TerminalOptions> for PtyOptions {
             working_directory: options.working_directory.take(),
             shell: options.command().map(Into::into),
             hold: options.hold,
+            env: options.env,
         }
     }
 }
@@ -229,6 +269,8 @@ pub enum Subcommands {
     #[cfg(unix)]
     Msg(MessageOptions),
     Migrate(MigrateOptions),
+    Replay(ReplayOptions),
+    Config(ConfigOptions),
 }
 
 /// Send a message to the Alacritty socket.
@@ -253,6 +295,34 @@ pub enum SocketMessage {
 
     /// Update the Alacritty configuration.
     Config(IpcConfig),
+
+    /// Switch between the light and dark color scheme.
+    ColorScheme(IpcColorScheme),
+
+    /// List all windows of the Alacritty process.
+    ListWindows,
+
+    /// Print the text of a window.
+    GetText(IpcText),
+
+    /// Print the active terminal modes of a window.
+    GetMode(IpcWindow),
+
+    /// Save the content of a window as PNG image.
+    Screenshot(IpcScreenshot),
+
+    /// Open the windows of a session layout file.
+    OpenSession(IpcSession),
+}
+
+impl SocketMessage {
+    /// Whether Alacritty will reply to this message.
+    pub fn expects_reply(&self) -> bool {
+        matches!(
+            self,
+            Self::ListWindows | Self::GetText(_) | Self::GetMode(_) | Self::Screenshot(_)
+        )
+    }
 }
 
 /// Migrate the configuration file.
@@ -279,6 +349,50 @@ pub struct MigrateOptions {
     pub silent: bool,
 }
 
+/// Replay a recorded session in the current terminal.
+#[derive(Args, Clone, Debug)]
+pub struct ReplayOptions {
+    /// Path to the asciicast recording.
+    #[clap(value_hint = ValueHint::FilePath)]
+    pub file: PathBuf,
+
+    /// Playback speed multiplier.
+    #[clap(short, long, default_value_t = 1.)]
+    pub speed: f64,
+
+    /// Limit pauses between events to the specified number of seconds.
+    #[clap(short, long)]
+    pub idle_time_limit: Option<f64>,
+}
+
+/// Validate or print the configuration file.
+#[derive(Args, Debug)]
+pub struct ConfigOptions {
+    #[clap(subcommand)]
+    pub command: ConfigCommand,
+}
+
+/// Available `config` subcommands.
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Check the configuration file and its imports for errors.
+    Check(ConfigCheckOptions),
+
+    /// Print the effective configuration, including imports and CLI overrides.
+    Dump,
+
+    /// Print the JSON Schema of the configuration file.
+    Schema,
+}
+
+/// Parameters to the `config check` subcommand.
+#[derive(Args, Debug)]
+pub struct ConfigCheckOptions {
+    /// Path to the configuration file.
+    #[clap(value_hint = ValueHint::FilePath)]
+    pub config_file: Option<PathBuf>,
+}
+
 /// Subset of options that we pass to 'create-window' IPC subcommand.
 #[derive(Serialize, Deserialize, Args, Default, Clone, Debug, PartialEq, Eq)]
 pub struct WindowOptions {
@@ -305,6 +419,16 @@ impl WindowOptions {
     pub fn config_overrides(&self) -> ParsedOptions {
         ParsedOptions::from_options(&self.option)
     }
+
+    /// Add a config override [example: 'cursor.style="Beam"'].
+    pub fn add_option(&mut self, option: String) {
+        self.option.push(option);
+    }
+
+    /// Remove all config overrides.
+    pub fn clear_options(&mut self) {
+        self.option.clear();
+    }
 }
 
 /// Parameters to the `config` IPC subcommand.
@@ -326,6 +450,82 @@ pub struct IpcConfig {
     pub reset: bool,
 }
 
+/// Parameters to the `color-scheme` IPC subcommand.
+#[cfg(unix)]
+#[derive(Args, Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct IpcColorScheme {
+    /// Color scheme which should be used.
+    #[clap(value_enum)]
+    pub scheme: ColorSchemeChange,
+
+    /// Window ID for the color scheme change.
+    ///
+    /// Use `-1` to apply this change to all windows.
+    #[clap(short, long, allow_hyphen_values = true, env = "ALACRITTY_WINDOW_ID")]
+    pub window_id: Option<i128>,
+}
+
+/// Change of the active color scheme.
+#[derive(ValueEnum, Serialize, Deserialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ColorSchemeChange {
+    Light,
+    Dark,
+    #[default]
+    Toggle,
+}
+
+/// Parameters to the `get-text` IPC subcommand.
+#[cfg(unix)]
+#[derive(Args, Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct IpcText {
+    #[clap(flatten)]
+    pub window: IpcWindow,
+
+    /// First line of the text, negative lines are part of the scrollback history.
+    ///
+    /// Defaults to the topmost line of the viewport.
+    #[clap(long, allow_negative_numbers = true)]
+    pub start: Option<i32>,
+
+    /// Last line of the text, negative lines are part of the scrollback history.
+    ///
+    /// Defaults to the bottommost line of the viewport.
+    #[clap(long, allow_negative_numbers = true)]
+    pub end: Option<i32>,
+}
+
+/// Parameters to the `screenshot` IPC subcommand.
+#[cfg(unix)]
+#[derive(Args, Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct IpcScreenshot {
+    #[clap(flatten)]
+    pub window: IpcWindow,
+
+    /// Path of the PNG file.
+    #[clap(value_hint = ValueHint::FilePath)]
+    pub path: PathBuf,
+}
+
+/// Parameters to the `open-session` IPC subcommand.
+#[cfg(unix)]
+#[derive(Args, Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct IpcSession {
+    /// Path of the session layout file.
+    #[clap(value_hint = ValueHint::FilePath)]
+    pub path: PathBuf,
+}
+
+/// Window targeted by an IPC query.
+#[cfg(unix)]
+#[derive(Args, Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct IpcWindow {
+    /// Window ID for the query.
+    ///
+    /// Defaults to the focused window.
+    #[clap(short, long, env = "ALACRITTY_WINDOW_ID")]
+    pub window_id: Option<u64>,
+}
+
 /// Parsed CLI config overrides.
 #[derive(Debug, Default)]
 pub struct ParsedOptions {
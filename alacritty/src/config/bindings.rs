@@ -5,6 +5,7 @@ use std::fmt::{self, Debug, Display};
 use bitflags::bitflags;
 use serde::de::{self, Error as SerdeError, MapAccess, Unexpected, Visitor};
 use serde::{Deserialize, Deserializer};
+use serde_json::{json, Map, Value};
 use toml::Value as SerdeValue;
 use winit::event::MouseButton;
 use winit::keyboard::{
@@ -12,6 +13,8 @@ use winit::keyboard::{
 };
 use winit::platform::scancode::PhysicalKeyExtScancode;
 
+use alacritty_config::schema::enum_schema;
+use alacritty_config::{ConfigSchema, SerdeReplace};
 use alacritty_config_derive::{ConfigDeserialize, SerdeReplace};
 
 use alacritty_terminal::term::TermMode;
@@ -40,6 +43,9 @@ pub struct Binding<T> {
     ///
     /// For example, this might be a key like "G", or a mouse button.
     pub trigger: T,
+
+    /// Keys which must be pressed in order before the trigger.
+    pub sequence: Vec<SequenceKey>,
 }
 
 /// Bindings that are triggered by a keyboard key.
@@ -54,19 +60,30 @@ impl<T: Eq> Binding<T> {
         // Check input first since bindings are stored in one big list. This is
         // the most likely item to fail so prioritizing it here allows more
         // checks to be short circuited.
-        self.trigger == *input
-            && self.mods == mods
-            && mode.contains(self.mode)
-            && !mode.intersects(self.notmode)
+        self.trigger == *input && self.mods == mods && self.is_active(mode)
+    }
+
+    /// Check if the binding is available in the binding mode.
+    #[inline]
+    pub fn is_active(&self, mode: BindingMode) -> bool {
+        mode.contains(self.mode) && !mode.intersects(self.notmode)
     }
 
     #[inline]
     pub fn triggers_match(&self, binding: &Binding<T>) -> bool {
-        // Check the binding's key and modifiers.
-        if self.trigger != binding.trigger || self.mods != binding.mods {
+        // Check the binding's key, modifiers and preceding keys.
+        if self.trigger != binding.trigger
+            || self.mods != binding.mods
+            || self.sequence != binding.sequence
+        {
             return false;
         }
 
+        self.modes_intersect(binding)
+    }
+
+    /// Check if both bindings can be active at the same time.
+    fn modes_intersect(&self, binding: &Binding<T>) -> bool {
         let selfmode = if self.mode.is_empty() { BindingMode::all() } else { self.mode };
         let bindingmode = if binding.mode.is_empty() { BindingMode::all() } else { binding.mode };
 
@@ -84,6 +101,106 @@ impl<T: Eq> Binding<T> {
     }
 }
 
+impl<T> Binding<T> {
+    /// Configuration representation of the fields shared by key and mouse bindings.
+    ///
+    /// Bindings for hints or internal terminal modes cannot be configured directly, so they have
+    /// no representation.
+    fn schema_fields(&self) -> Option<Map<String, Value>> {
+        let mut binding = Map::new();
+
+        let (field, value) = match &self.action {
+            Action::Esc(chars) => ("chars", chars.schema_default()?),
+            Action::Command(program) => ("command", program.schema_default()?),
+            Action::Hint(_) => return None,
+            Action::ViMotion(motion) => ("action", SerdeViMotion(*motion).schema_default()?),
+            Action::Vi(action) => ("action", action.schema_default()?),
+            Action::Search(action) => ("action", action.schema_default()?),
+            Action::Mouse(action) => ("action", action.schema_default()?),
+            action => ("action", action.schema_default()?),
+        };
+        binding.insert(field.into(), value);
+
+        if !self.mods.is_empty() {
+            binding.insert("mods".into(), ModsWrapper(self.mods).schema_default()?);
+        }
+
+        let mode = ModeWrapper { mode: self.mode, not_mode: self.notmode };
+        if mode != ModeWrapper::default() {
+            binding.insert("mode".into(), mode.schema_default()?);
+        }
+
+        Some(binding)
+    }
+}
+
+impl ConfigSchema for KeyBinding {
+    fn schema() -> Value {
+        key_bindings_schema()["items"].take()
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        let mut binding = self.schema_fields()?;
+        binding.insert("key".into(), self.trigger.schema_default()?);
+        if !self.sequence.is_empty() {
+            binding.insert("sequence".into(), self.sequence.schema_default()?);
+        }
+        Some(Value::Object(binding))
+    }
+}
+
+impl ConfigSchema for MouseBinding {
+    fn schema() -> Value {
+        mouse_bindings_schema()["items"].take()
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        let mut binding = self.schema_fields()?;
+        binding.insert("mouse".into(), MouseButtonWrapper(self.trigger).schema_default()?);
+        Some(Value::Object(binding))
+    }
+}
+
+impl KeyBinding {
+    /// Check if the pressed `keys` are the start of the binding's key sequence.
+    pub fn sequence_starts_with(&self, keys: &[KeyPress], leader: Option<&KeyChord>) -> bool {
+        self.sequence.len() >= keys.len()
+            && self.sequence.iter().zip(keys).all(|(key, press)| key.matches(press, leader))
+    }
+
+    /// Check if this binding is completed by the first keys of the longer `binding` sequence.
+    ///
+    /// Since a completed sequence is reset, the longer sequence can never be triggered.
+    pub fn shadows_sequence(&self, binding: &KeyBinding, leader: Option<&KeyChord>) -> bool {
+        let len = self.sequence.len();
+        if len == 0 || len >= binding.sequence.len() || !self.modes_intersect(binding) {
+            return false;
+        }
+
+        let trigger = KeyChord { key: self.trigger.clone(), mods: ModsWrapper(self.mods) };
+        self.sequence.iter().zip(&binding.sequence).all(|(key, other)| {
+            key.chord(leader).is_some_and(|chord| other.chord(leader) == Some(chord))
+        }) && binding.sequence[len].chord(leader) == Some(&trigger)
+    }
+
+    /// Label of the key which follows the first `position` keys of the sequence.
+    pub fn sequence_key_label(&self, position: usize) -> String {
+        match self.sequence.get(position) {
+            Some(key) => key.to_string(),
+            None => {
+                KeyChord { key: self.trigger.clone(), mods: ModsWrapper(self.mods) }.to_string()
+            },
+        }
+    }
+
+    /// Label of all keys of the sequence, including the trigger.
+    pub fn sequence_label(&self) -> String {
+        let labels: Vec<_> =
+            (0..=self.sequence.len()).map(|position| self.sequence_key_label(position)).collect();
+        labels.join(" ")
+    }
+}
+
 #[derive(ConfigDeserialize, Debug, Clone, PartialEq, Eq)]
 pub enum Action {
     /// Write an escape sequence.
@@ -123,6 +240,27 @@ pub enum Action {
     /// Store current selection into selection buffer.
     CopySelection,
 
+    /// Store output of the last command into clipboard.
+    CopyLastCommandOutput,
+
+    /// Store current selection into clipboard, including its colors and text attributes.
+    ExportSelection,
+
+    /// Store visible content into clipboard, including its colors and text attributes.
+    ExportScreen,
+
+    /// Store scrollback history into clipboard, including its colors and text attributes.
+    ExportHistory,
+
+    /// Start or stop recording the terminal session.
+    ToggleRecording,
+
+    /// Save the window content as PNG image.
+    Screenshot,
+
+    /// Switch between the light and dark color palette.
+    ToggleColorScheme,
+
     /// Paste contents of selection buffer.
     PasteSelection,
 
@@ -159,6 +297,12 @@ pub enum Action {
     /// Scroll all the way to the bottom.
     ScrollToBottom,
 
+    /// Scroll to the previous shell prompt.
+    ScrollToPreviousPrompt,
+
+    /// Scroll to the next shell prompt.
+    ScrollToNextPrompt,
+
     /// Clear the display buffer(s) to remove history.
     ClearHistory,
 
@@ -219,9 +363,42 @@ pub enum Action {
     /// Create a new Alacritty window.
     CreateNewWindow,
 
-    /// Create new window in a tab.
+    /// Create a new tab.
     CreateNewTab,
 
+    /// Split the focused pane, placing the new pane on the right.
+    SplitRight,
+
+    /// Split the focused pane, placing the new pane below.
+    SplitDown,
+
+    /// Close the focused pane.
+    ClosePane,
+
+    /// Focus the pane on the left.
+    FocusPaneLeft,
+
+    /// Focus the pane on the right.
+    FocusPaneRight,
+
+    /// Focus the pane above.
+    FocusPaneUp,
+
+    /// Focus the pane below.
+    FocusPaneDown,
+
+    /// Move the closest vertical divider of the focused pane to the left.
+    ResizePaneLeft,
+
+    /// Move the closest vertical divider of the focused pane to the right.
+    ResizePaneRight,
+
+    /// Move the closest horizontal divider of the focused pane up.
+    ResizePaneUp,
+
+    /// Move the closest horizontal divider of the focused pane down.
+    ResizePaneDown,
+
     /// Toggle fullscreen.
     ToggleFullscreen,
 
@@ -387,6 +564,7 @@ macro_rules! bindings {
                 mode: _mode,
                 notmode: _notmode,
                 action: $action.into(),
+                sequence: Vec::new(),
             });
         )*
 
@@ -510,6 +688,8 @@ pub fn default_key_bindings() -> Vec<KeyBinding> {
         "w",      ModifiersState::SHIFT,    +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::WordRight;
         "e",      ModifiersState::SHIFT,    +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::WordRightEnd;
         "%",      ModifiersState::SHIFT,    +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::Bracket;
+        "[",                                +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::PromptUp;
+        "]",                                +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::PromptDown;
         Enter,                              +BindingMode::VI, +BindingMode::SEARCH; SearchAction::SearchConfirm;
         // Plain search.
         Escape,                             +BindingMode::SEARCH; SearchAction::SearchCancel;
@@ -737,6 +917,178 @@ impl<'a> Deserialize<'a> for BindingKey {
     }
 }
 
+/// Key and modifiers pressed as part of a binding sequence.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct KeyChord {
+    pub key: BindingKey,
+    #[serde(default)]
+    pub mods: ModsWrapper,
+}
+
+impl KeyChord {
+    /// Check if the key press matches this chord.
+    pub fn matches(&self, press: &KeyPress) -> bool {
+        press.binding_key(&self.key) == &self.key && press.mods == self.mods.0
+    }
+}
+
+impl Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let modifiers = [
+            (ModifiersState::CONTROL, "Control"),
+            (ModifiersState::ALT, "Alt"),
+            (ModifiersState::SHIFT, "Shift"),
+            (ModifiersState::SUPER, "Super"),
+        ];
+        for (modifier, name) in modifiers {
+            if self.mods.0.contains(modifier) {
+                write!(f, "{name}+")?;
+            }
+        }
+
+        match &self.key {
+            BindingKey::Keycode { key: Key::Character(c), .. } => f.write_str(c),
+            BindingKey::Keycode { key: Key::Named(named), .. } => write!(f, "{named:?}"),
+            BindingKey::Keycode { key, .. } => write!(f, "{key:?}"),
+            BindingKey::Scancode(PhysicalKey::Code(code)) => write!(f, "{code:?}"),
+            BindingKey::Scancode(key) => write!(f, "{key:?}"),
+        }
+    }
+}
+
+impl SerdeReplace for KeyChord {
+    fn replace(&mut self, value: SerdeValue) -> Result<(), Box<dyn std::error::Error>> {
+        *self = Self::deserialize(value)?;
+
+        Ok(())
+    }
+}
+
+impl ConfigSchema for KeyChord {
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "key": BindingKey::schema(),
+                "mods": ModsWrapper::schema(),
+            },
+            "required": ["key"],
+            "additionalProperties": false,
+        })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        let mut chord = json!({ "key": self.key.schema_default()? });
+        if !self.mods.0.is_empty() {
+            chord["mods"] = self.mods.schema_default()?;
+        }
+        Some(chord)
+    }
+}
+
+/// Key which has to be pressed before a binding's trigger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SequenceKey {
+    /// The configured leader key.
+    Leader,
+    Key(KeyChord),
+}
+
+impl SequenceKey {
+    /// Check if the key press matches this sequence element.
+    pub fn matches(&self, press: &KeyPress, leader: Option<&KeyChord>) -> bool {
+        self.chord(leader).is_some_and(|chord| chord.matches(press))
+    }
+
+    /// Chord which has to be pressed for this key, `None` if the leader is not configured.
+    pub fn chord<'a>(&'a self, leader: Option<&'a KeyChord>) -> Option<&'a KeyChord> {
+        match self {
+            Self::Leader => leader,
+            Self::Key(chord) => Some(chord),
+        }
+    }
+}
+
+impl Display for SequenceKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Leader => f.write_str("Leader"),
+            Self::Key(chord) => Display::fmt(chord, f),
+        }
+    }
+}
+
+impl<'a> Deserialize<'a> for SequenceKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'a>,
+    {
+        let value = SerdeValue::deserialize(deserializer)?;
+        match value {
+            SerdeValue::String(ref key) if key.eq_ignore_ascii_case("leader") => Ok(Self::Leader),
+            SerdeValue::Table(_) => KeyChord::deserialize(value).map(Self::Key),
+            _ => BindingKey::deserialize(value)
+                .map(|key| Self::Key(KeyChord { key, mods: ModsWrapper::default() })),
+        }
+        .map_err(D::Error::custom)
+    }
+}
+
+impl ConfigSchema for SequenceKey {
+    fn schema() -> Value {
+        json!({ "anyOf": [BindingKey::schema(), KeyChord::schema()] })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        match self {
+            Self::Leader => Some("Leader".into()),
+            Self::Key(chord) => chord.schema_default(),
+        }
+    }
+}
+
+/// Key press which is matched against key bindings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyPress {
+    /// Logical key and its location.
+    pub key: BindingKey,
+    /// Physical key.
+    pub scancode: BindingKey,
+    pub mods: ModifiersState,
+}
+
+impl KeyPress {
+    /// Representation of the pressed key comparable with `trigger`.
+    pub fn binding_key(&self, trigger: &BindingKey) -> &BindingKey {
+        match trigger {
+            BindingKey::Scancode(_) => &self.scancode,
+            BindingKey::Keycode { .. } => &self.key,
+        }
+    }
+
+    /// Check if the pressed key is a modifier or lock key.
+    pub fn is_modifier(&self) -> bool {
+        let key = match &self.key {
+            BindingKey::Keycode { key: Key::Named(key), .. } => key,
+            _ => return false,
+        };
+
+        matches!(
+            key,
+            NamedKey::Shift
+                | NamedKey::Control
+                | NamedKey::Alt
+                | NamedKey::AltGraph
+                | NamedKey::Super
+                | NamedKey::Meta
+                | NamedKey::Hyper
+                | NamedKey::CapsLock
+                | NamedKey::NumLock
+        )
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct ModeWrapper {
     pub mode: BindingMode,
@@ -900,6 +1252,7 @@ struct RawBinding {
     mode: BindingMode,
     notmode: BindingMode,
     action: Action,
+    sequence: Vec<SequenceKey>,
 }
 
 impl RawBinding {
@@ -911,6 +1264,7 @@ impl RawBinding {
                 action: self.action,
                 mode: self.mode,
                 notmode: self.notmode,
+                sequence: self.sequence,
             })
         } else {
             Err(Box::new(self))
@@ -925,6 +1279,7 @@ impl RawBinding {
                 action: self.action,
                 mode: self.mode,
                 notmode: self.notmode,
+                sequence: self.sequence,
             })
         } else {
             Err(Box::new(self))
@@ -937,7 +1292,8 @@ impl<'a> Deserialize<'a> for RawBinding {
     where
         D: Deserializer<'a>,
     {
-        const FIELDS: &[&str] = &["key", "mods", "mode", "action", "chars", "mouse", "command"];
+        const FIELDS: &[&str] =
+            &["key", "mods", "mode", "action", "chars", "mouse", "command", "sequence"];
 
         enum Field {
             Key,
@@ -947,6 +1303,7 @@ impl<'a> Deserialize<'a> for RawBinding {
             Chars,
             Mouse,
             Command,
+            Sequence,
         }
 
         impl<'a> Deserialize<'a> for Field {
@@ -975,6 +1332,7 @@ impl<'a> Deserialize<'a> for RawBinding {
                             "chars" => Ok(Field::Chars),
                             "mouse" => Ok(Field::Mouse),
                             "command" => Ok(Field::Command),
+                            "sequence" => Ok(Field::Sequence),
                             _ => Err(E::unknown_field(value, FIELDS)),
                         }
                     }
@@ -1004,6 +1362,7 @@ impl<'a> Deserialize<'a> for RawBinding {
                 let mut not_mode: Option<BindingMode> = None;
                 let mut mouse: Option<MouseButton> = None;
                 let mut command: Option<Program> = None;
+                let mut sequence: Option<Vec<SequenceKey>> = None;
 
                 use de::Error;
 
@@ -1108,6 +1467,13 @@ impl<'a> Deserialize<'a> for RawBinding {
 
                             command = Some(map.next_value::<Program>()?);
                         },
+                        Field::Sequence => {
+                            if sequence.is_some() {
+                                return Err(<V::Error as Error>::duplicate_field("sequence"));
+                            }
+
+                            sequence = Some(map.next_value::<Vec<SequenceKey>>()?);
+                        },
                     }
                 }
 
@@ -1142,7 +1508,14 @@ impl<'a> Deserialize<'a> for RawBinding {
                     return Err(V::Error::custom("bindings require mouse button or key"));
                 }
 
-                Ok(RawBinding { mode, notmode: not_mode, action, key, mouse, mods })
+                let sequence = sequence.unwrap_or_default();
+                if mouse.is_some() && !sequence.is_empty() {
+                    return Err(V::Error::custom(
+                        "key sequences are not supported for mouse bindings",
+                    ));
+                }
+
+                Ok(RawBinding { mode, notmode: not_mode, action, key, mouse, mods, sequence })
             }
         }
 
@@ -1238,6 +1611,214 @@ impl<'a> de::Deserialize<'a> for ModsWrapper {
     }
 }
 
+impl ConfigSchema for BindingKey {
+    fn schema() -> Value {
+        json!({ "anyOf": [{ "type": "string" }, { "type": "integer", "minimum": 0 }] })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        let (key, location) = match self {
+            Self::Scancode(key) => return key.to_scancode().map(Value::from),
+            Self::Keycode { key, location } => (key, location),
+        };
+
+        // Keys on the numpad and their standard counterparts use the legacy names, which are
+        // translated to a location when deserializing.
+        let name = match (key, location) {
+            (Key::Named(NamedKey::Enter), KeyLocation::Standard) => "Enter",
+            (Key::Named(NamedKey::Enter), KeyLocation::Numpad) => "NumpadEnter",
+            (Key::Character(c), KeyLocation::Standard) => match c.as_str() {
+                "+" => "Plus",
+                "," => "Comma",
+                "/" => "Slash",
+                "=" => "Equals",
+                "-" => "Minus",
+                "*" => "Asterisk",
+                digit if digit.len() == 1 && digit.as_bytes()[0].is_ascii_digit() => {
+                    return Some(format!("Key{digit}").into());
+                },
+                c => c,
+            },
+            (Key::Character(c), KeyLocation::Numpad) => match c.as_str() {
+                "+" => "NumpadAdd",
+                "," => "NumpadComma",
+                "." => "NumpadDecimal",
+                "/" => "NumpadDivide",
+                "=" => "NumpadEquals",
+                "-" => "NumpadSubtract",
+                "*" => "NumpadMultiply",
+                digit if digit.len() == 1 && digit.as_bytes()[0].is_ascii_digit() => {
+                    return Some(format!("Numpad{digit}").into());
+                },
+                _ => return None,
+            },
+            (Key::Character(c), _) => c.as_str(),
+            (Key::Named(named), KeyLocation::Standard | KeyLocation::Any) => {
+                return Some(format!("{named:?}").into());
+            },
+            _ => return None,
+        };
+
+        Some(name.into())
+    }
+}
+
+impl ConfigSchema for ModeWrapper {
+    fn schema() -> Value {
+        json!({ "type": "string", "examples": ["Vi|~Search", "~Alt", "AppCursor"] })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        let modes = [
+            (BindingMode::APP_CURSOR, "AppCursor"),
+            (BindingMode::APP_KEYPAD, "AppKeypad"),
+            (BindingMode::ALT_SCREEN, "Alt"),
+            (BindingMode::VI, "Vi"),
+            (BindingMode::SEARCH, "Search"),
+        ];
+
+        // Modes without a name are only used internally.
+        let configurable = modes.iter().fold(BindingMode::empty(), |all, (mode, _)| all | *mode);
+        if !configurable.contains(self.mode | self.not_mode) || *self == Self::default() {
+            return None;
+        }
+
+        let mut names = Vec::new();
+        for (mode, name) in modes {
+            if self.mode.contains(mode) {
+                names.push(name.to_owned());
+            } else if self.not_mode.contains(mode) {
+                names.push(format!("~{name}"));
+            }
+        }
+
+        Some(names.join("|").into())
+    }
+}
+
+impl ConfigSchema for ModsWrapper {
+    fn schema() -> Value {
+        json!({ "type": "string", "examples": ["None", "Control|Shift", "Alt"] })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        let modifiers = [
+            (ModifiersState::CONTROL, "Control"),
+            (ModifiersState::SHIFT, "Shift"),
+            (ModifiersState::ALT, "Alt"),
+            (ModifiersState::SUPER, "Super"),
+        ];
+
+        let names: Vec<_> = modifiers
+            .into_iter()
+            .filter_map(|(modifier, name)| self.0.contains(modifier).then_some(name))
+            .collect();
+
+        if names.is_empty() {
+            Some("None".into())
+        } else {
+            Some(names.join("|").into())
+        }
+    }
+}
+
+impl ConfigSchema for MouseButtonWrapper {
+    fn schema() -> Value {
+        // Unlike other enumerations, mouse buttons are case-sensitive.
+        json!({
+            "anyOf": [
+                { "type": "string", "enum": ["Left", "Right", "Middle", "Back", "Forward"] },
+                { "type": "integer", "minimum": 0, "maximum": 65536 },
+            ],
+        })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        match self.0 {
+            MouseButton::Left => Some("Left".into()),
+            MouseButton::Right => Some("Right".into()),
+            MouseButton::Middle => Some("Middle".into()),
+            MouseButton::Back => Some("Back".into()),
+            MouseButton::Forward => Some("Forward".into()),
+            MouseButton::Other(button) => Some(button.into()),
+        }
+    }
+}
+
+impl ConfigSchema for SerdeViMotion {
+    fn schema() -> Value {
+        enum_schema(&[
+            "Up",
+            "Down",
+            "Left",
+            "Right",
+            "First",
+            "Last",
+            "FirstOccupied",
+            "High",
+            "Middle",
+            "Low",
+            "SemanticLeft",
+            "SemanticRight",
+            "SemanticLeftEnd",
+            "SemanticRightEnd",
+            "WordLeft",
+            "WordRight",
+            "WordLeftEnd",
+            "WordRightEnd",
+            "Bracket",
+            "PromptUp",
+            "PromptDown",
+        ])
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        Some(format!("{:?}", self.0).into())
+    }
+}
+
+/// Schema for the list of keyboard bindings.
+pub fn key_bindings_schema() -> Value {
+    let mut schema = bindings_schema("key", BindingKey::schema());
+    schema["items"]["properties"]["sequence"] = Vec::<SequenceKey>::schema();
+    schema
+}
+
+/// Schema for the list of mouse bindings.
+pub fn mouse_bindings_schema() -> Value {
+    bindings_schema("mouse", MouseButtonWrapper::schema())
+}
+
+/// Schema for a list of bindings, activated by the `trigger` field.
+fn bindings_schema(trigger: &str, trigger_schema: Value) -> Value {
+    let action = json!({
+        "anyOf": [
+            Action::schema(),
+            ViAction::schema(),
+            SerdeViMotion::schema(),
+            SearchAction::schema(),
+            MouseAction::schema(),
+        ],
+    });
+
+    json!({
+        "type": "array",
+        "items": {
+            "type": "object",
+            "properties": {
+                trigger: trigger_schema,
+                "mods": ModsWrapper::schema(),
+                "mode": ModeWrapper::schema(),
+                "action": action,
+                "chars": { "type": "string" },
+                "command": Program::schema(),
+            },
+            "required": [trigger],
+            "additionalProperties": false,
+        },
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1254,6 +1835,7 @@ mod tests {
                 mode: BindingMode::empty(),
                 notmode: BindingMode::empty(),
                 trigger: Default::default(),
+                sequence: Vec::new(),
             }
         }
     }
@@ -1443,4 +2025,104 @@ mod tests {
         assert!(!binding.is_triggered_by(BindingMode::ALT_SCREEN, mods, &t));
         assert!(!binding.is_triggered_by(BindingMode::ALT_SCREEN | BindingMode::VI, mods, &t));
     }
+
+    #[test]
+    fn binding_mismatches_sequence() {
+        let b1 = MockBinding { sequence: vec![SequenceKey::Leader], ..MockBinding::default() };
+        let b2 = MockBinding::default();
+
+        assert!(!b1.triggers_match(&b2));
+        assert!(!b2.triggers_match(&b1));
+    }
+
+    fn keycode(key: &str) -> BindingKey {
+        BindingKey::Keycode { key: Key::Character(key.into()), location: KeyLocation::Any }
+    }
+
+    fn key_press(key: &str, mods: ModifiersState) -> KeyPress {
+        let scancode = BindingKey::Scancode(PhysicalKey::Code(KeyCode::KeyA));
+        KeyPress { key: keycode(key), scancode, mods }
+    }
+
+    #[test]
+    fn deserialize_key_sequence() {
+        let binding: KeyBinding = toml::from_str(
+            r#"
+            key = "c"
+            sequence = ["Leader", { key = "W", mods = "Control" }, "F1"]
+            action = "SpawnNewInstance"
+            "#,
+        )
+        .unwrap();
+
+        let f1 = BindingKey::Keycode { key: Key::Named(NamedKey::F1), location: KeyLocation::Any };
+        let control_w = KeyChord { key: keycode("w"), mods: ModsWrapper(ModifiersState::CONTROL) };
+        let f1 = KeyChord { key: f1, mods: ModsWrapper::default() };
+        let expected = vec![SequenceKey::Leader, SequenceKey::Key(control_w), SequenceKey::Key(f1)];
+        assert_eq!(binding.sequence, expected);
+
+        let mouse_binding = r#"
+            mouse = "Middle"
+            sequence = ["a"]
+            action = "Copy"
+        "#;
+        assert!(toml::from_str::<MouseBinding>(mouse_binding).is_err());
+    }
+
+    #[test]
+    fn key_sequence_prefix() {
+        let leader = KeyChord { key: keycode("a"), mods: ModsWrapper(ModifiersState::CONTROL) };
+        let binding = KeyBinding {
+            trigger: keycode("c"),
+            mods: ModifiersState::empty(),
+            action: Action::SpawnNewInstance,
+            mode: BindingMode::empty(),
+            notmode: BindingMode::empty(),
+            sequence: vec![SequenceKey::Leader],
+        };
+
+        let leader_press = key_press("a", ModifiersState::CONTROL);
+        assert!(binding.sequence_starts_with(&[], Some(&leader)));
+        assert!(binding.sequence_starts_with(&[leader_press.clone()], Some(&leader)));
+        assert!(!binding.sequence_starts_with(&[leader_press.clone()], None));
+        let unmodified_press = key_press("a", ModifiersState::empty());
+        assert!(!binding.sequence_starts_with(&[unmodified_press], Some(&leader)));
+        assert!(!binding.sequence_starts_with(&[leader_press.clone(), leader_press], Some(&leader)));
+
+        assert_eq!(binding.sequence_key_label(0), "Leader");
+        assert_eq!(binding.sequence_key_label(1), "c");
+        assert_eq!(leader.to_string(), "Control+a");
+    }
+
+    #[test]
+    fn key_sequence_shadowed() {
+        let leader = KeyChord { key: keycode("a"), mods: ModsWrapper(ModifiersState::CONTROL) };
+        let chord =
+            |key| SequenceKey::Key(KeyChord { key: keycode(key), mods: ModsWrapper::default() });
+        let shorter = KeyBinding {
+            trigger: keycode("c"),
+            mods: ModifiersState::empty(),
+            action: Action::SpawnNewInstance,
+            mode: BindingMode::empty(),
+            notmode: BindingMode::empty(),
+            sequence: vec![SequenceKey::Leader],
+        };
+        let longer = KeyBinding {
+            trigger: keycode("d"),
+            sequence: vec![SequenceKey::Leader, chord("c")],
+            ..shorter.clone()
+        };
+
+        assert!(shorter.shadows_sequence(&longer, Some(&leader)));
+        assert!(!shorter.shadows_sequence(&longer, None));
+        assert!(!longer.shadows_sequence(&shorter, Some(&leader)));
+        assert_eq!(longer.sequence_label(), "Leader c d");
+
+        let unrelated = KeyBinding { sequence: vec![chord("b"), chord("c")], ..longer.clone() };
+        assert!(!shorter.shadows_sequence(&unrelated, Some(&leader)));
+
+        let vi_only = KeyBinding { mode: BindingMode::VI, ..shorter.clone() };
+        let not_vi = KeyBinding { notmode: BindingMode::VI, ..longer };
+        assert!(!vi_only.shadows_sequence(&not_vi, Some(&leader)));
+    }
 }
@@ -0,0 +1,436 @@
+//! Configuration file validation for `alacritty config check`.
+//!
+//! The config is deserialized one key at a time, so the warnings and errors logged by the
+//! deserializer can be attributed to the location of that key in the file.
+
+use std::cell::RefCell;
+use std::fmt::{self, Display, Formatter};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::{fs, process};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde::de::{DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+use toml::{Spanned, Table, Value};
+
+use crate::cli::ConfigCheckOptions;
+use crate::config::{self, UiConfig, IMPORT_RECURSION_LIMIT};
+
+thread_local! {
+    /// Messages logged while deserializing the configuration.
+    static MESSAGES: RefCell<Vec<(Level, String)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Logger collecting the config warnings and errors.
+struct CaptureLogger;
+
+impl Log for CaptureLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= Level::Warn
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if self.enabled(record.metadata()) {
+            let message = (record.level(), record.args().to_string());
+            MESSAGES.with(|messages| messages.borrow_mut().push(message));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CaptureLogger = CaptureLogger;
+
+/// Check the configuration file and its imports, exiting with an error on any problem.
+pub fn check(options: ConfigCheckOptions, config_file: Option<PathBuf>) {
+    let config_path = options
+        .config_file
+        .or(config_file)
+        .or_else(|| config::installed_config("toml"))
+        .or_else(|| config::installed_config("yml"));
+
+    let config_path = match config_path {
+        Some(config_path) => config_path,
+        None => {
+            eprintln!("No configuration file found");
+            process::exit(1);
+        },
+    };
+
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(LevelFilter::Warn);
+    }
+
+    let mut diagnostics = Vec::new();
+    check_file(&config_path, IMPORT_RECURSION_LIMIT, &mut diagnostics);
+
+    if diagnostics.is_empty() {
+        println!("No problems found in {config_path:?}");
+        return;
+    }
+
+    for diagnostic in &diagnostics {
+        eprintln!("{diagnostic}");
+    }
+    process::exit(1);
+}
+
+/// Check a configuration file and all its imports.
+fn check_file(path: &Path, recursion_limit: usize, diagnostics: &mut Vec<Diagnostic>) {
+    take_messages();
+
+    let config = match config::deserialize_config(path, false) {
+        Ok(config) => config,
+        Err(err) => {
+            diagnostics.push(Diagnostic::new(path, Problem::new(Level::Error, err.to_string())));
+            return;
+        },
+    };
+
+    // Report problems with the file itself, like the deprecated YAML format.
+    for (level, message) in take_messages() {
+        diagnostics.push(Diagnostic::new(path, Problem::new(level, message)));
+    }
+
+    // Source positions are only available for TOML files.
+    let contents = fs::read_to_string(path).unwrap_or_default();
+    let contents = contents.strip_prefix('\u{FEFF}').unwrap_or(&contents);
+    let is_toml = path.extension().map_or(false, |extension| extension == "toml");
+    let source = is_toml.then_some(contents);
+
+    for problem in check_config(&config, source) {
+        diagnostics.push(Diagnostic::new(path, problem));
+    }
+
+    let import_paths = match config::imports(&config, recursion_limit) {
+        Ok(import_paths) => import_paths,
+        Err(err) => {
+            diagnostics.push(Diagnostic::new(path, Problem::new(Level::Error, err)));
+            return;
+        },
+    };
+
+    for import_path in import_paths {
+        match import_path {
+            // Missing imports are permitted, to allow for optional imports.
+            Ok(import_path) if !import_path.exists() => (),
+            Ok(import_path) => check_file(&import_path, recursion_limit - 1, diagnostics),
+            Err(err) => diagnostics.push(Diagnostic::new(path, Problem::new(Level::Error, err))),
+        }
+    }
+}
+
+/// Check a parsed configuration file.
+///
+/// Returns all problems with their line and column, if the source of the file is available.
+fn check_config(config: &Value, source: Option<&str>) -> Vec<Problem> {
+    let location = source.and_then(|source| {
+        LocationSeed(config).deserialize(toml::Deserializer::new(source)).ok().map(|l| (source, l))
+    });
+
+    let mut problems = Vec::new();
+    let mut keys = Vec::new();
+    check_value(&mut keys, config, location.as_ref().map(|(_, l)| l), &mut problems);
+
+    problems
+        .into_iter()
+        .map(|(problem, span)| {
+            let position = location.as_ref().zip(span).map(|((source, _), span)| {
+                let before = &source[..span.start];
+                let line = before.matches('\n').count() + 1;
+                let column = before.rsplit('\n').next().unwrap_or_default().chars().count() + 1;
+                (line, column)
+            });
+            Problem { position, ..problem }
+        })
+        .collect()
+}
+
+/// Check a value and attribute its problems to the children responsible for them.
+fn check_value(
+    keys: &mut Vec<PathSegment>,
+    value: &Value,
+    location: Option<&Location>,
+    problems: &mut Vec<(Problem, Option<Range<usize>>)>,
+) {
+    let mut messages = deserialize_messages(keys, value);
+    if messages.is_empty() {
+        return;
+    }
+
+    let children: Vec<(PathSegment, &Value)> = match value {
+        Value::Table(table) => {
+            table.iter().map(|(key, value)| (PathSegment::Key(key.clone()), value)).collect()
+        },
+        Value::Array(array) => {
+            array.iter().enumerate().map(|(i, value)| (PathSegment::Index(i), value)).collect()
+        },
+        _ => Vec::new(),
+    };
+
+    for (segment, child) in children {
+        let child_location = location.and_then(|location| location.get(&segment));
+        keys.push(segment);
+
+        // Only descend into children which are responsible for some of the problems, since
+        // some values are invalid when their fields are deserialized in isolation.
+        let child_messages = deserialize_messages(keys, child);
+        if !child_messages.is_empty()
+            && child_messages.iter().all(|message| messages.contains(message))
+        {
+            for message in &child_messages {
+                if let Some(index) = messages.iter().position(|m| m == message) {
+                    messages.remove(index);
+                }
+            }
+
+            check_value(keys, child, child_location, problems);
+        }
+
+        keys.pop();
+    }
+
+    let span = location.and_then(Location::span);
+    for (level, message) in messages {
+        problems.push((Problem::new(level, message), span.clone()));
+    }
+}
+
+/// Deserialize a single value of the config, returning all logged warnings and errors.
+fn deserialize_messages(keys: &[PathSegment], value: &Value) -> Vec<(Level, String)> {
+    // Embed the value in an otherwise empty config.
+    let mut config = value.clone();
+    for segment in keys.iter().rev() {
+        config = match segment {
+            PathSegment::Key(key) => Value::Table(Table::from_iter([(key.clone(), config)])),
+            PathSegment::Index(_) => Value::Array(vec![config]),
+        };
+    }
+
+    take_messages();
+    let _ = UiConfig::deserialize(config);
+    take_messages()
+}
+
+/// Remove all captured log messages.
+fn take_messages() -> Vec<(Level, String)> {
+    MESSAGES.with(|messages| messages.take())
+}
+
+/// Component of the path to a config value.
+#[derive(Debug, PartialEq, Eq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Positions of config values in the source file.
+#[derive(Debug)]
+enum Location {
+    Table(Vec<(String, Location)>),
+    Array(Vec<Location>),
+    Value(Range<usize>),
+}
+
+impl Location {
+    /// Location of a child value.
+    fn get(&self, segment: &PathSegment) -> Option<&Location> {
+        match (self, segment) {
+            (Self::Table(table), PathSegment::Key(key)) => {
+                table.iter().find(|(k, _)| k == key).map(|(_, location)| location)
+            },
+            (Self::Array(array), PathSegment::Index(index)) => array.get(*index),
+            _ => None,
+        }
+    }
+
+    /// Byte range of the value.
+    ///
+    /// Tables and arrays are located at their first value.
+    fn span(&self) -> Option<Range<usize>> {
+        match self {
+            Self::Table(table) => {
+                table.iter().filter_map(|(_, location)| location.span()).min_by_key(|s| s.start)
+            },
+            Self::Array(array) => {
+                array.iter().filter_map(Location::span).min_by_key(|span| span.start)
+            },
+            Self::Value(span) => Some(span.clone()),
+        }
+    }
+}
+
+/// Deserializer for the [`Location`] of a value.
+///
+/// Tables created implicitly through dotted keys have no position, so only the positions of
+/// scalar values are read. The parsed value is used to tell tables and scalars apart.
+struct LocationSeed<'a>(&'a Value);
+
+impl<'de, 'a> DeserializeSeed<'de> for LocationSeed<'a> {
+    type Value = Location;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match self.0 {
+            Value::Table(table) => deserializer.deserialize_map(TableVisitor(table)),
+            Value::Array(array) => deserializer.deserialize_seq(ArrayVisitor(array)),
+            _ => {
+                let spanned = Spanned::<IgnoredAny>::deserialize(deserializer)?;
+                Ok(Location::Value(spanned.span()))
+            },
+        }
+    }
+}
+
+struct TableVisitor<'a>(&'a Table);
+
+impl<'de, 'a> Visitor<'de> for TableVisitor<'a> {
+    type Value = Location;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("a table")
+    }
+
+    fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        let mut locations = Vec::new();
+        while let Some(key) = map.next_key::<String>()? {
+            match self.0.get(&key) {
+                Some(value) => locations.push((key, map.next_value_seed(LocationSeed(value))?)),
+                None => {
+                    map.next_value::<IgnoredAny>()?;
+                },
+            }
+        }
+        Ok(Location::Table(locations))
+    }
+}
+
+struct ArrayVisitor<'a>(&'a [Value]);
+
+impl<'de, 'a> Visitor<'de> for ArrayVisitor<'a> {
+    type Value = Location;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("an array")
+    }
+
+    fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+    where
+        S: SeqAccess<'de>,
+    {
+        let mut locations = Vec::new();
+        for value in self.0 {
+            match seq.next_element_seed(LocationSeed(value))? {
+                Some(location) => locations.push(location),
+                None => break,
+            }
+        }
+        Ok(Location::Array(locations))
+    }
+}
+
+/// Problem with a config value.
+#[derive(Debug)]
+struct Problem {
+    level: Level,
+    message: String,
+
+    /// Line and column of the value.
+    position: Option<(usize, usize)>,
+}
+
+impl Problem {
+    fn new(level: Level, message: String) -> Self {
+        Self { level, message, position: None }
+    }
+}
+
+/// Problem found in a configuration file.
+struct Diagnostic {
+    path: PathBuf,
+    problem: Problem,
+}
+
+impl Diagnostic {
+    fn new(path: &Path, problem: Problem) -> Self {
+        Self { path: path.to_owned(), problem }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if let Some((line, column)) = self.problem.position {
+            write!(f, ":{line}:{column}")?;
+        }
+
+        let level = match self.problem.level {
+            Level::Error => "error",
+            _ => "warning",
+        };
+
+        // Strip the prefixes used for the message bar.
+        let message = self.problem.message.trim();
+        let message = message.strip_prefix("Config error: ").unwrap_or(message);
+        let message = message.strip_prefix("Config warning: ").unwrap_or(message);
+
+        write!(f, ": {level}: {message}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_str(source: &str) -> Vec<Problem> {
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(LevelFilter::Warn);
+
+        let config = toml::from_str(source).unwrap();
+        check_config(&config, Some(source))
+    }
+
+    #[test]
+    fn valid_config() {
+        let problems = check_str(
+            r#"
+            [font.normal]
+            family = "monospace"
+
+            [[hints.enabled]]
+            regex = "[a-f0-9]{40}"
+            action = "Copy"
+            "#,
+        );
+        assert!(problems.is_empty(), "{problems:?}");
+    }
+
+    #[test]
+    fn located_problems() {
+        let source = "[window]\nopacity = 1.0\ntitel = \"x\"\n\n[font]\nsize = \"big\"\n";
+        let problems = check_str(source);
+
+        assert_eq!(problems.len(), 2, "{problems:?}");
+        assert!(problems.iter().any(|problem| problem.level == Level::Warn
+            && problem.message.contains("titel")
+            && problem.position == Some((3, 9))));
+        assert!(problems.iter().any(|problem| problem.level == Level::Error
+            && problem.message.contains("size")
+            && problem.position == Some((6, 8))));
+    }
+
+    #[test]
+    fn deprecated_keys() {
+        let problems = check_str("draw_bold_text_with_bright_colors = true");
+
+        assert_eq!(problems.len(), 1, "{problems:?}");
+        assert!(problems[0].message.contains("deprecated"));
+        assert_eq!(problems[0].position, Some((1, 37)));
+    }
+}
@@ -1,7 +1,12 @@
+use log::error;
 use serde::de::Error as SerdeError;
 use serde::{Deserialize, Deserializer};
+use serde_json::{json, Value as JsonValue};
+use toml::Value;
 
-use alacritty_config_derive::ConfigDeserialize;
+use alacritty_config::{ConfigSchema, SerdeReplace};
+use alacritty_config_derive::{ConfigDeserialize, SerdeReplace};
+use alacritty_terminal::term::color_scheme::ColorScheme;
 
 use crate::display::color::{CellRgb, Rgb};
 
@@ -21,6 +26,9 @@ pub struct Colors {
     pub transparent_background_colors: bool,
     pub draw_bold_text_with_bright_colors: bool,
     footer_bar: BarColors,
+    pane_divider: Option<Rgb>,
+    light: Option<ColorPalette>,
+    dark: Option<ColorPalette>,
 }
 
 impl Colors {
@@ -31,6 +39,59 @@ impl Colors {
     pub fn footer_bar_background(&self) -> Rgb {
         self.footer_bar.background.unwrap_or(self.primary.foreground)
     }
+
+    pub fn pane_divider(&self) -> Rgb {
+        self.pane_divider.unwrap_or(self.bright.black)
+    }
+
+    /// Color overrides for a color scheme.
+    pub fn palette(&self, scheme: ColorScheme) -> Option<&ColorPalette> {
+        match scheme {
+            ColorScheme::Light => self.light.as_ref(),
+            ColorScheme::Dark => self.dark.as_ref(),
+        }
+    }
+}
+
+/// Colors which replace the `[colors]` section while a color scheme is active.
+#[derive(SerdeReplace, Clone, Debug, PartialEq)]
+pub struct ColorPalette(Value);
+
+impl ColorPalette {
+    /// Apply the palette on top of the base colors.
+    pub fn apply(&self, colors: &mut Colors) {
+        if let Err(err) = colors.replace(self.0.clone()) {
+            error!("Unable to apply color palette: {}", err);
+        }
+    }
+}
+
+impl ConfigSchema for ColorPalette {
+    fn schema() -> JsonValue {
+        // Palettes accept the same keys as `[colors]`, which cannot be inlined recursively.
+        json!({ "$ref": "#/properties/colors" })
+    }
+
+    fn schema_default(&self) -> Option<JsonValue> {
+        serde_json::to_value(&self.0).ok()
+    }
+}
+
+// Valid palettes contain no floats, so equality is always reflexive.
+impl Eq for ColorPalette {}
+
+impl<'de> Deserialize<'de> for ColorPalette {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer)?;
+
+        // Reject palettes which could not be applied to the colors.
+        Colors::default().replace(value.clone()).map_err(SerdeError::custom)?;
+
+        Ok(Self(value))
+    }
 }
 
 #[derive(ConfigDeserialize, Copy, Clone, Default, Debug, PartialEq, Eq)]
@@ -90,6 +151,24 @@ impl IndexedColor {
     }
 }
 
+impl ConfigSchema for IndexedColor {
+    fn schema() -> JsonValue {
+        json!({
+            "type": "object",
+            "properties": {
+                "color": Rgb::schema(),
+                "index": { "type": "integer", "minimum": 16, "maximum": 255 },
+            },
+            "required": ["color", "index"],
+            "additionalProperties": false,
+        })
+    }
+
+    fn schema_default(&self) -> Option<JsonValue> {
+        Some(json!({ "color": self.color.schema_default(), "index": self.index() }))
+    }
+}
+
 #[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
 struct ColorIndex(u8);
 
@@ -270,3 +349,32 @@ impl Default for DimColors {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_scheme_palettes() {
+        let colors: Colors = toml::from_str(
+            r##"
+            [primary]
+            foreground = "#ffffff"
+
+            [light.primary]
+            background = "#eeeeee"
+            "##,
+        )
+        .unwrap();
+
+        assert!(colors.palette(ColorScheme::Dark).is_none());
+
+        let mut light = colors.clone();
+        colors.palette(ColorScheme::Light).unwrap().apply(&mut light);
+        assert_eq!(light.primary.background, Rgb::new(0xee, 0xee, 0xee));
+        assert_eq!(light.primary.foreground, Rgb::new(0xff, 0xff, 0xff));
+
+        // Palettes with unknown fields are rejected.
+        assert!(toml::from_str::<ColorPalette>("primary.fg = \"#000000\"").is_err());
+    }
+}
@@ -2,7 +2,9 @@ use std::cmp;
 use std::time::Duration;
 
 use serde::Deserialize;
+use serde_json::{json, Value};
 
+use alacritty_config::ConfigSchema;
 use alacritty_config_derive::{ConfigDeserialize, SerdeReplace};
 use alacritty_terminal::vte::ansi::{CursorShape as VteCursorShape, CursorStyle as VteCursorStyle};
 
@@ -101,6 +103,34 @@ impl ConfigCursorStyle {
     }
 }
 
+impl ConfigSchema for ConfigCursorStyle {
+    fn schema() -> Value {
+        json!({
+            "anyOf": [
+                CursorShape::schema(),
+                {
+                    "type": "object",
+                    "properties": {
+                        "shape": CursorShape::schema(),
+                        "blinking": CursorBlinking::schema(),
+                    },
+                    "additionalProperties": false,
+                },
+            ],
+        })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        match self {
+            Self::Shape(shape) => shape.schema_default(),
+            Self::WithBlinking { shape, blinking } => Some(json!({
+                "shape": shape.schema_default(),
+                "blinking": blinking.schema_default(),
+            })),
+        }
+    }
+}
+
 impl From<ConfigCursorStyle> for VteCursorStyle {
     fn from(config_style: ConfigCursorStyle) -> Self {
         match config_style {
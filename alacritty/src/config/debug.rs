@@ -55,4 +55,7 @@ pub enum RendererPreference {
 
     /// Pure GLES 2 renderer.
     Gles2Pure,
+
+    /// CPU renderer, for systems without working OpenGL drivers.
+    Software,
 }
@@ -0,0 +1,18 @@
+use alacritty_config_derive::ConfigDeserialize;
+
+#[derive(ConfigDeserialize, Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Export {
+    /// Format used by the export actions.
+    pub format: ExportFormat,
+}
+
+/// Format of exported terminal content.
+#[derive(ConfigDeserialize, Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// HTML with inline styles.
+    #[default]
+    Html,
+
+    /// Text with ANSI escape sequences.
+    Ansi,
+}
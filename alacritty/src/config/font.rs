@@ -1,9 +1,12 @@
 use std::fmt;
+use std::str::FromStr;
 
 use crossfont::Size as FontSize;
-use serde::de::{self, Visitor};
+use serde::de::{self, Error as _, Visitor};
 use serde::{Deserialize, Deserializer};
+use serde_json::{json, Value};
 
+use alacritty_config::ConfigSchema;
 use alacritty_config_derive::{ConfigDeserialize, SerdeReplace};
 
 use crate::config::ui_config::Delta;
@@ -42,6 +45,12 @@ pub struct Font {
 
     /// Whether to use the built-in font for box drawing characters.
     pub builtin_box_drawing: bool,
+
+    /// Shape text to render ligatures and complex scripts.
+    pub ligatures: bool,
+
+    /// OpenType features applied while shaping.
+    pub features: Vec<FontFeature>,
 }
 
 impl Font {
@@ -80,6 +89,8 @@ impl Default for Font {
     fn default() -> Font {
         Self {
             builtin_box_drawing: true,
+            ligatures: Default::default(),
+            features: Default::default(),
             glyph_offset: Default::default(),
             use_thin_strokes: Default::default(),
             bold_italic: Default::default(),
@@ -129,6 +140,76 @@ impl SecondaryFontDescription {
     }
 }
 
+/// OpenType feature setting.
+///
+/// Features are specified by their tag, which can be prefixed by `-` to disable them or suffixed
+/// by `=<value>` to select an alternate.
+#[derive(SerdeReplace, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FontFeature {
+    pub tag: [u8; 4],
+    pub value: u32,
+}
+
+impl FromStr for FontFeature {
+    type Err = String;
+
+    fn from_str(feature: &str) -> Result<Self, Self::Err> {
+        let (feature, value) = match feature.split_once('=') {
+            Some((feature, value)) => {
+                let value = value.trim().parse().map_err(|_| {
+                    format!("invalid value for font feature {feature:?}: {value:?}")
+                })?;
+                (feature, Some(value))
+            },
+            None => (feature, None),
+        };
+
+        let feature = feature.trim();
+        let (tag, value) = match (feature.strip_prefix('-'), value) {
+            (Some(tag), None) => (tag, 0),
+            (Some(_), Some(_)) => {
+                return Err(format!("disabled font feature {feature:?} cannot have a value"))
+            },
+            (None, value) => (feature.strip_prefix('+').unwrap_or(feature), value.unwrap_or(1)),
+        };
+
+        // Tags shorter than four characters are padded with spaces.
+        if tag.is_empty() || tag.len() > 4 || !tag.bytes().all(|byte| byte.is_ascii_graphic()) {
+            return Err(format!("invalid font feature tag {tag:?}"));
+        }
+        let mut bytes = [b' '; 4];
+        bytes[..tag.len()].copy_from_slice(tag.as_bytes());
+
+        Ok(Self { tag: bytes, value })
+    }
+}
+
+impl<'de> Deserialize<'de> for FontFeature {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let feature = String::deserialize(deserializer)?;
+        feature.parse().map_err(D::Error::custom)
+    }
+}
+
+impl ConfigSchema for FontFeature {
+    fn schema() -> Value {
+        json!({ "type": "string", "examples": ["ss01", "-calt", "cv01=2"] })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        let tag = String::from_utf8_lossy(&self.tag);
+        let tag = tag.trim_end();
+        match self.value {
+            0 => Some(format!("-{tag}").into()),
+            1 => Some(tag.into()),
+            value => Some(format!("{tag}={value}").into()),
+        }
+    }
+}
+
 #[derive(SerdeReplace, Debug, Clone, PartialEq, Eq)]
 struct Size(FontSize);
 
@@ -163,3 +244,34 @@ impl<'de> Deserialize<'de> for Size {
         deserializer.deserialize_any(NumVisitor)
     }
 }
+
+impl ConfigSchema for Size {
+    fn schema() -> Value {
+        json!({ "type": "number", "exclusiveMinimum": 0 })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        self.0.as_pt().schema_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_font_features() {
+        let feature = |tag: &[u8; 4], value| FontFeature { tag: *tag, value };
+
+        assert_eq!("ss01".parse(), Ok(feature(b"ss01", 1)));
+        assert_eq!("+zero".parse(), Ok(feature(b"zero", 1)));
+        assert_eq!("-calt".parse(), Ok(feature(b"calt", 0)));
+        assert_eq!("cv01=2".parse(), Ok(feature(b"cv01", 2)));
+        assert_eq!("cv".parse(), Ok(feature(b"cv  ", 1)));
+
+        assert!("".parse::<FontFeature>().is_err());
+        assert!("calts".parse::<FontFeature>().is_err());
+        assert!("-calt=1".parse::<FontFeature>().is_err());
+        assert!("cv01=x".parse::<FontFeature>().is_err());
+    }
+}
@@ -3,6 +3,8 @@ use std::path::{Path, PathBuf};
 use std::result::Result as StdResult;
 use std::{env, fs, io};
 
+use alacritty_config::schema::SCHEMA_DIALECT;
+use alacritty_config::ConfigSchema;
 use log::{debug, error, info, warn};
 use serde::Deserialize;
 use serde_yaml::Error as YamlError;
@@ -11,27 +13,32 @@ use toml::ser::Error as TomlSeError;
 use toml::{Table, Value};
 
 pub mod bell;
+pub mod check;
 pub mod color;
 pub mod cursor;
 pub mod debug;
+pub mod export;
 pub mod font;
 pub mod monitor;
+pub mod notification;
+pub mod recording;
 pub mod scrolling;
 pub mod selection;
 pub mod serde_utils;
 pub mod terminal;
+pub mod trigger;
 pub mod ui_config;
 pub mod window;
 
 mod bindings;
 mod mouse;
 
-use crate::cli::Options;
-#[cfg(test)]
-pub use crate::config::bindings::Binding;
+use crate::cli::{Options, ParsedOptions};
 pub use crate::config::bindings::{
-    Action, BindingKey, BindingMode, MouseAction, SearchAction, ViAction,
+    Action, BindingKey, BindingMode, KeyBinding, KeyPress, MouseAction, SearchAction, ViAction,
 };
+#[cfg(test)]
+pub use crate::config::bindings::{Binding, KeyLocation};
 pub use crate::config::ui_config::UiConfig;
 use crate::logging::LOG_TARGET_CONFIG;
 
@@ -166,6 +173,43 @@ pub fn reload(config_path: &Path, options: &mut Options) -> Result<UiConfig> {
     Ok(config)
 }
 
+/// Print the effective configuration, including imports and CLI overrides, as TOML.
+pub fn dump(config_file: Option<PathBuf>, overrides: &mut ParsedOptions) -> Result<()> {
+    let config_path =
+        config_file.or_else(|| installed_config("toml")).or_else(|| installed_config("yml"));
+
+    let mut config = match config_path {
+        Some(config_path) => read_config(&config_path)?,
+        None => UiConfig::default(),
+    };
+    overrides.override_config(&mut config);
+
+    print!("{}", effective_config(&config)?);
+
+    Ok(())
+}
+
+/// Serialize all configuration options, without invalid or unknown fields.
+fn effective_config(config: &UiConfig) -> Result<String> {
+    let mut config = Value::try_from(config.schema_default())?;
+
+    // Imports are already merged into the config.
+    if let Value::Table(table) = &mut config {
+        table.remove("import");
+    }
+
+    Ok(toml::to_string(&config)?)
+}
+
+/// Print the JSON Schema of the configuration file.
+pub fn schema() {
+    let mut schema = UiConfig::schema();
+    schema["$schema"] = SCHEMA_DIALECT.into();
+    schema["title"] = "Alacritty configuration".into();
+
+    println!("{schema:#}");
+}
+
 /// Modifications after the `UiConfig` object is created.
 fn after_loading(config: &mut UiConfig, options: &mut Options) {
     // Override config with CLI options.
@@ -173,6 +217,8 @@ fn after_loading(config: &mut UiConfig, options: &mut Options) {
 
     // Create key bindings for regex hints.
     config.generate_hint_bindings();
+
+    config.check_key_sequences();
 }
 
 /// Load configuration file and log errors.
@@ -199,7 +245,7 @@ fn read_config(path: &Path) -> Result<UiConfig> {
 }
 
 /// Deserialize all configuration files as generic Value.
-fn parse_config(
+pub fn parse_config(
     path: &Path,
     config_paths: &mut Vec<PathBuf>,
     recursion_limit: usize,
@@ -439,4 +485,46 @@ window = "Hello""#
         let toml = yaml_to_toml(contents);
         assert!(toml.is_empty());
     }
+
+    #[test]
+    fn effective_config_without_invalid_fields() {
+        let config: UiConfig = toml::from_str(
+            r#"
+            unknown = true
+
+            [scrolling]
+            history = "invalid"
+            multiplier = 5
+
+            [[keyboard.bindings]]
+            key = "N"
+            mods = "Control|Shift"
+            action = "CreateNewWindow"
+            "#,
+        )
+        .unwrap();
+
+        let dump = effective_config(&config).unwrap();
+        let value: Value = toml::from_str(&dump).unwrap();
+
+        assert!(value.get("unknown").is_none());
+        assert!(value.get("import").is_none());
+        assert_eq!(value["scrolling"]["history"].as_integer(), Some(10000));
+        assert_eq!(value["scrolling"]["multiplier"].as_integer(), Some(5));
+        assert_eq!(value["font"]["size"].as_float(), Some(11.25));
+
+        let mut binding = Table::new();
+        binding.insert("key".into(), "n".into());
+        binding.insert("mods".into(), "Control|Shift".into());
+        binding.insert("action".into(), "CreateNewWindow".into());
+        let bindings = value["keyboard"]["bindings"].as_array().unwrap();
+        assert!(bindings.contains(&Value::Table(binding)));
+
+        // Loading the dump results in the same configuration.
+        let reloaded: UiConfig = toml::from_str(&dump).unwrap();
+        assert_eq!(reloaded.scrolling, config.scrolling);
+        assert_eq!(reloaded.font, config.font);
+        assert_eq!(reloaded.hints, config.hints);
+        assert_eq!(reloaded.mouse, config.mouse);
+    }
 }
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Deserializer};
+use serde_json::Value;
 
+use alacritty_config::ConfigSchema;
 use alacritty_config_derive::{ConfigDeserialize, SerdeReplace};
 
 use crate::config::bindings::{self, MouseBinding};
@@ -20,6 +22,17 @@ impl Default for MouseBindings {
     }
 }
 
+impl ConfigSchema for MouseBindings {
+    fn schema() -> Value {
+        bindings::mouse_bindings_schema()
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        let bindings: Vec<_> = self.0.iter().filter_map(MouseBinding::schema_default).collect();
+        Some(bindings.into())
+    }
+}
+
 impl<'de> Deserialize<'de> for MouseBindings {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -0,0 +1,12 @@
+use alacritty_config_derive::ConfigDeserialize;
+
+use crate::config::ui_config::Program;
+
+#[derive(ConfigDeserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct Notifications {
+    /// Command receiving the title and body of notifications as arguments.
+    pub command: Option<Program>,
+
+    /// Ignore notifications while the terminal is focused.
+    pub unfocused_only: bool,
+}
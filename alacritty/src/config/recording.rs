@@ -0,0 +1,12 @@
+use std::path::PathBuf;
+
+use alacritty_config_derive::ConfigDeserialize;
+
+#[derive(ConfigDeserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct Recording {
+    /// Directory for recordings started through the `ToggleRecording` action.
+    pub directory: Option<PathBuf>,
+
+    /// Record input sent to the terminal.
+    pub input: bool,
+}
@@ -1,6 +1,8 @@
 use serde::de::Error as SerdeError;
 use serde::{Deserialize, Deserializer};
+use serde_json::{json, Value};
 
+use alacritty_config::ConfigSchema;
 use alacritty_config_derive::{ConfigDeserialize, SerdeReplace};
 
 /// Maximum scrollback amount configurable.
@@ -11,12 +13,23 @@ pub const MAX_SCROLLBACK_LINES: u32 = 100_000;
 pub struct Scrolling {
     pub multiplier: u8,
 
+    /// Restore the scrollback history of closed windows.
+    pub persist: bool,
+
+    /// Compressed history beyond the scrollback history.
+    pub archive: Archive,
+
     history: ScrollingHistory,
 }
 
 impl Default for Scrolling {
     fn default() -> Self {
-        Self { multiplier: 3, history: Default::default() }
+        Self {
+            multiplier: 3,
+            persist: Default::default(),
+            archive: Default::default(),
+            history: Default::default(),
+        }
     }
 }
 
@@ -26,6 +39,16 @@ impl Scrolling {
     }
 }
 
+/// Compressed scrollback history.
+#[derive(ConfigDeserialize, Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Archive {
+    /// Maximum number of compressed lines, `0` disables the archive.
+    pub lines: usize,
+
+    /// Store the compressed lines in a temporary file.
+    pub disk: bool,
+}
+
 #[derive(SerdeReplace, Copy, Clone, Debug, PartialEq, Eq)]
 struct ScrollingHistory(u32);
 
@@ -35,6 +58,16 @@ impl Default for ScrollingHistory {
     }
 }
 
+impl ConfigSchema for ScrollingHistory {
+    fn schema() -> Value {
+        json!({ "type": "integer", "minimum": 0, "maximum": MAX_SCROLLBACK_LINES })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        self.0.schema_default()
+    }
+}
+
 impl<'de> Deserialize<'de> for ScrollingHistory {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
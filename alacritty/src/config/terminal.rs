@@ -1,15 +1,21 @@
 use serde::{de, Deserialize, Deserializer};
+use serde_json::Value as JsonValue;
 use toml::Value;
 
+use alacritty_config::schema::enum_schema;
+use alacritty_config::ConfigSchema;
 use alacritty_config_derive::{ConfigDeserialize, SerdeReplace};
 use alacritty_terminal::term::Osc52;
 
 use crate::config::ui_config::StringVisitor;
 
-#[derive(ConfigDeserialize, Default, Copy, Clone, Debug, PartialEq)]
+#[derive(ConfigDeserialize, Default, Clone, Debug, PartialEq)]
 pub struct Terminal {
     /// OSC52 support mode.
     pub osc52: SerdeOsc52,
+
+    /// Regex used to locate shell prompts.
+    pub prompt_regex: Option<String>,
 }
 
 #[derive(SerdeReplace, Default, Copy, Clone, Debug, PartialEq)]
@@ -24,3 +30,19 @@ impl<'de> Deserialize<'de> for SerdeOsc52 {
         Osc52::deserialize(Value::String(value)).map(SerdeOsc52).map_err(de::Error::custom)
     }
 }
+
+impl ConfigSchema for SerdeOsc52 {
+    fn schema() -> JsonValue {
+        enum_schema(&["Disabled", "OnlyCopy", "OnlyPaste", "CopyPaste"])
+    }
+
+    fn schema_default(&self) -> Option<JsonValue> {
+        let osc52 = match self.0 {
+            Osc52::Disabled => "Disabled",
+            Osc52::OnlyCopy => "OnlyCopy",
+            Osc52::OnlyPaste => "OnlyPaste",
+            Osc52::CopyPaste => "CopyPaste",
+        };
+        Some(osc52.into())
+    }
+}
@@ -0,0 +1,110 @@
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use alacritty_config::ConfigSchema;
+use alacritty_config_derive::ConfigDeserialize;
+
+use crate::config::color::MatchColors;
+use crate::config::ui_config::{LazyRegex, Program};
+
+/// Action executed when terminal output matches a regex.
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Trigger {
+    /// Regex matched against new terminal output.
+    pub regex: LazyRegex,
+
+    /// Action executed for every match.
+    #[serde(flatten)]
+    pub action: TriggerAction,
+}
+
+impl ConfigSchema for Trigger {
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "regex": LazyRegex::schema(),
+                "action": TriggerInternalAction::schema(),
+                "command": Program::schema(),
+                "highlight": MatchColors::schema(),
+                "respond": { "type": "string" },
+            },
+            "required": ["regex"],
+            "additionalProperties": false,
+        })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        let mut trigger = json!({ "regex": self.regex.schema_default()? });
+
+        match &self.action {
+            TriggerAction::Action(action) => trigger["action"] = action.schema_default()?,
+            TriggerAction::Command(program) => trigger["command"] = program.schema_default()?,
+            TriggerAction::Highlight(colors) => trigger["highlight"] = colors.schema_default()?,
+            TriggerAction::Respond(text) => trigger["respond"] = text.as_str().into(),
+        }
+
+        Some(trigger)
+    }
+}
+
+/// Actions for triggers.
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum TriggerAction {
+    /// Built-in trigger action.
+    #[serde(rename = "action")]
+    Action(TriggerInternalAction),
+
+    /// Command receiving the regex's capture groups as arguments.
+    #[serde(rename = "command")]
+    Command(Program),
+
+    /// Colors applied to the matching text.
+    #[serde(rename = "highlight")]
+    Highlight(MatchColors),
+
+    /// Text written to the PTY.
+    #[serde(rename = "respond")]
+    Respond(String),
+}
+
+/// Built-in actions for triggers.
+#[derive(ConfigDeserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TriggerInternalAction {
+    /// Ring the bell.
+    Bell,
+    /// Show the matching text as desktop notification.
+    Notify,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Triggers {
+        triggers: Vec<Trigger>,
+    }
+
+    #[test]
+    fn trigger_actions() {
+        let triggers: Triggers = toml::from_str(
+            r##"
+            triggers = [
+                { regex = "ERROR", highlight = { background = "#ff0000" } },
+                { regex = "done", action = "Notify" },
+                { regex = "(\\w+)\\.rs", command = { program = "echo", args = ["-n"] } },
+                { regex = "\\[y/N\\]", respond = "y\r" },
+            ]
+            "##,
+        )
+        .unwrap();
+
+        let actions: Vec<_> = triggers.triggers.into_iter().map(|trigger| trigger.action).collect();
+        assert!(matches!(actions[0], TriggerAction::Highlight(_)));
+        assert_eq!(actions[1], TriggerAction::Action(TriggerInternalAction::Notify));
+        let command = Program::WithArgs { program: "echo".into(), args: vec!["-n".into()] };
+        assert_eq!(actions[2], TriggerAction::Command(command));
+        assert_eq!(actions[3], TriggerAction::Respond(String::from("y\r")));
+    }
+}
@@ -4,32 +4,38 @@ use std::error::Error;
 use std::fmt::{self, Formatter};
 use std::path::PathBuf;
 use std::rc::Rc;
+use std::time::Duration;
 
-use alacritty_config::SerdeReplace;
+use alacritty_config::{ConfigSchema, SerdeReplace};
 use alacritty_terminal::term::Config as TermConfig;
 use alacritty_terminal::tty::{Options as PtyOptions, Shell};
 use log::{error, warn};
 use serde::de::{Error as SerdeError, MapAccess, Visitor};
 use serde::{Deserialize, Deserializer};
+use serde_json::{json, Value};
 use unicode_width::UnicodeWidthChar;
 use winit::keyboard::{Key, ModifiersState};
 
-use alacritty_config_derive::{ConfigDeserialize, SerdeReplace};
+use alacritty_config_derive::{ConfigDeserialize, ConfigSchema, SerdeReplace};
 use alacritty_terminal::term::search::RegexSearch;
 
 use crate::config::bell::BellConfig;
 use crate::config::bindings::{
-    self, Action, Binding, BindingKey, KeyBinding, KeyLocation, ModeWrapper, ModsWrapper,
-    MouseBinding,
+    self, Action, Binding, BindingKey, KeyBinding, KeyChord, KeyLocation, ModeWrapper, ModsWrapper,
+    MouseBinding, SequenceKey,
 };
 use crate::config::color::Colors;
 use crate::config::cursor::Cursor;
 use crate::config::debug::Debug;
+use crate::config::export::Export;
 use crate::config::font::Font;
 use crate::config::mouse::{Mouse, MouseBindings};
+use crate::config::notification::Notifications;
+use crate::config::recording::Recording;
 use crate::config::scrolling::Scrolling;
 use crate::config::selection::Selection;
 use crate::config::terminal::Terminal;
+use crate::config::trigger::Trigger;
 use crate::config::window::WindowConfig;
 use crate::config::LOG_TARGET_CONFIG;
 
@@ -75,6 +81,9 @@ pub struct UiConfig {
     /// Bell configuration.
     pub bell: BellConfig,
 
+    /// Desktop notifications requested by applications.
+    pub notifications: Notifications,
+
     /// RGB values for colors.
     pub colors: Colors,
 
@@ -85,6 +94,9 @@ pub struct UiConfig {
     /// Regex hints for interacting with terminal content.
     pub hints: Hints,
 
+    /// Actions executed for matching terminal output.
+    pub triggers: Vec<Trigger>,
+
     /// Offer IPC through a unix socket.
     #[cfg(unix)]
     pub ipc_socket: bool,
@@ -92,6 +104,12 @@ pub struct UiConfig {
     /// Config for the alacritty_terminal itself.
     pub terminal: Terminal,
 
+    /// Session recording configuration.
+    pub recording: Recording,
+
+    /// Styled export configuration.
+    pub export: Export,
+
     /// Path to a shell program to run on startup.
     pub shell: Option<Program>,
 
@@ -129,11 +147,15 @@ impl Default for UiConfig {
             draw_bold_text_with_bright_colors: Default::default(),
             working_directory: Default::default(),
             mouse_bindings: Default::default(),
+            notifications: Default::default(),
             config_paths: Default::default(),
             key_bindings: Default::default(),
             alt_send_esc: Default::default(),
             scrolling: Default::default(),
             selection: Default::default(),
+            recording: Default::default(),
+            triggers: Default::default(),
+            export: Default::default(),
             keyboard: Default::default(),
             terminal: Default::default(),
             import: Default::default(),
@@ -157,9 +179,12 @@ impl UiConfig {
         TermConfig {
             semantic_escape_chars: self.selection.semantic_escape_chars.clone(),
             scrolling_history: self.scrolling.history() as usize,
+            scrolling_archive: self.scrolling.archive.lines,
+            scrolling_archive_spill: self.scrolling.archive.disk,
             vi_mode_cursor_style: self.cursor.vi_mode_style(),
             default_cursor_style: self.cursor.style(),
             osc52: self.terminal.osc52.0,
+            prompt_regex: self.terminal.prompt_regex.clone(),
             kitty_keyboard: true,
         }
     }
@@ -167,7 +192,8 @@ impl UiConfig {
     /// Derive [`PtyOptions`] from the config.
     pub fn pty_config(&self) -> PtyOptions {
         let shell = self.shell.clone().map(Into::into);
-        PtyOptions { shell, working_directory: self.working_directory.clone(), hold: false }
+        let working_directory = self.working_directory.clone();
+        PtyOptions { shell, working_directory, hold: false, env: HashMap::new() }
     }
 
     /// Generate key bindings for all keyboard hints.
@@ -193,12 +219,38 @@ impl UiConfig {
                 mode: binding.mode.mode,
                 notmode: binding.mode.not_mode,
                 action: Action::Hint(hint.clone()),
+                sequence: Vec::new(),
             };
 
             key_bindings.push(binding);
         }
     }
 
+    /// Warn about key sequences which can never be completed.
+    pub fn check_key_sequences(&self) {
+        let leader = self.leader_key();
+        let bindings = self.key_bindings();
+
+        for binding in bindings.iter().filter(|binding| !binding.sequence.is_empty()) {
+            if leader.is_none() && binding.sequence.contains(&SequenceKey::Leader) {
+                warn!(
+                    target: LOG_TARGET_CONFIG,
+                    "Key sequence \"{}\" uses Leader, but keyboard.leader is not set",
+                    binding.sequence_label()
+                );
+            } else if let Some(shorter) =
+                bindings.iter().find(|shorter| shorter.shadows_sequence(binding, leader))
+            {
+                warn!(
+                    target: LOG_TARGET_CONFIG,
+                    "Key sequence \"{}\" is shadowed by \"{}\"",
+                    binding.sequence_label(),
+                    shorter.sequence_label()
+                );
+            }
+        }
+    }
+
     #[inline]
     pub fn window_opacity(&self) -> f32 {
         self.window.opacity.as_f32()
@@ -213,6 +265,17 @@ impl UiConfig {
         }
     }
 
+    #[inline]
+    pub fn leader_key(&self) -> Option<&KeyChord> {
+        self.keyboard.leader.as_ref()
+    }
+
+    /// Time before a pending key sequence is cancelled, zero if it never expires.
+    #[inline]
+    pub fn key_sequence_timeout(&self) -> Duration {
+        Duration::from_millis(self.keyboard.sequence_timeout)
+    }
+
     #[inline]
     pub fn mouse_bindings(&self) -> &[MouseBinding] {
         if let Some(mouse_bindings) = self.mouse_bindings.as_ref() {
@@ -229,10 +292,22 @@ impl UiConfig {
 }
 
 /// Keyboard configuration.
-#[derive(ConfigDeserialize, Default, Clone, Debug, PartialEq)]
+#[derive(ConfigDeserialize, Clone, Debug, PartialEq)]
 struct Keyboard {
     /// Keybindings.
     bindings: KeyBindings,
+
+    /// Key starting binding sequences which use `Leader`.
+    leader: Option<KeyChord>,
+
+    /// Milliseconds before a partially entered key sequence is cancelled.
+    sequence_timeout: u64,
+}
+
+impl Default for Keyboard {
+    fn default() -> Self {
+        Self { bindings: Default::default(), leader: None, sequence_timeout: 1000 }
+    }
 }
 
 #[derive(SerdeReplace, Clone, Debug, PartialEq, Eq)]
@@ -244,6 +319,17 @@ impl Default for KeyBindings {
     }
 }
 
+impl ConfigSchema for KeyBindings {
+    fn schema() -> Value {
+        bindings::key_bindings_schema()
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        let bindings: Vec<_> = self.0.iter().filter_map(KeyBinding::schema_default).collect();
+        Some(bindings.into())
+    }
+}
+
 impl<'de> Deserialize<'de> for KeyBindings {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -358,6 +444,16 @@ impl Default for HintsAlphabet {
     }
 }
 
+impl ConfigSchema for HintsAlphabet {
+    fn schema() -> Value {
+        json!({ "type": "string", "minLength": 2 })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        self.0.schema_default()
+    }
+}
+
 impl<'de> Deserialize<'de> for HintsAlphabet {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -406,8 +502,28 @@ pub enum HintAction {
     Command(Program),
 }
 
+impl ConfigSchema for HintAction {
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": HintInternalAction::schema(),
+                "command": Program::schema(),
+            },
+            "additionalProperties": false,
+        })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        match self {
+            Self::Action(action) => Some(json!({ "action": action.schema_default()? })),
+            Self::Command(program) => Some(json!({ "command": program.schema_default()? })),
+        }
+    }
+}
+
 /// Hint configuration.
-#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Deserialize, ConfigSchema, Clone, Debug, PartialEq, Eq)]
 pub struct Hint {
     /// Regex for finding matches.
     #[serde(flatten)]
@@ -432,7 +548,7 @@ pub struct Hint {
     binding: Option<HintBinding>,
 }
 
-#[derive(Default, Clone, Debug, PartialEq, Eq)]
+#[derive(ConfigSchema, Default, Clone, Debug, PartialEq, Eq)]
 pub struct HintContent {
     /// Regex for finding matches.
     pub regex: Option<LazyRegex>,
@@ -518,6 +634,30 @@ pub struct HintBinding {
     pub mode: ModeWrapper,
 }
 
+impl ConfigSchema for HintBinding {
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "key": BindingKey::schema(),
+                "mods": ModsWrapper::schema(),
+                "mode": ModeWrapper::schema(),
+            },
+            "required": ["key"],
+            "additionalProperties": false,
+        })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        let mut binding =
+            json!({ "key": self.key.schema_default()?, "mods": self.mods.schema_default()? });
+        if let Some(mode) = self.mode.schema_default() {
+            binding["mode"] = mode;
+        }
+        Some(binding)
+    }
+}
+
 /// Hint mouse highlighting.
 #[derive(ConfigDeserialize, Default, Copy, Clone, Debug, PartialEq, Eq)]
 pub struct HintMouse {
@@ -542,6 +682,19 @@ impl LazyRegex {
     }
 }
 
+impl ConfigSchema for LazyRegex {
+    fn schema() -> Value {
+        json!({ "type": "string" })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        match &*self.0.borrow() {
+            LazyRegexVariant::Pattern(regex) => Some(regex.as_str().into()),
+            _ => None,
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for LazyRegex {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -622,6 +775,17 @@ impl Percentage {
     }
 }
 
+impl ConfigSchema for Percentage {
+    fn schema() -> Value {
+        // Values outside of 0..1 are clamped instead of rejected.
+        json!({ "type": "number", "description": "Clamped between 0.0 and 1.0." })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        self.0.schema_default()
+    }
+}
+
 #[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(untagged, deny_unknown_fields)]
 pub enum Program {
@@ -658,6 +822,32 @@ impl From<Program> for Shell {
     }
 }
 
+impl ConfigSchema for Program {
+    fn schema() -> Value {
+        json!({
+            "anyOf": [
+                { "type": "string" },
+                {
+                    "type": "object",
+                    "properties": {
+                        "program": { "type": "string" },
+                        "args": { "type": "array", "items": { "type": "string" } },
+                    },
+                    "required": ["program"],
+                    "additionalProperties": false,
+                },
+            ],
+        })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        match self {
+            Self::Just(program) => Some(program.as_str().into()),
+            Self::WithArgs { program, args } => Some(json!({ "program": program, "args": args })),
+        }
+    }
+}
+
 impl SerdeReplace for Program {
     fn replace(&mut self, value: toml::Value) -> Result<(), Box<dyn Error>> {
         *self = Self::deserialize(value)?;
@@ -1,14 +1,18 @@
 use std::fmt::{self, Formatter};
+use std::path::Path;
 
 use log::{error, warn};
 use serde::de::{self, MapAccess, Visitor};
 use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{json, Map, Value};
 
 #[cfg(target_os = "macos")]
 use winit::platform::macos::OptionAsAlt as WinitOptionAsAlt;
 use winit::window::{Fullscreen, Theme as WinitTheme};
 
+use alacritty_config::ConfigSchema;
 use alacritty_config_derive::{ConfigDeserialize, SerdeReplace};
+use alacritty_terminal::term::working_directory::WorkingDirectory;
 
 use crate::config::ui_config::{Delta, Percentage};
 use crate::config::LOG_TARGET_CONFIG;
@@ -37,6 +41,9 @@ pub struct WindowConfig {
     /// Use dynamic title.
     pub dynamic_title: bool,
 
+    /// Template for the window title.
+    pub title_template: Option<String>,
+
     /// Information to identify a particular window.
     #[config(flatten)]
     pub identity: Identity,
@@ -67,6 +74,7 @@ impl Default for WindowConfig {
     fn default() -> Self {
         Self {
             dynamic_title: true,
+            title_template: Default::default(),
             blur: Default::default(),
             embed: Default::default(),
             padding: Default::default(),
@@ -85,6 +93,47 @@ impl Default for WindowConfig {
 }
 
 impl WindowConfig {
+    /// Window title for a terminal's title and working directory.
+    ///
+    /// The terminal's title is only used with `dynamic_title` enabled.
+    pub fn title(&self, title: Option<&str>, directory: Option<&WorkingDirectory>) -> String {
+        let title = title.filter(|_| self.dynamic_title).unwrap_or(&self.identity.title);
+        let template = match &self.title_template {
+            Some(template) => template,
+            None => return title.into(),
+        };
+
+        let mut rendered = String::new();
+        let mut rest = template.as_str();
+        while let Some(start) = rest.find('{') {
+            rendered.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            let (name, len) = match rest.find('}') {
+                Some(end) => (&rest[1..end], end + 1),
+                None => ("", 1),
+            };
+
+            match (name, directory) {
+                ("title", _) => rendered.push_str(title),
+                ("cwd", Some(directory)) => rendered.push_str(&home_relative(&directory.path)),
+                ("host", Some(directory)) => rendered.push_str(&directory.host),
+                ("cwd" | "host", None) => (),
+                // Keep unknown placeholders as they are.
+                _ => {
+                    rendered.push('{');
+                    rest = &rest[1..];
+                    continue;
+                },
+            }
+
+            rest = &rest[len..];
+        }
+        rendered.push_str(rest);
+
+        rendered
+    }
+
     #[inline]
     pub fn dimensions(&self) -> Option<Dimensions> {
         let (lines, columns) = (self.dimensions.lines, self.dimensions.columns);
@@ -206,6 +255,10 @@ pub struct Class {
 }
 
 impl Class {
+    /// Configurable fields of the class table.
+    const FIELDS: [(&'static str, fn(&mut Class) -> &mut String); 2] =
+        [("general", |class| &mut class.general), ("instance", |class| &mut class.instance)];
+
     pub fn new(general: impl ToString, instance: impl ToString) -> Self {
         Self { general: general.to_string(), instance: instance.to_string() }
     }
@@ -244,26 +297,19 @@ impl<'de> Deserialize<'de> for Class {
                 let mut class = Self::Value::default();
 
                 while let Some((key, value)) = map.next_entry::<String, toml::Value>()? {
-                    match key.as_str() {
-                        "instance" => match String::deserialize(value) {
-                            Ok(instance) => class.instance = instance,
-                            Err(err) => {
-                                error!(
-                                    target: LOG_TARGET_CONFIG,
-                                    "Config error: class.instance: {}", err
-                                );
-                            },
+                    let field = match Class::FIELDS.iter().find(|(name, _)| *name == key) {
+                        Some((_, field)) => field(&mut class),
+                        None => {
+                            warn!(target: LOG_TARGET_CONFIG, "Unrecognized class field: {key}");
+                            continue;
                         },
-                        "general" => match String::deserialize(value) {
-                            Ok(general) => class.general = general,
-                            Err(err) => {
-                                error!(
-                                    target: LOG_TARGET_CONFIG,
-                                    "Config error: class.instance: {}", err
-                                );
-                            },
+                    };
+
+                    match String::deserialize(value) {
+                        Ok(value) => *field = value,
+                        Err(err) => {
+                            error!(target: LOG_TARGET_CONFIG, "Config error: class.{key}: {err}");
                         },
-                        key => warn!(target: LOG_TARGET_CONFIG, "Unrecognized class field: {key}"),
                     }
                 }
 
@@ -275,6 +321,29 @@ impl<'de> Deserialize<'de> for Class {
     }
 }
 
+impl ConfigSchema for Class {
+    fn schema() -> Value {
+        let properties: Map<String, Value> =
+            Self::FIELDS.iter().map(|(name, _)| ((*name).into(), String::schema())).collect();
+
+        json!({
+            "anyOf": [
+                String::schema(),
+                { "type": "object", "properties": properties, "additionalProperties": false },
+            ],
+        })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        let mut class = self.clone();
+        let fields = Self::FIELDS.iter().map(|(name, field)| {
+            let value = field(&mut class).as_str().into();
+            ((*name).into(), value)
+        });
+        Some(Value::Object(fields.collect()))
+    }
+}
+
 #[derive(ConfigDeserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OptionAsAlt {
     /// The left `Option` key is treated as `Alt`.
@@ -306,3 +375,39 @@ impl From<Theme> for WinitTheme {
         }
     }
 }
+
+/// Path with the home directory replaced by `~`.
+fn home_relative(path: &Path) -> String {
+    match home::home_dir().and_then(|home| path.strip_prefix(home).ok().map(Path::to_owned)) {
+        Some(relative) if relative.as_os_str().is_empty() => "~".into(),
+        Some(relative) => format!("~/{}", relative.display()),
+        None => path.display().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::path::PathBuf;
+
+    #[test]
+    fn title_template() {
+        let mut config = WindowConfig {
+            title_template: Some("{title} {nope} ({host}:{cwd}) {".into()),
+            ..Default::default()
+        };
+
+        let directory = WorkingDirectory { host: "box".into(), path: PathBuf::from("/tmp/{cwd}") };
+        let title = config.title(Some("{host}"), Some(&directory));
+        assert_eq!(title, "{host} {nope} (box:/tmp/{cwd}) {");
+
+        assert_eq!(config.title(None, None), "Alacritty {nope} (:) {");
+
+        config.dynamic_title = false;
+        assert_eq!(config.title(Some("vim"), None), "Alacritty {nope} (:) {");
+
+        config.title_template = None;
+        assert_eq!(config.title(Some("vim"), None), "Alacritty");
+    }
+}
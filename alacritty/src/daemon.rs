@@ -12,11 +12,14 @@ use {
     std::error::Error,
     std::os::unix::process::CommandExt,
     std::os::unix::io::RawFd,
-    std::path::PathBuf,
+    std::path::{Path, PathBuf},
 };
 
 #[cfg(not(windows))]
 use libc::pid_t;
+
+#[cfg(not(windows))]
+use alacritty_terminal::term::Term;
 #[cfg(windows)]
 use windows_sys::Win32::System::Threading::{CREATE_NEW_PROCESS_GROUP, CREATE_NO_WINDOW};
 
@@ -49,8 +52,7 @@ where
 pub fn spawn_daemon<I, S>(
     program: &str,
     args: I,
-    master_fd: RawFd,
-    shell_pid: u32,
+    working_directory: Option<&Path>,
 ) -> io::Result<()>
 where
     I: IntoIterator<Item = S> + Copy,
@@ -58,7 +60,7 @@ where
 {
     let mut command = Command::new(program);
     command.args(args).stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
-    if let Ok(cwd) = foreground_process_path(master_fd, shell_pid) {
+    if let Some(cwd) = working_directory {
         command.current_dir(cwd);
     }
     unsafe {
@@ -82,6 +84,32 @@ where
     }
 }
 
+/// Get working directory of a terminal.
+///
+/// The directory reported by the shell through OSC 7 is preferred, since the foreground process
+/// might not be the shell, or might be connected to a different host. Without a directory
+/// reported for the local host, this falls back to the foreground process's directory.
+#[cfg(not(windows))]
+pub fn working_directory<T>(term: &Term<T>, master_fd: RawFd, shell_pid: u32) -> Option<PathBuf> {
+    match term.working_directory() {
+        Some(directory) if is_local_host(&directory.host) => Some(directory.path.clone()),
+        _ => foreground_process_path(master_fd, shell_pid).ok(),
+    }
+}
+
+/// Check if a reported host name refers to this machine.
+#[cfg(not(windows))]
+fn is_local_host(host: &str) -> bool {
+    if host.is_empty() || host == "localhost" {
+        return true;
+    }
+
+    let mut buffer = [0u8; 256];
+    let result = unsafe { libc::gethostname(buffer.as_mut_ptr().cast(), buffer.len()) };
+    let len = buffer.iter().position(|&b| b == 0).unwrap_or(buffer.len());
+    result == 0 && &buffer[..len] == host.as_bytes()
+}
+
 /// Get working directory of controlling process.
 #[cfg(not(windows))]
 pub fn foreground_process_path(
@@ -5,7 +5,9 @@ use std::str::FromStr;
 use log::trace;
 use serde::de::{Error as SerdeError, Visitor};
 use serde::{Deserialize, Deserializer};
+use serde_json::{json, Value};
 
+use alacritty_config::ConfigSchema;
 use alacritty_config_derive::SerdeReplace;
 use alacritty_terminal::term::color::COUNT;
 use alacritty_terminal::vte::ansi::{NamedColor, Rgb as VteRgb};
@@ -267,6 +269,16 @@ impl<'de> Deserialize<'de> for Rgb {
     }
 }
 
+impl ConfigSchema for Rgb {
+    fn schema() -> Value {
+        json!({ "type": "string", "pattern": "^(#|0x)[0-9a-fA-F]{6}$" })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        Some(self.to_string().into())
+    }
+}
+
 impl Display for Rgb {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
@@ -323,6 +335,20 @@ impl Default for CellRgb {
     }
 }
 
+impl ConfigSchema for CellRgb {
+    fn schema() -> Value {
+        json!({ "anyOf": [{ "enum": ["CellForeground", "CellBackground"] }, Rgb::schema()] })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        match self {
+            Self::CellForeground => Some("CellForeground".into()),
+            Self::CellBackground => Some("CellBackground".into()),
+            Self::Rgb(rgb) => rgb.schema_default(),
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for CellRgb {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
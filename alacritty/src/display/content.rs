@@ -3,6 +3,7 @@ use std::ops::Deref;
 use std::{cmp, mem};
 
 use alacritty_terminal::event::EventListener;
+use alacritty_terminal::graphics::GraphicCell;
 use alacritty_terminal::grid::{Dimensions, Indexed};
 use alacritty_terminal::index::{Column, Line, Point};
 use alacritty_terminal::selection::SelectionRange;
@@ -11,11 +12,14 @@ use alacritty_terminal::term::search::{Match, RegexSearch};
 use alacritty_terminal::term::{self, RenderableContent as TerminalContent, Term, TermMode};
 use alacritty_terminal::vte::ansi::{Color, CursorShape, NamedColor};
 
+use crate::config::color::MatchColors;
 use crate::config::UiConfig;
 use crate::display::color::{CellRgb, List, Rgb, DIM_FACTOR};
 use crate::display::hint::{self, HintState};
-use crate::display::{Display, SizeInfo};
+use crate::display::Display;
 use crate::event::SearchState;
+use crate::renderer::ShapedGlyph;
+use crate::trigger::TriggerState;
 
 /// Minimum contrast between a fixed cursor color and the cell's background.
 pub const MIN_CURSOR_CONTRAST: f64 = 1.5;
@@ -29,29 +33,35 @@ pub struct RenderableContent<'a> {
     cursor_shape: CursorShape,
     cursor_point: Point<usize>,
     search: Option<HintMatches<'a>>,
+    triggers: TriggerHighlights,
     hint: Option<Hint<'a>>,
     config: &'a UiConfig,
     colors: &'a List,
     focused_match: Option<&'a Match>,
-    size: &'a SizeInfo,
+    columns: usize,
 }
 
 impl<'a> RenderableContent<'a> {
+    /// Collect the content of a terminal.
+    ///
+    /// UI state like hints and IME input is only applied to the focused pane.
     pub fn new<T: EventListener>(
         config: &'a UiConfig,
         display: &'a mut Display,
         term: &'a Term<T>,
         search_state: &'a mut SearchState,
+        trigger_state: &TriggerState,
+        focused_pane: bool,
     ) -> Self {
         let search = search_state.dfas().map(|dfas| HintMatches::visible_regex_matches(term, dfas));
+        let triggers = TriggerHighlights::new(trigger_state.visible_highlights(term));
         let focused_match = search_state.focused_match();
         let terminal_content = term.renderable_content();
 
         // Find terminal cursor shape.
         let cursor_shape = if terminal_content.cursor.shape == CursorShape::Hidden
-            || display.cursor_hidden
             || search_state.regex().is_some()
-            || display.ime.preedit().is_some()
+            || (focused_pane && (display.cursor_hidden || display.ime.preedit().is_some()))
         {
             CursorShape::Hidden
         } else if !term.is_focused && config.cursor.unfocused_hollow {
@@ -65,7 +75,7 @@ impl<'a> RenderableContent<'a> {
         let display_offset = terminal_content.display_offset;
         let cursor_point = term::point_to_viewport(display_offset, cursor_point).unwrap();
 
-        let hint = if display.hint_state.active() {
+        let hint = if focused_pane && display.hint_state.active() {
             display.hint_state.update_matches(term);
             Some(Hint::from(&display.hint_state))
         } else {
@@ -74,12 +84,13 @@ impl<'a> RenderableContent<'a> {
 
         Self {
             colors: &display.colors,
-            size: &display.size_info,
+            columns: term.columns(),
             cursor: RenderableCursor::new_hidden(),
             terminal_content,
             focused_match,
             cursor_shape,
             cursor_point,
+            triggers,
             search,
             config,
             hint,
@@ -197,6 +208,8 @@ pub struct RenderableCell {
 pub struct RenderableCellExtra {
     pub zerowidth: Option<Vec<char>>,
     pub hyperlink: Option<Hyperlink>,
+    pub graphic: Option<GraphicCell>,
+    pub glyphs: Option<Vec<ShapedGlyph>>,
 }
 
 impl RenderableCell {
@@ -226,7 +239,7 @@ impl RenderableCell {
         let mut character = cell.c;
         let mut flags = cell.flags;
 
-        let num_cols = content.size.columns();
+        let num_cols = content.columns;
         if let Some((c, is_first)) = content
             .hint
             .as_mut()
@@ -264,6 +277,9 @@ impl RenderableCell {
                 (colors.search.matches.foreground, colors.search.matches.background)
             };
             Self::compute_cell_rgb(&mut fg, &mut bg, &mut bg_alpha, config_fg, config_bg);
+        } else if let Some(colors) = content.triggers.advance(cell.point) {
+            let (config_fg, config_bg) = (colors.foreground, colors.background);
+            Self::compute_cell_rgb(&mut fg, &mut bg, &mut bg_alpha, config_fg, config_bg);
         }
 
         // Apply transparency to all renderable cells if `transparent_background_colors` is set
@@ -281,11 +297,14 @@ impl RenderableCell {
 
         let zerowidth = cell.zerowidth();
         let hyperlink = cell.hyperlink();
+        let graphic = cell.graphic().cloned();
 
-        let extra = (zerowidth.is_some() || hyperlink.is_some()).then(|| {
+        let extra = (zerowidth.is_some() || hyperlink.is_some() || graphic.is_some()).then(|| {
             Box::new(RenderableCellExtra {
                 zerowidth: zerowidth.map(|zerowidth| zerowidth.to_vec()),
                 hyperlink,
+                graphic,
+                glyphs: None,
             })
         });
 
@@ -431,6 +450,10 @@ impl RenderableCursor {
     pub fn point(&self) -> Point<usize> {
         self.point
     }
+
+    pub fn set_point(&mut self, point: Point<usize>) {
+        self.point = point;
+    }
 }
 
 /// Regex hints for keyboard shortcuts.
@@ -539,3 +562,34 @@ impl<'a> Deref for HintMatches<'a> {
         self.matches.deref()
     }
 }
+
+/// Visible trigger highlights.
+struct TriggerHighlights {
+    /// Highlighted matches, ordered by their start.
+    highlights: Vec<(Match, MatchColors)>,
+
+    /// Index of the first highlight which might contain the next point.
+    index: usize,
+}
+
+impl TriggerHighlights {
+    fn new(highlights: Vec<(Match, MatchColors)>) -> Self {
+        Self { highlights, index: 0 }
+    }
+
+    /// Advance the highlight tracker to the next point.
+    ///
+    /// This will return the colors of the highlight containing the point, if there is one.
+    fn advance(&mut self, point: Point) -> Option<MatchColors> {
+        while self.highlights.get(self.index).is_some_and(|(bounds, _)| *bounds.end() < point) {
+            self.index += 1;
+        }
+
+        // Highlights of different triggers can overlap.
+        self.highlights[self.index..]
+            .iter()
+            .take_while(|(bounds, _)| *bounds.start() <= point)
+            .find(|(bounds, _)| bounds.contains(&point))
+            .map(|(_, colors)| *colors)
+    }
+}
@@ -6,16 +6,18 @@ use std::fmt::{self, Formatter};
 use std::mem::{self, ManuallyDrop};
 use std::num::NonZeroU32;
 use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use glutin::context::{NotCurrentContext, PossiblyCurrentContext};
 use glutin::prelude::*;
-use glutin::surface::{Surface, SwapInterval, WindowSurface};
+use glutin::surface::{Rect, Surface, SwapInterval, WindowSurface};
 
 use log::{debug, info};
 use parking_lot::MutexGuard;
 use raw_window_handle::RawWindowHandle;
 use serde::{Deserialize, Serialize};
+use softbuffer::SoftBufferError;
 use winit::dpi::PhysicalSize;
 use winit::keyboard::ModifiersState;
 use winit::window::CursorIcon;
@@ -23,7 +25,7 @@ use winit::window::CursorIcon;
 use crossfont::{Rasterize, Rasterizer, Size as FontSize};
 use unicode_width::UnicodeWidthChar;
 
-use alacritty_terminal::event::{EventListener, OnResize, WindowSize};
+use alacritty_terminal::event::{EventListener, WindowSize};
 use alacritty_terminal::grid::Dimensions as TermDimensions;
 use alacritty_terminal::index::{Column, Direction, Line, Point};
 use alacritty_terminal::selection::Selection;
@@ -49,10 +51,15 @@ use crate::display::meter::Meter;
 use crate::display::window::Window;
 use crate::event::{Event, EventType, Mouse, SearchState};
 use crate::message_bar::{MessageBuffer, MessageType};
+use crate::pane::PaneRect;
+use crate::renderer::graphics::RenderGraphics;
 use crate::renderer::rects::{RenderLine, RenderLines, RenderRect};
 use crate::renderer::{self, GlyphCache, Renderer};
 use crate::scheduler::{Scheduler, TimerId, Topic};
+use crate::screenshot::ScreenshotRequest;
 use crate::string::{ShortenDirection, StrShortener};
+use crate::tab::{self, TabBar};
+use crate::trigger::TriggerState;
 
 pub mod color;
 pub mod content;
@@ -89,6 +96,9 @@ pub enum Error {
 
     /// Error during context operations.
     Context(glutin::error::Error),
+
+    /// Error creating the software rendering surface.
+    SoftwareSurface(SoftBufferError),
 }
 
 impl std::error::Error for Error {
@@ -98,6 +108,7 @@ impl std::error::Error for Error {
             Error::Font(err) => err.source(),
             Error::Render(err) => err.source(),
             Error::Context(err) => err.source(),
+            Error::SoftwareSurface(err) => err.source(),
         }
     }
 }
@@ -109,6 +120,7 @@ impl fmt::Display for Error {
             Error::Font(err) => err.fmt(f),
             Error::Render(err) => err.fmt(f),
             Error::Context(err) => err.fmt(f),
+            Error::SoftwareSurface(err) => err.fmt(f),
         }
     }
 }
@@ -137,6 +149,12 @@ impl From<glutin::error::Error> for Error {
     }
 }
 
+impl From<SoftBufferError> for Error {
+    fn from(val: SoftBufferError) -> Self {
+        Error::SoftwareSurface(val)
+    }
+}
+
 /// Terminal size info.
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
 pub struct SizeInfo<T = f32> {
@@ -273,6 +291,19 @@ impl SizeInfo<f32> {
             && y > self.padding_y as usize
     }
 
+    /// Size of a pane covering part of the terminal grid.
+    ///
+    /// The padding is extended to the origin of the pane, so all positions are relative to it.
+    pub fn pane(&self, rect: &PaneRect) -> SizeInfo {
+        SizeInfo {
+            padding_x: self.cell_width.mul_add(rect.column as f32, self.padding_x),
+            padding_y: self.cell_height.mul_add(rect.line as f32, self.padding_y),
+            screen_lines: cmp::max(rect.lines, MIN_SCREEN_LINES),
+            columns: cmp::max(rect.columns, MIN_COLUMNS),
+            ..*self
+        }
+    }
+
     /// Calculate padding to spread it evenly around the terminal content.
     #[inline]
     fn dynamic_padding(padding: f32, dimension: f32, cell_dimension: f32) -> f32 {
@@ -335,7 +366,33 @@ impl DisplayUpdate {
     }
 }
 
-/// The display wraps a window, font rasterizer, and GPU renderer.
+/// Panes drawn into a window.
+pub struct PaneFrame<'a, T> {
+    /// Grid area of the pane with keyboard focus.
+    pub focused: PaneRect,
+
+    /// Trigger highlights of the pane with keyboard focus.
+    pub trigger_state: &'a TriggerState,
+
+    /// All panes without keyboard focus.
+    pub unfocused: Vec<UnfocusedPane<'a, T>>,
+
+    /// Areas separating the panes.
+    pub dividers: Vec<PaneRect>,
+
+    /// Tabs of the window, when there's more than one.
+    pub tab_bar: Option<TabBar>,
+}
+
+/// Pane which is drawn without keyboard focus.
+pub struct UnfocusedPane<'a, T> {
+    pub rect: PaneRect,
+    pub terminal: MutexGuard<'a, Term<T>>,
+    pub search_state: &'a mut SearchState,
+    pub trigger_state: &'a TriggerState,
+}
+
+/// The display wraps a window, font rasterizer, and renderer.
 pub struct Display {
     pub window: Window,
 
@@ -378,23 +435,27 @@ pub struct Display {
     /// Font size used by the window.
     pub font_size: FontSize,
 
+    /// Screenshots waiting for the next frame.
+    pending_screenshots: Vec<ScreenshotRequest>,
+
     // Mouse point position when highlighting hints.
     hint_mouse_point: Option<Point>,
 
     renderer: ManuallyDrop<Renderer>,
 
-    surface: ManuallyDrop<Surface<WindowSurface>>,
-
-    context: ManuallyDrop<Replaceable<PossiblyCurrentContext>>,
+    surface: ManuallyDrop<RenderSurface>,
 
     glyph_cache: GlyphCache,
     meter: Meter,
 }
 
 impl Display {
+    /// Create a new display.
+    ///
+    /// Without an OpenGL context, the software renderer is used.
     pub fn new(
         window: Window,
-        gl_context: NotCurrentContext,
+        gl_context: Option<NotCurrentContext>,
         config: &UiConfig,
         _tabbed: bool,
     ) -> Result<Display, Error> {
@@ -417,18 +478,30 @@ impl Display {
             window.request_inner_size(size);
         }
 
-        // Create the GL surface to draw into.
-        let surface = renderer::platform::create_gl_surface(
-            &gl_context,
-            window.inner_size(),
-            window.raw_window_handle(),
-        )?;
+        let (mut surface, mut renderer) = match gl_context {
+            Some(gl_context) => {
+                // Create the GL surface to draw into.
+                let surface = renderer::platform::create_gl_surface(
+                    &gl_context,
+                    window.inner_size(),
+                    window.raw_window_handle(),
+                )?;
 
-        // Make the context current.
-        let context = gl_context.make_current(&surface)?;
+                // Make the context current.
+                let context = gl_context.make_current(&surface)?;
 
-        // Create renderer.
-        let mut renderer = Renderer::new(&context, config.debug.renderer)?;
+                // Create renderer.
+                let renderer = Renderer::new(&context, config.debug.renderer)?;
+
+                (RenderSurface::Gl { context: Replaceable::new(context), surface }, renderer)
+            },
+            None => {
+                info!("Using software renderer");
+
+                let (context, surface) = renderer::platform::create_software_surface(&window)?;
+                (RenderSurface::Software { surface, _context: context }, Renderer::software())
+            },
+        };
 
         // Load font common glyphs to accelerate rendering.
         debug!("Filling glyph cache with common glyphs");
@@ -455,6 +528,7 @@ impl Display {
         info!("Width: {}, Height: {}", size_info.width(), size_info.height());
 
         // Update OpenGL projection.
+        surface.resize(&size_info);
         renderer.resize(&size_info);
 
         // Clear screen.
@@ -470,7 +544,7 @@ impl Display {
         // On Wayland we can safely ignore this call, since the window isn't visible until you
         // actually draw something into it and commit those changes.
         if !is_wayland {
-            surface.swap_buffers(&context).expect("failed to swap buffers.");
+            surface.present(&renderer, None);
             renderer.finish();
         }
 
@@ -498,12 +572,13 @@ impl Display {
         damage_tracker.debug = config.debug.highlight_damage;
 
         // Disable vsync.
-        if let Err(err) = surface.set_swap_interval(&context, SwapInterval::DontWait) {
-            info!("Failed to disable vsync: {}", err);
+        if let RenderSurface::Gl { context, surface } = &surface {
+            if let Err(err) = surface.set_swap_interval(context, SwapInterval::DontWait) {
+                info!("Failed to disable vsync: {}", err);
+            }
         }
 
         Ok(Self {
-            context: ManuallyDrop::new(Replaceable::new(context)),
             visual_bell: VisualBell::from(&config.bell),
             renderer: ManuallyDrop::new(renderer),
             surface: ManuallyDrop::new(surface),
@@ -520,6 +595,7 @@ impl Display {
             vi_highlighted_hint: Default::default(),
             highlighted_hint: Default::default(),
             hint_mouse_point: Default::default(),
+            pending_screenshots: Default::default(),
             pending_update: Default::default(),
             cursor_hidden: Default::default(),
             meter: Default::default(),
@@ -527,44 +603,52 @@ impl Display {
         })
     }
 
+    /// Save the next frame as screenshot.
+    ///
+    /// The window must be redrawn for the screenshot to be taken.
+    pub fn screenshot(&mut self, request: ScreenshotRequest) {
+        self.pending_screenshots.push(request);
+    }
+
+    /// OpenGL context of the window.
+    ///
+    /// This is `None` when using the software renderer.
     #[inline]
-    pub fn gl_context(&self) -> &PossiblyCurrentContext {
-        self.context.get()
+    pub fn gl_context(&self) -> Option<&PossiblyCurrentContext> {
+        match self.surface.deref() {
+            RenderSurface::Gl { context, .. } => Some(context.get()),
+            RenderSurface::Software { .. } => None,
+        }
     }
 
     pub fn make_not_current(&mut self) {
-        if self.context.get().is_current() {
-            self.context.replace_with(|context| {
-                context
-                    .make_not_current()
-                    .expect("failed to disable context")
-                    .treat_as_possibly_current()
-            });
+        if let RenderSurface::Gl { context, .. } = self.surface.deref_mut() {
+            if context.get().is_current() {
+                context.replace_with(|context| {
+                    context
+                        .make_not_current()
+                        .expect("failed to disable context")
+                        .treat_as_possibly_current()
+                });
+            }
         }
     }
 
     pub fn make_current(&self) {
-        if !self.context.get().is_current() {
-            self.context.make_current(&self.surface).expect("failed to make context current")
+        if let RenderSurface::Gl { context, surface } = self.surface.deref() {
+            if !context.get().is_current() {
+                context.make_current(surface).expect("failed to make context current")
+            }
         }
     }
 
-    fn swap_buffers(&self) {
-        #[allow(clippy::single_match)]
-        let res = match (self.surface.deref(), &self.context.get()) {
-            #[cfg(not(any(target_os = "macos", windows)))]
-            (Surface::Egl(surface), PossiblyCurrentContext::Egl(context))
-                if matches!(self.raw_window_handle, RawWindowHandle::Wayland(_))
-                    && !self.damage_tracker.debug =>
-            {
-                let damage = self.damage_tracker.shape_frame_damage(self.size_info.into());
-                surface.swap_buffers_with_damage(context, &damage)
-            },
-            (surface, context) => surface.swap_buffers(context),
-        };
-        if let Err(err) = res {
-            debug!("error calling swap_buffers: {}", err);
-        }
+    fn swap_buffers(&mut self) {
+        // Only pass damage on Wayland, since it's unreliable on other platforms.
+        let damage = (matches!(self.raw_window_handle, RawWindowHandle::Wayland(_))
+            && !self.damage_tracker.debug)
+            .then(|| self.damage_tracker.shape_frame_damage(self.size_info.into()));
+
+        self.surface.present(&self.renderer, damage);
     }
 
     /// Update font size and cell dimensions.
@@ -593,16 +677,14 @@ impl Display {
     // performed in [`Self::process_renderer_update`] right before drawing.
     //
     /// Process update events.
-    pub fn handle_update<T>(
+    ///
+    /// The terminals of the window's panes must be resized afterwards.
+    pub fn handle_update(
         &mut self,
-        terminal: &mut Term<T>,
-        pty_resize_handle: &mut dyn OnResize,
         message_buffer: &MessageBuffer,
         search_state: &mut SearchState,
         config: &UiConfig,
-    ) where
-        T: EventListener,
-    {
+    ) {
         let pending_update = mem::take(&mut self.pending_update);
 
         let (mut cell_width, mut cell_height) =
@@ -655,17 +737,10 @@ impl Display {
             self.window.set_resize_increments(PhysicalSize::new(cell_width, cell_height));
         }
 
-        // Resize when terminal when its dimensions have changed.
+        // Resize damage tracking when the grid dimensions have changed.
         if self.size_info.screen_lines() != new_size.screen_lines
             || self.size_info.columns() != new_size.columns()
         {
-            // Resize PTY.
-            pty_resize_handle.on_resize(new_size.into());
-
-            // Resize terminal.
-            terminal.resize(new_size);
-
-            // Resize damage tracking.
             self.damage_tracker.resize(new_size.screen_lines(), new_size.columns());
         }
 
@@ -678,6 +753,7 @@ impl Display {
             // Clear focused search match.
             search_state.clear_focused_match();
         }
+
         self.size_info = new_size;
     }
 
@@ -694,9 +770,7 @@ impl Display {
 
         // Resize renderer.
         if renderer_update.resize {
-            let width = NonZeroU32::new(self.size_info.width() as u32).unwrap();
-            let height = NonZeroU32::new(self.size_info.height() as u32).unwrap();
-            self.surface.resize(&self.context, width, height);
+            self.surface.resize(&self.size_info);
         }
 
         // Ensure we're modifying the correct OpenGL context.
@@ -714,19 +788,27 @@ impl Display {
 
     /// Draw the screen.
     ///
-    /// A reference to Term whose state is being drawn must be provided.
+    /// A reference to the Term of the focused pane must be provided, all other panes are passed
+    /// through `panes`.
     ///
     /// This call may block if vsync is enabled.
     pub fn draw<T: EventListener>(
         &mut self,
         mut terminal: MutexGuard<'_, Term<T>>,
+        panes: PaneFrame<'_, T>,
         scheduler: &mut Scheduler,
         message_buffer: &MessageBuffer,
         config: &UiConfig,
         search_state: &mut SearchState,
     ) {
+        let PaneFrame { focused, trigger_state, unfocused, dividers, tab_bar } = panes;
+
+        // Release archived lines decompressed by searches since the last frame.
+        terminal.grid_mut().trim_archive_cache();
+
         // Collect renderable content before the terminal is dropped.
-        let mut content = RenderableContent::new(config, self, &terminal, search_state);
+        let mut content =
+            RenderableContent::new(config, self, &terminal, search_state, trigger_state, true);
         let mut grid_cells = Vec::new();
         for cell in &mut content {
             grid_cells.push(cell);
@@ -735,7 +817,13 @@ impl Display {
         let foreground_color = content.color(NamedColor::Foreground as usize);
         let background_color = content.color(NamedColor::Background as usize);
         let display_offset = content.display_offset();
-        let cursor = content.cursor();
+        let mut cursor = content.cursor();
+
+        // Shape text before moving it into the pane, breaking up ligatures under the cursor.
+        let visible_cursor = (cursor.shape() != CursorShape::Hidden).then(|| cursor.point());
+        self.glyph_cache.shape(&mut grid_cells, visible_cursor);
+
+        cursor.set_point(pane_point(cursor.point(), &focused));
 
         let cursor_point = terminal.grid().cursor.point;
         let total_lines = terminal.grid().total_lines();
@@ -765,7 +853,9 @@ impl Display {
         if self.collect_damage() {
             let requires_full_damage = self.visual_bell.intensity() != 0.
                 || self.hint_state.active()
-                || search_state.regex().is_some();
+                || search_state.regex().is_some()
+                || !unfocused.is_empty()
+                || tab_bar.is_some();
 
             if requires_full_damage {
                 self.damage_tracker.frame().mark_fully_damaged();
@@ -783,7 +873,14 @@ impl Display {
         self.make_current();
 
         self.renderer.clear(background_color, config.window_opacity());
+
+        // Draw all panes without keyboard focus.
+        for pane in unfocused {
+            self.draw_unfocused_pane(pane, config, background_color);
+        }
+
         let mut lines = RenderLines::new();
+        let mut graphics = RenderGraphics::new();
 
         // Optimize loop hint comparator.
         let has_highlighted_hint =
@@ -826,26 +923,39 @@ impl Display {
                         }
                     }
 
+                    // Move the cell into the focused pane.
+                    cell.point = pane_point(cell.point, &focused);
+
                     // Update underline/strikeout.
                     lines.update(&cell);
 
+                    // Update inline images.
+                    graphics.update(&cell);
+
                     cell
                 }),
             );
         }
 
+        // Draw inline images on top of the text.
+        self.renderer.draw_graphics(&size_info, graphics);
+
         let mut rects = lines.rects(&metrics, &size_info);
 
+        // Draw the dividers between panes.
+        let divider_color = config.colors.pane_divider();
+        rects.extend(dividers.iter().map(|divider| self.divider_rect(divider, divider_color)));
+
         if let Some(vi_cursor_point) = vi_cursor_point {
             // Indicate vi mode by showing the cursor's position in the top right corner.
-            let line = (-vi_cursor_point.line.0 + size_info.bottommost_line().0) as usize;
+            let line = (focused.lines as i32 - 1 - vi_cursor_point.line.0) as usize;
             let obstructed_column = Some(vi_cursor_point)
                 .filter(|point| point.line == -(display_offset as i32))
                 .map(|point| point.column);
-            self.draw_line_indicator(config, total_lines, obstructed_column, line);
+            self.draw_line_indicator(config, &focused, total_lines, obstructed_column, line);
         } else if search_state.regex().is_some() {
             // Show current display offset in vi-less search to indicate match position.
-            self.draw_line_indicator(config, total_lines, None, display_offset);
+            self.draw_line_indicator(config, &focused, total_lines, None, display_offset);
         };
 
         // Draw cursor.
@@ -892,11 +1002,9 @@ impl Display {
 
                 Some(Point::new(line, column))
             },
-            None => {
-                let num_lines = self.size_info.screen_lines();
-                term::point_to_viewport(display_offset, cursor_point)
-                    .filter(|point| point.line < num_lines)
-            },
+            None => term::point_to_viewport(display_offset, cursor_point)
+                .filter(|point| point.line < focused.lines)
+                .map(|point| pane_point(point, &focused)),
         };
 
         // Handle IME.
@@ -923,6 +1031,7 @@ impl Display {
             let bg = match message.ty() {
                 MessageType::Error => config.colors.normal.red,
                 MessageType::Warning => config.colors.normal.yellow,
+                MessageType::Info => config.colors.normal.blue,
             };
 
             let x = 0;
@@ -959,14 +1068,27 @@ impl Display {
             self.renderer.draw_rects(&size_info, &metrics, rects);
         }
 
-        self.draw_render_timer(config);
+        if let Some(tab_bar) = &tab_bar {
+            self.draw_tab_bar(config, tab_bar);
+        }
 
         // Draw hyperlink uri preview.
         if has_highlighted_hint {
             let cursor_point = vi_cursor_point.or(Some(cursor_point));
-            self.draw_hyperlink_preview(config, cursor_point, display_offset);
+            self.draw_hyperlink_preview(config, &focused, cursor_point, display_offset);
+        }
+
+        // Capture the frame before any debug overlays are added.
+        if !self.pending_screenshots.is_empty() {
+            let pixels: Arc<[u8]> = self.renderer.read_pixels(&self.size_info).into();
+            let (width, height) = (self.size_info.width() as u32, self.size_info.height() as u32);
+            for request in self.pending_screenshots.drain(..) {
+                request.save(width, height, pixels.clone());
+            }
         }
 
+        self.draw_render_timer(config);
+
         // Notify winit that we're about to present.
         self.window.pre_present_notify();
 
@@ -997,6 +1119,86 @@ impl Display {
         self.damage_tracker.swap_damage();
     }
 
+    /// Draw the content of a pane without keyboard focus.
+    fn draw_unfocused_pane<T: EventListener>(
+        &mut self,
+        pane: UnfocusedPane<'_, T>,
+        config: &UiConfig,
+        window_background: Rgb,
+    ) {
+        let UnfocusedPane { rect, mut terminal, search_state, trigger_state } = pane;
+
+        let mut content =
+            RenderableContent::new(config, self, &terminal, search_state, trigger_state, false);
+        let mut grid_cells = Vec::new();
+        for mut cell in &mut content {
+            cell.point = pane_point(cell.point, &rect);
+            grid_cells.push(cell);
+        }
+        let background_color = content.color(NamedColor::Background as usize);
+        let mut cursor = content.cursor();
+        cursor.set_point(pane_point(cursor.point(), &rect));
+
+        let visible_cursor = (cursor.shape() != CursorShape::Hidden).then(|| cursor.point());
+        self.glyph_cache.shape(&mut grid_cells, visible_cursor);
+
+        // Panes are always fully redrawn.
+        terminal.reset_damage();
+        drop(terminal);
+
+        let metrics = self.glyph_cache.font_metrics();
+        let size_info = self.size_info;
+
+        // Fill the pane's background when it differs from the window's.
+        if background_color != window_background {
+            let x = size_info.cell_width().mul_add(rect.column as f32, size_info.padding_x());
+            let y = size_info.cell_height().mul_add(rect.line as f32, size_info.padding_y());
+            let width = rect.columns as f32 * size_info.cell_width();
+            let height = rect.lines as f32 * size_info.cell_height();
+            let alpha = config.window_opacity();
+            let background = RenderRect::new(x, y, width, height, background_color, alpha);
+            self.renderer.draw_rects(&size_info, &metrics, vec![background]);
+        }
+
+        let mut lines = RenderLines::new();
+        let mut graphics = RenderGraphics::new();
+        self.renderer.draw_cells(
+            &size_info,
+            &mut self.glyph_cache,
+            grid_cells.into_iter().map(|cell| {
+                lines.update(&cell);
+                graphics.update(&cell);
+                cell
+            }),
+        );
+        self.renderer.draw_graphics(&size_info, graphics);
+
+        let mut rects = lines.rects(&metrics, &size_info);
+        rects.extend(cursor.rects(&size_info, config.cursor.thickness()));
+        self.renderer.draw_rects(&size_info, &metrics, rects);
+    }
+
+    /// Create a thin line centered in the cells of a pane divider.
+    fn divider_rect(&self, divider: &PaneRect, color: Rgb) -> RenderRect {
+        let size_info = &self.size_info;
+        let thickness = self.glyph_cache.font_metrics().underline_thickness.round().max(1.);
+
+        let mut x = size_info.cell_width().mul_add(divider.column as f32, size_info.padding_x());
+        let mut y = size_info.cell_height().mul_add(divider.line as f32, size_info.padding_y());
+        let mut width = divider.columns as f32 * size_info.cell_width();
+        let mut height = divider.lines as f32 * size_info.cell_height();
+
+        if divider.columns == 1 {
+            x += ((width - thickness) / 2.).floor();
+            width = thickness;
+        } else {
+            y += ((height - thickness) / 2.).floor();
+            height = thickness;
+        }
+
+        RenderRect::new(x, y, width, height, color, 1.)
+    }
+
     /// Update to a new configuration.
     pub fn update_config(&mut self, config: &UiConfig) {
         self.damage_tracker.debug = config.debug.highlight_damage;
@@ -1010,6 +1212,7 @@ impl Display {
     pub fn update_highlighted_hints<T>(
         &mut self,
         term: &Term<T>,
+        size_info: &SizeInfo,
         config: &UiConfig,
         mouse: &Mouse,
         modifiers: ModifiersState,
@@ -1033,7 +1236,7 @@ impl Display {
         }
 
         // Find highlighted hint at mouse position.
-        let point = mouse.point(&self.size_info, term.grid().display_offset());
+        let point = mouse.point(size_info, term.grid().display_offset());
         let highlighted_hint = hint::highlighted_at(term, config, point, modifiers);
 
         // Update cursor shape.
@@ -1175,10 +1378,11 @@ impl Display {
     fn draw_hyperlink_preview(
         &mut self,
         config: &UiConfig,
+        pane: &PaneRect,
         cursor_point: Option<Point>,
         display_offset: usize,
     ) {
-        let num_cols = self.size_info.columns();
+        let num_cols = pane.columns;
         let uris: Vec<_> = self
             .highlighted_hint
             .iter()
@@ -1196,7 +1400,7 @@ impl Display {
 
         // Lines we shouldn't show preview on, because it'll obscure the highlighted hint.
         let mut protected_lines = Vec::with_capacity(max_protected_lines);
-        if self.size_info.screen_lines() > max_protected_lines {
+        if pane.lines > max_protected_lines {
             // Prefer to show preview even when it'll likely obscure the highlighted hint, when
             // there's no place left for it.
             protected_lines.push(self.hint_mouse_point.map(|point| point.line));
@@ -1204,8 +1408,8 @@ impl Display {
         }
 
         // Find the line in viewport we can draw preview on without obscuring protected lines.
-        let viewport_bottom = self.size_info.bottommost_line() - Line(display_offset as i32);
-        let viewport_top = viewport_bottom - (self.size_info.screen_lines() - 1);
+        let viewport_bottom = Line(pane.lines as i32 - 1) - display_offset;
+        let viewport_top = viewport_bottom - (pane.lines - 1);
         let uri_lines = (viewport_top.0..=viewport_bottom.0)
             .rev()
             .map(|line| Some(Line(line)))
@@ -1218,14 +1422,16 @@ impl Display {
                 }
             })
             .take(uris.len())
-            .flat_map(|line| term::point_to_viewport(display_offset, Point::new(line, Column(0))));
+            .flat_map(|line| term::point_to_viewport(display_offset, Point::new(line, Column(0))))
+            .map(|point| pane_point(point, pane));
 
         let fg = config.colors.footer_bar_foreground();
         let bg = config.colors.footer_bar_background();
         for (uri, point) in uris.into_iter().zip(uri_lines) {
             // Damage the uri preview.
             if self.collect_damage() {
-                let damage = LineDamageBounds::new(point.line, point.column.0, num_cols);
+                let damage =
+                    LineDamageBounds::new(point.line, point.column.0, point.column.0 + num_cols);
                 self.damage_tracker.frame().damage_line(damage);
 
                 // Damage the uri preview for the next frame as well.
@@ -1258,6 +1464,31 @@ impl Display {
         );
     }
 
+    /// Draw the titles of all tabs at the top of the window.
+    #[inline(never)]
+    fn draw_tab_bar(&mut self, config: &UiConfig, tab_bar: &TabBar) {
+        let colors = &config.colors;
+        let spans = tab::spans(tab_bar.titles.len(), self.size_info.columns());
+        for (i, (title, span)) in tab_bar.titles.iter().zip(spans).enumerate() {
+            let (fg, bg) = if i == tab_bar.active {
+                (colors.footer_bar_foreground(), colors.footer_bar_background())
+            } else {
+                (colors.primary.foreground, colors.pane_divider())
+            };
+
+            // Assure text length covers the entire tab.
+            let label = format!(" {}: {} ", i + 1, title);
+            let width = span.len();
+            let shortener =
+                StrShortener::new(&label, width, ShortenDirection::Right, Some(SHORTENER));
+            let text = format!("{:<1$}", shortener.collect::<String>(), width);
+
+            let point = Point::new(0, Column(span.start));
+            let glyph_cache = &mut self.glyph_cache;
+            self.renderer.draw_string(point, fg, bg, text.chars(), &self.size_info, glyph_cache);
+        }
+    }
+
     /// Draw render timer.
     #[inline(never)]
     fn draw_render_timer(&mut self, config: &UiConfig) {
@@ -1286,17 +1517,18 @@ impl Display {
     fn draw_line_indicator(
         &mut self,
         config: &UiConfig,
+        pane: &PaneRect,
         total_lines: usize,
         obstructed_column: Option<Column>,
         line: usize,
     ) {
-        let columns = self.size_info.columns();
         let text = format!("[{}/{}]", line, total_lines - 1);
-        let column = Column(self.size_info.columns().saturating_sub(text.len()));
-        let point = Point::new(0, column);
+        let column = Column(pane.columns.saturating_sub(text.len()));
+        let point = pane_point(Point::new(0, column), pane);
 
         if self.collect_damage() {
-            let damage = LineDamageBounds::new(point.line, point.column.0, columns - 1);
+            let damage =
+                LineDamageBounds::new(point.line, point.column.0, pane.column + pane.columns - 1);
             self.damage_tracker.frame().damage_line(damage);
             // Damage it on the next frame in case it goes away.
             self.damage_tracker.next_frame().damage_line(damage);
@@ -1368,7 +1600,6 @@ impl Drop for Display {
         self.make_current();
         unsafe {
             ManuallyDrop::drop(&mut self.renderer);
-            ManuallyDrop::drop(&mut self.context);
             ManuallyDrop::drop(&mut self.surface);
         }
     }
@@ -1454,6 +1685,61 @@ pub struct RendererUpdate {
     clear_font_cache: bool,
 }
 
+/// Target presenting the rendered frames.
+enum RenderSurface {
+    /// OpenGL surface with its context.
+    Gl { context: Replaceable<PossiblyCurrentContext>, surface: Surface<WindowSurface> },
+
+    /// Shared memory buffer for the software renderer.
+    Software { surface: softbuffer::Surface, _context: softbuffer::Context },
+}
+
+impl RenderSurface {
+    /// Resize the surface to the window dimensions.
+    fn resize(&mut self, size_info: &SizeInfo) {
+        let width = NonZeroU32::new(size_info.width() as u32).unwrap();
+        let height = NonZeroU32::new(size_info.height() as u32).unwrap();
+
+        match self {
+            Self::Gl { context, surface } => surface.resize(context, width, height),
+            Self::Software { surface, .. } => {
+                if let Err(err) = surface.resize(width, height) {
+                    debug!("error resizing software surface: {}", err);
+                }
+            },
+        }
+    }
+
+    /// Present the last frame drawn by `renderer`.
+    ///
+    /// The damage is only used by the OpenGL surface when EGL is available.
+    fn present(&mut self, renderer: &Renderer, _damage: Option<Vec<Rect>>) {
+        let res = match (self, renderer) {
+            #[cfg(not(any(target_os = "macos", windows)))]
+            (Self::Gl { context, surface: Surface::Egl(surface) }, _) if _damage.is_some() => {
+                match context.get() {
+                    PossiblyCurrentContext::Egl(context) => surface
+                        .swap_buffers_with_damage(context, &_damage.unwrap_or_default())
+                        .map_err(|err| err.to_string()),
+                    _ => unreachable!("EGL surface without EGL context"),
+                }
+            },
+            (Self::Gl { context, surface }, _) => {
+                surface.swap_buffers(context.get()).map_err(|err| err.to_string())
+            },
+            (Self::Software { surface, .. }, Renderer::Software(renderer)) => {
+                renderer::platform::present_software(surface, renderer.framebuffer())
+                    .map_err(|err| err.to_string())
+            },
+            (Self::Software { .. }, Renderer::Gl(_)) => unreachable!("software surface with GL"),
+        };
+
+        if let Err(err) = res {
+            debug!("error presenting frame: {}", err);
+        }
+    }
+}
+
 /// Struct for safe in-place replacement.
 ///
 /// This struct allows easily replacing struct fields that provide `self -> Self` methods in-place,
@@ -1543,6 +1829,12 @@ impl FrameTimer {
     }
 }
 
+/// Convert a point in a pane's viewport to the window's viewport.
+#[inline]
+fn pane_point(point: Point<usize>, rect: &PaneRect) -> Point<usize> {
+    Point::new(point.line + rect.line, point.column + rect.column)
+}
+
 /// Calculate the cell dimensions based on font metrics.
 ///
 /// This will return a tuple of the cell width and height.
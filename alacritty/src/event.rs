@@ -10,8 +10,8 @@ use std::fmt::Debug;
 use std::os::unix::io::RawFd;
 use std::path::PathBuf;
 use std::rc::Rc;
-use std::time::{Duration, Instant};
-use std::{env, f32, mem};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::{env, f32, fs, mem};
 
 use ahash::RandomState;
 use crossfont::Size as FontSize;
@@ -25,7 +25,7 @@ use winit::event::{
 use winit::event_loop::{
     ControlFlow, DeviceEvents, EventLoop, EventLoopProxy, EventLoopWindowTarget,
 };
-use winit::window::WindowId;
+use winit::window::{Theme, WindowId};
 
 use alacritty_terminal::event::{Event as TerminalEvent, EventListener, Notify};
 use alacritty_terminal::event_loop::Notifier;
@@ -35,23 +35,31 @@ use alacritty_terminal::selection::{Selection, SelectionType};
 use alacritty_terminal::term::search::{Match, RegexSearch};
 use alacritty_terminal::term::{self, ClipboardType, Term, TermMode};
 
+use crate::cli::{ColorSchemeChange, Options as CliOptions, WindowOptions};
 #[cfg(unix)]
 use crate::cli::{IpcConfig, ParsedOptions};
-use crate::cli::{Options as CliOptions, WindowOptions};
 use crate::clipboard::Clipboard;
+use crate::config::export::ExportFormat;
 use crate::config::ui_config::{HintAction, HintInternalAction};
-use crate::config::{self, UiConfig};
-#[cfg(not(windows))]
-use crate::daemon::foreground_process_path;
+use crate::config::{self, KeyPress, UiConfig};
 use crate::daemon::spawn_daemon;
+#[cfg(not(windows))]
+use crate::daemon::working_directory;
 use crate::display::color::Rgb;
 use crate::display::hint::HintMatch;
 use crate::display::window::Window;
 use crate::display::{Display, Preedit, SizeInfo};
-use crate::input::{self, ActionContext as _, FONT_SIZE_STEP};
+use crate::input::{self, ActionContext as _, ExportScope, FONT_SIZE_STEP};
+#[cfg(unix)]
+use crate::ipc::IpcQuery;
 use crate::logging::LOG_TARGET_CONFIG;
-use crate::message_bar::{Message, MessageBuffer};
+use crate::message_bar::{Message, MessageBuffer, MessageType};
+use crate::notification;
+use crate::pane::{PaneCommand, PaneId};
+use crate::recording::{self, Recording};
 use crate::scheduler::{Scheduler, TimerId, Topic};
+#[cfg(not(target_os = "macos"))]
+use crate::tab::TabCommand;
 use crate::window_context::WindowContext;
 
 /// Duration after the last user input until an unlimited search is performed.
@@ -66,19 +74,35 @@ const MAX_SEARCH_HISTORY_SIZE: usize = 255;
 /// Touch zoom speed.
 const TOUCH_ZOOM_FACTOR: f32 = 0.01;
 
+/// Message bar target of the pending key sequence hint.
+const KEY_SEQUENCE_TARGET: &str = "alacritty_key_sequence";
+
 /// Alacritty events.
 #[derive(Debug, Clone)]
 pub struct Event {
     /// Limit event to a specific window.
     window_id: Option<WindowId>,
 
+    /// Pane of the window which emitted the event.
+    pane_id: Option<PaneId>,
+
     /// Event payload.
     payload: EventType,
 }
 
 impl Event {
     pub fn new<I: Into<Option<WindowId>>>(payload: EventType, window_id: I) -> Self {
-        Self { window_id: window_id.into(), payload }
+        Self { window_id: window_id.into(), pane_id: None, payload }
+    }
+
+    /// Pane of the window which emitted the event.
+    pub fn pane_id(&self) -> Option<PaneId> {
+        self.pane_id
+    }
+
+    /// Event payload.
+    pub fn payload(&self) -> &EventType {
+        &self.payload
     }
 }
 
@@ -96,10 +120,17 @@ pub enum EventType {
     Message(Message),
     Scroll(Scroll),
     CreateWindow(WindowOptions),
+    Pane(PaneCommand),
+    #[cfg(not(target_os = "macos"))]
+    Tab(TabCommand),
     #[cfg(unix)]
     IpcConfig(IpcConfig),
+    #[cfg(unix)]
+    IpcQuery(IpcQuery),
+    ColorScheme(ColorSchemeChange),
     BlinkCursor,
     BlinkCursorTimeout,
+    KeySequenceTimeout,
     SearchNext,
     Frame,
 }
@@ -208,6 +239,7 @@ impl Default for InlineSearchState {
 
 pub struct ActionContext<'a, N, T> {
     pub notifier: &'a mut N,
+    pub recording: &'a mut Recording,
     pub terminal: &'a mut Term<T>,
     pub clipboard: &'a mut Clipboard,
     pub mouse: &'a mut Mouse,
@@ -215,6 +247,7 @@ pub struct ActionContext<'a, N, T> {
     pub modifiers: &'a mut Modifiers,
     pub display: &'a mut Display,
     pub message_buffer: &'a mut MessageBuffer,
+    pub key_sequence: &'a mut Vec<KeyPress>,
     pub config: &'a UiConfig,
     pub cursor_blink_timed_out: &'a mut bool,
     pub event_loop: &'a EventLoopWindowTarget<Event>,
@@ -224,6 +257,7 @@ pub struct ActionContext<'a, N, T> {
     pub inline_search_state: &'a mut InlineSearchState,
     pub dirty: &'a mut bool,
     pub occluded: &'a mut bool,
+    pub size_info: SizeInfo,
     pub preserve_title: bool,
     #[cfg(not(windows))]
     pub master_fd: RawFd,
@@ -245,6 +279,11 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
 
     #[inline]
     fn size_info(&self) -> SizeInfo {
+        self.size_info
+    }
+
+    #[inline]
+    fn window_size_info(&self) -> SizeInfo {
         self.display.size_info
     }
 
@@ -419,6 +458,14 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
                 continue;
             }
 
+            // New instances shouldn't overwrite the recording.
+            if arg == "--record" {
+                let _ = env_args.next();
+                continue;
+            } else if arg.starts_with("--record=") {
+                continue;
+            }
+
             args.push(arg);
         }
 
@@ -428,9 +475,8 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
     #[cfg(not(windows))]
     fn create_new_window(&mut self, #[cfg(target_os = "macos")] tabbing_id: Option<String>) {
         let mut options = WindowOptions::default();
-        if let Ok(working_directory) = foreground_process_path(self.master_fd, self.shell_pid) {
-            options.terminal_options.working_directory = Some(working_directory);
-        }
+        options.terminal_options.working_directory =
+            working_directory(self.terminal(), self.master_fd, self.shell_pid);
 
         #[cfg(target_os = "macos")]
         {
@@ -447,13 +493,99 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
             .send_event(Event::new(EventType::CreateWindow(WindowOptions::default()), None));
     }
 
+    fn pane_command(&mut self, command: PaneCommand) {
+        let event = Event::new(EventType::Pane(command), self.display.window.id());
+        let _ = self.event_proxy.send_event(event);
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    fn tab_command(&mut self, command: TabCommand) {
+        let event = Event::new(EventType::Tab(command), self.display.window.id());
+        let _ = self.event_proxy.send_event(event);
+    }
+
+    fn change_color_scheme(&mut self, change: ColorSchemeChange) {
+        let event = Event::new(EventType::ColorScheme(change), self.display.window.id());
+        let _ = self.event_proxy.send_event(event);
+    }
+
+    fn close_window(&mut self) {
+        let event = Event::new(TerminalEvent::Exit.into(), self.display.window.id());
+        let _ = self.event_proxy.send_event(event);
+    }
+
+    fn export(&mut self, scope: ExportScope) {
+        if scope == ExportScope::Selection && self.selection_is_empty() {
+            return;
+        }
+
+        let bounds = match scope {
+            ExportScope::Selection => None,
+            ExportScope::Screen => {
+                let start = -(self.terminal.grid().display_offset() as i32);
+                Some((Line(start), Line(start + self.terminal.screen_lines() as i32 - 1)))
+            },
+            ExportScope::History => {
+                Some((self.terminal.topmost_line(), self.terminal.bottommost_line()))
+            },
+        };
+        let bounds = bounds.map(|(start, end)| {
+            (Point::new(start, Column(0)), Point::new(end, self.terminal.last_column()))
+        });
+
+        match self.config.export.format {
+            ExportFormat::Html => {
+                // Combine the colors changed by escape sequences with the configured palette.
+                let colors = std::array::from_fn(|index| {
+                    self.terminal.colors()[index].unwrap_or(self.display.colors[index].0)
+                });
+
+                let html = match bounds {
+                    Some((start, end)) => self.terminal.bounds_to_html(start, end, &colors),
+                    None => self.terminal.selection_to_html(&colors).unwrap_or_default(),
+                };
+
+                // The clipboard only supports plain text, so HTML is written to a file instead.
+                let path = export_path();
+                match fs::write(&path, html) {
+                    Ok(()) => info!("Exported terminal content to {:?}", path),
+                    Err(err) => error!("Unable to export terminal content to {:?}: {}", path, err),
+                }
+            },
+            ExportFormat::Ansi => {
+                let text = match bounds {
+                    Some((start, end)) => self.terminal.bounds_to_ansi(start, end),
+                    None => self.terminal.selection_to_ansi().unwrap_or_default(),
+                };
+                self.clipboard.store(ClipboardType::Clipboard, text);
+            },
+        }
+    }
+
+    fn toggle_recording(&mut self) {
+        if let Some(path) = self.recording.stop() {
+            info!("Stopped recording to {:?}", path);
+            return;
+        }
+
+        let path = recording::new_path(&self.config.recording);
+        let size = self.size_info.into();
+        match self.recording.start(path.clone(), size, &self.config.recording) {
+            Ok(()) => info!("Started recording to {:?}", path),
+            Err(err) => error!("Unable to record session to {:?}: {}", path, err),
+        }
+    }
+
     fn spawn_daemon<I, S>(&self, program: &str, args: I)
     where
         I: IntoIterator<Item = S> + Debug + Copy,
         S: AsRef<OsStr>,
     {
         #[cfg(not(windows))]
-        let result = spawn_daemon(program, args, self.master_fd, self.shell_pid);
+        let result = {
+            let cwd = working_directory(self.terminal(), self.master_fd, self.shell_pid);
+            spawn_daemon(program, args, cwd.as_deref())
+        };
         #[cfg(windows)]
         let result = spawn_daemon(program, args);
 
@@ -890,6 +1022,37 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
         self.inline_search_state
     }
 
+    fn key_sequence(&self) -> &[KeyPress] {
+        self.key_sequence
+    }
+
+    /// Update the pending key sequence and its hint in the message bar.
+    fn set_key_sequence(&mut self, keys: Vec<KeyPress>, hint: Option<String>) {
+        let window_id = self.display.window.id();
+        self.scheduler.unschedule(TimerId::new(Topic::KeySequence, window_id));
+
+        // Resize for the message bar if a hint is added or removed.
+        if !self.key_sequence.is_empty() || hint.is_some() {
+            self.message_buffer.remove_target(KEY_SEQUENCE_TARGET);
+            self.display.pending_update.dirty = true;
+        }
+
+        if let Some(hint) = hint {
+            let mut message = Message::new(hint, MessageType::Info);
+            message.set_target(KEY_SEQUENCE_TARGET.into());
+            self.message_buffer.push_front(message);
+        }
+
+        let timeout = self.config.key_sequence_timeout();
+        if !keys.is_empty() && timeout != Duration::ZERO {
+            let event = Event::new(EventType::KeySequenceTimeout, window_id);
+            let timer_id = TimerId::new(Topic::KeySequence, window_id);
+            self.scheduler.schedule(event, timeout, false, timer_id);
+        }
+
+        *self.key_sequence = keys;
+    }
+
     /// Start vi mode inline search.
     fn start_inline_search(&mut self, direction: Direction, stop_short: bool) {
         self.inline_search_state.stop_short = stop_short;
@@ -932,6 +1095,19 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
 }
 
 impl<'a, N: Notify + 'a, T: EventListener> ActionContext<'a, N, T> {
+    /// Update the window title from the terminal's title and working directory.
+    fn update_title(&mut self) {
+        let window_config = &self.config.window;
+        if self.preserve_title
+            || (!window_config.dynamic_title && window_config.title_template.is_none())
+        {
+            return;
+        }
+
+        let title = window_config.title(self.terminal.title(), self.terminal.working_directory());
+        self.display.window.set_title(title);
+    }
+
     fn update_search(&mut self) {
         let regex = match self.search_state.regex() {
             Some(regex) => regex,
@@ -1138,6 +1314,14 @@ impl<'a, N: Notify + 'a, T: EventListener> ActionContext<'a, N, T> {
     }
 }
 
+/// Path for a new HTML export in the home directory.
+fn export_path() -> PathBuf {
+    let directory = home::home_dir().unwrap_or_else(|| PathBuf::from("."));
+
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |t| t.as_secs());
+    directory.join(format!("alacritty-{timestamp}.html"))
+}
+
 /// Identified purpose of the touch input.
 #[derive(Debug)]
 pub enum TouchPurpose {
@@ -1300,23 +1484,16 @@ impl input::Processor<EventProxy, ActionContext<'_, Notifier, EventProxy>> {
                     self.ctx.display.cursor_hidden = false;
                     *self.ctx.dirty = true;
                 },
+                EventType::KeySequenceTimeout => self.ctx.set_key_sequence(Vec::new(), None),
                 // Add message only if it's not already queued.
                 EventType::Message(message) if !self.ctx.message_buffer.is_queued(&message) => {
                     self.ctx.message_buffer.push(message);
                     self.ctx.display.pending_update.dirty = true;
                 },
                 EventType::Terminal(event) => match event {
-                    TerminalEvent::Title(title) => {
-                        if !self.ctx.preserve_title && self.ctx.config.window.dynamic_title {
-                            self.ctx.window().set_title(title);
-                        }
-                    },
-                    TerminalEvent::ResetTitle => {
-                        let window_config = &self.ctx.config.window;
-                        if window_config.dynamic_title {
-                            self.ctx.display.window.set_title(window_config.identity.title.clone());
-                        }
-                    },
+                    TerminalEvent::Title(_)
+                    | TerminalEvent::ResetTitle
+                    | TerminalEvent::WorkingDirectory(_) => self.ctx.update_title(),
                     TerminalEvent::Bell => {
                         // Set window urgency hint when window is not focused.
                         let focused = self.ctx.terminal.is_focused;
@@ -1332,6 +1509,26 @@ impl input::Processor<EventProxy, ActionContext<'_, Notifier, EventProxy>> {
                             self.ctx.spawn_daemon(bell_command.program(), bell_command.args());
                         }
                     },
+                    TerminalEvent::Notification { title, body, urgency } => {
+                        let config = &self.ctx.config.notifications;
+                        if config.unfocused_only && self.ctx.terminal.is_focused {
+                            debug!("Ignoring notification while focused: {}", title);
+                        } else {
+                            // Fallback to the window title for notifications without a title.
+                            let title = if title.is_empty() {
+                                self.ctx.display.window.title().to_owned()
+                            } else {
+                                title
+                            };
+
+                            match notification::command(config, &title, &body, urgency) {
+                                Some(program) => {
+                                    self.ctx.spawn_daemon(program.program(), program.args())
+                                },
+                                None => debug!("Desktop notifications are not supported"),
+                            }
+                        }
+                    },
                     TerminalEvent::ClipboardStore(clipboard_type, content) => {
                         if self.ctx.terminal.is_focused {
                             self.ctx.clipboard.store(clipboard_type, content);
@@ -1359,15 +1556,19 @@ impl input::Processor<EventProxy, ActionContext<'_, Notifier, EventProxy>> {
                     TerminalEvent::Exit | TerminalEvent::ChildExit(_) | TerminalEvent::Wakeup => (),
                 },
                 #[cfg(unix)]
-                EventType::IpcConfig(_) => (),
+                EventType::IpcConfig(_) | EventType::IpcQuery(_) => (),
                 EventType::Message(_)
                 | EventType::ConfigReload(_)
                 | EventType::CreateWindow(_)
+                | EventType::Pane(_)
+                | EventType::ColorScheme(_)
                 | EventType::Frame => (),
+                #[cfg(not(target_os = "macos"))]
+                EventType::Tab(_) => (),
             },
             WinitEvent::WindowEvent { event, .. } => {
                 match event {
-                    WindowEvent::CloseRequested => self.ctx.terminal.exit(),
+                    WindowEvent::CloseRequested => self.ctx.close_window(),
                     WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
                         let old_scale_factor =
                             mem::replace(&mut self.ctx.window().scale_factor, scale_factor);
@@ -1427,6 +1628,13 @@ impl input::Processor<EventProxy, ActionContext<'_, Notifier, EventProxy>> {
                     WindowEvent::Occluded(occluded) => {
                         *self.ctx.occluded = occluded;
                     },
+                    WindowEvent::ThemeChanged(theme) => {
+                        let change = match theme {
+                            Theme::Light => ColorSchemeChange::Light,
+                            Theme::Dark => ColorSchemeChange::Dark,
+                        };
+                        self.ctx.change_color_scheme(change);
+                    },
                     WindowEvent::DroppedFile(path) => {
                         let path: String = path.to_string_lossy().into();
                         self.ctx.paste(&(path + " "), true);
@@ -1477,7 +1685,6 @@ impl input::Processor<EventProxy, ActionContext<'_, Notifier, EventProxy>> {
                     | WindowEvent::AxisMotion { .. }
                     | WindowEvent::HoveredFileCancelled
                     | WindowEvent::Destroyed
-                    | WindowEvent::ThemeChanged(_)
                     | WindowEvent::HoveredFile(_)
                     | WindowEvent::RedrawRequested
                     | WindowEvent::Moved(_) => (),
@@ -1536,10 +1743,14 @@ impl Processor {
         proxy: EventLoopProxy<Event>,
         options: WindowOptions,
     ) -> Result<(), Box<dyn Error>> {
+        // Override config with the session options of the initial window.
+        let mut config_overrides = options.config_overrides();
+        let config = config_overrides.override_config_rc(self.config.clone());
+
         let window_context =
-            WindowContext::initial(event_loop, proxy, self.config.clone(), options)?;
+            WindowContext::initial(event_loop, proxy, config, options, config_overrides)?;
 
-        self.gl_display = Some(window_context.display.gl_context().display());
+        self.gl_display = window_context.display.gl_context().map(|context| context.display());
         self.windows.insert(window_context.id(), window_context);
 
         Ok(())
@@ -1575,11 +1786,11 @@ impl Processor {
     pub fn run(
         &mut self,
         event_loop: EventLoop<Event>,
-        initial_window_options: WindowOptions,
+        initial_windows: Vec<WindowOptions>,
     ) -> Result<(), Box<dyn Error>> {
         let proxy = event_loop.create_proxy();
         let mut scheduler = Scheduler::new(proxy.clone());
-        let mut initial_window_options = Some(initial_window_options);
+        let mut initial_windows = Some(initial_windows);
 
         // Disable all device events, since we don't care about them.
         event_loop.listen_device_events(DeviceEvents::Never);
@@ -1605,11 +1816,12 @@ impl Processor {
                     // Creating window inside event loop is required for platforms like macOS to
                     // properly initialize state, like tab management. Otherwise the first
                     // window won't handle tabs.
-                    let initial_window_options = match initial_window_options.take() {
-                        Some(initial_window_options) => initial_window_options,
+                    let mut initial_windows = match initial_windows.take() {
+                        Some(initial_windows) => initial_windows.into_iter(),
                         None => return,
                     };
 
+                    let initial_window_options = initial_windows.next().unwrap_or_default();
                     if let Err(err) = self.create_initial_window(
                         event_loop,
                         proxy.clone(),
@@ -1620,6 +1832,12 @@ impl Processor {
                         return;
                     }
 
+                    // Open the remaining windows of the session.
+                    for options in initial_windows {
+                        let event = Event::new(EventType::CreateWindow(options), None);
+                        let _ = proxy.send_event(event);
+                    }
+
                     info!("Initialisation complete");
                 },
                 WinitEvent::LoopExiting => {
@@ -1642,9 +1860,14 @@ impl Processor {
                 // NOTE: This event bypasses batching to minimize input latency.
                 WinitEvent::UserEvent(Event {
                     window_id: Some(window_id),
+                    pane_id,
                     payload: EventType::Terminal(TerminalEvent::Wakeup),
                 }) => {
                     if let Some(window_context) = self.windows.get_mut(&window_id) {
+                        if let Some(pane_id) = pane_id {
+                            window_context.run_triggers(pane_id);
+                        }
+
                         window_context.dirty = true;
                         if window_context.display.window.has_frame {
                             window_context.display.window.request_redraw();
@@ -1655,6 +1878,7 @@ impl Processor {
                 WinitEvent::UserEvent(Event {
                     window_id: Some(window_id),
                     payload: EventType::Frame,
+                    ..
                 }) => {
                     if let Some(window_context) = self.windows.get_mut(&window_id) {
                         window_context.display.window.has_frame = true;
@@ -1666,8 +1890,17 @@ impl Processor {
                 // Check for shutdown.
                 WinitEvent::UserEvent(Event {
                     window_id: Some(window_id),
+                    pane_id,
                     payload: EventType::Terminal(TerminalEvent::Exit),
                 }) => {
+                    // Only close the pane if the window has others left.
+                    let window_context = self.windows.get_mut(&window_id);
+                    if let Some((window_context, pane_id)) = window_context.zip(pane_id) {
+                        if window_context.close_pane(pane_id) {
+                            return;
+                        }
+                    }
+
                     // Remove the closed terminal.
                     let window_context = match self.windows.remove(&window_id) {
                         Some(window_context) => window_context,
@@ -1748,6 +1981,7 @@ impl Processor {
                 WinitEvent::UserEvent(Event {
                     payload: EventType::IpcConfig(ipc_config),
                     window_id,
+                    ..
                 }) => {
                     // Try and parse options as toml.
                     let mut options = ParsedOptions::from_options(&ipc_config.options);
@@ -1774,6 +2008,25 @@ impl Processor {
                         }
                     }
                 },
+                // Answer IPC queries.
+                #[cfg(unix)]
+                WinitEvent::UserEvent(Event { payload: EventType::IpcQuery(query), .. }) => {
+                    query.answer(self.windows.values_mut());
+                },
+                // Switch the color scheme of each window with matching ID.
+                WinitEvent::UserEvent(Event {
+                    payload: EventType::ColorScheme(change),
+                    window_id,
+                    ..
+                }) => {
+                    for (_, window_context) in self
+                        .windows
+                        .iter_mut()
+                        .filter(|(id, _)| window_id.is_none() || window_id == Some(**id))
+                    {
+                        window_context.set_color_scheme(self.config.clone(), change);
+                    }
+                },
                 // Create a new terminal window.
                 WinitEvent::UserEvent(Event {
                     payload: EventType::CreateWindow(options), ..
@@ -1851,21 +2104,23 @@ impl Processor {
 pub struct EventProxy {
     proxy: EventLoopProxy<Event>,
     window_id: WindowId,
+    pane_id: PaneId,
 }
 
 impl EventProxy {
-    pub fn new(proxy: EventLoopProxy<Event>, window_id: WindowId) -> Self {
-        Self { proxy, window_id }
+    pub fn new(proxy: EventLoopProxy<Event>, window_id: WindowId, pane_id: PaneId) -> Self {
+        Self { proxy, window_id, pane_id }
     }
 
     /// Send an event to the event loop.
     pub fn send_event(&self, event: EventType) {
-        let _ = self.proxy.send_event(Event::new(event, self.window_id));
+        let (window_id, pane_id) = (Some(self.window_id), Some(self.pane_id));
+        let _ = self.proxy.send_event(Event { window_id, pane_id, payload: event });
     }
 }
 
 impl EventListener for EventProxy {
     fn send_event(&self, event: TerminalEvent) {
-        let _ = self.proxy.send_event(Event::new(event.into(), self.window_id));
+        EventProxy::send_event(self, event.into());
     }
 }
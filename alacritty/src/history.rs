@@ -0,0 +1,155 @@
+//! Persistence of the scrollback history across windows.
+
+use std::error::Error;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+use std::{mem, process};
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use log::error;
+
+use alacritty_terminal::term::persist::{PackedGrid, SavedGrid};
+use alacritty_terminal::thread;
+
+/// Header identifying history files, including the format version.
+const MAGIC: &[u8; 4] = b"ACH\x03";
+
+/// History writes running in the background.
+static PENDING_SAVES: Mutex<Vec<JoinHandle<()>>> = Mutex::new(Vec::new());
+
+/// Path of the history shared by all windows with the same key.
+///
+/// The key is either an explicit history ID, or the working directory of the window.
+pub fn path(key: &str) -> Option<PathBuf> {
+    let file_name = format!("{:016x}", fnv1a(key.as_bytes()));
+    history_dir().map(|dir| dir.join(file_name))
+}
+
+/// Load the history stored at `path`, removing it from disk.
+///
+/// Returns `None` if no history was stored.
+pub fn take(path: &Path) -> Result<Option<SavedGrid>, Box<dyn Error>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(_) if !path.exists() => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    // Only restore the history once, even if it is invalid.
+    let _ = fs::remove_file(path);
+
+    let mut reader = BufReader::new(file);
+    let mut magic = [0; MAGIC.len()];
+    reader.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err("unsupported history format".into());
+    }
+
+    let packed: PackedGrid = bincode::deserialize_from(DeflateDecoder::new(reader))?;
+    let saved = packed.unpack().ok_or("invalid history dimensions")?;
+    Ok(Some(saved))
+}
+
+/// Store the history at `path` on a background thread.
+///
+/// Use [`wait_for_saves`] to make sure the history was written before exiting.
+pub fn save_in_background(path: PathBuf, saved: SavedGrid) {
+    let handle = thread::spawn_named("history writer", move || {
+        if let Err(err) = save(&path, &saved) {
+            error!("Unable to persist scrollback history: {}", err);
+        }
+    });
+
+    let mut pending = PENDING_SAVES.lock().unwrap();
+    pending.retain(|handle| !handle.is_finished());
+    pending.push(handle);
+}
+
+/// Wait for all history writes running in the background.
+pub fn wait_for_saves() {
+    let pending = mem::take(&mut *PENDING_SAVES.lock().unwrap());
+    for handle in pending {
+        let _ = handle.join();
+    }
+}
+
+/// Store the history at `path`, replacing any existing history.
+pub fn save(path: &Path, saved: &SavedGrid) -> Result<(), Box<dyn Error>> {
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    // Write to a temporary file first, so concurrently closing windows can't corrupt the history.
+    let count = COUNT.fetch_add(1, Ordering::Relaxed);
+    let tmp_path = path.with_extension(format!("{}-{count}.tmp", process::id()));
+
+    // The history can contain sensitive output, so it's only readable by the user.
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut writer = BufWriter::new(options.open(&tmp_path)?);
+    writer.write_all(MAGIC)?;
+
+    let mut encoder = DeflateEncoder::new(writer, Compression::fast());
+    bincode::serialize_into(&mut encoder, &saved.pack())?;
+    encoder.finish()?.flush()?;
+
+    fs::rename(&tmp_path, path)?;
+
+    Ok(())
+}
+
+/// Directory for persisted history files.
+#[cfg(not(windows))]
+fn history_dir() -> Option<PathBuf> {
+    let xdg = xdg::BaseDirectories::with_prefix("alacritty").ok()?;
+    xdg.create_state_directory("history").ok()
+}
+
+/// Directory for persisted history files.
+#[cfg(windows)]
+fn history_dir() -> Option<PathBuf> {
+    let dir = dirs::data_local_dir()?.join("alacritty").join("history");
+    fs::create_dir_all(&dir).ok()?;
+    Some(dir)
+}
+
+/// FNV-1a hash, which is stable across versions and platforms.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alacritty_terminal::event::VoidListener;
+    use alacritty_terminal::term::test::TermSize;
+    use alacritty_terminal::term::{Config, Term};
+    use alacritty_terminal::vte::ansi::Handler;
+
+    #[test]
+    fn roundtrip() {
+        let mut term = Term::new(Config::default(), &TermSize::new(10, 3), VoidListener);
+        for c in "history".chars() {
+            term.input(c);
+        }
+        let saved = term.save_grid();
+
+        let path = std::env::temp_dir().join(format!("alacritty-history-{}", process::id()));
+        save(&path, &saved).unwrap();
+
+        assert_eq!(take(&path).unwrap(), Some(saved));
+        assert!(!path.exists());
+        assert_eq!(take(&path).unwrap(), None);
+    }
+}
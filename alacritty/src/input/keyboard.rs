@@ -12,7 +12,7 @@ use alacritty_terminal::event::EventListener;
 use alacritty_terminal::term::TermMode;
 use winit::platform::modifier_supplement::KeyEventExtModifierSupplement;
 
-use crate::config::{Action, BindingKey, BindingMode};
+use crate::config::{Action, BindingKey, BindingMode, KeyBinding, KeyPress};
 use crate::event::TYPING_SEARCH_DELAY;
 use crate::input::{ActionContext, Execute, Processor};
 use crate::scheduler::{TimerId, Topic};
@@ -82,6 +82,7 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
         let mods = if self.alt_send_esc(&key, text) { mods } else { mods & !ModifiersState::ALT };
 
         let build_key_sequence = Self::should_build_sequence(&key, text, mode, mods);
+        let is_enter = key.logical_key == Key::Named(NamedKey::Enter);
 
         let bytes = if build_key_sequence {
             build_sequence(key, mods, mode)
@@ -97,6 +98,11 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
 
         // Write only if we have something to write.
         if !bytes.is_empty() {
+            // Track submitted commands, to allow copying their output.
+            if is_enter {
+                self.ctx.terminal_mut().mark_command_submitted();
+            }
+
             self.ctx.on_terminal_input_start();
             self.ctx.write_to_pty(bytes);
         }
@@ -187,17 +193,26 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
             key.logical_key.clone()
         };
 
+        let press = KeyPress {
+            key: BindingKey::Keycode { key: logical_key, location: key.location.into() },
+            scancode: BindingKey::Scancode(key.physical_key),
+            mods,
+        };
+
+        // Key sequences take precedence over regular bindings.
+        if self.process_key_sequence(&press, mode) {
+            return true;
+        }
+
         for i in 0..self.ctx.config().key_bindings().len() {
             let binding = &self.ctx.config().key_bindings()[i];
 
-            let key = match (&binding.trigger, &logical_key) {
-                (BindingKey::Scancode(_), _) => BindingKey::Scancode(key.physical_key),
-                (_, code) => {
-                    BindingKey::Keycode { key: code.clone(), location: key.location.into() }
-                },
-            };
+            // Bindings with a sequence are only triggered by `process_key_sequence`.
+            if !binding.sequence.is_empty() {
+                continue;
+            }
 
-            if binding.is_triggered_by(mode, mods, &key) {
+            if binding.is_triggered_by(mode, mods, press.binding_key(&binding.trigger)) {
                 // Pass through the key if any of the bindings has the `ReceiveChar` action.
                 *suppress_chars.get_or_insert(true) &= binding.action != Action::ReceiveChar;
 
@@ -209,6 +224,66 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
         suppress_chars.unwrap_or(false)
     }
 
+    /// Advance the pending key sequence.
+    ///
+    /// Returns `true` if the key press was consumed by the sequence.
+    pub(super) fn process_key_sequence(&mut self, press: &KeyPress, mode: BindingMode) -> bool {
+        let pending = self.ctx.key_sequence().len();
+
+        // Allow pressing modifiers for the next key of the sequence.
+        if pending > 0 && press.is_modifier() {
+            return true;
+        }
+
+        let mut keys = self.ctx.key_sequence().to_vec();
+        keys.push(press.clone());
+
+        let config = self.ctx.config();
+        let leader = config.leader_key();
+
+        // Find all bindings completed by this key press.
+        let actions: Vec<Action> = config
+            .key_bindings()
+            .iter()
+            .filter(|binding| pending > 0 && binding.sequence.len() == pending)
+            .filter(|binding| binding.sequence_starts_with(&keys[..pending], leader))
+            .filter(|binding| {
+                binding.is_triggered_by(mode, press.mods, press.binding_key(&binding.trigger))
+            })
+            .map(|binding| binding.action.clone())
+            .collect();
+
+        if !actions.is_empty() {
+            self.ctx.set_key_sequence(Vec::new(), None);
+            for action in actions {
+                action.execute(&mut self.ctx);
+            }
+            return true;
+        }
+
+        // Find all bindings which still require further keys.
+        let candidates: Vec<&KeyBinding> = config
+            .key_bindings()
+            .iter()
+            .filter(|binding| binding.sequence.len() > pending && binding.is_active(mode))
+            .filter(|binding| binding.sequence_starts_with(&keys, leader))
+            .collect();
+
+        if !candidates.is_empty() {
+            let hint = sequence_hint(&candidates, keys.len());
+            self.ctx.set_key_sequence(keys, Some(hint));
+            return true;
+        }
+
+        // Cancel the sequence when the key doesn't continue it.
+        if pending > 0 {
+            self.ctx.set_key_sequence(Vec::new(), None);
+            return true;
+        }
+
+        false
+    }
+
     /// Handle key release.
     fn key_release(&mut self, key: KeyEvent, mode: TermMode, mods: ModifiersState) {
         if !mode.contains(TermMode::REPORT_EVENT_TYPES)
@@ -254,6 +329,32 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
     }
 }
 
+/// Describe the next keys of all bindings continuing a key sequence.
+///
+/// The `position` is the number of keys which have already been pressed.
+fn sequence_hint(bindings: &[&KeyBinding], position: usize) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for binding in bindings {
+        let description = if binding.sequence.len() > position {
+            String::from("…")
+        } else {
+            match &binding.action {
+                Action::Esc(chars) => format!("{chars:?}"),
+                Action::Command(program) => program.program().into(),
+                Action::Hint(_) => String::from("Hint"),
+                action => action.to_string(),
+            }
+        };
+
+        let line = format!("{}  {description}", binding.sequence_key_label(position));
+        if !lines.contains(&line) {
+            lines.push(line);
+        }
+    }
+
+    lines.join("\n")
+}
+
 /// Build a key's keyboard escape sequence based on the given `key`, `mods`, and `mode`.
 ///
 /// The key sequences for `APP_KEYPAD` and alike are handled inside the bindings.
@@ -28,15 +28,16 @@ use winit::window::CursorIcon;
 
 use alacritty_terminal::event::EventListener;
 use alacritty_terminal::grid::{Dimensions, Scroll};
-use alacritty_terminal::index::{Boundary, Column, Direction, Point, Side};
+use alacritty_terminal::index::{Boundary, Column, Direction, Line, Point, Side};
 use alacritty_terminal::selection::SelectionType;
 use alacritty_terminal::term::search::Match;
 use alacritty_terminal::term::{ClipboardType, Term, TermMode};
 use alacritty_terminal::vi_mode::ViMotion;
 use alacritty_terminal::vte::ansi::{ClearMode, Handler};
 
+use crate::cli::ColorSchemeChange;
 use crate::clipboard::Clipboard;
-use crate::config::{Action, BindingMode, MouseAction, SearchAction, UiConfig, ViAction};
+use crate::config::{Action, BindingMode, KeyPress, MouseAction, SearchAction, UiConfig, ViAction};
 use crate::display::hint::HintMatch;
 use crate::display::window::Window;
 use crate::display::{Display, SizeInfo};
@@ -44,7 +45,11 @@ use crate::event::{
     ClickState, Event, EventType, InlineSearchState, Mouse, TouchPurpose, TouchZoom,
 };
 use crate::message_bar::{self, Message};
+use crate::pane::{PaneCommand, PaneDirection, SplitDirection};
 use crate::scheduler::{Scheduler, TimerId, Topic};
+use crate::screenshot::{self, ScreenshotRequest};
+#[cfg(not(target_os = "macos"))]
+use crate::tab::TabCommand;
 
 pub mod keyboard;
 
@@ -75,10 +80,19 @@ pub struct Processor<T: EventListener, A: ActionContext<T>> {
     _phantom: PhantomData<T>,
 }
 
+/// Terminal content included by the export actions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ExportScope {
+    Selection,
+    Screen,
+    History,
+}
+
 pub trait ActionContext<T: EventListener> {
     fn write_to_pty<B: Into<Cow<'static, [u8]>>>(&self, _data: B) {}
     fn mark_dirty(&mut self) {}
     fn size_info(&self) -> SizeInfo;
+    fn window_size_info(&self) -> SizeInfo;
     fn copy_selection(&mut self, _ty: ClipboardType) {}
     fn start_selection(&mut self, _ty: SelectionType, _point: Point, _side: Side) {}
     fn toggle_selection(&mut self, _ty: SelectionType, _point: Point, _side: Side) {}
@@ -99,6 +113,13 @@ pub trait ActionContext<T: EventListener> {
     fn create_new_window(&mut self, _tabbing_id: Option<String>) {}
     #[cfg(not(target_os = "macos"))]
     fn create_new_window(&mut self) {}
+    fn toggle_recording(&mut self) {}
+    fn export(&mut self, _scope: ExportScope) {}
+    fn pane_command(&mut self, _command: PaneCommand) {}
+    #[cfg(not(target_os = "macos"))]
+    fn tab_command(&mut self, _command: TabCommand) {}
+    fn change_color_scheme(&mut self, _change: ColorSchemeChange) {}
+    fn close_window(&mut self) {}
     fn change_font_size(&mut self, _delta: f32) {}
     fn reset_font_size(&mut self) {}
     fn pop_message(&mut self) {}
@@ -127,6 +148,10 @@ pub trait ActionContext<T: EventListener> {
     fn inline_search_next(&mut self) {}
     fn inline_search_previous(&mut self) {}
     fn hint_input(&mut self, _character: char) {}
+    fn key_sequence(&self) -> &[KeyPress] {
+        &[]
+    }
+    fn set_key_sequence(&mut self, _keys: Vec<KeyPress>, _hint: Option<String>) {}
     fn trigger_hint(&mut self, _hint: &HintMatch) {}
     fn expand_selection(&mut self) {}
     fn on_terminal_input_start(&mut self) {}
@@ -301,7 +326,26 @@ impl<T: EventListener> Execute<T> for Action {
             Action::Copy => ctx.copy_selection(ClipboardType::Clipboard),
             #[cfg(not(any(target_os = "macos", windows)))]
             Action::CopySelection => ctx.copy_selection(ClipboardType::Selection),
+            Action::CopyLastCommandOutput => {
+                // Keep the current selection when the last command has no output.
+                let term = ctx.terminal_mut();
+                let output = term.last_command_output().filter(|selection| !selection.is_empty());
+                if let Some(selection) = output {
+                    term.selection = Some(selection);
+                    ctx.copy_selection(ClipboardType::Clipboard);
+                    ctx.mark_dirty();
+                }
+            },
             Action::ClearSelection => ctx.clear_selection(),
+            Action::ToggleRecording => ctx.toggle_recording(),
+            Action::ToggleColorScheme => ctx.change_color_scheme(ColorSchemeChange::Toggle),
+            Action::ExportSelection => ctx.export(ExportScope::Selection),
+            Action::ExportScreen => ctx.export(ExportScope::Screen),
+            Action::ExportHistory => ctx.export(ExportScope::History),
+            Action::Screenshot => {
+                ctx.display().screenshot(ScreenshotRequest::new(screenshot::new_path()));
+                ctx.mark_dirty();
+            },
             Action::Paste => {
                 let text = ctx.clipboard_mut().load(ClipboardType::Clipboard);
                 ctx.paste(&text, true);
@@ -321,7 +365,18 @@ impl<T: EventListener> Execute<T> for Action {
             #[cfg(not(target_os = "macos"))]
             Action::Hide => ctx.window().set_visible(false),
             Action::Minimize => ctx.window().set_minimized(true),
-            Action::Quit => ctx.terminal_mut().exit(),
+            Action::Quit => ctx.close_window(),
+            Action::SplitRight => ctx.pane_command(PaneCommand::Split(SplitDirection::Right)),
+            Action::SplitDown => ctx.pane_command(PaneCommand::Split(SplitDirection::Down)),
+            Action::ClosePane => ctx.pane_command(PaneCommand::Close),
+            Action::FocusPaneLeft => ctx.pane_command(PaneCommand::Focus(PaneDirection::Left)),
+            Action::FocusPaneRight => ctx.pane_command(PaneCommand::Focus(PaneDirection::Right)),
+            Action::FocusPaneUp => ctx.pane_command(PaneCommand::Focus(PaneDirection::Up)),
+            Action::FocusPaneDown => ctx.pane_command(PaneCommand::Focus(PaneDirection::Down)),
+            Action::ResizePaneLeft => ctx.pane_command(PaneCommand::Resize(PaneDirection::Left)),
+            Action::ResizePaneRight => ctx.pane_command(PaneCommand::Resize(PaneDirection::Right)),
+            Action::ResizePaneUp => ctx.pane_command(PaneCommand::Resize(PaneDirection::Up)),
+            Action::ResizePaneDown => ctx.pane_command(PaneCommand::Resize(PaneDirection::Down)),
             Action::IncreaseFontSize => ctx.change_font_size(FONT_SIZE_STEP),
             Action::DecreaseFontSize => ctx.change_font_size(-FONT_SIZE_STEP),
             Action::ResetFontSize => ctx.reset_font_size(),
@@ -376,6 +431,30 @@ impl<T: EventListener> Execute<T> for Action {
                 term.vi_motion(ViMotion::FirstOccupied);
                 ctx.mark_dirty();
             },
+            Action::ScrollToPreviousPrompt | Action::ScrollToNextPrompt => {
+                let direction = match self {
+                    Action::ScrollToPreviousPrompt => Direction::Left,
+                    _ => Direction::Right,
+                };
+
+                // Find closest prompt relative to the top of the viewport.
+                let term = ctx.terminal_mut();
+                let display_offset = term.grid().display_offset() as i32;
+                let origin = Point::new(Line(-display_offset), Column(0));
+                let prompt = match term.prompt_search(origin, direction) {
+                    Some(prompt) => prompt,
+                    None => return,
+                };
+
+                // Move vi mode cursor.
+                if term.mode().contains(TermMode::VI) {
+                    term.vi_mode_cursor.point = prompt;
+                    ctx.mark_dirty();
+                }
+
+                // Scroll the prompt to the top of the viewport.
+                ctx.scroll(Scroll::Delta(-display_offset - prompt.line.0));
+            },
             Action::ClearHistory => ctx.terminal_mut().clear_screen(ClearMode::Saved),
             Action::ClearLogNotice => ctx.pop_message(),
             #[cfg(not(target_os = "macos"))]
@@ -412,6 +491,32 @@ impl<T: EventListener> Execute<T> for Action {
             Action::SelectTab9 => ctx.window().select_tab_at_index(8),
             #[cfg(target_os = "macos")]
             Action::SelectLastTab => ctx.window().select_last_tab(),
+            #[cfg(not(target_os = "macos"))]
+            Action::CreateNewTab => ctx.tab_command(TabCommand::Create),
+            #[cfg(not(target_os = "macos"))]
+            Action::SelectNextTab => ctx.tab_command(TabCommand::Next),
+            #[cfg(not(target_os = "macos"))]
+            Action::SelectPreviousTab => ctx.tab_command(TabCommand::Previous),
+            #[cfg(not(target_os = "macos"))]
+            Action::SelectTab1 => ctx.tab_command(TabCommand::Select(0)),
+            #[cfg(not(target_os = "macos"))]
+            Action::SelectTab2 => ctx.tab_command(TabCommand::Select(1)),
+            #[cfg(not(target_os = "macos"))]
+            Action::SelectTab3 => ctx.tab_command(TabCommand::Select(2)),
+            #[cfg(not(target_os = "macos"))]
+            Action::SelectTab4 => ctx.tab_command(TabCommand::Select(3)),
+            #[cfg(not(target_os = "macos"))]
+            Action::SelectTab5 => ctx.tab_command(TabCommand::Select(4)),
+            #[cfg(not(target_os = "macos"))]
+            Action::SelectTab6 => ctx.tab_command(TabCommand::Select(5)),
+            #[cfg(not(target_os = "macos"))]
+            Action::SelectTab7 => ctx.tab_command(TabCommand::Select(6)),
+            #[cfg(not(target_os = "macos"))]
+            Action::SelectTab8 => ctx.tab_command(TabCommand::Select(7)),
+            #[cfg(not(target_os = "macos"))]
+            Action::SelectTab9 => ctx.tab_command(TabCommand::Select(8)),
+            #[cfg(not(target_os = "macos"))]
+            Action::SelectLastTab => ctx.tab_command(TabCommand::Last),
             _ => (),
         }
     }
@@ -496,9 +601,8 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
             x.saturating_sub(size_info.padding_x() as usize) % size_info.cell_width() as usize;
         let half_cell_width = (size_info.cell_width() / 2.0) as usize;
 
-        let additional_padding =
-            (size_info.width() - size_info.padding_x() * 2.) % size_info.cell_width();
-        let end_of_grid = size_info.width() - size_info.padding_x() - additional_padding;
+        let end_of_grid =
+            size_info.padding_x() + size_info.columns() as f32 * size_info.cell_width();
 
         if cell_x > half_cell_width
             // Edge case when mouse leaves the window.
@@ -953,7 +1057,7 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
         if self.message_bar_cursor_state() == Some(CursorIcon::Pointer)
             && state == ElementState::Pressed
         {
-            let size = self.ctx.size_info();
+            let size = self.ctx.window_size_info();
 
             let current_lines = self.ctx.message().map_or(0, |m| m.text(&size).len());
 
@@ -1025,13 +1129,13 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
         let search_height = usize::from(self.ctx.search_active());
 
         // Calculate Y position of the end of the last terminal line.
-        let size = self.ctx.size_info();
+        let size = self.ctx.window_size_info();
         let terminal_end = size.padding_y() as usize
             + size.cell_height() as usize * (size.screen_lines() + search_height);
 
         let mouse = self.ctx.mouse();
         let display_offset = self.ctx.terminal().grid().display_offset();
-        let point = self.ctx.mouse().point(&self.ctx.size_info(), display_offset);
+        let point = self.ctx.mouse().point(&size, display_offset);
 
         if self.ctx.message().is_none() || (mouse.y <= terminal_end) {
             None
@@ -1105,13 +1209,14 @@ mod tests {
     use super::*;
 
     use winit::event::{DeviceId, Event as WinitEvent, WindowEvent};
-    use winit::keyboard::Key;
+    use winit::keyboard::{Key, KeyCode, NamedKey, PhysicalKey};
     use winit::window::WindowId;
 
     use alacritty_terminal::event::Event as TerminalEvent;
 
-    use crate::config::Binding;
-    use crate::message_bar::MessageBuffer;
+    use crate::config::{Binding, BindingKey, KeyLocation};
+    use crate::message_bar::{MessageBuffer, MessageType};
+    use crate::pane::PaneRect;
 
     const KEY: Key<&'static str> = Key::Character("0");
 
@@ -1121,12 +1226,15 @@ mod tests {
     struct ActionContext<'a, T> {
         pub terminal: &'a mut Term<T>,
         pub size_info: &'a SizeInfo,
+        pub window_size_info: &'a SizeInfo,
         pub mouse: &'a mut Mouse,
         pub clipboard: &'a mut Clipboard,
         pub message_buffer: &'a mut MessageBuffer,
         pub modifiers: Modifiers,
         config: &'a UiConfig,
         inline_search_state: &'a mut InlineSearchState,
+        key_sequence: Vec<KeyPress>,
+        key_sequence_hint: Option<String>,
     }
 
     impl<'a, T: EventListener> super::ActionContext<T> for ActionContext<'a, T> {
@@ -1151,6 +1259,15 @@ mod tests {
             false
         }
 
+        fn key_sequence(&self) -> &[KeyPress] {
+            &self.key_sequence
+        }
+
+        fn set_key_sequence(&mut self, keys: Vec<KeyPress>, hint: Option<String>) {
+            self.key_sequence = keys;
+            self.key_sequence_hint = hint;
+        }
+
         fn terminal(&self) -> &Term<T> {
             self.terminal
         }
@@ -1163,6 +1280,10 @@ mod tests {
             *self.size_info
         }
 
+        fn window_size_info(&self) -> SizeInfo {
+            *self.window_size_info
+        }
+
         fn selection_is_empty(&self) -> bool {
             true
         }
@@ -1267,11 +1388,14 @@ mod tests {
                     terminal: &mut terminal,
                     mouse: &mut mouse,
                     size_info: &size,
+                    window_size_info: &size,
                     clipboard: &mut clipboard,
                     modifiers: Default::default(),
                     message_buffer: &mut message_buffer,
                     inline_search_state: &mut inline_search_state,
                     config: &cfg,
+                    key_sequence: Vec::new(),
+                    key_sequence_hint: None,
                 };
 
                 let mut processor = Processor::new(context);
@@ -1443,7 +1567,7 @@ mod tests {
 
     test_process_binding! {
         name: process_binding_nomode_shiftmod_require_shift,
-        binding: Binding { trigger: KEY, mods: ModifiersState::SHIFT, action: Action::from("\x1b[1;2D"), mode: BindingMode::empty(), notmode: BindingMode::empty() },
+        binding: Binding { trigger: KEY, mods: ModifiersState::SHIFT, action: Action::from("\x1b[1;2D"), mode: BindingMode::empty(), notmode: BindingMode::empty(), sequence: Vec::new() },
         triggers: true,
         mode: BindingMode::empty(),
         mods: ModifiersState::SHIFT,
@@ -1451,7 +1575,7 @@ mod tests {
 
     test_process_binding! {
         name: process_binding_nomode_nomod_require_shift,
-        binding: Binding { trigger: KEY, mods: ModifiersState::SHIFT, action: Action::from("\x1b[1;2D"), mode: BindingMode::empty(), notmode: BindingMode::empty() },
+        binding: Binding { trigger: KEY, mods: ModifiersState::SHIFT, action: Action::from("\x1b[1;2D"), mode: BindingMode::empty(), notmode: BindingMode::empty(), sequence: Vec::new() },
         triggers: false,
         mode: BindingMode::empty(),
         mods: ModifiersState::empty(),
@@ -1459,7 +1583,7 @@ mod tests {
 
     test_process_binding! {
         name: process_binding_nomode_controlmod,
-        binding: Binding { trigger: KEY, mods: ModifiersState::CONTROL, action: Action::from("\x1b[1;5D"), mode: BindingMode::empty(), notmode: BindingMode::empty() },
+        binding: Binding { trigger: KEY, mods: ModifiersState::CONTROL, action: Action::from("\x1b[1;5D"), mode: BindingMode::empty(), notmode: BindingMode::empty(), sequence: Vec::new() },
         triggers: true,
         mode: BindingMode::empty(),
         mods: ModifiersState::CONTROL,
@@ -1467,7 +1591,7 @@ mod tests {
 
     test_process_binding! {
         name: process_binding_nomode_nomod_require_not_appcursor,
-        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1b[D"), mode: BindingMode::empty(), notmode: BindingMode::APP_CURSOR },
+        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1b[D"), mode: BindingMode::empty(), notmode: BindingMode::APP_CURSOR, sequence: Vec::new() },
         triggers: true,
         mode: BindingMode::empty(),
         mods: ModifiersState::empty(),
@@ -1475,7 +1599,7 @@ mod tests {
 
     test_process_binding! {
         name: process_binding_appcursormode_nomod_require_appcursor,
-        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1bOD"), mode: BindingMode::APP_CURSOR, notmode: BindingMode::empty() },
+        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1bOD"), mode: BindingMode::APP_CURSOR, notmode: BindingMode::empty(), sequence: Vec::new() },
         triggers: true,
         mode: BindingMode::APP_CURSOR,
         mods: ModifiersState::empty(),
@@ -1483,7 +1607,7 @@ mod tests {
 
     test_process_binding! {
         name: process_binding_nomode_nomod_require_appcursor,
-        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1bOD"), mode: BindingMode::APP_CURSOR, notmode: BindingMode::empty() },
+        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1bOD"), mode: BindingMode::APP_CURSOR, notmode: BindingMode::empty(), sequence: Vec::new() },
         triggers: false,
         mode: BindingMode::empty(),
         mods: ModifiersState::empty(),
@@ -1491,7 +1615,7 @@ mod tests {
 
     test_process_binding! {
         name: process_binding_appcursormode_appkeypadmode_nomod_require_appcursor,
-        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1bOD"), mode: BindingMode::APP_CURSOR, notmode: BindingMode::empty() },
+        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1bOD"), mode: BindingMode::APP_CURSOR, notmode: BindingMode::empty(), sequence: Vec::new() },
         triggers: true,
         mode: BindingMode::APP_CURSOR | BindingMode::APP_KEYPAD,
         mods: ModifiersState::empty(),
@@ -1499,9 +1623,150 @@ mod tests {
 
     test_process_binding! {
         name: process_binding_fail_with_extra_mods,
-        binding: Binding { trigger: KEY, mods: ModifiersState::SUPER, action: Action::from("arst"), mode: BindingMode::empty(), notmode: BindingMode::empty() },
+        binding: Binding { trigger: KEY, mods: ModifiersState::SUPER, action: Action::from("arst"), mode: BindingMode::empty(), notmode: BindingMode::empty(), sequence: Vec::new() },
         triggers: false,
         mode: BindingMode::empty(),
         mods: ModifiersState::ALT | ModifiersState::SUPER,
     }
+
+    #[test]
+    fn message_bar_below_all_panes() {
+        let mut clipboard = Clipboard::new_nop();
+        let cfg = UiConfig::default();
+        let window_size = SizeInfo::new(21.0, 51.0, 3.0, 3.0, 0., 0., false);
+        let size = window_size.pane(&PaneRect::new(0, 0, 8, 7));
+
+        let mut terminal = Term::new(cfg.term_options(), &size, MockEventProxy);
+
+        // Place the mouse below the pane, but above the message bar of the window.
+        let mut mouse = Mouse { y: 30, ..Mouse::default() };
+
+        let mut inline_search_state = InlineSearchState::default();
+        let mut message_buffer = MessageBuffer::default();
+        message_buffer.push(Message::new(String::from("message"), MessageType::Error));
+
+        let context = ActionContext {
+            terminal: &mut terminal,
+            mouse: &mut mouse,
+            size_info: &size,
+            window_size_info: &window_size,
+            clipboard: &mut clipboard,
+            modifiers: Default::default(),
+            message_buffer: &mut message_buffer,
+            inline_search_state: &mut inline_search_state,
+            config: &cfg,
+            key_sequence: Vec::new(),
+            key_sequence_hint: None,
+        };
+
+        let processor = Processor::new(context);
+
+        assert_eq!(processor.message_bar_cursor_state(), None);
+    }
+
+    type KeySequenceProcessor<'a> = Processor<MockEventProxy, ActionContext<'a, MockEventProxy>>;
+
+    /// Run `f` with a `Control+a c` sequence binding, which clears the pending message.
+    fn with_key_sequence<F: FnOnce(&mut KeySequenceProcessor<'_>)>(f: F) {
+        let mut clipboard = Clipboard::new_nop();
+        let cfg: UiConfig = toml::from_str(
+            r#"
+            [keyboard]
+            leader = { key = "a", mods = "Control" }
+            bindings = [{ key = "c", sequence = ["Leader"], action = "ClearLogNotice" }]
+            "#,
+        )
+        .unwrap();
+        let size = SizeInfo::new(21.0, 51.0, 3.0, 3.0, 0., 0., false);
+
+        let mut terminal = Term::new(cfg.term_options(), &size, MockEventProxy);
+        let mut mouse = Mouse::default();
+        let mut inline_search_state = InlineSearchState::default();
+        let mut message_buffer = MessageBuffer::default();
+        message_buffer.push(Message::new(String::from("message"), MessageType::Error));
+
+        let context = ActionContext {
+            terminal: &mut terminal,
+            mouse: &mut mouse,
+            size_info: &size,
+            window_size_info: &size,
+            clipboard: &mut clipboard,
+            modifiers: Default::default(),
+            message_buffer: &mut message_buffer,
+            inline_search_state: &mut inline_search_state,
+            config: &cfg,
+            key_sequence: Vec::new(),
+            key_sequence_hint: None,
+        };
+
+        f(&mut Processor::new(context));
+    }
+
+    fn key_press(key: Key, mods: ModifiersState) -> KeyPress {
+        KeyPress {
+            key: BindingKey::Keycode { key, location: KeyLocation::Standard },
+            scancode: BindingKey::Scancode(PhysicalKey::Code(KeyCode::KeyA)),
+            mods,
+        }
+    }
+
+    fn leader_press() -> KeyPress {
+        key_press(Key::Character("a".into()), ModifiersState::CONTROL)
+    }
+
+    fn char_press(c: &str) -> KeyPress {
+        key_press(Key::Character(c.into()), ModifiersState::empty())
+    }
+
+    #[test]
+    fn key_sequence_prefix() {
+        with_key_sequence(|processor| {
+            // Keys which don't start a sequence are left to the regular bindings.
+            assert!(!processor.process_key_sequence(&char_press("c"), BindingMode::empty()));
+            assert!(processor.ctx.key_sequence.is_empty());
+
+            assert!(processor.process_key_sequence(&leader_press(), BindingMode::empty()));
+            assert_eq!(processor.ctx.key_sequence, vec![leader_press()]);
+            assert!(processor.ctx.key_sequence_hint.is_some());
+            assert!(processor.ctx.message_buffer.message().is_some());
+        });
+    }
+
+    #[test]
+    fn key_sequence_completion() {
+        with_key_sequence(|processor| {
+            assert!(processor.process_key_sequence(&leader_press(), BindingMode::empty()));
+            assert!(processor.process_key_sequence(&char_press("c"), BindingMode::empty()));
+
+            assert!(processor.ctx.key_sequence.is_empty());
+            assert!(processor.ctx.key_sequence_hint.is_none());
+            assert!(processor.ctx.message_buffer.message().is_none());
+        });
+    }
+
+    #[test]
+    fn key_sequence_cancel_on_mismatch() {
+        with_key_sequence(|processor| {
+            assert!(processor.process_key_sequence(&leader_press(), BindingMode::empty()));
+            assert!(processor.process_key_sequence(&char_press("x"), BindingMode::empty()));
+
+            assert!(processor.ctx.key_sequence.is_empty());
+            assert!(processor.ctx.key_sequence_hint.is_none());
+            assert!(processor.ctx.message_buffer.message().is_some());
+        });
+    }
+
+    #[test]
+    fn key_sequence_modifier_pass_through() {
+        with_key_sequence(|processor| {
+            assert!(processor.process_key_sequence(&leader_press(), BindingMode::empty()));
+
+            let shift = key_press(Key::Named(NamedKey::Shift), ModifiersState::SHIFT);
+            assert!(processor.process_key_sequence(&shift, BindingMode::empty()));
+            assert_eq!(processor.ctx.key_sequence, vec![leader_press()]);
+
+            assert!(processor.process_key_sequence(&char_press("c"), BindingMode::empty()));
+            assert!(processor.ctx.message_buffer.message().is_none());
+        });
+    }
 }
@@ -4,9 +4,12 @@ use std::ffi::OsStr;
 use std::io::{BufRead, BufReader, Error as IoError, ErrorKind, Result as IoResult, Write};
 use std::os::unix::net::{UnixListener, UnixStream};
 use std::path::PathBuf;
+use std::sync::mpsc::{self, Sender};
+use std::time::Duration;
 use std::{env, fs, process};
 
 use log::warn;
+use serde::{Deserialize, Serialize};
 use winit::event_loop::EventLoopProxy;
 use winit::window::WindowId;
 
@@ -14,10 +17,126 @@ use alacritty_terminal::thread;
 
 use crate::cli::{Options, SocketMessage};
 use crate::event::{Event, EventType};
+use crate::screenshot::ScreenshotRequest;
+use crate::window_context::WindowContext;
 
 /// Environment variable name for the IPC socket path.
 const ALACRITTY_SOCKET_ENV: &str = "ALACRITTY_SOCKET";
 
+/// Maximum time to wait for the event loop to answer a query.
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Reply to an IPC query.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum SocketReply {
+    /// All windows of the Alacritty process.
+    Windows(Vec<WindowInfo>),
+
+    /// Text of a window.
+    Text(String),
+
+    /// Names of the active terminal modes.
+    Mode(Vec<String>),
+
+    /// Path of the saved screenshot.
+    Screenshot(PathBuf),
+
+    /// The query could not be answered.
+    Error(String),
+}
+
+/// Description of a single window.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WindowInfo {
+    /// Window ID, usable as `--window-id` for other messages.
+    pub id: u64,
+
+    /// Current window title.
+    pub title: String,
+
+    /// Terminal dimensions in cells.
+    pub columns: usize,
+    pub lines: usize,
+
+    /// Window dimensions in pixels.
+    pub width: u32,
+    pub height: u32,
+
+    /// Whether the window has keyboard focus.
+    pub focused: bool,
+
+    /// Working directory of the foreground process.
+    pub cwd: Option<PathBuf>,
+}
+
+/// IPC query waiting for a reply from the event loop.
+#[derive(Debug, Clone)]
+pub struct IpcQuery {
+    message: SocketMessage,
+    reply: Sender<SocketReply>,
+}
+
+impl IpcQuery {
+    /// Answer the query using the windows of this process.
+    pub fn answer<'a>(self, windows: impl Iterator<Item = &'a mut WindowContext>) {
+        let mut windows: Vec<_> = windows.collect();
+        windows.sort_by_key(|window_context| u64::from(window_context.id()));
+
+        let reply = match &self.message {
+            SocketMessage::ListWindows => {
+                SocketReply::Windows(windows.iter().map(|window| window.ipc_info()).collect())
+            },
+            SocketMessage::GetText(text) => {
+                match find_window(&mut windows, text.window.window_id) {
+                    Ok(window) => SocketReply::Text(window.ipc_text(text.start, text.end)),
+                    Err(err) => err,
+                }
+            },
+            SocketMessage::GetMode(query) => match find_window(&mut windows, query.window_id) {
+                Ok(window) => SocketReply::Mode(window.ipc_mode()),
+                Err(err) => err,
+            },
+            SocketMessage::Screenshot(screenshot) => {
+                match find_window(&mut windows, screenshot.window.window_id) {
+                    // The reply is sent once the next frame was saved.
+                    Ok(window) => {
+                        let request =
+                            ScreenshotRequest::with_reply(screenshot.path.clone(), self.reply);
+                        window.screenshot(request);
+                        return;
+                    },
+                    Err(err) => err,
+                }
+            },
+            SocketMessage::CreateWindow(_)
+            | SocketMessage::Config(_)
+            | SocketMessage::ColorScheme(_)
+            | SocketMessage::OpenSession(_) => {
+                SocketReply::Error(String::from("message is not a query"))
+            },
+        };
+
+        let _ = self.reply.send(reply);
+    }
+}
+
+/// Find the window targeted by a query, defaulting to the focused window.
+fn find_window<'a>(
+    windows: &'a mut [&mut WindowContext],
+    window_id: Option<u64>,
+) -> Result<&'a mut WindowContext, SocketReply> {
+    let mut windows = windows.iter_mut();
+    let window = match window_id {
+        Some(id) => windows.find(|window| u64::from(window.id()) == id),
+        None => windows.find(|window| window.is_focused()),
+    };
+
+    window.map(|window| &mut **window).ok_or_else(|| match window_id {
+        Some(id) => SocketReply::Error(format!("no window with ID {id}")),
+        None => SocketReply::Error(String::from("no window ID specified and no window is focused")),
+    })
+}
+
 /// Create an IPC socket.
 pub fn spawn_ipc_socket(options: &Options, event_proxy: EventLoopProxy<Event>) -> Option<PathBuf> {
     // Create the IPC socket and export its path as env variable if necessary.
@@ -71,6 +190,28 @@ pub fn spawn_ipc_socket(options: &Options, event_proxy: EventLoopProxy<Event>) -
                     let event = Event::new(EventType::IpcConfig(ipc_config), window_id);
                     let _ = event_proxy.send_event(event);
                 },
+                SocketMessage::ColorScheme(ipc_scheme) => {
+                    let window_id = ipc_scheme
+                        .window_id
+                        .and_then(|id| u64::try_from(id).ok())
+                        .map(WindowId::from);
+                    let event = Event::new(EventType::ColorScheme(ipc_scheme.scheme), window_id);
+                    let _ = event_proxy.send_event(event);
+                },
+                message => {
+                    // Wait for the event loop to answer the query.
+                    let (reply, receiver) = mpsc::channel();
+                    let query = IpcQuery { message, reply };
+                    let _ = event_proxy.send_event(Event::new(EventType::IpcQuery(query), None));
+
+                    let reply = receiver.recv_timeout(QUERY_TIMEOUT).unwrap_or_else(|_| {
+                        SocketReply::Error(String::from("timed out waiting for a reply"))
+                    });
+
+                    if let Err(err) = write_reply(stream.get_mut(), &reply) {
+                        warn!("Failed to write reply to socket: {}", err);
+                    }
+                },
             }
         }
     });
@@ -79,14 +220,38 @@ pub fn spawn_ipc_socket(options: &Options, event_proxy: EventLoopProxy<Event>) -
 }
 
 /// Send a message to the active Alacritty socket.
-pub fn send_message(socket: Option<PathBuf>, message: SocketMessage) -> IoResult<()> {
+///
+/// Returns Alacritty's reply if the message is a query.
+pub fn send_message(
+    socket: Option<PathBuf>,
+    message: SocketMessage,
+) -> IoResult<Option<SocketReply>> {
     let mut socket = find_socket(socket)?;
 
-    let message = serde_json::to_string(&message)?;
-    socket.write_all(message[..].as_bytes())?;
+    let mut data = serde_json::to_string(&message)?;
+    data.push('\n');
+    socket.write_all(data.as_bytes())?;
     let _ = socket.flush();
 
-    Ok(())
+    if !message.expects_reply() {
+        return Ok(None);
+    }
+
+    let mut reply = String::new();
+    BufReader::new(socket).read_line(&mut reply)?;
+    if reply.is_empty() {
+        return Err(IoError::new(ErrorKind::UnexpectedEof, "no reply received"));
+    }
+
+    Ok(Some(serde_json::from_str(&reply)?))
+}
+
+/// Write a reply as a single line of JSON.
+fn write_reply(stream: &mut UnixStream, reply: &SocketReply) -> IoResult<()> {
+    let mut data = serde_json::to_string(reply)?;
+    data.push('\n');
+    stream.write_all(data.as_bytes())?;
+    stream.flush()
 }
 
 /// Directory for the IPC socket file.
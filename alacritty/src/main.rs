@@ -33,6 +33,7 @@ mod config;
 mod daemon;
 mod display;
 mod event;
+mod history;
 mod input;
 #[cfg(unix)]
 mod ipc;
@@ -41,11 +42,18 @@ mod logging;
 mod macos;
 mod message_bar;
 mod migrate;
+mod notification;
+mod pane;
 #[cfg(windows)]
 mod panic;
+mod recording;
 mod renderer;
 mod scheduler;
+mod screenshot;
+mod session;
 mod string;
+mod tab;
+mod trigger;
 mod window_context;
 
 mod gl {
@@ -53,11 +61,13 @@ mod gl {
     include!(concat!(env!("OUT_DIR"), "/gl_bindings.rs"));
 }
 
+use crate::cli::{ConfigCommand, Options, Subcommands};
 #[cfg(unix)]
-use crate::cli::MessageOptions;
-use crate::cli::{Options, Subcommands};
+use crate::cli::{MessageOptions, SocketMessage};
 use crate::config::{monitor, UiConfig};
 use crate::event::{Event, Processor};
+#[cfg(unix)]
+use crate::ipc::SocketReply;
 #[cfg(target_os = "macos")]
 use crate::macos::locale;
 
@@ -74,12 +84,20 @@ fn main() -> Result<(), Box<dyn Error>> {
     }
 
     // Load command line options.
-    let options = Options::new();
+    let mut options = Options::new();
 
     match options.subcommands {
         #[cfg(unix)]
         Some(Subcommands::Msg(options)) => msg(options)?,
         Some(Subcommands::Migrate(options)) => migrate::migrate(options),
+        Some(Subcommands::Replay(options)) => recording::replay(options)?,
+        Some(Subcommands::Config(config_options)) => match config_options.command {
+            ConfigCommand::Check(check_options) => {
+                config::check::check(check_options, options.config_file)
+            },
+            ConfigCommand::Dump => config::dump(options.config_file, &mut options.config_options)?,
+            ConfigCommand::Schema => config::schema(),
+        },
         None => alacritty(options)?,
     }
 
@@ -88,8 +106,31 @@ fn main() -> Result<(), Box<dyn Error>> {
 
 /// `msg` subcommand entrypoint.
 #[cfg(unix)]
-fn msg(options: MessageOptions) -> Result<(), Box<dyn Error>> {
-    ipc::send_message(options.socket, options.message).map_err(|err| err.into())
+fn msg(mut options: MessageOptions) -> Result<(), Box<dyn Error>> {
+    // Open sessions by requesting each of their windows.
+    if let SocketMessage::OpenSession(session) = &options.message {
+        for window_options in session::load(&session.path)?.window_options() {
+            let message = SocketMessage::CreateWindow(window_options);
+            ipc::send_message(options.socket.clone(), message)?;
+        }
+        return Ok(());
+    }
+
+    // Resolve paths relative to the working directory of the client.
+    if let SocketMessage::Screenshot(screenshot) = &mut options.message {
+        screenshot.path = env::current_dir()?.join(&screenshot.path);
+    }
+
+    match ipc::send_message(options.socket, options.message)? {
+        Some(SocketReply::Windows(windows)) => println!("{}", serde_json::to_string(&windows)?),
+        Some(SocketReply::Mode(modes)) => println!("{}", serde_json::to_string(&modes)?),
+        Some(SocketReply::Text(text)) => println!("{text}"),
+        Some(SocketReply::Screenshot(path)) => println!("{}", path.display()),
+        Some(SocketReply::Error(err)) => return Err(err.into()),
+        None => (),
+    }
+
+    Ok(())
 }
 
 /// Temporary files stored for Alacritty.
@@ -142,6 +183,18 @@ fn alacritty(mut options: Options) -> Result<(), Box<dyn Error>> {
     let config = config::load(&mut options);
     log_config_path(&config);
 
+    // Load the windows of the session layout file.
+    let mut initial_windows = match &options.session {
+        Some(path) => session::load(path)?.window_options(),
+        None => Vec::new(),
+    };
+    if initial_windows.is_empty() {
+        // CLI config overrides are already applied to the global config.
+        let mut window_options = options.window_options.clone();
+        window_options.clear_options();
+        initial_windows.push(window_options);
+    }
+
     // Update the log level from config.
     log::set_max_level(config.debug.log_level);
 
@@ -186,11 +239,10 @@ fn alacritty(mut options: Options) -> Result<(), Box<dyn Error>> {
     };
 
     // Event processor.
-    let window_options = options.window_options.clone();
     let mut processor = Processor::new(config, options, &window_event_loop);
 
     // Start event loop and block until shutdown.
-    let result = processor.run(window_event_loop, window_options);
+    let result = processor.run(window_event_loop, initial_windows);
 
     // This explicit drop is needed for Windows, ConPTY backend. Otherwise a deadlock can occur.
     // The cause:
@@ -205,6 +257,9 @@ fn alacritty(mut options: Options) -> Result<(), Box<dyn Error>> {
     // FIXME: Change PTY API to enforce the correct drop order with the typesystem.
     drop(processor);
 
+    // Make sure the scrollback history of all closed windows was written.
+    history::wait_for_saves();
+
     // FIXME patch notify library to have a shutdown method.
     // config_reloader.join().ok();
 
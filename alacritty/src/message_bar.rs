@@ -27,6 +27,9 @@ pub enum MessageType {
 
     /// A message represents a warning.
     Warning,
+
+    /// A message provides information about the current input.
+    Info,
 }
 
 impl Message {
@@ -182,6 +185,12 @@ impl MessageBuffer {
         self.messages.push_back(message);
     }
 
+    /// Add a new message which is shown immediately.
+    #[inline]
+    pub fn push_front(&mut self, message: Message) {
+        self.messages.push_front(message);
+    }
+
     /// Check whether the message is already queued in the message bar.
     #[inline]
     pub fn is_queued(&self, message: &Message) -> bool {
@@ -0,0 +1,65 @@
+//! Desktop notifications.
+
+use alacritty_terminal::term::notification::Urgency;
+
+use crate::config::notification::Notifications;
+use crate::config::ui_config::Program;
+
+/// Command which shows a desktop notification.
+///
+/// Without a configured command, the platform's notification service is used. Returns `None` if
+/// there is no notification service available on this platform.
+pub fn command(
+    config: &Notifications,
+    title: &str,
+    body: &str,
+    urgency: Urgency,
+) -> Option<Program> {
+    match &config.command {
+        Some(command) => {
+            let mut args = command.args().to_vec();
+            args.extend([title.into(), body.into()]);
+            Some(Program::WithArgs { program: command.program().into(), args })
+        },
+        None => platform_command(title, body, urgency),
+    }
+}
+
+/// Send the notification through the freedesktop notification interface.
+#[cfg(not(any(target_os = "macos", windows)))]
+fn platform_command(title: &str, body: &str, urgency: Urgency) -> Option<Program> {
+    let urgency = match urgency {
+        Urgency::Low => "low",
+        Urgency::Normal => "normal",
+        Urgency::Critical => "critical",
+    };
+
+    Some(Program::WithArgs {
+        program: String::from("notify-send"),
+        args: vec![
+            String::from("--app-name=Alacritty"),
+            format!("--urgency={urgency}"),
+            String::from("--"),
+            title.into(),
+            body.into(),
+        ],
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn platform_command(title: &str, body: &str, _urgency: Urgency) -> Option<Program> {
+    let script =
+        format!("display notification \"{}\" with title \"{}\"", escape(body), escape(title));
+    Some(Program::WithArgs { program: String::from("osascript"), args: vec!["-e".into(), script] })
+}
+
+#[cfg(windows)]
+fn platform_command(_title: &str, _body: &str, _urgency: Urgency) -> Option<Program> {
+    None
+}
+
+/// Escape text for use in an AppleScript string literal.
+#[cfg(target_os = "macos")]
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
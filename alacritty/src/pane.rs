@@ -0,0 +1,650 @@
+//! Split panes sharing a single window.
+
+use std::cmp::Reverse;
+use std::error::Error;
+use std::ffi::OsStr;
+use std::fmt::Debug;
+use std::mem;
+#[cfg(not(windows))]
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use log::{debug, info, warn};
+use winit::event_loop::EventLoopProxy;
+use winit::window::WindowId;
+
+use alacritty_terminal::event::{Event as TerminalEvent, Notify, OnResize};
+use alacritty_terminal::event_loop::{EventLoop as PtyEventLoop, Msg, Notifier};
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::sync::FairMutex;
+use alacritty_terminal::term::{Term, TermMode};
+use alacritty_terminal::tty;
+
+use crate::cli::TerminalOptions;
+use crate::config::UiConfig;
+#[cfg(not(windows))]
+use crate::daemon;
+use crate::daemon::spawn_daemon;
+use crate::display::SizeInfo;
+use crate::event::{Event, EventProxy, InlineSearchState, SearchState};
+use crate::history;
+use crate::recording::Recording;
+use crate::trigger::TriggerState;
+
+/// Unique identifier of a pane.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PaneId(usize);
+
+impl PaneId {
+    /// Create a new unique pane ID.
+    pub fn next() -> Self {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// Side on which a new pane is created.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SplitDirection {
+    /// Place the new pane to the right of the current one.
+    Right,
+
+    /// Place the new pane below the current one.
+    Down,
+}
+
+/// Direction for moving focus and dividers between panes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PaneDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl PaneDirection {
+    /// Split which is crossed when moving in this direction.
+    fn split_direction(self) -> SplitDirection {
+        match self {
+            Self::Left | Self::Right => SplitDirection::Right,
+            Self::Up | Self::Down => SplitDirection::Down,
+        }
+    }
+}
+
+/// Changes to the panes of a window.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PaneCommand {
+    Split(SplitDirection),
+    Close,
+    Focus(PaneDirection),
+    Resize(PaneDirection),
+}
+
+/// Area of the terminal grid covered by a pane, in cells.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct PaneRect {
+    pub line: usize,
+    pub column: usize,
+    pub lines: usize,
+    pub columns: usize,
+}
+
+impl PaneRect {
+    pub fn new(line: usize, column: usize, lines: usize, columns: usize) -> Self {
+        Self { line, column, lines, columns }
+    }
+
+    /// Divide the area in two, separated by a divider which is one cell wide.
+    fn split(self, direction: SplitDirection, ratio: f32) -> (Self, Self, Self) {
+        let (mut first, mut divider, mut second) = (self, self, self);
+
+        let length = self.length(direction);
+        let position = split_position(length, ratio);
+        let second_length = length.saturating_sub(position + 1);
+
+        match direction {
+            SplitDirection::Right => {
+                first.columns = position;
+                divider.column += position;
+                divider.columns = 1;
+                second.column += position + 1;
+                second.columns = second_length;
+            },
+            SplitDirection::Down => {
+                first.lines = position;
+                divider.line += position;
+                divider.lines = 1;
+                second.line += position + 1;
+                second.lines = second_length;
+            },
+        }
+
+        (first, divider, second)
+    }
+
+    /// Size of the area perpendicular to the dividers of a split.
+    pub fn length(&self, direction: SplitDirection) -> usize {
+        match direction {
+            SplitDirection::Right => self.columns,
+            SplitDirection::Down => self.lines,
+        }
+    }
+
+    /// Number of cells shared with an area directly next to this one.
+    ///
+    /// Areas are adjacent when only a divider separates them.
+    fn adjacent_overlap(&self, other: &Self, direction: PaneDirection) -> Option<usize> {
+        let adjacent = match direction {
+            PaneDirection::Left => other.column + other.columns + 1 == self.column,
+            PaneDirection::Right => self.column + self.columns + 1 == other.column,
+            PaneDirection::Up => other.line + other.lines + 1 == self.line,
+            PaneDirection::Down => self.line + self.lines + 1 == other.line,
+        };
+
+        let overlap = match direction.split_direction() {
+            SplitDirection::Right => overlap(self.line, self.lines, other.line, other.lines),
+            SplitDirection::Down => overlap(self.column, self.columns, other.column, other.columns),
+        };
+
+        (adjacent && overlap > 0).then_some(overlap)
+    }
+}
+
+/// Position and size of all panes and dividers in a window.
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct Arrangement {
+    pub panes: Vec<(PaneId, PaneRect)>,
+    pub dividers: Vec<PaneRect>,
+}
+
+/// Tree of splits between the panes of a window.
+#[derive(Debug)]
+pub struct Layout {
+    root: Node,
+}
+
+#[derive(Debug)]
+enum Node {
+    Pane(PaneId),
+    Split { direction: SplitDirection, ratio: f32, first: Box<Node>, second: Box<Node> },
+}
+
+impl Layout {
+    pub fn new(pane: PaneId) -> Self {
+        Self { root: Node::Pane(pane) }
+    }
+
+    /// Split `pane` in half, placing `new_pane` on the specified side.
+    pub fn split(&mut self, pane: PaneId, new_pane: PaneId, direction: SplitDirection) {
+        if let Some(node) = self.root.find_mut(pane) {
+            let first = mem::replace(node, Node::Pane(new_pane));
+            *node = Node::Split {
+                direction,
+                ratio: 0.5,
+                first: Box::new(first),
+                second: Box::new(Node::Pane(new_pane)),
+            };
+        }
+    }
+
+    /// Remove a pane, giving its space to its sibling.
+    ///
+    /// Returns `false` if the pane could not be removed.
+    pub fn remove(&mut self, pane: PaneId) -> bool {
+        self.root.remove(pane)
+    }
+
+    /// Check if the pane is part of the layout.
+    pub fn contains(&self, pane: PaneId) -> bool {
+        self.root.contains(pane)
+    }
+
+    /// Compute the position of all panes and dividers inside of `area`.
+    pub fn arrange(&self, area: PaneRect) -> Arrangement {
+        let mut arrangement = Arrangement::default();
+        self.root.arrange(area, &mut arrangement);
+        arrangement
+    }
+
+    /// Find the pane next to `pane` in the specified direction.
+    ///
+    /// If multiple panes are adjacent, the one sharing the longest edge is picked.
+    pub fn neighbor(
+        &self,
+        area: PaneRect,
+        pane: PaneId,
+        direction: PaneDirection,
+    ) -> Option<PaneId> {
+        let panes = self.arrange(area).panes;
+        let rect = panes.iter().find(|(id, _)| *id == pane)?.1;
+
+        panes
+            .iter()
+            .filter_map(|(id, other)| Some((rect.adjacent_overlap(other, direction)?, *id)))
+            .min_by_key(|(overlap, _)| Reverse(*overlap))
+            .map(|(_, id)| id)
+    }
+
+    /// Move the closest divider of `pane` one cell into the specified direction.
+    ///
+    /// Returns `false` if no divider was moved.
+    pub fn resize(&mut self, area: PaneRect, pane: PaneId, direction: PaneDirection) -> bool {
+        self.root.resize(area, pane, direction) == Some(true)
+    }
+}
+
+impl Node {
+    /// Find the leaf of a pane.
+    fn find_mut(&mut self, pane: PaneId) -> Option<&mut Self> {
+        match self {
+            Self::Pane(id) if *id == pane => Some(self),
+            Self::Pane(_) => None,
+            Self::Split { first, second, .. } => {
+                first.find_mut(pane).or_else(|| second.find_mut(pane))
+            },
+        }
+    }
+
+    fn contains(&self, pane: PaneId) -> bool {
+        match self {
+            Self::Pane(id) => *id == pane,
+            Self::Split { first, second, .. } => first.contains(pane) || second.contains(pane),
+        }
+    }
+
+    fn is_pane(&self, pane: PaneId) -> bool {
+        matches!(self, Self::Pane(id) if *id == pane)
+    }
+
+    fn remove(&mut self, pane: PaneId) -> bool {
+        let sibling = match self {
+            Self::Split { first, second, .. } if first.is_pane(pane) => {
+                mem::replace(second.as_mut(), Self::Pane(pane))
+            },
+            Self::Split { first, second, .. } if second.is_pane(pane) => {
+                mem::replace(first.as_mut(), Self::Pane(pane))
+            },
+            Self::Split { first, second, .. } => return first.remove(pane) || second.remove(pane),
+            Self::Pane(_) => return false,
+        };
+
+        *self = sibling;
+
+        true
+    }
+
+    fn arrange(&self, area: PaneRect, arrangement: &mut Arrangement) {
+        match self {
+            Self::Pane(id) => arrangement.panes.push((*id, area)),
+            Self::Split { direction, ratio, first, second } => {
+                let (first_area, divider, second_area) = area.split(*direction, *ratio);
+                first.arrange(first_area, arrangement);
+                arrangement.dividers.push(divider);
+                second.arrange(second_area, arrangement);
+            },
+        }
+    }
+
+    /// Move the divider of the innermost split crossed by `direction`.
+    ///
+    /// Returns `None` if the pane is not part of this node, or whether a matching split was
+    /// found otherwise.
+    fn resize(&mut self, area: PaneRect, pane: PaneId, direction: PaneDirection) -> Option<bool> {
+        let (split_direction, ratio, first, second) = match self {
+            Self::Pane(id) => return (*id == pane).then_some(false),
+            Self::Split { direction, ratio, first, second } => (*direction, ratio, first, second),
+        };
+
+        let (first_area, _, second_area) = area.split(split_direction, *ratio);
+        let resized = first
+            .resize(first_area, pane, direction)
+            .or_else(|| second.resize(second_area, pane, direction))?;
+
+        if resized || direction.split_direction() != split_direction {
+            return Some(resized);
+        }
+
+        // Keep at least one cell on both sides of the divider.
+        let available = area.length(split_direction).saturating_sub(1);
+        if available < 2 {
+            return Some(true);
+        }
+
+        let position = split_position(area.length(split_direction), *ratio);
+        let position = match direction {
+            PaneDirection::Left | PaneDirection::Up => position.saturating_sub(1),
+            PaneDirection::Right | PaneDirection::Down => position + 1,
+        };
+        *ratio = position.clamp(1, available - 1) as f32 / available as f32;
+
+        Some(true)
+    }
+}
+
+/// Cells in front of the divider when splitting `length` cells.
+fn split_position(length: usize, ratio: f32) -> usize {
+    let available = length.saturating_sub(1);
+    let position = (available as f32 * ratio).round() as usize;
+    position.clamp(1, available.max(1))
+}
+
+/// Length of the intersection between two ranges.
+fn overlap(start: usize, length: usize, other_start: usize, other_length: usize) -> usize {
+    let end = (start + length).min(other_start + other_length);
+    end.saturating_sub(start.max(other_start))
+}
+
+/// Terminal running inside of a pane.
+pub struct Pane {
+    pub id: PaneId,
+    pub terminal: Arc<FairMutex<Term<EventProxy>>>,
+    pub notifier: Notifier,
+    pub recording: Recording,
+    pub search_state: SearchState,
+    pub inline_search_state: InlineSearchState,
+    pub trigger_state: TriggerState,
+
+    /// Grid area covered by the pane.
+    pub rect: PaneRect,
+
+    /// Size of the pane, with the padding extending to the pane's origin.
+    pub size_info: SizeInfo,
+
+    /// Last title requested by the terminal.
+    pub title: Option<String>,
+
+    #[cfg(not(windows))]
+    pub master_fd: RawFd,
+    #[cfg(not(windows))]
+    pub shell_pid: u32,
+
+    history_path: Option<PathBuf>,
+}
+
+impl Pane {
+    /// Start a new terminal.
+    ///
+    /// The scrollback history is only persisted with `persist_history`, since all panes of a
+    /// window share the same history key.
+    pub fn new(
+        config: &UiConfig,
+        options: &TerminalOptions,
+        size_info: SizeInfo,
+        proxy: EventLoopProxy<Event>,
+        window_id: WindowId,
+        persist_history: bool,
+    ) -> Result<Self, Box<dyn Error>> {
+        let mut pty_config = config.pty_config();
+        options.override_pty_config(&mut pty_config);
+
+        info!("PTY dimensions: {:?} x {:?}", size_info.screen_lines(), size_info.columns());
+
+        let id = PaneId::next();
+        let event_proxy = EventProxy::new(proxy, window_id, id);
+
+        // Create the terminal.
+        //
+        // This object contains all of the state about what's being displayed. It's
+        // wrapped in a clonable mutex since both the I/O loop and display need to
+        // access it.
+        let mut terminal = Term::new(config.term_options(), &size_info, event_proxy.clone());
+        let (cell_width, cell_height) = (size_info.cell_width(), size_info.cell_height());
+        terminal.set_cell_size(cell_width as usize, cell_height as usize);
+
+        // Restore the scrollback history of a previously closed window.
+        let history_path = if config.scrolling.persist && persist_history {
+            options.history_key(&pty_config).and_then(|key| history::path(&key))
+        } else {
+            None
+        };
+        if let Some(path) = &history_path {
+            match history::take(path) {
+                Ok(Some(saved)) => terminal.restore_grid(saved),
+                Ok(None) => (),
+                Err(err) => warn!("Unable to restore scrollback history: {}", err),
+            }
+        }
+
+        // Triggers only match output written after the restored history.
+        let trigger_state = TriggerState::new(&terminal);
+
+        let terminal = Arc::new(FairMutex::new(terminal));
+
+        // Create the PTY.
+        //
+        // The PTY forks a process to run the shell on the slave side of the
+        // pseudoterminal. A file descriptor for the master side is retained for
+        // reading/writing to the shell.
+        let pty = tty::new(&pty_config, size_info.into(), window_id.into())?;
+
+        #[cfg(not(windows))]
+        let master_fd = pty.file().as_raw_fd();
+        #[cfg(not(windows))]
+        let shell_pid = pty.child().id();
+
+        // Create the pseudoterminal I/O loop.
+        //
+        // PTY I/O is ran on another thread as to not occupy cycles used by the
+        // renderer and input processing. Note that access to the terminal state is
+        // synchronized since the I/O loop updates the state, and the display
+        // consumes it periodically.
+        let event_loop = PtyEventLoop::new(
+            Arc::clone(&terminal),
+            event_proxy.clone(),
+            pty,
+            pty_config.hold,
+            config.debug.ref_test,
+        )?;
+
+        // The event loop channel allows write requests from the event processor
+        // to be sent to the pty loop and ultimately written to the pty.
+        let loop_tx = event_loop.channel();
+
+        // Start recording the session before any output is read.
+        let mut recording = Recording::new(loop_tx.clone());
+        if let Some(path) = &options.record {
+            recording.start(path.clone(), size_info.into(), &config.recording)?;
+        }
+
+        // Kick off the I/O thread.
+        let _io_thread = event_loop.spawn();
+
+        // Start cursor blinking, in case `Focused` isn't sent on startup.
+        if config.cursor.style().blinking {
+            event_proxy.send_event(TerminalEvent::CursorBlinkingChange.into());
+        }
+
+        Ok(Self {
+            id,
+            terminal,
+            notifier: Notifier(loop_tx),
+            recording,
+            rect: PaneRect::new(0, 0, size_info.screen_lines(), size_info.columns()),
+            size_info,
+            history_path,
+            #[cfg(not(windows))]
+            master_fd,
+            #[cfg(not(windows))]
+            shell_pid,
+            search_state: Default::default(),
+            inline_search_state: Default::default(),
+            title: Default::default(),
+            trigger_state,
+        })
+    }
+
+    /// Working directory of the terminal's shell.
+    #[cfg(not(windows))]
+    pub fn working_directory(&self) -> Option<PathBuf> {
+        let terminal = self.terminal.lock();
+        daemon::working_directory(&terminal, self.master_fd, self.shell_pid)
+    }
+
+    /// Start a new process in the background.
+    pub fn spawn_daemon<I, S>(&self, program: &str, args: I)
+    where
+        I: IntoIterator<Item = S> + Debug + Copy,
+        S: AsRef<OsStr>,
+    {
+        #[cfg(not(windows))]
+        let result = spawn_daemon(program, args, self.working_directory().as_deref());
+        #[cfg(windows)]
+        let result = spawn_daemon(program, args);
+
+        match result {
+            Ok(_) => debug!("Launched {} with args {:?}", program, args),
+            Err(_) => warn!("Unable to launch {} with args {:?}", program, args),
+        }
+    }
+
+    /// Move the pane to a new area of the window.
+    pub fn resize(&mut self, rect: PaneRect, size_info: SizeInfo) {
+        let old_size = mem::replace(&mut self.size_info, size_info);
+        self.rect = rect;
+
+        let mut terminal = self.terminal.lock();
+
+        // Resize the terminal when its dimensions have changed.
+        if old_size.screen_lines() != size_info.screen_lines()
+            || old_size.columns() != size_info.columns()
+        {
+            self.notifier.on_resize(size_info.into());
+            terminal.resize(size_info);
+
+            // Clear focused search match.
+            self.search_state.clear_focused_match();
+        }
+
+        // Update cell dimensions used for image placement.
+        terminal.set_cell_size(size_info.cell_width() as usize, size_info.cell_height() as usize);
+    }
+
+    /// Update the keyboard focus of the terminal.
+    pub fn set_focused(&self, is_focused: bool) {
+        let mut terminal = self.terminal.lock();
+        if terminal.is_focused == is_focused {
+            return;
+        }
+
+        terminal.is_focused = is_focused;
+
+        if terminal.mode().contains(TermMode::FOCUS_IN_OUT) {
+            let chr = if is_focused { "I" } else { "O" };
+            self.notifier.notify(format!("\x1b[{}", chr).into_bytes());
+        }
+    }
+}
+
+impl Drop for Pane {
+    fn drop(&mut self) {
+        // Persist the scrollback history for the next window.
+        if let Some(path) = self.history_path.take() {
+            let saved = self.terminal.lock().save_grid();
+            history::save_in_background(path, saved);
+        }
+
+        // Shutdown the terminal's PTY.
+        let _ = self.notifier.0.send(Msg::Shutdown);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pane(id: usize) -> PaneId {
+        PaneId(id)
+    }
+
+    #[test]
+    fn split_right() {
+        let mut layout = Layout::new(pane(0));
+        layout.split(pane(0), pane(1), SplitDirection::Right);
+
+        let arrangement = layout.arrange(PaneRect::new(0, 0, 24, 81));
+        let expected =
+            vec![(pane(0), PaneRect::new(0, 0, 24, 40)), (pane(1), PaneRect::new(0, 41, 24, 40))];
+        assert_eq!(arrangement.panes, expected);
+        assert_eq!(arrangement.dividers, vec![PaneRect::new(0, 40, 24, 1)]);
+    }
+
+    #[test]
+    fn nested_splits() {
+        let mut layout = Layout::new(pane(0));
+        layout.split(pane(0), pane(1), SplitDirection::Right);
+        layout.split(pane(1), pane(2), SplitDirection::Down);
+
+        let arrangement = layout.arrange(PaneRect::new(0, 0, 25, 81));
+        let expected = vec![
+            (pane(0), PaneRect::new(0, 0, 25, 40)),
+            (pane(1), PaneRect::new(0, 41, 12, 40)),
+            (pane(2), PaneRect::new(13, 41, 12, 40)),
+        ];
+        assert_eq!(arrangement.panes, expected);
+        let expected = vec![PaneRect::new(0, 40, 25, 1), PaneRect::new(12, 41, 1, 40)];
+        assert_eq!(arrangement.dividers, expected);
+    }
+
+    #[test]
+    fn remove_pane() {
+        let mut layout = Layout::new(pane(0));
+        layout.split(pane(0), pane(1), SplitDirection::Right);
+        layout.split(pane(1), pane(2), SplitDirection::Down);
+
+        assert!(layout.remove(pane(1)));
+        assert!(!layout.remove(pane(1)));
+
+        let arrangement = layout.arrange(PaneRect::new(0, 0, 25, 81));
+        let expected =
+            vec![(pane(0), PaneRect::new(0, 0, 25, 40)), (pane(2), PaneRect::new(0, 41, 25, 40))];
+        assert_eq!(arrangement.panes, expected);
+
+        assert!(layout.remove(pane(0)));
+        assert!(!layout.remove(pane(2)));
+
+        let arrangement = layout.arrange(PaneRect::new(0, 0, 25, 81));
+        assert_eq!(arrangement.panes, vec![(pane(2), PaneRect::new(0, 0, 25, 81))]);
+        assert!(arrangement.dividers.is_empty());
+    }
+
+    #[test]
+    fn neighbors() {
+        let mut layout = Layout::new(pane(0));
+        layout.split(pane(0), pane(1), SplitDirection::Right);
+        layout.split(pane(1), pane(2), SplitDirection::Down);
+        let area = PaneRect::new(0, 0, 25, 81);
+
+        assert_eq!(layout.neighbor(area, pane(0), PaneDirection::Right), Some(pane(1)));
+        assert_eq!(layout.neighbor(area, pane(0), PaneDirection::Left), None);
+        assert_eq!(layout.neighbor(area, pane(1), PaneDirection::Down), Some(pane(2)));
+        assert_eq!(layout.neighbor(area, pane(2), PaneDirection::Up), Some(pane(1)));
+        assert_eq!(layout.neighbor(area, pane(2), PaneDirection::Left), Some(pane(0)));
+        assert_eq!(layout.neighbor(area, pane(2), PaneDirection::Right), None);
+    }
+
+    #[test]
+    fn resize_divider() {
+        let mut layout = Layout::new(pane(0));
+        layout.split(pane(0), pane(1), SplitDirection::Right);
+        let area = PaneRect::new(0, 0, 24, 81);
+
+        assert!(layout.resize(area, pane(1), PaneDirection::Right));
+        assert!(layout.resize(area, pane(0), PaneDirection::Right));
+        assert!(!layout.resize(area, pane(0), PaneDirection::Down));
+
+        let arrangement = layout.arrange(area);
+        let expected =
+            vec![(pane(0), PaneRect::new(0, 0, 24, 42)), (pane(1), PaneRect::new(0, 43, 24, 38))];
+        assert_eq!(arrangement.panes, expected);
+
+        // Dividers can't be moved past the edge of the split.
+        for _ in 0..100 {
+            layout.resize(area, pane(0), PaneDirection::Left);
+        }
+        let arrangement = layout.arrange(area);
+        assert_eq!(arrangement.panes[0].1, PaneRect::new(0, 0, 24, 1));
+    }
+}
@@ -0,0 +1,204 @@
+//! Recording and replay of terminal sessions.
+
+use std::error::Error;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
+use serde_json as json;
+
+use alacritty_terminal::asciicast::{self, Recorder};
+use alacritty_terminal::event::{VoidListener, WindowSize};
+use alacritty_terminal::event_loop::{EventLoopSender, Msg};
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Line, Point};
+use alacritty_terminal::parser::Processor;
+use alacritty_terminal::term::{Config, Term, TermMode};
+use alacritty_terminal::vte::ansi::Timeout;
+
+use crate::cli::ReplayOptions;
+use crate::config::recording::Recording as RecordingConfig;
+
+/// Session recording of a single terminal.
+pub struct Recording {
+    sender: EventLoopSender,
+
+    /// Path of the active recording.
+    path: Option<PathBuf>,
+}
+
+impl Recording {
+    pub fn new(sender: EventLoopSender) -> Self {
+        Self { sender, path: None }
+    }
+
+    /// Start recording to `path`, replacing any active recording.
+    pub fn start(
+        &mut self,
+        path: PathBuf,
+        size: WindowSize,
+        config: &RecordingConfig,
+    ) -> Result<(), Box<dyn Error>> {
+        let writer = BufWriter::new(File::create(&path)?);
+        let recorder = Recorder::new(Box::new(writer), size, config.input)?;
+        self.sender.send(Msg::StartRecording(recorder))?;
+        self.path = Some(path);
+
+        Ok(())
+    }
+
+    /// Stop the active recording.
+    ///
+    /// Returns the path of the stopped recording.
+    pub fn stop(&mut self) -> Option<PathBuf> {
+        let path = self.path.take()?;
+        let _ = self.sender.send(Msg::StopRecording);
+        Some(path)
+    }
+}
+
+/// Path for a new recording in the configured recording directory.
+pub fn new_path(config: &RecordingConfig) -> PathBuf {
+    let directory =
+        config.directory.clone().or_else(home::home_dir).unwrap_or_else(|| PathBuf::from("."));
+
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |t| t.as_secs());
+    directory.join(format!("alacritty-{timestamp}.cast"))
+}
+
+/// Header of an asciicast recording.
+#[derive(Deserialize, Debug)]
+struct Header {
+    version: u32,
+    width: u16,
+    height: u16,
+    idle_time_limit: Option<f64>,
+}
+
+/// Replay an asciicast recording to STDOUT.
+///
+/// The recorded output is emulated at the recorded terminal size, then the resulting screen
+/// content is drawn whenever playback pauses.
+pub fn replay(options: ReplayOptions) -> Result<(), Box<dyn Error>> {
+    if options.speed <= 0. {
+        return Err("replay speed must be positive".into());
+    }
+
+    let mut lines = BufReader::new(File::open(&options.file)?).lines();
+
+    let header = lines.next().ok_or("recording is empty")??;
+    let header: Header = json::from_str(&header)?;
+    if header.version != asciicast::VERSION {
+        return Err(format!("unsupported asciicast version {}", header.version).into());
+    }
+    let idle_time_limit = options.idle_time_limit.or(header.idle_time_limit);
+
+    let size = window_size(header.width, header.height);
+    let mut terminal = Term::new(Config::default(), &size, VoidListener);
+    let mut parser = Processor::new();
+
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(b"\x1b[H\x1b[2J")?;
+
+    let start = Instant::now();
+    let mut position = 0.;
+    let mut last_time = 0.;
+    let mut dirty = false;
+
+    for line in lines {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (time, code, data): (f64, String, String) = json::from_str(&line)?;
+
+        // Wait for the event, skipping over long periods of inactivity.
+        let mut delay = (time - last_time).max(0.);
+        if let Some(idle_time_limit) = idle_time_limit {
+            delay = delay.min(idle_time_limit);
+        }
+        last_time = time;
+        position += delay / options.speed;
+
+        let target = Duration::from_secs_f64(position);
+        if let Some(remaining) = target.checked_sub(start.elapsed()) {
+            flush_sync(&mut parser, &mut terminal);
+            if dirty && !parser.sync_timeout().pending_timeout() {
+                draw(&mut stdout, &terminal)?;
+                dirty = false;
+            }
+
+            thread::sleep(remaining);
+        }
+
+        // Input is ignored, since it was already echoed by the application.
+        match code.as_str() {
+            "o" => {
+                flush_sync(&mut parser, &mut terminal);
+                for byte in data.bytes() {
+                    parser.advance(&mut terminal, byte);
+                }
+                dirty = true;
+            },
+            "r" => {
+                let size = data
+                    .split_once('x')
+                    .and_then(|(columns, lines)| Some((columns.parse().ok()?, lines.parse().ok()?)))
+                    .ok_or_else(|| format!("invalid resize event {data:?}"))?;
+                terminal.resize(window_size(size.0, size.1));
+                dirty = true;
+            },
+            _ => (),
+        }
+    }
+
+    parser.stop_sync(&mut terminal);
+    draw(&mut stdout, &terminal)?;
+
+    // Leave the cursor below the replayed screen.
+    write!(stdout, "\x1b[{};1H\x1b[?25h", terminal.screen_lines() + 1)?;
+    stdout.flush()?;
+
+    Ok(())
+}
+
+/// Terminal dimensions for a recorded size.
+fn window_size(columns: u16, lines: u16) -> WindowSize {
+    WindowSize { num_cols: columns.max(1), num_lines: lines.max(1), cell_width: 1, cell_height: 1 }
+}
+
+/// Apply a synchronized update once its timeout has expired.
+fn flush_sync(parser: &mut Processor, terminal: &mut Term<VoidListener>) {
+    let timeout = parser.sync_timeout().sync_timeout();
+    if timeout.is_some_and(|timeout| timeout <= Instant::now()) {
+        parser.stop_sync(terminal);
+    }
+}
+
+/// Draw the visible terminal content over the previous frame.
+fn draw(stdout: &mut impl Write, terminal: &Term<VoidListener>) -> io::Result<()> {
+    let mut frame = String::from("\x1b[?25l");
+    for line in (0..terminal.screen_lines() as i32).map(Line) {
+        let start = Point::new(line, Column(0));
+        let end = Point::new(line, terminal.last_column());
+        let text = terminal.bounds_to_ansi(start, end);
+        let _ = write!(frame, "\x1b[{};1H{text}\x1b[K", line.0 + 1);
+    }
+
+    // Clear lines left over from a taller screen.
+    frame.push_str("\x1b[J");
+
+    let cursor = terminal.grid().cursor.point;
+    let _ = write!(frame, "\x1b[{};{}H", cursor.line.0 + 1, cursor.column.0 + 1);
+    if terminal.mode().contains(TermMode::SHOW_CURSOR) {
+        frame.push_str("\x1b[?25h");
+    }
+
+    stdout.write_all(frame.as_bytes())?;
+    stdout.flush()
+}
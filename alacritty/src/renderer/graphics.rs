@@ -0,0 +1,323 @@
+//! Rendering of inline images.
+
+use std::collections::HashMap;
+use std::mem;
+use std::sync::{Arc, Weak};
+
+use ahash::RandomState;
+
+use alacritty_terminal::graphics::{GraphicCell, GraphicData, GraphicId};
+use alacritty_terminal::index::Point;
+
+use crate::display::content::RenderableCell;
+use crate::display::SizeInfo;
+use crate::gl;
+use crate::gl::types::*;
+use crate::renderer::shader::{ShaderProgram, ShaderVersion};
+use crate::renderer::{self, cstr};
+
+/// Shader sources for graphics rendering program.
+static GRAPHICS_SHADER_F: &str = include_str!("../../res/graphics.f.glsl");
+static GRAPHICS_SHADER_V: &str = include_str!("../../res/graphics.v.glsl");
+
+/// Part of an image covering a single visible cell.
+#[derive(Debug, Clone)]
+pub struct RenderGraphic {
+    pub point: Point<usize>,
+    pub graphic: GraphicCell,
+}
+
+/// Images visible in the current frame.
+#[derive(Default)]
+pub struct RenderGraphics {
+    pub(super) inner: Vec<RenderGraphic>,
+}
+
+impl RenderGraphics {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Update the stored graphics with the next cell info.
+    #[inline]
+    pub fn update(&mut self, cell: &RenderableCell) {
+        if let Some(graphic) = cell.extra.as_ref().and_then(|extra| extra.graphic.as_ref()) {
+            self.inner.push(RenderGraphic { point: cell.point, graphic: graphic.clone() });
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct Vertex {
+    // Normalized screen coordinates.
+    x: f32,
+    y: f32,
+
+    // Texture coordinates.
+    u: f32,
+    v: f32,
+}
+
+/// Image uploaded to the GPU.
+#[derive(Debug)]
+struct Texture {
+    id: GLuint,
+
+    /// Image the texture was created from.
+    ///
+    /// Once the terminal drops all references to the image, the texture can be deleted.
+    graphic: Weak<GraphicData>,
+}
+
+#[derive(Debug)]
+pub struct GraphicsRenderer {
+    // GL buffer objects.
+    vao: GLuint,
+    vbo: GLuint,
+
+    program: ShaderProgram,
+    textures: HashMap<GraphicId, Texture, RandomState>,
+    vertices: Vec<Vertex>,
+}
+
+impl GraphicsRenderer {
+    pub fn new(shader_version: ShaderVersion) -> Result<Self, renderer::Error> {
+        let mut vao: GLuint = 0;
+        let mut vbo: GLuint = 0;
+
+        let program =
+            ShaderProgram::new(shader_version, None, GRAPHICS_SHADER_V, GRAPHICS_SHADER_F)?;
+        let u_graphic = program.get_uniform_location(cstr!("graphic"))?;
+
+        unsafe {
+            // Sample images from the first texture unit.
+            gl::UseProgram(program.id());
+            gl::Uniform1i(u_graphic, 0);
+            gl::UseProgram(0);
+
+            // Allocate buffers.
+            gl::GenVertexArrays(1, &mut vao);
+            gl::GenBuffers(1, &mut vbo);
+
+            gl::BindVertexArray(vao);
+
+            // VBO binding is not part of VAO itself, but VBO binding is stored in attributes.
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+
+            // Position.
+            gl::VertexAttribPointer(
+                0,
+                2,
+                gl::FLOAT,
+                gl::FALSE,
+                mem::size_of::<Vertex>() as i32,
+                std::ptr::null(),
+            );
+            gl::EnableVertexAttribArray(0);
+
+            // Texture coordinates.
+            gl::VertexAttribPointer(
+                1,
+                2,
+                gl::FLOAT,
+                gl::FALSE,
+                mem::size_of::<Vertex>() as i32,
+                (mem::size_of::<f32>() * 2) as *const _,
+            );
+            gl::EnableVertexAttribArray(1);
+
+            // Reset buffer bindings.
+            gl::BindVertexArray(0);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+        }
+
+        Ok(Self { vao, vbo, program, textures: Default::default(), vertices: Vec::new() })
+    }
+
+    pub fn draw(&mut self, size_info: &SizeInfo, graphics: RenderGraphics) {
+        // Delete textures of images which are no longer referenced by the terminal.
+        self.textures.retain(|_, texture| {
+            let alive = texture.graphic.strong_count() > 0;
+            if !alive {
+                unsafe { gl::DeleteTextures(1, &texture.id) };
+            }
+            alive
+        });
+
+        if graphics.is_empty() {
+            return;
+        }
+
+        // Group cells by their image, to draw each image with a single call.
+        let mut graphics = graphics.inner;
+        graphics.sort_unstable_by_key(|graphic| graphic.graphic.placement.graphic.id);
+
+        unsafe {
+            // Bind VAO to enable vertex attribute slots.
+            gl::BindVertexArray(self.vao);
+
+            // Bind VBO only once for buffer data upload only.
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
+
+            gl::UseProgram(self.program.id());
+            gl::ActiveTexture(gl::TEXTURE0);
+        }
+
+        let half_width = size_info.width() / 2.;
+        let half_height = size_info.height() / 2.;
+
+        let mut start = 0;
+        while start < graphics.len() {
+            let graphic = &graphics[start].graphic.placement.graphic;
+            let end = graphics[start..]
+                .iter()
+                .position(|next| next.graphic.placement.graphic.id != graphic.id)
+                .map_or(graphics.len(), |len| start + len);
+
+            self.vertices.clear();
+            for graphic in &graphics[start..end] {
+                Self::add_cell(&mut self.vertices, size_info, half_width, half_height, graphic);
+            }
+
+            let texture = self.texture(graphic);
+
+            unsafe {
+                gl::BindTexture(gl::TEXTURE_2D, texture);
+
+                // Upload accumulated vertices.
+                gl::BufferData(
+                    gl::ARRAY_BUFFER,
+                    (self.vertices.len() * mem::size_of::<Vertex>()) as isize,
+                    self.vertices.as_ptr() as *const _,
+                    gl::STREAM_DRAW,
+                );
+
+                // Draw all vertices as list of triangles.
+                gl::DrawArrays(gl::TRIANGLES, 0, self.vertices.len() as i32);
+            }
+
+            start = end;
+        }
+
+        unsafe {
+            // Disable program.
+            gl::UseProgram(0);
+
+            // Reset buffer and texture bindings to nothing.
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            gl::BindVertexArray(0);
+        }
+    }
+
+    /// Get the texture for an image, uploading it if necessary.
+    fn texture(&mut self, graphic: &Arc<GraphicData>) -> GLuint {
+        if let Some(texture) = self.textures.get(&graphic.id) {
+            return texture.id;
+        }
+
+        let mut id: GLuint = 0;
+        unsafe {
+            gl::GenTextures(1, &mut id);
+            gl::BindTexture(gl::TEXTURE_2D, id);
+
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::RGBA as i32,
+                graphic.width as i32,
+                graphic.height as i32,
+                0,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                graphic.pixels.as_ptr() as *const _,
+            );
+        }
+
+        self.textures.insert(graphic.id, Texture { id, graphic: Arc::downgrade(graphic) });
+
+        id
+    }
+
+    /// Add the part of an image covering a cell.
+    fn add_cell(
+        vertices: &mut Vec<Vertex>,
+        size_info: &SizeInfo,
+        half_width: f32,
+        half_height: f32,
+        graphic: &RenderGraphic,
+    ) {
+        let placement = &graphic.graphic.placement;
+        let (cell_width, cell_height) = (size_info.cell_width(), size_info.cell_height());
+
+        // Cell position in pixels.
+        let cell_x = size_info.padding_x() + graphic.point.column.0 as f32 * cell_width;
+        let cell_y = size_info.padding_y() + graphic.point.line as f32 * cell_height;
+
+        // Image position in pixels.
+        let x = cell_x - graphic.graphic.column as f32 * cell_width + placement.offset_x as f32;
+        let y = cell_y - graphic.graphic.line as f32 * cell_height + placement.offset_y as f32;
+        let (width, height) = (placement.width as f32, placement.height as f32);
+
+        // Clip the image to the cell.
+        let left = x.max(cell_x);
+        let right = (x + width).min(cell_x + cell_width);
+        let top = y.max(cell_y);
+        let bottom = (y + height).min(cell_y + cell_height);
+        if left >= right || top >= bottom {
+            return;
+        }
+
+        // Map the clipped region to the displayed part of the texture.
+        let image_width = placement.graphic.width as f32;
+        let image_height = placement.graphic.height as f32;
+        let x_scale = placement.source_width as f32 / width;
+        let y_scale = placement.source_height as f32 / height;
+
+        // Calculate vertex positions in normalized device coordinates.
+        // NDC range from -1 to +1, with Y pointing up.
+        let vertex = |vertex_x: f32, vertex_y: f32| Vertex {
+            x: vertex_x / half_width - 1.0,
+            y: -vertex_y / half_height + 1.0,
+            u: (placement.source_x as f32 + (vertex_x - x) * x_scale) / image_width,
+            v: (placement.source_y as f32 + (vertex_y - y) * y_scale) / image_height,
+        };
+
+        // Make quad vertices.
+        let quad =
+            [vertex(left, top), vertex(left, bottom), vertex(right, top), vertex(right, bottom)];
+
+        // Append the vertices to form two triangles.
+        vertices.push(quad[0]);
+        vertices.push(quad[1]);
+        vertices.push(quad[2]);
+        vertices.push(quad[2]);
+        vertices.push(quad[3]);
+        vertices.push(quad[1]);
+    }
+}
+
+impl Drop for GraphicsRenderer {
+    fn drop(&mut self) {
+        unsafe {
+            for texture in self.textures.values() {
+                gl::DeleteTextures(1, &texture.id);
+            }
+
+            gl::DeleteBuffers(1, &self.vbo);
+            gl::DeleteVertexArrays(1, &self.vao);
+        }
+    }
+}
@@ -20,15 +20,19 @@ use crate::display::color::Rgb;
 use crate::display::content::RenderableCell;
 use crate::display::SizeInfo;
 use crate::gl;
+use crate::renderer::graphics::{GraphicsRenderer, RenderGraphics};
 use crate::renderer::rects::{RectRenderer, RenderRect};
 use crate::renderer::shader::ShaderError;
+use crate::renderer::software::SoftwareRenderer;
 
+pub mod graphics;
 pub mod platform;
 pub mod rects;
 mod shader;
+pub mod software;
 mod text;
 
-pub use text::{GlyphCache, LoaderApi};
+pub use text::{GlyphCache, LoaderApi, ShapedGlyph};
 
 use shader::ShaderVersion;
 use text::{Gles2Renderer, Glsl3Renderer, TextRenderer};
@@ -94,9 +98,19 @@ enum TextRendererProvider {
 }
 
 #[derive(Debug)]
-pub struct Renderer {
+pub enum Renderer {
+    /// GPU accelerated rendering using OpenGL.
+    Gl(GlRenderer),
+
+    /// CPU rendering into a framebuffer.
+    Software(SoftwareRenderer),
+}
+
+#[derive(Debug)]
+pub struct GlRenderer {
     text_renderer: TextRendererProvider,
     rect_renderer: RectRenderer,
+    graphics_renderer: GraphicsRenderer,
 }
 
 /// Wrapper around gl::GetString with error checking and reporting.
@@ -119,13 +133,145 @@ fn gl_get_string(
 }
 
 impl Renderer {
-    /// Create a new renderer.
+    /// Create a new OpenGL renderer.
     ///
     /// This will automatically pick between the GLES2 and GLSL3 renderer based on the GPU's
     /// supported OpenGL version.
     pub fn new(
         context: &PossiblyCurrentContext,
         renderer_preference: Option<RendererPreference>,
+    ) -> Result<Self, Error> {
+        GlRenderer::new(context, renderer_preference).map(Self::Gl)
+    }
+
+    /// Create a new software renderer.
+    pub fn software() -> Self {
+        Self::Software(SoftwareRenderer::new())
+    }
+
+    pub fn draw_cells<I: Iterator<Item = RenderableCell>>(
+        &mut self,
+        size_info: &SizeInfo,
+        glyph_cache: &mut GlyphCache,
+        cells: I,
+    ) {
+        match self {
+            Self::Gl(renderer) => renderer.draw_cells(size_info, glyph_cache, cells),
+            Self::Software(renderer) => renderer.draw_cells(size_info, glyph_cache, cells),
+        }
+    }
+
+    /// Draw a string in a variable location. Used for printing the render timer, warnings and
+    /// errors.
+    pub fn draw_string(
+        &mut self,
+        point: Point<usize>,
+        fg: Rgb,
+        bg: Rgb,
+        string_chars: impl Iterator<Item = char>,
+        size_info: &SizeInfo,
+        glyph_cache: &mut GlyphCache,
+    ) {
+        let mut skip_next = false;
+        let cells = string_chars.enumerate().filter_map(|(i, character)| {
+            if skip_next {
+                skip_next = false;
+                return None;
+            }
+
+            let mut flags = Flags::empty();
+            if character.width() == Some(2) {
+                flags.insert(Flags::WIDE_CHAR);
+                // Wide character is always followed by a spacer, so skip it.
+                skip_next = true;
+            }
+
+            Some(RenderableCell {
+                point: Point::new(point.line, point.column + i),
+                character,
+                extra: None,
+                flags: Flags::empty(),
+                bg_alpha: 1.0,
+                fg,
+                bg,
+                underline: fg,
+            })
+        });
+
+        self.draw_cells(size_info, glyph_cache, cells);
+    }
+
+    pub fn with_loader<F, T>(&mut self, func: F) -> T
+    where
+        F: FnOnce(LoaderApi<'_>) -> T,
+    {
+        match self {
+            Self::Gl(renderer) => renderer.with_loader(func),
+            Self::Software(renderer) => renderer.with_loader(func),
+        }
+    }
+
+    /// Draw all rectangles simultaneously to prevent excessive program swaps.
+    pub fn draw_rects(&mut self, size_info: &SizeInfo, metrics: &Metrics, rects: Vec<RenderRect>) {
+        match self {
+            Self::Gl(renderer) => renderer.draw_rects(size_info, metrics, rects),
+            Self::Software(renderer) => renderer.draw_rects(size_info, metrics, rects),
+        }
+    }
+
+    /// Draw all visible images.
+    pub fn draw_graphics(&mut self, size_info: &SizeInfo, graphics: RenderGraphics) {
+        match self {
+            Self::Gl(renderer) => renderer.draw_graphics(size_info, graphics),
+            Self::Software(renderer) => renderer.draw_graphics(size_info, graphics),
+        }
+    }
+
+    /// Fill the window with `color` and `alpha`.
+    pub fn clear(&mut self, color: Rgb, alpha: f32) {
+        match self {
+            Self::Gl(renderer) => renderer.clear(color, alpha),
+            Self::Software(renderer) => renderer.clear(color),
+        }
+    }
+
+    pub fn finish(&self) {
+        if let Self::Gl(renderer) = self {
+            renderer.finish();
+        }
+    }
+
+    /// Read the pixels of the current frame.
+    ///
+    /// The pixels are returned as opaque RGBA, starting at the top-left corner.
+    pub fn read_pixels(&self, size_info: &SizeInfo) -> Vec<u8> {
+        match self {
+            Self::Gl(renderer) => renderer.read_pixels(size_info),
+            Self::Software(renderer) => renderer.framebuffer().to_rgba(),
+        }
+    }
+
+    /// Set the viewport for cell rendering.
+    #[inline]
+    pub fn set_viewport(&self, size: &SizeInfo) {
+        if let Self::Gl(renderer) = self {
+            renderer.set_viewport(size);
+        }
+    }
+
+    /// Resize the renderer.
+    pub fn resize(&mut self, size_info: &SizeInfo) {
+        match self {
+            Self::Gl(renderer) => renderer.resize(size_info),
+            Self::Software(renderer) => renderer.resize(size_info),
+        }
+    }
+}
+
+impl GlRenderer {
+    fn new(
+        context: &PossiblyCurrentContext,
+        renderer_preference: Option<RendererPreference>,
     ) -> Result<Self, Error> {
         // We need to load OpenGL functions once per instance, but only after we make our context
         // current due to WGL limitations.
@@ -151,18 +297,22 @@ impl Renderer {
             Some(RendererPreference::Glsl3) => (true, true),
             Some(RendererPreference::Gles2) => (false, true),
             Some(RendererPreference::Gles2Pure) => (false, false),
-            None => (shader_version.as_ref() >= "3.3" && !is_gles_context, true),
+            Some(RendererPreference::Software) | None => {
+                (shader_version.as_ref() >= "3.3" && !is_gles_context, true)
+            },
         };
 
-        let (text_renderer, rect_renderer) = if use_glsl3 {
+        let (text_renderer, rect_renderer, graphics_renderer) = if use_glsl3 {
             let text_renderer = TextRendererProvider::Glsl3(Glsl3Renderer::new()?);
             let rect_renderer = RectRenderer::new(ShaderVersion::Glsl3)?;
-            (text_renderer, rect_renderer)
+            let graphics_renderer = GraphicsRenderer::new(ShaderVersion::Glsl3)?;
+            (text_renderer, rect_renderer, graphics_renderer)
         } else {
             let text_renderer =
                 TextRendererProvider::Gles2(Gles2Renderer::new(allow_dsb, is_gles_context)?);
             let rect_renderer = RectRenderer::new(ShaderVersion::Gles2)?;
-            (text_renderer, rect_renderer)
+            let graphics_renderer = GraphicsRenderer::new(ShaderVersion::Gles2)?;
+            (text_renderer, rect_renderer, graphics_renderer)
         };
 
         // Enable debug logging for OpenGL as well.
@@ -175,7 +325,7 @@ impl Renderer {
             }
         }
 
-        Ok(Self { text_renderer, rect_renderer })
+        Ok(Self { text_renderer, rect_renderer, graphics_renderer })
     }
 
     pub fn draw_cells<I: Iterator<Item = RenderableCell>>(
@@ -194,46 +344,6 @@ impl Renderer {
         }
     }
 
-    /// Draw a string in a variable location. Used for printing the render timer, warnings and
-    /// errors.
-    pub fn draw_string(
-        &mut self,
-        point: Point<usize>,
-        fg: Rgb,
-        bg: Rgb,
-        string_chars: impl Iterator<Item = char>,
-        size_info: &SizeInfo,
-        glyph_cache: &mut GlyphCache,
-    ) {
-        let mut skip_next = false;
-        let cells = string_chars.enumerate().filter_map(|(i, character)| {
-            if skip_next {
-                skip_next = false;
-                return None;
-            }
-
-            let mut flags = Flags::empty();
-            if character.width() == Some(2) {
-                flags.insert(Flags::WIDE_CHAR);
-                // Wide character is always followed by a spacer, so skip it.
-                skip_next = true;
-            }
-
-            Some(RenderableCell {
-                point: Point::new(point.line, point.column + i),
-                character,
-                extra: None,
-                flags: Flags::empty(),
-                bg_alpha: 1.0,
-                fg,
-                bg,
-                underline: fg,
-            })
-        });
-
-        self.draw_cells(size_info, glyph_cache, cells);
-    }
-
     pub fn with_loader<F, T>(&mut self, func: F) -> T
     where
         F: FnOnce(LoaderApi<'_>) -> T,
@@ -269,6 +379,28 @@ impl Renderer {
         }
     }
 
+    /// Draw all visible images.
+    pub fn draw_graphics(&mut self, size_info: &SizeInfo, graphics: RenderGraphics) {
+        // Prepare graphics rendering state.
+        unsafe {
+            // Remove padding from viewport.
+            gl::Viewport(0, 0, size_info.width() as i32, size_info.height() as i32);
+            gl::BlendFuncSeparate(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA, gl::SRC_ALPHA, gl::ONE);
+        }
+
+        // Always call into the graphics renderer, so it can release unused textures.
+        self.graphics_renderer.draw(size_info, graphics);
+
+        // Activate regular state again.
+        unsafe {
+            // Reset blending strategy.
+            gl::BlendFunc(gl::SRC1_COLOR, gl::ONE_MINUS_SRC1_COLOR);
+
+            // Restore viewport with padding.
+            self.set_viewport(size_info);
+        }
+    }
+
     /// Fill the window with `color` and `alpha`.
     pub fn clear(&self, color: Rgb, alpha: f32) {
         unsafe {
@@ -288,6 +420,39 @@ impl Renderer {
         }
     }
 
+    fn read_pixels(&self, size_info: &SizeInfo) -> Vec<u8> {
+        let width = size_info.width() as usize;
+        let height = size_info.height() as usize;
+        let mut pixels = vec![0u8; width * height * 4];
+
+        unsafe {
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            gl::ReadPixels(
+                0,
+                0,
+                width as i32,
+                height as i32,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                pixels.as_mut_ptr() as *mut _,
+            );
+        }
+
+        // OpenGL starts at the bottom-left corner.
+        let stride = width * 4;
+        let mut flipped = Vec::with_capacity(pixels.len());
+        for row in pixels.chunks_exact(stride).rev() {
+            flipped.extend_from_slice(row);
+        }
+
+        // Ignore window opacity, since the colors are premultiplied.
+        for alpha in flipped.iter_mut().skip(3).step_by(4) {
+            *alpha = u8::MAX;
+        }
+
+        flipped
+    }
+
     /// Set the viewport for cell rendering.
     #[inline]
     pub fn set_viewport(&self, size: &SizeInfo) {
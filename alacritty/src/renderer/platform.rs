@@ -11,12 +11,16 @@ use glutin::error::Result as GlutinResult;
 use glutin::prelude::*;
 use glutin::surface::{Surface, SurfaceAttributesBuilder, WindowSurface};
 use log::{debug, LevelFilter};
+use softbuffer::SoftBufferError;
 
 use raw_window_handle::{RawDisplayHandle, RawWindowHandle};
 use winit::dpi::PhysicalSize;
 #[cfg(all(feature = "x11", not(any(target_os = "macos", windows))))]
 use winit::platform::x11;
 
+use crate::display::window::Window;
+use crate::renderer::software::Framebuffer;
+
 /// Create the GL display.
 pub fn create_gl_display(
     raw_display_handle: RawDisplayHandle,
@@ -159,3 +163,33 @@ pub fn create_gl_surface(
     // Create the GL surface to draw into.
     unsafe { gl_display.create_window_surface(&gl_config, &surface_attributes) }
 }
+
+/// Create the shared memory surface used by the software renderer.
+pub fn create_software_surface(
+    window: &Window,
+) -> Result<(softbuffer::Context, softbuffer::Surface), SoftBufferError> {
+    // SAFETY: The display owns both the surface and the window, and drops the surface first.
+    let context = unsafe { softbuffer::Context::new(window)? };
+    let mut surface = unsafe { softbuffer::Surface::new(&context, window)? };
+
+    let size = window.inner_size();
+    let width = NonZeroU32::new(size.width.max(1)).unwrap();
+    let height = NonZeroU32::new(size.height.max(1)).unwrap();
+    surface.resize(width, height)?;
+
+    Ok((context, surface))
+}
+
+/// Copy the software renderer's framebuffer to the window.
+pub fn present_software(
+    surface: &mut softbuffer::Surface,
+    framebuffer: &Framebuffer,
+) -> Result<(), SoftBufferError> {
+    let mut buffer = surface.buffer_mut()?;
+
+    // Sizes only differ briefly while a resize is pending.
+    let len = buffer.len().min(framebuffer.pixels().len());
+    buffer[..len].copy_from_slice(&framebuffer.pixels()[..len]);
+
+    buffer.present()
+}
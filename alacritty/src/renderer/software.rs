@@ -0,0 +1,756 @@
+//! Software rendering into a CPU framebuffer.
+//!
+//! This renderer does not depend on OpenGL, which allows running Alacritty on systems without
+//! working graphics drivers. Its output mirrors the OpenGL renderers, so it can also be used to
+//! inspect rendered frames pixel by pixel.
+
+use std::f32::consts::PI;
+
+use crossfont::{BitmapBuffer, GlyphKey, Metrics, RasterizedGlyph};
+
+use alacritty_terminal::term::cell::Flags;
+
+use crate::display::color::Rgb;
+use crate::display::content::RenderableCell;
+use crate::display::SizeInfo;
+use crate::renderer::graphics::{RenderGraphic, RenderGraphics};
+use crate::renderer::rects::{RectKind, RenderRect};
+use crate::renderer::text::glyph_cache::{Glyph, LoadGlyph};
+use crate::renderer::{GlyphCache, LoaderApi};
+
+/// Renderer drawing into a [`Framebuffer`] without GPU acceleration.
+#[derive(Debug, Default)]
+pub struct SoftwareRenderer {
+    framebuffer: Framebuffer,
+    glyphs: GlyphStore,
+}
+
+impl SoftwareRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Last rendered frame.
+    #[inline]
+    pub fn framebuffer(&self) -> &Framebuffer {
+        &self.framebuffer
+    }
+
+    pub fn draw_cells<I: Iterator<Item = RenderableCell>>(
+        &mut self,
+        size_info: &SizeInfo,
+        glyph_cache: &mut GlyphCache,
+        cells: I,
+    ) {
+        // Glyphs can exceed their cell, so all backgrounds are drawn before the first glyph.
+        let cells: Vec<_> = cells.collect();
+        let clip = TextArea::new(size_info);
+
+        for cell in cells.iter().filter(|cell| cell.bg_alpha > 0.) {
+            let columns = if cell.flags.contains(Flags::WIDE_CHAR) { 2. } else { 1. };
+            let x = clip.x + cell.point.column.0 as f32 * size_info.cell_width();
+            let y = clip.y + cell.point.line as f32 * size_info.cell_height();
+            let width = columns * size_info.cell_width();
+            let rect =
+                RenderRect::new(x, y, width, size_info.cell_height(), cell.bg, cell.bg_alpha);
+            self.framebuffer.fill_rect(&rect, &clip);
+        }
+
+        for cell in cells {
+            self.draw_cell(cell, glyph_cache, size_info, &clip);
+        }
+    }
+
+    /// Draw the glyphs of a single cell.
+    fn draw_cell(
+        &mut self,
+        mut cell: RenderableCell,
+        glyph_cache: &mut GlyphCache,
+        size_info: &SizeInfo,
+        clip: &TextArea,
+    ) {
+        // Get font key for cell.
+        let font_key = match cell.flags & Flags::BOLD_ITALIC {
+            Flags::BOLD_ITALIC => glyph_cache.bold_italic_key,
+            Flags::ITALIC => glyph_cache.italic_key,
+            Flags::BOLD => glyph_cache.bold_key,
+            _ => glyph_cache.font_key,
+        };
+
+        // Ignore hidden cells and render tabs as spaces to prevent font issues.
+        let hidden = cell.flags.contains(Flags::HIDDEN);
+        if cell.character == '\t' || hidden {
+            cell.character = ' ';
+        }
+
+        // Render glyphs selected by text shaping.
+        match cell.extra.as_mut().and_then(|extra| extra.glyphs.take()) {
+            // Cells merged into a ligature have no glyph of their own.
+            Some(glyphs) if glyphs.is_empty() => return,
+            Some(glyphs) => {
+                for glyph in glyphs {
+                    let glyph = glyph_cache.get_shaped(cell.flags, glyph, &mut self.glyphs);
+                    self.draw_glyph(&cell, &glyph, size_info, clip);
+                }
+                return;
+            },
+            None => (),
+        }
+
+        let mut glyph_key =
+            GlyphKey { font_key, size: glyph_cache.font_size, character: cell.character };
+
+        let glyph = glyph_cache.get(glyph_key, &mut self.glyphs, true);
+        self.draw_glyph(&cell, &glyph, size_info, clip);
+
+        // Render visible zero-width characters.
+        if let Some(zerowidth) =
+            cell.extra.as_mut().and_then(|extra| extra.zerowidth.take().filter(|_| !hidden))
+        {
+            for character in zerowidth {
+                glyph_key.character = character;
+                let glyph = glyph_cache.get(glyph_key, &mut self.glyphs, false);
+                self.draw_glyph(&cell, &glyph, size_info, clip);
+            }
+        }
+    }
+
+    /// Blend a loaded glyph into the framebuffer.
+    fn draw_glyph(
+        &mut self,
+        cell: &RenderableCell,
+        glyph: &Glyph,
+        size_info: &SizeInfo,
+        clip: &TextArea,
+    ) {
+        let bitmap = match self.glyphs.get(glyph) {
+            Some(bitmap) => bitmap,
+            None => return,
+        };
+
+        let cell_x = clip.x + cell.point.column.0 as f32 * size_info.cell_width();
+        let cell_y = clip.y + cell.point.line as f32 * size_info.cell_height();
+        let x = (cell_x + f32::from(glyph.left)) as i32;
+        let y = (cell_y + size_info.cell_height() - f32::from(glyph.top)) as i32;
+
+        let framebuffer = &mut self.framebuffer;
+        for row in 0..bitmap.height {
+            for column in 0..bitmap.width {
+                let (pixel_x, pixel_y) = (x + column as i32, y + row as i32);
+                if !clip.contains(pixel_x, pixel_y) {
+                    continue;
+                }
+
+                let index = row * bitmap.width + column;
+                match &bitmap.buffer {
+                    BitmapBuffer::Rgb(buffer) => {
+                        let coverage = &buffer[index * 3..index * 3 + 3];
+                        let coverage = [coverage[0], coverage[1], coverage[2]];
+                        framebuffer.blend(pixel_x, pixel_y, cell.fg, coverage);
+                    },
+                    BitmapBuffer::Rgba(buffer) => {
+                        let pixel = &buffer[index * 4..index * 4 + 4];
+                        let color = Rgb::new(pixel[0], pixel[1], pixel[2]);
+                        framebuffer.blend_premultiplied(pixel_x, pixel_y, color, pixel[3]);
+                    },
+                }
+            }
+        }
+    }
+
+    pub fn with_loader<F, T>(&mut self, func: F) -> T
+    where
+        F: FnOnce(LoaderApi<'_>) -> T,
+    {
+        func(LoaderApi::Software(&mut self.glyphs))
+    }
+
+    /// Draw all rectangles, using the same order as the OpenGL renderers.
+    pub fn draw_rects(&mut self, size_info: &SizeInfo, metrics: &Metrics, rects: Vec<RenderRect>) {
+        let clip = TextArea::window(size_info);
+        let pattern = LinePattern::new(size_info, metrics);
+
+        // Plain rects are drawn last, to put the visual bell and damage rects above the lines.
+        for kind in [RectKind::DashedUnderline, RectKind::DottedUnderline, RectKind::Undercurl] {
+            for rect in rects.iter().filter(|rect| rect.kind == kind) {
+                self.framebuffer.fill_pattern(rect, &clip, |x, y| pattern.alpha(kind, x, y));
+            }
+        }
+
+        for rect in rects.iter().filter(|rect| rect.kind == RectKind::Normal) {
+            self.framebuffer.fill_rect(rect, &clip);
+        }
+    }
+
+    /// Draw all visible images.
+    pub fn draw_graphics(&mut self, size_info: &SizeInfo, graphics: RenderGraphics) {
+        for graphic in &graphics.inner {
+            self.draw_graphic(size_info, graphic);
+        }
+    }
+
+    /// Draw the part of an image covering a single cell.
+    fn draw_graphic(&mut self, size_info: &SizeInfo, graphic: &RenderGraphic) {
+        let placement = &graphic.graphic.placement;
+        let data = &placement.graphic;
+        let (cell_width, cell_height) = (size_info.cell_width(), size_info.cell_height());
+
+        // Cell position in pixels.
+        let cell_x = size_info.padding_x() + graphic.point.column.0 as f32 * cell_width;
+        let cell_y = size_info.padding_y() + graphic.point.line as f32 * cell_height;
+
+        // Image position in pixels.
+        let x = cell_x - graphic.graphic.column as f32 * cell_width + placement.offset_x as f32;
+        let y = cell_y - graphic.graphic.line as f32 * cell_height + placement.offset_y as f32;
+
+        let x_scale = placement.source_width as f32 / placement.width as f32;
+        let y_scale = placement.source_height as f32 / placement.height as f32;
+
+        // Clip the image to the cell.
+        let left = x.max(cell_x).round() as i32;
+        let right = (x + placement.width as f32).min(cell_x + cell_width).round() as i32;
+        let top = y.max(cell_y).round() as i32;
+        let bottom = (y + placement.height as f32).min(cell_y + cell_height).round() as i32;
+
+        for pixel_y in top..bottom {
+            let source_y = placement.source_y as f32 + (pixel_y as f32 + 0.5 - y) * y_scale;
+            let source_y = (source_y as usize).min(data.height - 1);
+
+            for pixel_x in left..right {
+                let source_x = placement.source_x as f32 + (pixel_x as f32 + 0.5 - x) * x_scale;
+                let source_x = (source_x as usize).min(data.width - 1);
+
+                let index = (source_y * data.width + source_x) * 4;
+                let pixel = &data.pixels[index..index + 4];
+                let color = Rgb::new(pixel[0], pixel[1], pixel[2]);
+                let alpha = pixel[3];
+                self.framebuffer.blend(pixel_x, pixel_y, color, [alpha; 3]);
+            }
+        }
+    }
+
+    /// Fill the framebuffer with `color`.
+    ///
+    /// The framebuffer has no alpha channel, so the window opacity is ignored.
+    pub fn clear(&mut self, color: Rgb) {
+        self.framebuffer.pixels.fill(pack(color));
+    }
+
+    /// Resize the framebuffer to the window dimensions.
+    pub fn resize(&mut self, size_info: &SizeInfo) {
+        self.framebuffer.resize(size_info.width() as usize, size_info.height() as usize);
+    }
+}
+
+/// Pixel buffer using one `0x00RRGGBB` value per pixel.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    pixels: Vec<u32>,
+}
+
+impl Framebuffer {
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Pixels in row-major order.
+    #[inline]
+    pub fn pixels(&self) -> &[u32] {
+        &self.pixels
+    }
+
+    /// Color of a single pixel.
+    pub fn pixel(&self, x: usize, y: usize) -> Rgb {
+        let pixel = self.pixels[y * self.width + x];
+        Rgb::new((pixel >> 16) as u8, (pixel >> 8) as u8, pixel as u8)
+    }
+
+    /// Pixels as opaque RGBA.
+    pub fn to_rgba(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|pixel| [(pixel >> 16) as u8, (pixel >> 8) as u8, *pixel as u8, u8::MAX])
+            .collect()
+    }
+
+    fn resize(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+        self.pixels.resize(width * height, 0);
+    }
+
+    /// Blend `color` into a pixel, using separate coverage for each channel.
+    fn blend(&mut self, x: i32, y: i32, color: Rgb, coverage: [u8; 3]) {
+        let index = match self.index(x, y) {
+            Some(index) => index,
+            None => return,
+        };
+
+        let dst = self.pixels[index];
+        let channel = |shift: u32, src: u8, coverage: u8| {
+            let (src, coverage) = (u32::from(src), u32::from(coverage));
+            let dst = (dst >> shift) & 0xff;
+            ((src * coverage + dst * (255 - coverage) + 127) / 255) << shift
+        };
+
+        self.pixels[index] = channel(16, color.r, coverage[0])
+            | channel(8, color.g, coverage[1])
+            | channel(0, color.b, coverage[2]);
+    }
+
+    /// Blend a color with premultiplied alpha into a pixel.
+    fn blend_premultiplied(&mut self, x: i32, y: i32, color: Rgb, alpha: u8) {
+        let index = match self.index(x, y) {
+            Some(index) => index,
+            None => return,
+        };
+
+        let dst = self.pixels[index];
+        let channel = |shift: u32, src: u8| {
+            let dst = (dst >> shift) & 0xff;
+            let value = u32::from(src) + (dst * (255 - u32::from(alpha)) + 127) / 255;
+            value.min(255) << shift
+        };
+
+        self.pixels[index] = channel(16, color.r) | channel(8, color.g) | channel(0, color.b);
+    }
+
+    /// Fill a rectangle, ignoring its kind.
+    fn fill_rect(&mut self, rect: &RenderRect, clip: &TextArea) {
+        self.fill_pattern(rect, clip, |_, _| 1.);
+    }
+
+    /// Fill a rectangle, using `alpha` to get the opacity at each pixel.
+    fn fill_pattern<F: Fn(i32, i32) -> f32>(
+        &mut self,
+        rect: &RenderRect,
+        clip: &TextArea,
+        alpha: F,
+    ) {
+        // Pixels are covered when their center is inside the rectangle.
+        let left = (rect.x.round() as i32).max(clip.x as i32);
+        let right = ((rect.x + rect.width).round() as i32).min(clip.right as i32);
+        let top = (rect.y.round() as i32).max(clip.y as i32);
+        let bottom = ((rect.y + rect.height).round() as i32).min(clip.bottom as i32);
+
+        for y in top..bottom {
+            for x in left..right {
+                let alpha = (alpha(x, y) * rect.alpha).clamp(0., 1.);
+                let coverage = (alpha * 255.).round() as u8;
+                self.blend(x, y, rect.color, [coverage; 3]);
+            }
+        }
+    }
+
+    /// Index of a pixel, if it is inside the framebuffer.
+    #[inline]
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        let (x, y) = (usize::try_from(x).ok()?, usize::try_from(y).ok()?);
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+}
+
+/// Region of the window pixels are drawn into.
+#[derive(Debug)]
+struct TextArea {
+    x: f32,
+    y: f32,
+    right: f32,
+    bottom: f32,
+}
+
+impl TextArea {
+    /// Area of the window without padding.
+    fn new(size_info: &SizeInfo) -> Self {
+        Self {
+            x: size_info.padding_x(),
+            y: size_info.padding_y(),
+            right: size_info.width() - size_info.padding_x(),
+            bottom: size_info.height() - size_info.padding_y(),
+        }
+    }
+
+    /// Area of the entire window.
+    fn window(size_info: &SizeInfo) -> Self {
+        Self { x: 0., y: 0., right: size_info.width(), bottom: size_info.height() }
+    }
+
+    #[inline]
+    fn contains(&self, x: i32, y: i32) -> bool {
+        let (x, y) = (x as f32, y as f32);
+        x >= self.x.floor() && x < self.right && y >= self.y.floor() && y < self.bottom
+    }
+}
+
+/// Parameters for drawing the patterns of underline rects.
+///
+/// This matches the rect shaders used by the OpenGL renderers.
+#[derive(Debug)]
+struct LinePattern {
+    cell_width: f32,
+    cell_height: f32,
+    padding_x: f32,
+    padding_bottom: f32,
+    window_height: f32,
+    underline_position: f32,
+    underline_thickness: f32,
+    undercurl_position: f32,
+}
+
+impl LinePattern {
+    fn new(size_info: &SizeInfo, metrics: &Metrics) -> Self {
+        let viewport_height = size_info.height() - size_info.padding_y();
+        let padding_bottom = viewport_height
+            - (viewport_height / size_info.cell_height()).floor() * size_info.cell_height();
+
+        Self {
+            padding_bottom,
+            cell_width: size_info.cell_width(),
+            cell_height: size_info.cell_height(),
+            padding_x: size_info.padding_x(),
+            window_height: size_info.height(),
+            underline_position: metrics.descent.abs() - metrics.underline_position.abs(),
+            underline_thickness: metrics.underline_thickness,
+            undercurl_position: (0.5 * metrics.descent).abs(),
+        }
+    }
+
+    /// Opacity of the pattern at a window pixel.
+    fn alpha(&self, kind: RectKind, x: i32, y: i32) -> f32 {
+        // Convert to cell coordinates with the Y axis pointing up.
+        let fragment_x = x as f32 + 0.5;
+        let fragment_y = self.window_height - y as f32 - 0.5;
+        let cell_x = (fragment_x - self.padding_x).rem_euclid(self.cell_width).floor();
+        let cell_y = (fragment_y - self.padding_bottom).rem_euclid(self.cell_height).floor();
+
+        match kind {
+            RectKind::Undercurl => self.undercurl(cell_x, cell_y),
+            RectKind::DottedUnderline if self.underline_thickness < 2. => {
+                self.dotted(fragment_x, cell_x, cell_y)
+            },
+            RectKind::DottedUnderline => self.dotted_aliased(cell_x, cell_y),
+            RectKind::DashedUnderline => self.dashed(cell_x),
+            RectKind::Normal | RectKind::NumKinds => 1.,
+        }
+    }
+
+    fn undercurl(&self, x: f32, y: f32) -> f32 {
+        // The undercurl position is used as amplitude, since it's half of the descent.
+        let position = self.undercurl_position;
+        let undercurl =
+            position / 2. * ((x + 0.5) * 2. * PI / self.cell_width).cos() + position - 1.;
+
+        let half_thickness = (self.underline_thickness - 1.).max(0.) / 2.;
+        let top = undercurl + half_thickness;
+        let bottom = undercurl - half_thickness;
+
+        let distance = (y - top).max((bottom - y).max(0.));
+        1. - distance * distance
+    }
+
+    fn dotted(&self, fragment_x: f32, x: f32, y: f32) -> f32 {
+        // Invert the pattern every other cell to keep the spacing even with odd cell widths.
+        let mut cell_even = 0.;
+        if self.cell_width as i32 % 2 != 0 {
+            cell_even = ((fragment_x - self.padding_x) / self.cell_width).rem_euclid(2.);
+        }
+
+        if x.rem_euclid(2.) as i32 != cell_even as i32 {
+            return 0.;
+        }
+
+        1. - (self.underline_position.floor() - y).abs()
+    }
+
+    fn dotted_aliased(&self, x: f32, y: f32) -> f32 {
+        let thickness = self.underline_thickness;
+        let dot_number = (x / thickness).floor();
+
+        let radius = thickness / 2.;
+        let center_y = self.underline_position - 1.;
+
+        let left_center = (dot_number - dot_number.rem_euclid(2.)) * thickness + radius;
+        let right_center = left_center + 2. * thickness;
+
+        let distance_left = (x - left_center).hypot(y - center_y);
+        let distance_right = (x - right_center).hypot(y - center_y);
+
+        (1. - (distance_left.min(distance_right) - radius)).max(0.)
+    }
+
+    fn dashed(&self, x: f32) -> f32 {
+        // Dashes of adjacent cells connect, so the dash length is half of the total length.
+        let half_dash_len = (self.cell_width / 4. + 0.5).floor();
+
+        if x > half_dash_len - 1. && x < self.cell_width - half_dash_len {
+            0.
+        } else {
+            1.
+        }
+    }
+}
+
+/// Rasterized glyphs loaded by the software renderer.
+#[derive(Debug, Default)]
+pub struct GlyphStore {
+    glyphs: Vec<RasterizedGlyph>,
+}
+
+impl GlyphStore {
+    /// Get the bitmap of a loaded glyph.
+    fn get(&self, glyph: &Glyph) -> Option<&GlyphBitmap> {
+        self.glyphs.get(glyph.tex_id as usize).map(GlyphBitmap::from)
+    }
+}
+
+impl LoadGlyph for GlyphStore {
+    fn load_glyph(&mut self, rasterized: &RasterizedGlyph) -> Glyph {
+        // The texture ID is used as index into the loaded glyphs.
+        let tex_id = self.glyphs.len() as u32;
+        self.glyphs.push(rasterized.clone());
+
+        Glyph {
+            tex_id,
+            multicolor: matches!(rasterized.buffer, BitmapBuffer::Rgba(_)),
+            top: rasterized.top as i16,
+            left: rasterized.left as i16,
+            width: rasterized.width as i16,
+            height: rasterized.height as i16,
+            uv_bot: 0.,
+            uv_left: 0.,
+            uv_width: 0.,
+            uv_height: 0.,
+        }
+    }
+
+    fn clear(&mut self) {
+        self.glyphs.clear();
+    }
+}
+
+/// Pixel data of a loaded glyph.
+#[derive(Debug)]
+struct GlyphBitmap<'a> {
+    width: usize,
+    height: usize,
+    buffer: &'a BitmapBuffer,
+}
+
+impl<'a> From<&'a RasterizedGlyph> for GlyphBitmap<'a> {
+    fn from(glyph: &'a RasterizedGlyph) -> Self {
+        Self {
+            width: glyph.width.max(0) as usize,
+            height: glyph.height.max(0) as usize,
+            buffer: &glyph.buffer,
+        }
+    }
+}
+
+/// Convert a color to the framebuffer's pixel format.
+#[inline]
+fn pack(color: Rgb) -> u32 {
+    (u32::from(color.r) << 16) | (u32::from(color.g) << 8) | u32::from(color.b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alacritty_terminal::index::{Column, Point};
+
+    const BLACK: Rgb = Rgb::new(0, 0, 0);
+    const WHITE: Rgb = Rgb::new(255, 255, 255);
+    const RED: Rgb = Rgb::new(255, 0, 0);
+
+    /// Window with 4x2 cells of 10x20 pixels and 5 pixels of padding.
+    fn size_info() -> SizeInfo {
+        SizeInfo::new(50., 50., 10., 20., 5., 5., false)
+    }
+
+    fn renderer(size_info: &SizeInfo) -> SoftwareRenderer {
+        let mut renderer = SoftwareRenderer::new();
+        renderer.resize(size_info);
+        renderer.clear(BLACK);
+        renderer
+    }
+
+    fn cell(column: usize, character: char) -> RenderableCell {
+        RenderableCell {
+            character,
+            point: Point::new(0, Column(column)),
+            fg: WHITE,
+            bg: RED,
+            bg_alpha: 0.,
+            underline: WHITE,
+            flags: Flags::empty(),
+            extra: None,
+        }
+    }
+
+    #[test]
+    fn clear_and_resize() {
+        let size_info = size_info();
+        let renderer = renderer(&size_info);
+
+        let framebuffer = renderer.framebuffer();
+        assert_eq!((framebuffer.width(), framebuffer.height()), (50, 50));
+        assert!(framebuffer.pixels().iter().all(|pixel| *pixel == 0));
+    }
+
+    #[test]
+    fn rgba_pixels() {
+        let size_info = size_info();
+        let mut renderer = renderer(&size_info);
+        renderer.clear(Rgb::new(1, 2, 3));
+
+        let rgba = renderer.framebuffer().to_rgba();
+        assert_eq!(rgba.len(), 50 * 50 * 4);
+        assert_eq!(&rgba[..8], &[1, 2, 3, 255, 1, 2, 3, 255]);
+    }
+
+    #[test]
+    fn rect_blending() {
+        let size_info = size_info();
+        let mut renderer = renderer(&size_info);
+        renderer.clear(WHITE);
+
+        let clip = TextArea::window(&size_info);
+        let rect = RenderRect::new(10., 10., 5., 5., BLACK, 0.5);
+        renderer.framebuffer.fill_rect(&rect, &clip);
+
+        let framebuffer = renderer.framebuffer();
+        assert_eq!(framebuffer.pixel(10, 10), Rgb::new(127, 127, 127));
+        assert_eq!(framebuffer.pixel(14, 14), Rgb::new(127, 127, 127));
+        assert_eq!(framebuffer.pixel(15, 14), WHITE);
+        assert_eq!(framebuffer.pixel(9, 10), WHITE);
+    }
+
+    #[test]
+    fn rect_clipped_to_window() {
+        let size_info = size_info();
+        let mut renderer = renderer(&size_info);
+
+        let clip = TextArea::window(&size_info);
+        let rect = RenderRect::new(-10., 45., 100., 100., RED, 1.);
+        renderer.framebuffer.fill_rect(&rect, &clip);
+
+        let framebuffer = renderer.framebuffer();
+        assert_eq!(framebuffer.pixel(0, 49), RED);
+        assert_eq!(framebuffer.pixel(49, 45), RED);
+        assert_eq!(framebuffer.pixel(49, 44), BLACK);
+    }
+
+    #[test]
+    fn subpixel_glyph() {
+        let size_info = size_info();
+        let mut renderer = renderer(&size_info);
+
+        // Glyph with a red, half covered and fully covered pixel.
+        let rasterized = RasterizedGlyph {
+            character: 'x',
+            width: 3,
+            height: 1,
+            top: 5,
+            left: 1,
+            advance: (10, 0),
+            buffer: BitmapBuffer::Rgb(vec![255, 0, 0, 128, 128, 128, 255, 255, 255]),
+        };
+        let glyph = renderer.glyphs.load_glyph(&rasterized);
+
+        let clip = TextArea::new(&size_info);
+        renderer.draw_glyph(&cell(1, 'x'), &glyph, &size_info, &clip);
+
+        // Glyph starts at padding + column + left, and `top` pixels above the cell bottom.
+        let framebuffer = renderer.framebuffer();
+        assert_eq!(framebuffer.pixel(16, 20), RED);
+        assert_eq!(framebuffer.pixel(17, 20), Rgb::new(128, 128, 128));
+        assert_eq!(framebuffer.pixel(18, 20), WHITE);
+        assert_eq!(framebuffer.pixel(19, 20), BLACK);
+        assert_eq!(framebuffer.pixel(16, 19), BLACK);
+    }
+
+    #[test]
+    fn colored_glyph() {
+        let size_info = size_info();
+        let mut renderer = renderer(&size_info);
+        renderer.clear(WHITE);
+
+        // Premultiplied half transparent red.
+        let rasterized = RasterizedGlyph {
+            character: 'x',
+            width: 1,
+            height: 1,
+            top: 20,
+            left: 0,
+            advance: (10, 0),
+            buffer: BitmapBuffer::Rgba(vec![128, 0, 0, 128]),
+        };
+        let glyph = renderer.glyphs.load_glyph(&rasterized);
+        assert!(glyph.multicolor);
+
+        let clip = TextArea::new(&size_info);
+        renderer.draw_glyph(&cell(0, 'x'), &glyph, &size_info, &clip);
+
+        assert_eq!(renderer.framebuffer().pixel(5, 5), Rgb::new(255, 127, 127));
+    }
+
+    #[test]
+    fn glyph_clipped_to_text_area() {
+        let size_info = size_info();
+        let mut renderer = renderer(&size_info);
+
+        // Glyph overlapping the left padding.
+        let rasterized = RasterizedGlyph {
+            character: 'x',
+            width: 2,
+            height: 1,
+            top: 20,
+            left: -1,
+            advance: (10, 0),
+            buffer: BitmapBuffer::Rgb(vec![255; 6]),
+        };
+        let glyph = renderer.glyphs.load_glyph(&rasterized);
+
+        let clip = TextArea::new(&size_info);
+        renderer.draw_glyph(&cell(0, 'x'), &glyph, &size_info, &clip);
+
+        assert_eq!(renderer.framebuffer().pixel(4, 5), BLACK);
+        assert_eq!(renderer.framebuffer().pixel(5, 5), WHITE);
+    }
+
+    #[test]
+    fn dashed_underline() {
+        let pattern = LinePattern {
+            cell_width: 10.,
+            cell_height: 20.,
+            padding_x: 0.,
+            padding_bottom: 0.,
+            window_height: 40.,
+            underline_position: 2.,
+            underline_thickness: 1.,
+            undercurl_position: 2.,
+        };
+
+        let dashes: Vec<_> =
+            (0..10).map(|x| pattern.alpha(RectKind::DashedUnderline, x, 19)).collect();
+        assert_eq!(dashes, [1., 1., 1., 0., 0., 0., 0., 1., 1., 1.]);
+    }
+
+    #[test]
+    fn clear_glyphs() {
+        let mut store = GlyphStore::default();
+        let glyph = store.load_glyph(&RasterizedGlyph::default());
+        assert!(store.get(&glyph).is_some());
+
+        store.clear();
+        assert!(store.get(&glyph).is_none());
+    }
+}
@@ -210,7 +210,7 @@ impl<'a> TextRenderer<'a> for Gles2Renderer {
     }
 
     fn loader_api(&mut self) -> LoaderApi<'_> {
-        LoaderApi {
+        LoaderApi::Atlas {
             active_tex: &mut self.active_tex,
             atlas: &mut self.atlas,
             current_atlas: &mut self.current_atlas,
@@ -188,7 +188,7 @@ impl<'a> TextRenderer<'a> for Glsl3Renderer {
     }
 
     fn loader_api(&mut self) -> LoaderApi<'_> {
-        LoaderApi {
+        LoaderApi::Atlas {
             active_tex: &mut self.active_tex,
             atlas: &mut self.atlas,
             current_atlas: &mut self.current_atlas,
@@ -8,11 +8,16 @@ use crossfont::{
 use log::{error, info};
 use unicode_width::UnicodeWidthChar;
 
+use alacritty_terminal::index::Point;
+use alacritty_terminal::term::cell::Flags;
+
 use crate::config::font::{Font, FontDescription};
 use crate::config::ui_config::Delta;
+use crate::display::content::RenderableCell;
 use crate::gl::types::*;
 
 use super::builtin_font;
+use super::shaper::{self, ShapedGlyph, Shaper};
 
 /// `LoadGlyph` allows for copying a rasterized glyph into graphics memory.
 pub trait LoadGlyph {
@@ -76,6 +81,12 @@ pub struct GlyphCache {
 
     /// Whether to use the built-in font for box drawing characters.
     builtin_box_drawing: bool,
+
+    /// Shaper for ligatures and complex scripts.
+    shaper: Option<Shaper>,
+
+    /// Cache of buffered glyphs selected by the shaper.
+    shaped_cache: HashMap<(usize, ShapedGlyph), Glyph, RandomState>,
 }
 
 impl GlyphCache {
@@ -101,6 +112,8 @@ impl GlyphCache {
             glyph_offset: font.glyph_offset,
             metrics,
             builtin_box_drawing: font.builtin_box_drawing,
+            shaper: Shaper::new(font),
+            shaped_cache: Default::default(),
         })
     }
 
@@ -239,6 +252,42 @@ impl GlyphCache {
         *self.cache.entry(glyph_key).or_insert(glyph)
     }
 
+    /// Shape the text of all cells.
+    ///
+    /// This does nothing unless ligatures are enabled.
+    pub fn shape(&mut self, cells: &mut [RenderableCell], cursor: Option<Point<usize>>) {
+        if let Some(shaper) = &mut self.shaper {
+            shaper.shape(cells, cursor, self.font_size.as_px());
+        }
+    }
+
+    /// Get a glyph selected by the shaper.
+    ///
+    /// If the glyph has never been loaded before, it will be rasterized and inserted into the
+    /// cache.
+    pub fn get_shaped<L: ?Sized>(
+        &mut self,
+        flags: Flags,
+        glyph: ShapedGlyph,
+        loader: &mut L,
+    ) -> Glyph
+    where
+        L: LoadGlyph,
+    {
+        let key = (shaper::face_index(flags), glyph);
+        if let Some(glyph) = self.shaped_cache.get(&key) {
+            return *glyph;
+        }
+
+        let rasterized = match &self.shaper {
+            Some(shaper) => shaper.rasterize(flags, glyph, self.font_size.as_px()),
+            None => Default::default(),
+        };
+        let glyph = self.load_glyph(loader, rasterized);
+
+        *self.shaped_cache.entry(key).or_insert(glyph)
+    }
+
     /// Load glyph into the atlas.
     ///
     /// This will apply all transforms defined for the glyph cache to the rasterized glyph before
@@ -267,6 +316,7 @@ impl GlyphCache {
     pub fn reset_glyph_cache<L: LoadGlyph>(&mut self, loader: &mut L) {
         loader.clear();
         self.cache = Default::default();
+        self.shaped_cache = Default::default();
 
         self.load_common_glyphs(loader);
     }
@@ -301,6 +351,11 @@ impl GlyphCache {
         self.metrics = metrics;
         self.builtin_box_drawing = font.builtin_box_drawing;
 
+        // Only reload the shaper's fonts when they have changed.
+        if !self.shaper.as_ref().map_or(false, |shaper| shaper.matches(font)) {
+            self.shaper = Shaper::new(font);
+        }
+
         Ok(())
     }
 
@@ -7,18 +7,21 @@ use crate::display::content::RenderableCell;
 use crate::display::SizeInfo;
 use crate::gl;
 use crate::gl::types::*;
+use crate::renderer::software::GlyphStore;
 
 mod atlas;
 mod builtin_font;
 mod gles2;
 mod glsl3;
 pub mod glyph_cache;
+mod shaper;
 
 use atlas::Atlas;
 pub use gles2::Gles2Renderer;
 pub use glsl3::Glsl3Renderer;
 pub use glyph_cache::GlyphCache;
 use glyph_cache::{Glyph, LoadGlyph};
+pub use shaper::ShapedGlyph;
 
 // NOTE: These flags must be in sync with their usage in the text.*.glsl shaders.
 bitflags! {
@@ -152,6 +155,20 @@ pub trait TextRenderApi<T: TextRenderBatch>: LoadGlyph {
             cell.character = ' ';
         }
 
+        // Render glyphs selected by text shaping.
+        match cell.extra.as_mut().and_then(|extra| extra.glyphs.take()) {
+            // Cells merged into a ligature only render their background.
+            Some(glyphs) if glyphs.is_empty() => cell.character = ' ',
+            Some(glyphs) => {
+                for glyph in glyphs {
+                    let glyph = glyph_cache.get_shaped(cell.flags, glyph, self);
+                    self.add_render_item(&cell, &glyph, size_info);
+                }
+                return;
+            },
+            None => (),
+        }
+
         let mut glyph_key =
             GlyphKey { font_key, size: glyph_cache.font_size, character: cell.character };
 
@@ -180,19 +197,29 @@ pub trait TextShader {
 }
 
 #[derive(Debug)]
-pub struct LoaderApi<'a> {
-    active_tex: &'a mut GLuint,
-    atlas: &'a mut Vec<Atlas>,
-    current_atlas: &'a mut usize,
+pub enum LoaderApi<'a> {
+    /// Load glyphs into the OpenGL texture atlas.
+    Atlas { active_tex: &'a mut GLuint, atlas: &'a mut Vec<Atlas>, current_atlas: &'a mut usize },
+
+    /// Keep glyphs in memory for the software renderer.
+    Software(&'a mut GlyphStore),
 }
 
 impl<'a> LoadGlyph for LoaderApi<'a> {
     fn load_glyph(&mut self, rasterized: &RasterizedGlyph) -> Glyph {
-        Atlas::load_glyph(self.active_tex, self.atlas, self.current_atlas, rasterized)
+        match self {
+            Self::Atlas { active_tex, atlas, current_atlas } => {
+                Atlas::load_glyph(active_tex, atlas, current_atlas, rasterized)
+            },
+            Self::Software(glyphs) => glyphs.load_glyph(rasterized),
+        }
     }
 
     fn clear(&mut self) {
-        Atlas::clear_atlas(self.atlas, self.current_atlas)
+        match self {
+            Self::Atlas { atlas, current_atlas, .. } => Atlas::clear_atlas(atlas, current_atlas),
+            Self::Software(glyphs) => glyphs.clear(),
+        }
     }
 }
 
@@ -0,0 +1,596 @@
+//! Text shaping for ligatures and complex scripts.
+//!
+//! Cells are shaped in runs of identical style, but every glyph is still rendered within the
+//! cell its cluster starts in, to keep the text aligned to the grid. Only glyphs which differ
+//! from the font's default glyph for a character are rendered from the shaped output, everything
+//! else goes through the regular glyph cache and its font fallback.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use ab_glyph_rasterizer::{point, Point, Rasterizer};
+use crossfont::{BitmapBuffer, RasterizedGlyph, Slant, Weight};
+use log::{debug, warn};
+use rustybuzz::ttf_parser::{self, GlyphId, OutlineBuilder, Tag};
+use rustybuzz::{Face, Feature, UnicodeBuffer};
+
+use alacritty_terminal::index::Point as GridPoint;
+use alacritty_terminal::term::cell::Flags;
+
+use crate::config::font::{Font, FontDescription, FontFeature};
+use crate::display::content::{RenderableCell, RenderableCellExtra};
+
+/// Maximum number of shaped runs kept in the cache.
+const MAX_CACHED_RUNS: usize = 4096;
+
+/// Slant and weight of the regular, bold, italic and bold italic faces.
+const STYLES: [(Slant, Weight); 4] = [
+    (Slant::Normal, Weight::Normal),
+    (Slant::Normal, Weight::Bold),
+    (Slant::Italic, Weight::Normal),
+    (Slant::Italic, Weight::Bold),
+];
+
+/// Glyph selected by the shaper.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ShapedGlyph {
+    /// Glyph index in the font.
+    pub id: u16,
+
+    /// Horizontal offset from the origin of the cell in pixels.
+    pub x: i32,
+
+    /// Vertical offset from the baseline in pixels.
+    pub y: i32,
+}
+
+/// Shaped glyphs of every cell in a run, positioned in font units.
+///
+/// Cells without glyphs are rendered without shaping.
+type ShapedRun = Vec<Option<Vec<ShapedGlyph>>>;
+
+/// Characters of a run, with the index of the cell they belong to.
+type RunText = Vec<(char, u32)>;
+
+/// Font file data of one face.
+struct FaceData {
+    path: PathBuf,
+    data: Vec<u8>,
+    index: u32,
+    units_per_em: u16,
+}
+
+impl FaceData {
+    /// Read a font file, checking that it can be parsed.
+    fn load(path: PathBuf, index: u32) -> Option<Self> {
+        let data = match fs::read(&path) {
+            Ok(data) => data,
+            Err(err) => {
+                warn!("Unable to read font {path:?} for shaping: {err}");
+                return None;
+            },
+        };
+
+        let units_per_em = match ttf_parser::Face::parse(&data, index) {
+            Ok(face) => face.units_per_em(),
+            Err(err) => {
+                warn!("Unable to parse font {path:?} for shaping: {err}");
+                return None;
+            },
+        };
+
+        debug!("Loaded font {path:?} for shaping");
+
+        Some(Self { path, data, index, units_per_em })
+    }
+
+    /// Parse the font's tables.
+    fn face(&self) -> Option<Face<'_>> {
+        Face::from_slice(&self.data, self.index)
+    }
+}
+
+/// Text shaper for the configured fonts.
+pub struct Shaper {
+    /// Faces for regular, bold, italic and bold italic text.
+    faces: [Option<Arc<FaceData>>; 4],
+
+    /// OpenType features applied to all text.
+    features: Vec<Feature>,
+
+    /// Shaped runs by face and text.
+    runs: HashMap<(usize, RunText), ShapedRun>,
+
+    /// Font descriptions the faces were loaded for.
+    descriptions: [FontDescription; 4],
+
+    /// Configured features the shaper was created with.
+    configured_features: Vec<FontFeature>,
+}
+
+impl Shaper {
+    /// Load the fonts for shaping, if ligatures are enabled.
+    pub fn new(font: &Font) -> Option<Self> {
+        if !font.ligatures {
+            return None;
+        }
+
+        if cfg!(any(target_os = "macos", windows)) {
+            warn!("Font ligatures are only supported with fontconfig");
+            return None;
+        }
+
+        // Share faces which resolve to the same font file.
+        let descriptions = descriptions(font);
+        let mut faces: [Option<Arc<FaceData>>; 4] = Default::default();
+        for (i, (description, (slant, weight))) in descriptions.iter().zip(STYLES).enumerate() {
+            let (path, index) = match font_file(description, slant, weight) {
+                Some(file) => file,
+                None => {
+                    warn!("Unable to find \"{}\" font for shaping", description.family);
+                    continue;
+                },
+            };
+
+            let loaded = faces[..i]
+                .iter()
+                .flatten()
+                .find(|face| face.path == path && face.index == index)
+                .cloned();
+            faces[i] = loaded.or_else(|| FaceData::load(path, index).map(Arc::new));
+        }
+
+        let features = font.features.iter().map(feature).collect();
+        let configured_features = font.features.clone();
+
+        Some(Self { faces, features, runs: HashMap::new(), descriptions, configured_features })
+    }
+
+    /// Check if the shaper was created for the fonts of a config.
+    pub fn matches(&self, font: &Font) -> bool {
+        font.ligatures
+            && self.descriptions == descriptions(font)
+            && self.configured_features == font.features
+    }
+
+    /// Shape all cells, storing glyphs which differ from a plain rendering in the cells.
+    ///
+    /// The cell under the cursor is shaped separately, to break up any ligature it is part of.
+    pub fn shape(
+        &mut self,
+        cells: &mut [RenderableCell],
+        cursor: Option<GridPoint<usize>>,
+        size: f32,
+    ) {
+        let Self { faces, features, runs, .. } = self;
+
+        if runs.len() > MAX_CACHED_RUNS {
+            runs.clear();
+        }
+
+        // Faces are only parsed when a run is missing from the cache.
+        let mut parsed: [Option<Option<Face<'_>>>; 4] = Default::default();
+
+        let mut start = 0;
+        while start < cells.len() {
+            let end = run_end(cells, start, cursor);
+            let run = &mut cells[start..end];
+            start = end;
+
+            if run[0].flags.contains(Flags::HIDDEN) {
+                continue;
+            }
+
+            let index = face_index(run[0].flags);
+            let data = match &faces[index] {
+                Some(data) => data,
+                None => continue,
+            };
+
+            let shaped = match runs.entry((index, run_text(run))) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let face = parsed[index].get_or_insert_with(|| data.face());
+                    match face {
+                        Some(face) => {
+                            let shaped = shape_run(face, features, &entry.key().1, run.len());
+                            entry.insert(shaped)
+                        },
+                        None => continue,
+                    }
+                },
+            };
+
+            let scale = size / f32::from(data.units_per_em);
+            for (cell, glyphs) in run.iter_mut().zip(shaped.iter()) {
+                let glyphs = match glyphs {
+                    Some(glyphs) => glyphs,
+                    None => continue,
+                };
+
+                let glyphs = glyphs
+                    .iter()
+                    .map(|glyph| ShapedGlyph {
+                        id: glyph.id,
+                        x: (glyph.x as f32 * scale).round() as i32,
+                        y: (glyph.y as f32 * scale).round() as i32,
+                    })
+                    .collect();
+
+                // Zerowidth characters are part of the shaped glyphs.
+                let extra = cell.extra.get_or_insert_with(|| {
+                    Box::new(RenderableCellExtra {
+                        zerowidth: None,
+                        hyperlink: None,
+                        graphic: None,
+                        glyphs: None,
+                    })
+                });
+                extra.zerowidth = None;
+                extra.glyphs = Some(glyphs);
+            }
+        }
+    }
+
+    /// Rasterize a shaped glyph.
+    pub fn rasterize(&self, flags: Flags, glyph: ShapedGlyph, size: f32) -> RasterizedGlyph {
+        let face = self.faces[face_index(flags)]
+            .as_ref()
+            .and_then(|face| ttf_parser::Face::parse(&face.data, face.index).ok());
+
+        face.and_then(|face| rasterize(&face, glyph, size)).unwrap_or_default()
+    }
+}
+
+/// Characters of a run, including zerowidth characters.
+fn run_text(run: &[RenderableCell]) -> RunText {
+    let mut text = Vec::with_capacity(run.len());
+    for (i, cell) in run.iter().enumerate() {
+        let character = if cell.character == '\t' { ' ' } else { cell.character };
+        text.push((character, i as u32));
+
+        let zerowidth = cell.extra.as_ref().and_then(|extra| extra.zerowidth.as_ref());
+        for &character in zerowidth.into_iter().flatten() {
+            text.push((character, i as u32));
+        }
+    }
+    text
+}
+
+/// Shape the text of a run with `cells` cells.
+///
+/// Cells whose glyphs match a plain rendering, or which contain characters missing from the
+/// font, are left to the regular glyph cache and its font fallback.
+fn shape_run(
+    face: &Face<'_>,
+    features: &[Feature],
+    text: &[(char, u32)],
+    cells: usize,
+) -> ShapedRun {
+    let mut buffer = UnicodeBuffer::new();
+    for &(character, cluster) in text {
+        buffer.add(character, cluster);
+    }
+    buffer.guess_segment_properties();
+
+    let output = rustybuzz::shape(face, features, buffer);
+
+    // Position glyphs relative to the cell their cluster starts in.
+    let mut glyphs = vec![Vec::new(); cells];
+    let mut missing = vec![false; cells];
+    let mut pens = vec![0; cells];
+    for (info, position) in output.glyph_infos().iter().zip(output.glyph_positions()) {
+        let cluster = info.cluster as usize;
+        missing[cluster] |= info.glyph_id == 0;
+
+        glyphs[cluster].push(ShapedGlyph {
+            id: info.glyph_id as u16,
+            x: pens[cluster] + position.x_offset,
+            y: position.y_offset,
+        });
+        pens[cluster] += position.x_advance;
+    }
+
+    let mut shaped = Vec::with_capacity(cells);
+    let mut cluster_missing = false;
+    for (i, glyphs) in glyphs.into_iter().enumerate() {
+        let mut characters = text.iter().filter(|(_, cluster)| *cluster as usize == i);
+        let character = characters.next().map_or(' ', |(character, _)| *character);
+        let has_zerowidth = characters.next().is_some();
+
+        // Cells merged into a cluster fall back together with it.
+        if !glyphs.is_empty() {
+            cluster_missing = missing[i];
+        }
+
+        let default_glyph = face.glyph_index(character).map(|id| id.0);
+        let is_default = !has_zerowidth
+            && glyphs.len() == 1
+            && Some(glyphs[0].id) == default_glyph
+            && glyphs[0].x == 0
+            && glyphs[0].y == 0;
+
+        shaped.push((!is_default && !cluster_missing).then_some(glyphs));
+    }
+
+    shaped
+}
+
+/// Find the end of the run of cells starting at `start`.
+fn run_end(cells: &[RenderableCell], start: usize, cursor: Option<GridPoint<usize>>) -> usize {
+    let first = &cells[start];
+    if Some(first.point) == cursor {
+        return start + 1;
+    }
+
+    let mut previous = first;
+    for (i, cell) in cells.iter().enumerate().skip(start + 1) {
+        let columns = if previous.flags.contains(Flags::WIDE_CHAR) { 2 } else { 1 };
+        let is_adjacent = cell.point.line == previous.point.line
+            && cell.point.column == previous.point.column + columns;
+
+        let same_style = cell.fg == first.fg
+            && cell.flags & (Flags::BOLD_ITALIC | Flags::HIDDEN)
+                == first.flags & (Flags::BOLD_ITALIC | Flags::HIDDEN);
+
+        let has_graphic = cell.extra.as_ref().map_or(false, |extra| extra.graphic.is_some());
+
+        if !is_adjacent || !same_style || has_graphic || Some(cell.point) == cursor {
+            return i;
+        }
+
+        previous = cell;
+    }
+
+    cells.len()
+}
+
+/// Index of the face used for a cell.
+pub fn face_index(flags: Flags) -> usize {
+    match flags & Flags::BOLD_ITALIC {
+        Flags::BOLD_ITALIC => 3,
+        Flags::ITALIC => 2,
+        Flags::BOLD => 1,
+        _ => 0,
+    }
+}
+
+/// Font descriptions for regular, bold, italic and bold italic text.
+fn descriptions(font: &Font) -> [FontDescription; 4] {
+    [font.normal().clone(), font.bold(), font.italic(), font.bold_italic()]
+}
+
+/// Convert a configured feature to the shaper's representation.
+fn feature(feature: &FontFeature) -> Feature {
+    Feature::new(Tag::from_bytes(&feature.tag), feature.value, ..)
+}
+
+/// Find the font file for a face.
+///
+/// This runs the same fontconfig query crossfont uses to load the font, so shaping uses the
+/// exact face the rest of the text is rendered with.
+#[cfg(not(any(target_os = "macos", windows)))]
+fn font_file(
+    description: &FontDescription,
+    slant: Slant,
+    weight: Weight,
+) -> Option<(PathBuf, u32)> {
+    use crossfont::ft::fc;
+
+    let config = fc::Config::get_current();
+    let mut pattern = fc::Pattern::new();
+    pattern.add_family(&description.family);
+
+    match &description.style {
+        Some(style) => {
+            pattern.add_style(style);
+        },
+        None => {
+            pattern.set_slant(match slant {
+                Slant::Normal => fc::Slant::Roman,
+                Slant::Italic => fc::Slant::Italic,
+                Slant::Oblique => fc::Slant::Oblique,
+            });
+            pattern.set_weight(match weight {
+                Weight::Normal => fc::Weight::Regular,
+                Weight::Bold => fc::Weight::Bold,
+            });
+        },
+    }
+
+    pattern.config_substitute(config, fc::MatchKind::Pattern);
+    pattern.default_substitute();
+
+    let font = fc::font_match(config, &pattern)?;
+    let path = font.file(0)?;
+    let index = font.index().next().unwrap_or(0);
+
+    Some((path, index as u32))
+}
+
+/// Find the font file for a face.
+#[cfg(any(target_os = "macos", windows))]
+fn font_file(
+    _description: &FontDescription,
+    _slant: Slant,
+    _weight: Weight,
+) -> Option<(PathBuf, u32)> {
+    None
+}
+
+/// Rasterize the outline of a glyph.
+fn rasterize(
+    face: &ttf_parser::Face<'_>,
+    glyph: ShapedGlyph,
+    size: f32,
+) -> Option<RasterizedGlyph> {
+    let scale = size / f32::from(face.units_per_em());
+
+    let mut outline = Outline::default();
+    let bounds = face.outline_glyph(GlyphId(glyph.id), &mut outline)?;
+
+    // Pixel bounds of the glyph, relative to the cell's origin on the baseline.
+    let left = (f32::from(bounds.x_min) * scale).floor() as i32 + glyph.x;
+    let right = (f32::from(bounds.x_max) * scale).ceil() as i32 + glyph.x;
+    let top = (f32::from(bounds.y_max) * scale).ceil() as i32 + glyph.y;
+    let bottom = (f32::from(bounds.y_min) * scale).floor() as i32 + glyph.y;
+    let (width, height) = ((right - left).max(1), (top - bottom).max(1));
+
+    // Convert from font units to pixels in the bitmap, which grows downwards.
+    let transform = |p: Point| {
+        point(p.x * scale + (glyph.x - left) as f32, (top - glyph.y) as f32 - p.y * scale)
+    };
+
+    let mut rasterizer = Rasterizer::new(width as usize, height as usize);
+    for segment in outline.segments {
+        match segment {
+            Segment::Line(p0, p1) => rasterizer.draw_line(transform(p0), transform(p1)),
+            Segment::Quad(p0, p1, p2) => {
+                rasterizer.draw_quad(transform(p0), transform(p1), transform(p2))
+            },
+            Segment::Cubic(p0, p1, p2, p3) => {
+                rasterizer.draw_cubic(transform(p0), transform(p1), transform(p2), transform(p3))
+            },
+        }
+    }
+
+    let mut buffer = vec![0; width as usize * height as usize * 3];
+    rasterizer.for_each_pixel(|index, alpha| {
+        let alpha = (alpha.min(1.) * 255.).round() as u8;
+        buffer[index * 3..index * 3 + 3].fill(alpha);
+    });
+
+    // Shaped glyphs are positioned explicitly, so they must not be treated as zerowidth.
+    Some(RasterizedGlyph {
+        character: ' ',
+        width,
+        height,
+        top,
+        left,
+        advance: (0, 0),
+        buffer: BitmapBuffer::Rgb(buffer),
+    })
+}
+
+/// Segment of a glyph outline in font units.
+enum Segment {
+    Line(Point, Point),
+    Quad(Point, Point, Point),
+    Cubic(Point, Point, Point, Point),
+}
+
+/// Collector for the segments of a glyph outline.
+#[derive(Default)]
+struct Outline {
+    segments: Vec<Segment>,
+    start: Option<Point>,
+    last: Option<Point>,
+}
+
+impl OutlineBuilder for Outline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.start = Some(point(x, y));
+        self.last = self.start;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        if let Some(last) = self.last.replace(point(x, y)) {
+            self.segments.push(Segment::Line(last, point(x, y)));
+        }
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        if let Some(last) = self.last.replace(point(x, y)) {
+            self.segments.push(Segment::Quad(last, point(x1, y1), point(x, y)));
+        }
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        if let Some(last) = self.last.replace(point(x, y)) {
+            self.segments.push(Segment::Cubic(last, point(x1, y1), point(x2, y2), point(x, y)));
+        }
+    }
+
+    fn close(&mut self) {
+        if let (Some(last), Some(start)) = (self.last, self.start) {
+            self.segments.push(Segment::Line(last, start));
+        }
+        self.last = self.start;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alacritty_terminal::index::Column;
+
+    use crate::display::color::Rgb;
+
+    fn cell(column: usize, character: char) -> RenderableCell {
+        RenderableCell {
+            character,
+            point: GridPoint::new(0, Column(column)),
+            fg: Rgb::new(255, 255, 255),
+            bg: Rgb::new(0, 0, 0),
+            bg_alpha: 0.,
+            underline: Rgb::new(255, 255, 255),
+            flags: Flags::empty(),
+            extra: None,
+        }
+    }
+
+    fn runs(cells: &[RenderableCell], cursor: Option<GridPoint<usize>>) -> Vec<usize> {
+        let mut ends = Vec::new();
+        let mut start = 0;
+        while start < cells.len() {
+            start = run_end(cells, start, cursor);
+            ends.push(start);
+        }
+        ends
+    }
+
+    #[test]
+    fn runs_split_on_style_and_gaps() {
+        let mut cells: Vec<_> = "a->b".chars().enumerate().map(|(i, c)| cell(i, c)).collect();
+        assert_eq!(runs(&cells, None), vec![4]);
+
+        // Changing the font style splits the run.
+        cells[2].flags.insert(Flags::BOLD);
+        assert_eq!(runs(&cells, None), vec![2, 4]);
+
+        // Skipped empty cells split the run.
+        cells[2].flags.remove(Flags::BOLD);
+        cells[3].point.column = Column(5);
+        assert_eq!(runs(&cells, None), vec![3, 4]);
+    }
+
+    #[test]
+    fn runs_span_wide_chars() {
+        let mut cells = vec![cell(0, '字'), cell(2, '=')];
+        cells[0].flags.insert(Flags::WIDE_CHAR);
+        assert_eq!(runs(&cells, None), vec![2]);
+    }
+
+    #[test]
+    fn run_text_includes_zerowidth() {
+        let mut cells = vec![cell(0, 'e'), cell(1, '\t')];
+        cells[0].extra = Some(Box::new(RenderableCellExtra {
+            zerowidth: Some(vec!['\u{301}']),
+            hyperlink: None,
+            graphic: None,
+            glyphs: None,
+        }));
+        assert_eq!(run_text(&cells), vec![('e', 0), ('\u{301}', 0), (' ', 1)]);
+    }
+
+    #[test]
+    fn runs_break_under_cursor() {
+        let cells: Vec<_> = "a->b".chars().enumerate().map(|(i, c)| cell(i, c)).collect();
+        let cursor = Some(GridPoint::new(0, Column(2)));
+        assert_eq!(runs(&cells, cursor), vec![2, 3, 4]);
+    }
+}
@@ -0,0 +1,124 @@
+//! Capture of the rendered window content.
+
+use std::error::Error;
+#[cfg(feature = "png")]
+use std::fs::File;
+#[cfg(feature = "png")]
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+#[cfg(unix)]
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::{error, info};
+
+use alacritty_terminal::thread;
+
+#[cfg(unix)]
+use crate::ipc::SocketReply;
+
+/// Pending request to capture the next frame.
+#[derive(Debug)]
+pub struct ScreenshotRequest {
+    path: PathBuf,
+
+    /// IPC client waiting for the screenshot.
+    #[cfg(unix)]
+    reply: Option<Sender<SocketReply>>,
+}
+
+impl ScreenshotRequest {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            #[cfg(unix)]
+            reply: None,
+        }
+    }
+
+    /// Create a request which reports its result to an IPC client.
+    #[cfg(unix)]
+    pub fn with_reply(path: PathBuf, reply: Sender<SocketReply>) -> Self {
+        Self { path, reply: Some(reply) }
+    }
+
+    /// Write the frame to the requested path.
+    ///
+    /// The pixels are expected in RGBA format, starting at the top-left corner. Since encoding
+    /// large frames is slow, the file is written on a separate thread.
+    pub fn save(self, width: u32, height: u32, pixels: Arc<[u8]>) {
+        thread::spawn_named("screenshot", move || self.write(width, height, &pixels));
+    }
+
+    /// Encode the frame and report the result.
+    fn write(self, width: u32, height: u32, pixels: &[u8]) {
+        let result = save_png(&self.path, width, height, pixels).map_err(|err| err.to_string());
+
+        match &result {
+            Ok(()) => info!("Saved screenshot to {:?}", self.path),
+            Err(err) => error!("Unable to save screenshot to {:?}: {}", self.path, err),
+        }
+
+        #[cfg(unix)]
+        if let Some(reply) = self.reply {
+            let _ = reply.send(match result {
+                Ok(()) => SocketReply::Screenshot(self.path),
+                Err(err) => SocketReply::Error(err),
+            });
+        }
+    }
+}
+
+/// Path for a new screenshot in the home directory.
+pub fn new_path() -> PathBuf {
+    let directory = home::home_dir().unwrap_or_else(|| PathBuf::from("."));
+
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |t| t.as_secs());
+    directory.join(format!("alacritty-{timestamp}.png"))
+}
+
+/// Encode RGBA pixels as PNG file.
+#[cfg(feature = "png")]
+fn save_png(path: &Path, width: u32, height: u32, pixels: &[u8]) -> Result<(), Box<dyn Error>> {
+    let file = BufWriter::new(File::create(path)?);
+
+    let mut encoder = png::Encoder::new(file, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(pixels)?;
+    writer.finish()?;
+
+    Ok(())
+}
+
+/// Encode RGBA pixels as PNG file.
+#[cfg(not(feature = "png"))]
+fn save_png(_path: &Path, _width: u32, _height: u32, _pixels: &[u8]) -> Result<(), Box<dyn Error>> {
+    Err("Alacritty was built without PNG support".into())
+}
+
+#[cfg(all(test, feature = "png"))]
+mod tests {
+    use super::*;
+
+    use std::{fs, process};
+
+    #[test]
+    fn png_roundtrip() {
+        let pixels = [255, 0, 0, 255, 0, 255, 0, 255, 0, 0, 255, 255, 255, 255, 255, 255];
+        let path = std::env::temp_dir().join(format!("alacritty-screenshot-{}.png", process::id()));
+        save_png(&path, 2, 2, &pixels).unwrap();
+
+        let decoder = png::Decoder::new(File::open(&path).unwrap());
+        let mut reader = decoder.read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!((info.width, info.height), (2, 2));
+        assert_eq!(&buf[..info.buffer_size()], &pixels);
+    }
+}
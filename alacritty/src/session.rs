@@ -0,0 +1,146 @@
+//! Session layout files describing windows which are opened together.
+
+use std::collections::HashMap;
+use std::env;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::cli::WindowOptions;
+use crate::config::ui_config::Program;
+use crate::config::window::{Class, Dimensions};
+use crate::config::{self, IMPORT_RECURSION_LIMIT};
+
+/// Windows of a session layout file.
+#[derive(Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct Session {
+    pub windows: Vec<SessionWindow>,
+}
+
+impl Session {
+    /// Options for creating all windows of the session.
+    pub fn window_options(&self) -> Vec<WindowOptions> {
+        self.windows.iter().map(SessionWindow::window_options).collect()
+    }
+}
+
+/// Single window of a session.
+#[derive(Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct SessionWindow {
+    /// Shell startup directory, relative to the session file.
+    pub working_directory: Option<PathBuf>,
+
+    /// Command executed instead of the shell.
+    pub command: Option<Program>,
+
+    /// Window title.
+    pub title: Option<String>,
+
+    /// Window class/app_id on X11/Wayland.
+    pub class: Option<Class>,
+
+    /// Extra environment variables for the command.
+    pub env: HashMap<String, String>,
+
+    /// Initial window dimensions in cells.
+    pub dimensions: Option<Dimensions>,
+
+    /// Remain open after the command exits.
+    pub hold: bool,
+}
+
+impl SessionWindow {
+    /// Options for creating this window.
+    pub fn window_options(&self) -> WindowOptions {
+        let mut options = WindowOptions::default();
+
+        let terminal_options = &mut options.terminal_options;
+        terminal_options.working_directory = self.working_directory.clone();
+        terminal_options.env = self.env.clone();
+        terminal_options.hold = self.hold;
+        if let Some(command) = &self.command {
+            terminal_options.set_command(command);
+        }
+
+        options.window_identity.title = self.title.clone();
+        options.window_identity.class = self.class.clone();
+
+        if let Some(dimensions) = self.dimensions {
+            options.add_option(format!("window.dimensions.columns={}", dimensions.columns));
+            options.add_option(format!("window.dimensions.lines={}", dimensions.lines));
+        }
+
+        options
+    }
+}
+
+/// Load a session layout file and its imports.
+pub fn load(path: &Path) -> Result<Session, String> {
+    let path = env::current_dir().map_err(|err| err.to_string())?.join(path);
+
+    let value = match config::parse_config(&path, &mut Vec::new(), IMPORT_RECURSION_LIMIT) {
+        Ok(value) => value,
+        Err(config::Error::NotFound) => return Err(format!("Session file {path:?} not found")),
+        Err(err) => return Err(format!("Unable to load session {path:?}: {err}")),
+    };
+    let mut session = Session::deserialize(value)
+        .map_err(|err| format!("Unable to load session {path:?}: {err}"))?;
+
+    // Resolve working directories relative to the session file.
+    let base = path.parent().unwrap_or(&path);
+    for window in &mut session.windows {
+        if let Some(working_directory) = &mut window.working_directory {
+            let resolved = match (working_directory.strip_prefix("~/"), home::home_dir()) {
+                (Ok(stripped), Some(home_dir)) => home_dir.join(stripped),
+                _ => base.join(&working_directory),
+            };
+            *working_directory = resolved;
+        }
+    }
+
+    Ok(session)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_window_options() {
+        let session: Session = toml::from_str(
+            r#"
+            [[windows]]
+            title = "Editor"
+            command = { program = "vim", args = ["README.md"] }
+            env = { EDITOR = "vim" }
+            dimensions = { columns = 120, lines = 40 }
+
+            [[windows]]
+            hold = true
+            "#,
+        )
+        .unwrap();
+
+        let options = session.window_options();
+        assert_eq!(options.len(), 2);
+
+        let editor = &options[0];
+        assert_eq!(editor.window_identity.title.as_deref(), Some("Editor"));
+        assert_eq!(
+            editor.terminal_options.command(),
+            Some(Program::WithArgs { program: "vim".into(), args: vec!["README.md".into()] })
+        );
+        assert_eq!(editor.terminal_options.env.get("EDITOR").map(String::as_str), Some("vim"));
+        assert_eq!(editor.config_overrides().len(), 2);
+
+        assert!(options[1].terminal_options.hold);
+        assert_eq!(options[1].terminal_options.command(), None);
+    }
+
+    #[test]
+    fn unknown_window_field() {
+        assert!(toml::from_str::<Session>("[[windows]]\ntitel = \"Editor\"").is_err());
+    }
+}
@@ -0,0 +1,84 @@
+//! Tabs grouping the panes of a window.
+
+use std::ops::Range;
+
+use crate::pane::{Layout, PaneId};
+
+/// Group of panes which are shown together.
+#[derive(Debug)]
+pub struct Tab {
+    pub layout: Layout,
+
+    /// Pane with keyboard focus while the tab is active.
+    pub focused_pane: PaneId,
+}
+
+impl Tab {
+    pub fn new(pane: PaneId) -> Self {
+        Self { layout: Layout::new(pane), focused_pane: pane }
+    }
+}
+
+/// Tab changes requested through actions.
+#[cfg(not(target_os = "macos"))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TabCommand {
+    /// Open a new tab next to the active one.
+    Create,
+
+    /// Activate the next tab, wrapping around at the end.
+    Next,
+
+    /// Activate the previous tab, wrapping around at the start.
+    Previous,
+
+    /// Activate the tab at an index.
+    Select(usize),
+
+    /// Activate the last tab.
+    Last,
+}
+
+/// Titles shown in the tab bar at the top of the window.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TabBar {
+    pub titles: Vec<String>,
+    pub active: usize,
+}
+
+/// Divide `columns` evenly between `count` tabs.
+///
+/// Remaining columns are given to the first tabs.
+pub fn spans(count: usize, columns: usize) -> impl Iterator<Item = Range<usize>> {
+    let width = columns / count.max(1);
+    let remainder = columns % count.max(1);
+    (0..count).map(move |index| {
+        let start = index * width + index.min(remainder);
+        let end = start + width + usize::from(index < remainder);
+        start..end
+    })
+}
+
+/// Index of the tab covering `column`.
+pub fn tab_at(count: usize, columns: usize, column: usize) -> Option<usize> {
+    spans(count, columns).position(|span| span.contains(&column))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spans_cover_all_columns() {
+        assert_eq!(spans(3, 11).collect::<Vec<_>>(), vec![0..4, 4..8, 8..11]);
+        assert_eq!(spans(2, 10).collect::<Vec<_>>(), vec![0..5, 5..10]);
+    }
+
+    #[test]
+    fn tab_at_column() {
+        assert_eq!(tab_at(3, 11, 0), Some(0));
+        assert_eq!(tab_at(3, 11, 4), Some(1));
+        assert_eq!(tab_at(3, 11, 10), Some(2));
+        assert_eq!(tab_at(3, 11, 11), None);
+    }
+}
@@ -0,0 +1,346 @@
+//! Evaluation of triggers on new terminal output.
+
+use std::collections::{HashMap, VecDeque};
+use std::mem;
+use std::time::{Duration, Instant};
+
+use log::warn;
+
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Direction, Line, Point};
+use alacritty_terminal::term::search::{Match, RegexIter};
+use alacritty_terminal::term::{Term, TermMode};
+
+use crate::config::color::MatchColors;
+use crate::config::trigger::{Trigger, TriggerAction};
+
+/// Maximum number of responses of a trigger within [`RESPONSE_WINDOW`].
+const MAX_RESPONSES: usize = 5;
+
+/// Interval in which the responses of a trigger are limited.
+const RESPONSE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Position in the terminal output, which is stable while lines are scrolled into history.
+type OutputPoint = (i64, Column);
+
+/// Trigger evaluation progress of a terminal.
+#[derive(Debug)]
+pub struct TriggerState {
+    /// Output position of the cursor at the last evaluation.
+    cursor: OutputPoint,
+
+    /// Highlighted matches, ordered by their start.
+    highlights: Vec<Highlight>,
+
+    /// Start of all reported matches which might be found again, with their trigger's index.
+    reported: Vec<(usize, OutputPoint)>,
+
+    /// Number of columns the highlights were matched with.
+    columns: usize,
+
+    /// Recent responses of each trigger.
+    responses: HashMap<usize, VecDeque<Instant>>,
+}
+
+impl TriggerState {
+    /// Only output written after the creation of the state is evaluated.
+    pub fn new<T>(term: &Term<T>) -> Self {
+        Self {
+            cursor: cursor_position(term),
+            columns: term.columns(),
+            highlights: Default::default(),
+            reported: Default::default(),
+            responses: Default::default(),
+        }
+    }
+
+    /// Get all highlights intersecting with the viewport.
+    pub fn visible_highlights<T>(&self, term: &Term<T>) -> Vec<(Match, MatchColors)> {
+        // Highlights only apply to the primary screen.
+        if term.mode().contains(TermMode::ALT_SCREEN) || term.columns() != self.columns {
+            return Vec::new();
+        }
+
+        let scrolled_lines = term.grid().scrolled_lines() as i64;
+        let viewport_start = -(term.grid().display_offset() as i64);
+        let viewport_end = viewport_start + term.screen_lines() as i64 - 1;
+
+        self.highlights
+            .iter()
+            .filter_map(|highlight| {
+                let start_line = highlight.start.0 - scrolled_lines;
+                let end_line = highlight.end.0 - scrolled_lines;
+                if end_line < viewport_start || start_line > viewport_end {
+                    return None;
+                }
+
+                let start = Point::new(Line(start_line as i32), highlight.start.1);
+                let end = Point::new(Line(end_line as i32), highlight.end.1);
+                Some((start..=end, highlight.colors))
+            })
+            .collect()
+    }
+
+    /// Check if a trigger is allowed to respond, recording the response.
+    ///
+    /// This prevents triggers which match their own response from writing to the PTY forever.
+    pub fn allow_response(&mut self, index: usize) -> bool {
+        let now = Instant::now();
+        let responses = self.responses.entry(index).or_default();
+        while responses.front().is_some_and(|time| now - *time > RESPONSE_WINDOW) {
+            responses.pop_front();
+        }
+
+        if responses.len() >= MAX_RESPONSES {
+            warn!("Ignoring response of trigger {index}, since it responded too often");
+            return false;
+        }
+
+        responses.push_back(now);
+        true
+    }
+}
+
+/// Colors applied to a trigger match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Highlight {
+    start: OutputPoint,
+    end: OutputPoint,
+    colors: MatchColors,
+}
+
+/// Trigger match which requires an action.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TriggerMatch {
+    /// Index of the trigger in the configuration.
+    pub index: usize,
+
+    /// Text of the match.
+    pub text: String,
+
+    /// Capture groups of the match, starting with the entire match.
+    pub captures: Vec<String>,
+}
+
+/// Evaluate triggers for all output written since the last evaluation.
+///
+/// Highlights are stored in the state for rendering, the matches of all other triggers are
+/// returned for executing their actions.
+pub fn evaluate<T>(
+    term: &Term<T>,
+    state: &mut TriggerState,
+    triggers: &[Trigger],
+) -> Vec<TriggerMatch> {
+    // Full-screen applications redraw their content, which would repeat matches.
+    if term.mode().contains(TermMode::ALT_SCREEN) {
+        return Vec::new();
+    }
+
+    let cursor = mem::replace(&mut state.cursor, cursor_position(term));
+    let scrolled_lines = term.grid().scrolled_lines() as i64;
+    let topmost_line = term.topmost_line();
+    let cursor_line = term.grid().cursor.point.line;
+
+    // Matches can't follow the text when it is reflowed.
+    if mem::replace(&mut state.columns, term.columns()) != term.columns() {
+        state.highlights.clear();
+        state.reported.clear();
+    }
+
+    // Drop highlights which were removed from the scrollback history.
+    let topmost_output_line = scrolled_lines + topmost_line.0 as i64;
+    state.highlights.retain(|highlight| highlight.end.0 >= topmost_output_line);
+
+    // Convert the last cursor position back to the grid, clamping it to the available lines.
+    let line = cursor.0 - scrolled_lines;
+    let clamped_line = line.clamp(topmost_line.0 as i64, cursor_line.0 as i64);
+    let column = if clamped_line == line { cursor.1 } else { Column(0) };
+    let last_cursor = Point::new(Line(clamped_line as i32), column);
+
+    // Start at the beginning of the line, to allow matches including previous output.
+    let start = term.line_search_left(Point::new(last_cursor.line, Column(0)));
+    let end = Point::new(cursor_line, term.last_column());
+
+    let to_output = |point: Point| (scrolled_lines + point.line.0 as i64, point.column);
+
+    // Matches starting before the searched output can't be found again.
+    let output_start = to_output(start);
+    state.reported.retain(|(_, reported)| *reported >= output_start);
+
+    let mut matches = Vec::new();
+    for (index, trigger) in triggers.iter().enumerate() {
+        trigger.regex.with_compiled(|regex| {
+            // Ignore matches which were complete before the last evaluation.
+            let iter = RegexIter::new(start, end, Direction::Right, term, regex);
+            let regex_matches: Vec<_> =
+                iter.filter(|regex_match| *regex_match.end() >= last_cursor).collect();
+
+            for regex_match in regex_matches {
+                let match_start = to_output(*regex_match.start());
+
+                match &trigger.action {
+                    TriggerAction::Highlight(colors) => {
+                        let highlight = Highlight {
+                            start: match_start,
+                            end: to_output(*regex_match.end()),
+                            colors: *colors,
+                        };
+
+                        // Matches extending over multiple evaluations are found repeatedly,
+                        // possibly growing with the new output.
+                        let existing = state.highlights.iter_mut().find(|existing| {
+                            existing.start == highlight.start && existing.colors == highlight.colors
+                        });
+                        match existing {
+                            Some(existing) => *existing = highlight,
+                            None => state.highlights.push(highlight),
+                        }
+                    },
+                    // Only report matches extending over multiple evaluations once.
+                    _ if state.reported.contains(&(index, match_start)) => (),
+                    _ => {
+                        state.reported.push((index, match_start));
+
+                        let text = term.bounds_to_string(*regex_match.start(), *regex_match.end());
+                        let captures = regex.captures(term, &regex_match);
+                        matches.push(TriggerMatch { index, text, captures });
+                    },
+                }
+            }
+        });
+    }
+
+    state.highlights.sort_by_key(|highlight| highlight.start);
+
+    matches
+}
+
+/// Output position where the next character will be written.
+fn cursor_position<T>(term: &Term<T>) -> OutputPoint {
+    let grid = term.grid();
+    let line = grid.scrolled_lines() as i64 + grid.cursor.point.line.0 as i64;
+
+    if grid.cursor.input_needs_wrap {
+        (line + 1, Column(0))
+    } else {
+        (line, grid.cursor.point.column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde::Deserialize;
+
+    use alacritty_terminal::event::VoidListener;
+    use alacritty_terminal::parser::Processor;
+    use alacritty_terminal::term::test::TermSize;
+    use alacritty_terminal::term::Config;
+
+    #[derive(Deserialize)]
+    struct Triggers {
+        triggers: Vec<Trigger>,
+    }
+
+    fn triggers(config: &str) -> Vec<Trigger> {
+        toml::from_str::<Triggers>(config).unwrap().triggers
+    }
+
+    fn write(term: &mut Term<VoidListener>, text: &str) {
+        let mut parser = Processor::new();
+        for byte in text.bytes() {
+            parser.advance(term, byte);
+        }
+    }
+
+    /// Write text and evaluate the triggers, returning the text of all matches.
+    fn evaluate_text(
+        term: &mut Term<VoidListener>,
+        state: &mut TriggerState,
+        triggers: &[Trigger],
+        text: &str,
+    ) -> Vec<String> {
+        write(term, text);
+        evaluate(term, state, triggers)
+            .into_iter()
+            .map(|trigger_match| trigger_match.text)
+            .collect()
+    }
+
+    #[test]
+    fn growing_match() {
+        let size = TermSize::new(20, 5);
+        let mut term = Term::new(Config::default(), &size, VoidListener);
+        let mut state = TriggerState::new(&term);
+        let triggers = triggers(r#"triggers = [{ regex = "ERROR \\w+", respond = "x" }]"#);
+
+        assert_eq!(evaluate_text(&mut term, &mut state, &triggers, "ERROR fo"), ["ERROR fo"]);
+        assert!(evaluate_text(&mut term, &mut state, &triggers, "o bar").is_empty());
+        assert_eq!(evaluate_text(&mut term, &mut state, &triggers, " ERROR baz"), ["ERROR baz"]);
+    }
+
+    #[test]
+    fn split_match() {
+        let size = TermSize::new(20, 5);
+        let mut term = Term::new(Config::default(), &size, VoidListener);
+        let mut state = TriggerState::new(&term);
+        let triggers = triggers(r#"triggers = [{ regex = "done", action = "Notify" }]"#);
+
+        assert!(evaluate_text(&mut term, &mut state, &triggers, "do").is_empty());
+        assert_eq!(evaluate_text(&mut term, &mut state, &triggers, "ne"), ["done"]);
+        assert!(evaluate_text(&mut term, &mut state, &triggers, "\r\n").is_empty());
+
+        // Matches wrapping across lines.
+        assert!(evaluate_text(&mut term, &mut state, &triggers, &"x".repeat(18)).is_empty());
+        assert_eq!(evaluate_text(&mut term, &mut state, &triggers, "done"), ["done"]);
+    }
+
+    #[test]
+    fn scrolled_match() {
+        let size = TermSize::new(20, 3);
+        let mut term = Term::new(Config::default(), &size, VoidListener);
+        let mut state = TriggerState::new(&term);
+        let triggers = triggers(r#"triggers = [{ regex = "ERROR \\w+", respond = "x" }]"#);
+
+        assert_eq!(evaluate_text(&mut term, &mut state, &triggers, "ERROR a"), ["ERROR a"]);
+        assert!(evaluate_text(&mut term, &mut state, &triggers, "b\r\n\r\n\r\n").is_empty());
+        assert_eq!(evaluate_text(&mut term, &mut state, &triggers, "ERROR c"), ["ERROR c"]);
+        assert_eq!(state.reported.len(), 1);
+    }
+
+    #[test]
+    fn growing_highlight() {
+        let size = TermSize::new(20, 5);
+        let mut term = Term::new(Config::default(), &size, VoidListener);
+        let mut state = TriggerState::new(&term);
+        let triggers = triggers(
+            r##"triggers = [{ regex = "ERROR \\w+", highlight = { background = "#ff0000" } }]"##,
+        );
+
+        assert!(evaluate_text(&mut term, &mut state, &triggers, "ERROR fo").is_empty());
+        assert!(evaluate_text(&mut term, &mut state, &triggers, "o").is_empty());
+
+        let highlights = state.visible_highlights(&term);
+        assert_eq!(highlights.len(), 1);
+        assert_eq!(
+            highlights[0].0,
+            Point::new(Line(0), Column(0))..=Point::new(Line(0), Column(8))
+        );
+    }
+
+    #[test]
+    fn response_limit() {
+        let size = TermSize::new(10, 5);
+        let term = Term::new(Config::default(), &size, VoidListener);
+        let mut state = TriggerState::new(&term);
+
+        for _ in 0..MAX_RESPONSES {
+            assert!(state.allow_response(0));
+        }
+        assert!(!state.allow_response(0));
+
+        // Triggers are limited independently.
+        assert!(state.allow_response(1));
+    }
+}
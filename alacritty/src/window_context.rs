@@ -4,44 +4,51 @@ use std::error::Error;
 use std::fs::File;
 use std::io::Write;
 use std::mem;
-#[cfg(not(windows))]
-use std::os::unix::io::{AsRawFd, RawFd};
 use std::rc::Rc;
-use std::sync::Arc;
 
 use glutin::config::GetGlConfig;
 use glutin::display::GetGlDisplay;
 #[cfg(all(feature = "x11", not(any(target_os = "macos", windows))))]
 use glutin::platform::x11::X11GlConfigExt;
-use log::info;
+use log::{error, warn};
 use raw_window_handle::HasRawDisplayHandle;
 use serde_json as json;
-use winit::event::{Event as WinitEvent, Modifiers, WindowEvent};
+use winit::event::{ElementState, Event as WinitEvent, Modifiers, MouseButton, WindowEvent};
 use winit::event_loop::{EventLoopProxy, EventLoopWindowTarget};
-use winit::window::WindowId;
+use winit::window::{Theme, WindowId};
 
-use alacritty_terminal::event::Event as TerminalEvent;
-use alacritty_terminal::event_loop::{EventLoop as PtyEventLoop, Msg, Notifier};
+use alacritty_terminal::event::{Event as TerminalEvent, Notify};
 use alacritty_terminal::grid::{Dimensions, Scroll};
 use alacritty_terminal::index::Direction;
-use alacritty_terminal::sync::FairMutex;
+#[cfg(unix)]
+use alacritty_terminal::index::{Column, Line, Point};
+use alacritty_terminal::term::color_scheme::ColorScheme;
+use alacritty_terminal::term::notification::Urgency;
 use alacritty_terminal::term::test::TermSize;
-use alacritty_terminal::term::{Term, TermMode};
-use alacritty_terminal::tty;
+use alacritty_terminal::term::TermMode;
 
-use crate::cli::{ParsedOptions, WindowOptions};
+use crate::cli::{ColorSchemeChange, ParsedOptions, TerminalOptions, WindowOptions};
 use crate::clipboard::Clipboard;
-use crate::config::UiConfig;
+use crate::config::debug::RendererPreference;
+use crate::config::trigger::{TriggerAction, TriggerInternalAction};
+use crate::config::{KeyPress, UiConfig};
 use crate::display::window::Window;
-use crate::display::Display;
-use crate::event::{
-    ActionContext, Event, EventProxy, InlineSearchState, Mouse, SearchState, TouchPurpose,
-};
+use crate::display::{Display, PaneFrame, UnfocusedPane};
+use crate::event::{ActionContext, Event, EventProxy, EventType, Mouse, TouchPurpose};
+#[cfg(unix)]
+use crate::ipc::WindowInfo;
 #[cfg(unix)]
 use crate::logging::LOG_TARGET_IPC_CONFIG;
 use crate::message_bar::MessageBuffer;
+use crate::pane::{Pane, PaneCommand, PaneId, PaneRect, SplitDirection};
 use crate::scheduler::Scheduler;
-use crate::{input, renderer};
+#[cfg(unix)]
+use crate::screenshot::ScreenshotRequest;
+#[cfg(not(target_os = "macos"))]
+use crate::tab::TabCommand;
+use crate::tab::{self, Tab, TabBar};
+use crate::trigger::{self, TriggerMatch};
+use crate::{input, notification, renderer};
 
 /// Event context for one individual Alacritty window.
 pub struct WindowContext {
@@ -49,22 +56,21 @@ pub struct WindowContext {
     pub display: Display,
     pub dirty: bool,
     event_queue: Vec<WinitEvent<Event>>,
-    terminal: Arc<FairMutex<Term<EventProxy>>>,
+    panes: Vec<Pane>,
+    tabs: Vec<Tab>,
+    active_tab: usize,
+    tab_drag: Option<usize>,
     cursor_blink_timed_out: bool,
+    key_sequence: Vec<KeyPress>,
     modifiers: Modifiers,
-    inline_search_state: InlineSearchState,
-    search_state: SearchState,
-    notifier: Notifier,
     mouse: Mouse,
     touch: TouchPurpose,
     occluded: bool,
     preserve_title: bool,
-    #[cfg(not(windows))]
-    master_fd: RawFd,
-    #[cfg(not(windows))]
-    shell_pid: u32,
+    color_scheme: ColorScheme,
     window_config: ParsedOptions,
     config: Rc<UiConfig>,
+    proxy: EventLoopProxy<Event>,
 }
 
 impl WindowContext {
@@ -74,12 +80,30 @@ impl WindowContext {
         proxy: EventLoopProxy<Event>,
         config: Rc<UiConfig>,
         options: WindowOptions,
+        config_overrides: ParsedOptions,
     ) -> Result<Self, Box<dyn Error>> {
         let raw_display_handle = event_loop.raw_display_handle();
 
         let mut identity = config.window.identity.clone();
         options.window_identity.override_identity_config(&mut identity);
 
+        // The software renderer doesn't need any graphics API.
+        if config.debug.renderer == Some(RendererPreference::Software) {
+            let window = Window::new(
+                event_loop,
+                &config,
+                &identity,
+                #[cfg(all(feature = "x11", not(any(target_os = "macos", windows))))]
+                None,
+                #[cfg(target_os = "macos")]
+                &options.window_tabbing_id,
+            )?;
+
+            let display = Display::new(window, None, &config, false)?;
+
+            return Self::new(display, config, options, proxy, config_overrides);
+        }
+
         // Windows has different order of GL platform initialization compared to any other platform;
         // it requires the window first.
         #[cfg(windows)]
@@ -112,9 +136,9 @@ impl WindowContext {
         let gl_context =
             renderer::platform::create_gl_context(&gl_display, &gl_config, raw_window_handle)?;
 
-        let display = Display::new(window, gl_context, &config, false)?;
+        let display = Display::new(window, Some(gl_context), &config, false)?;
 
-        Self::new(display, config, options, proxy)
+        Self::new(display, config, options, proxy, config_overrides)
     }
 
     /// Create additional context with the graphics platform other windows are using.
@@ -127,10 +151,8 @@ impl WindowContext {
         config_overrides: ParsedOptions,
     ) -> Result<Self, Box<dyn Error>> {
         // Get any window and take its GL config and display to build a new context.
-        let (gl_display, gl_config) = {
-            let gl_context = self.display.gl_context();
-            (gl_context.display(), gl_context.config())
-        };
+        let gl_platform =
+            self.display.gl_context().map(|gl_context| (gl_context.display(), gl_context.config()));
 
         let mut identity = config.window.identity.clone();
         options.window_identity.override_identity_config(&mut identity);
@@ -140,18 +162,23 @@ impl WindowContext {
             &config,
             &identity,
             #[cfg(all(feature = "x11", not(any(target_os = "macos", windows))))]
-            gl_config.x11_visual(),
+            gl_platform.as_ref().and_then(|(_, gl_config)| gl_config.x11_visual()),
             #[cfg(target_os = "macos")]
             &options.window_tabbing_id,
         )?;
 
-        // Create context.
-        let raw_window_handle = window.raw_window_handle();
-        let gl_context = renderer::platform::create_gl_context(
-            &gl_display,
-            &gl_config,
-            Some(raw_window_handle),
-        )?;
+        // Create context, unless the software renderer is used.
+        let gl_context = match gl_platform {
+            Some((gl_display, gl_config)) => {
+                let raw_window_handle = window.raw_window_handle();
+                Some(renderer::platform::create_gl_context(
+                    &gl_display,
+                    &gl_config,
+                    Some(raw_window_handle),
+                )?)
+            },
+            None => None,
+        };
 
         // Check if new window will be opened as a tab.
         #[cfg(target_os = "macos")]
@@ -161,14 +188,7 @@ impl WindowContext {
 
         let display = Display::new(window, gl_context, &config, tabbed)?;
 
-        let mut window_context = Self::new(display, config, options, proxy)?;
-
-        // Set the config overrides at startup.
-        //
-        // These are already applied to `config`, so no update is necessary.
-        window_context.window_config = config_overrides;
-
-        Ok(window_context)
+        Self::new(display, config, options, proxy, config_overrides)
     }
 
     /// Create a new terminal window context.
@@ -177,100 +197,76 @@ impl WindowContext {
         config: Rc<UiConfig>,
         options: WindowOptions,
         proxy: EventLoopProxy<Event>,
+        config_overrides: ParsedOptions,
     ) -> Result<Self, Box<dyn Error>> {
-        let mut pty_config = config.pty_config();
-        options.terminal_options.override_pty_config(&mut pty_config);
-
         let preserve_title = options.window_identity.title.is_some();
 
-        info!(
-            "PTY dimensions: {:?} x {:?}",
-            display.size_info.screen_lines(),
-            display.size_info.columns()
-        );
-
-        let event_proxy = EventProxy::new(proxy, display.window.id());
-
-        // Create the terminal.
-        //
-        // This object contains all of the state about what's being displayed. It's
-        // wrapped in a clonable mutex since both the I/O loop and display need to
-        // access it.
-        let terminal = Term::new(config.term_options(), &display.size_info, event_proxy.clone());
-        let terminal = Arc::new(FairMutex::new(terminal));
-
-        // Create the PTY.
-        //
-        // The PTY forks a process to run the shell on the slave side of the
-        // pseudoterminal. A file descriptor for the master side is retained for
-        // reading/writing to the shell.
-        let pty = tty::new(&pty_config, display.size_info.into(), display.window.id().into())?;
+        // Follow the system theme with the color scheme.
+        let color_scheme = match display.window.theme() {
+            Some(Theme::Light) => ColorScheme::Light,
+            _ => ColorScheme::Dark,
+        };
 
-        #[cfg(not(windows))]
-        let master_fd = pty.file().as_raw_fd();
-        #[cfg(not(windows))]
-        let shell_pid = pty.child().id();
-
-        // Create the pseudoterminal I/O loop.
-        //
-        // PTY I/O is ran on another thread as to not occupy cycles used by the
-        // renderer and input processing. Note that access to the terminal state is
-        // synchronized since the I/O loop updates the state, and the display
-        // consumes it periodically.
-        let event_loop = PtyEventLoop::new(
-            Arc::clone(&terminal),
-            event_proxy.clone(),
-            pty,
-            pty_config.hold,
-            config.debug.ref_test,
+        let pane = Pane::new(
+            &config,
+            &options.terminal_options,
+            display.size_info,
+            proxy.clone(),
+            display.window.id(),
+            true,
         )?;
-
-        // The event loop channel allows write requests from the event processor
-        // to be sent to the pty loop and ultimately written to the pty.
-        let loop_tx = event_loop.channel();
-
-        // Kick off the I/O thread.
-        let _io_thread = event_loop.spawn();
-
-        // Start cursor blinking, in case `Focused` isn't sent on startup.
-        if config.cursor.style().blinking {
-            event_proxy.send_event(TerminalEvent::CursorBlinkingChange.into());
-        }
+        pane.terminal.lock().set_color_scheme(color_scheme);
 
         // Create context for the Alacritty window.
-        Ok(WindowContext {
+        let mut window_context = WindowContext {
             preserve_title,
-            terminal,
+            color_scheme,
+            tabs: vec![Tab::new(pane.id)],
+            panes: vec![pane],
             display,
-            #[cfg(not(windows))]
-            master_fd,
-            #[cfg(not(windows))]
-            shell_pid,
             config,
-            notifier: Notifier(loop_tx),
+            proxy,
             cursor_blink_timed_out: Default::default(),
-            inline_search_state: Default::default(),
             message_buffer: Default::default(),
-            window_config: Default::default(),
-            search_state: Default::default(),
+            key_sequence: Default::default(),
+            // The config overrides are already applied to `config` at startup.
+            window_config: config_overrides,
             event_queue: Default::default(),
+            active_tab: Default::default(),
+            tab_drag: Default::default(),
             modifiers: Default::default(),
             occluded: Default::default(),
             mouse: Default::default(),
             touch: Default::default(),
             dirty: Default::default(),
-        })
+        };
+
+        // Apply the palette of the initial color scheme.
+        if window_context.config.colors.palette(color_scheme).is_some() {
+            window_context.update_config(window_context.config.clone());
+        }
+
+        Ok(window_context)
     }
 
     /// Update the terminal window to the latest config.
     pub fn update_config(&mut self, new_config: Rc<UiConfig>) {
         let old_config = mem::replace(&mut self.config, new_config);
 
+        // Apply the palette of the active color scheme.
+        if let Some(palette) = self.config.colors.palette(self.color_scheme) {
+            let mut config = (*self.config).clone();
+            palette.apply(&mut config.colors);
+            self.config = Rc::new(config);
+        }
+
         // Apply ipc config if there are overrides.
         self.config = self.window_config.override_config_rc(self.config.clone());
 
         self.display.update_config(&self.config);
-        self.terminal.lock().set_options(self.config.term_options());
+        for pane in &self.panes {
+            pane.terminal.lock().set_options(self.config.term_options());
+        }
 
         // Reload cursor if its thickness has changed.
         if (old_config.cursor.thickness() - self.config.cursor.thickness()).abs() > f32::EPSILON {
@@ -300,18 +296,13 @@ impl WindowContext {
             self.display.pending_update.dirty = true;
         }
 
-        // Update title on config reload according to the following table.
-        //
-        // │cli │ dynamic_title │ current_title == old_config ││ set_title │
-        // │ Y  │       _       │              _              ││     N     │
-        // │ N  │       Y       │              Y              ││     Y     │
-        // │ N  │       Y       │              N              ││     N     │
-        // │ N  │       N       │              _              ││     Y     │
-        if !self.preserve_title
-            && (!self.config.window.dynamic_title
-                || self.display.window.title() == old_config.window.identity.title)
-        {
-            self.display.window.set_title(self.config.window.identity.title.clone());
+        // Update title on config reload, unless it was set on the command line.
+        if !self.preserve_title {
+            let terminal = self.focused().terminal.lock();
+            let title = self.config.window.title(terminal.title(), terminal.working_directory());
+            drop(terminal);
+
+            self.display.window.set_title(title);
         }
 
         let opaque = self.config.window_opacity() >= 1.;
@@ -337,6 +328,28 @@ impl WindowContext {
         self.dirty = true;
     }
 
+    /// Switch the color scheme of the window.
+    pub fn set_color_scheme(&mut self, config: Rc<UiConfig>, change: ColorSchemeChange) {
+        let color_scheme = match change {
+            ColorSchemeChange::Light => ColorScheme::Light,
+            ColorSchemeChange::Dark => ColorScheme::Dark,
+            ColorSchemeChange::Toggle => self.color_scheme.toggled(),
+        };
+
+        if color_scheme == self.color_scheme {
+            return;
+        }
+        self.color_scheme = color_scheme;
+
+        // Reload current config to pull in the new palette.
+        self.update_config(config);
+
+        // Notify applications about the new color scheme.
+        for pane in &self.panes {
+            pane.terminal.lock().set_color_scheme(color_scheme);
+        }
+    }
+
     /// Clear the window config overrides.
     #[cfg(unix)]
     pub fn reset_window_config(&mut self, config: Rc<UiConfig>) {
@@ -385,14 +398,44 @@ impl WindowContext {
             }
         }
 
+        let tab_bar = self.tab_bar();
+
+        // Lock the terminals of all panes in the active tab.
+        let focused_index = self.focused_index();
+        let layout = &self.tabs[self.active_tab].layout;
+        let dividers = layout.arrange(self.pane_area()).dividers;
+        let mut focused = None;
+        let mut unfocused = Vec::new();
+        for (i, pane) in self.panes.iter_mut().enumerate() {
+            if !layout.contains(pane.id) {
+                continue;
+            } else if i == focused_index {
+                let terminal = pane.terminal.lock();
+                focused = Some((pane.rect, terminal, &mut pane.search_state, &pane.trigger_state));
+            } else {
+                unfocused.push(UnfocusedPane {
+                    rect: pane.rect,
+                    terminal: pane.terminal.lock(),
+                    search_state: &mut pane.search_state,
+                    trigger_state: &pane.trigger_state,
+                });
+            }
+        }
+
+        let (rect, terminal, search_state, trigger_state) = match focused {
+            Some(focused) => focused,
+            None => return,
+        };
+
         // Redraw the window.
-        let terminal = self.terminal.lock();
+        let panes = PaneFrame { focused: rect, trigger_state, unfocused, dividers, tab_bar };
         self.display.draw(
             terminal,
+            panes,
             scheduler,
             &self.message_buffer,
             &self.config,
-            &mut self.search_state,
+            search_state,
         );
     }
 
@@ -421,27 +464,235 @@ impl WindowContext {
             },
         }
 
-        let mut terminal = self.terminal.lock();
+        let old_is_searching = self.focused().search_state.history_index.is_some();
+
+        // Group the events by the pane they are targeting, keeping their order.
+        let events = mem::take(&mut self.event_queue);
+        let mut batch = Vec::with_capacity(events.len());
+        let mut batch_pane = self.focused_pane();
+        for event in events {
+            let pane_id = match &event {
+                WinitEvent::UserEvent(event) => match event.payload() {
+                    EventType::Pane(command) => {
+                        let command = *command;
+                        self.process_events(
+                            batch_pane,
+                            &mut batch,
+                            event_loop,
+                            event_proxy,
+                            clipboard,
+                            scheduler,
+                        );
+                        self.pane_command(command);
+                        continue;
+                    },
+                    #[cfg(not(target_os = "macos"))]
+                    EventType::Tab(command) => {
+                        let command = *command;
+                        self.process_events(
+                            batch_pane,
+                            &mut batch,
+                            event_loop,
+                            event_proxy,
+                            clipboard,
+                            scheduler,
+                        );
+                        self.tab_command(command);
+                        continue;
+                    },
+                    _ => event.pane_id().unwrap_or(self.focused_pane()),
+                },
+                // Focus panes and tabs when they're clicked.
+                WinitEvent::WindowEvent {
+                    event: WindowEvent::MouseInput { state: ElementState::Pressed, button, .. },
+                    ..
+                } => {
+                    // Process pending mouse motion first, to get the click's position.
+                    self.process_events(
+                        batch_pane,
+                        &mut batch,
+                        event_loop,
+                        event_proxy,
+                        clipboard,
+                        scheduler,
+                    );
+
+                    let (x, y) = (self.mouse.x, self.mouse.y);
+
+                    // Clicks on the tab bar are not passed on to the terminal.
+                    if let Some(index) = self.tab_at(x, y) {
+                        if *button == MouseButton::Left {
+                            self.select_tab(index);
+                            self.tab_drag = Some(index);
+                        }
+                        continue;
+                    }
+
+                    let layout = &self.tabs[self.active_tab].layout;
+                    let clicked_pane = self
+                        .panes
+                        .iter()
+                        .find(|pane| {
+                            layout.contains(pane.id) && pane.size_info.contains_point(x, y)
+                        })
+                        .map(|pane| pane.id);
+                    if let Some(pane_id) = clicked_pane {
+                        self.focus_pane(pane_id);
+                    }
+
+                    self.focused_pane()
+                },
+                // Move tabs by dragging them to another position.
+                WinitEvent::WindowEvent {
+                    event:
+                        WindowEvent::MouseInput {
+                            state: ElementState::Released,
+                            button: MouseButton::Left,
+                            ..
+                        },
+                    ..
+                } if self.tab_drag.is_some() => {
+                    self.process_events(
+                        batch_pane,
+                        &mut batch,
+                        event_loop,
+                        event_proxy,
+                        clipboard,
+                        scheduler,
+                    );
+
+                    let from = self.tab_drag.take().unwrap_or_default();
+                    if let Some(to) = self.tab_at(self.mouse.x, self.mouse.y) {
+                        self.move_tab(from, to);
+                    }
+                    continue;
+                },
+                _ => self.focused_pane(),
+            };
+
+            if pane_id != batch_pane {
+                self.process_events(
+                    batch_pane,
+                    &mut batch,
+                    event_loop,
+                    event_proxy,
+                    clipboard,
+                    scheduler,
+                );
+                batch_pane = pane_id;
+            }
+
+            batch.push(event);
+        }
+        self.process_events(batch_pane, &mut batch, event_loop, event_proxy, clipboard, scheduler);
 
-        let old_is_searching = self.search_state.history_index.is_some();
+        // Process DisplayUpdate events.
+        if self.display.pending_update.dirty {
+            self.submit_display_update(old_is_searching);
+            self.dirty = true;
+        }
+
+        if self.dirty || self.mouse.hint_highlight_dirty {
+            let pane = &self.panes[self.focused_index()];
+            self.dirty |= self.display.update_highlighted_hints(
+                &pane.terminal.lock(),
+                &pane.size_info,
+                &self.config,
+                &self.mouse,
+                self.modifiers.state(),
+            );
+            self.mouse.hint_highlight_dirty = false;
+        }
+
+        // Don't call `request_redraw` when event is `RedrawRequested` since the `dirty` flag
+        // represents the current frame, but redraw is for the next frame.
+        if self.dirty
+            && self.display.window.has_frame
+            && !self.occluded
+            && !matches!(event, WinitEvent::WindowEvent { event: WindowEvent::RedrawRequested, .. })
+        {
+            self.display.window.request_redraw();
+        }
+    }
+
+    /// Process a batch of events with the context of a pane.
+    fn process_events(
+        &mut self,
+        pane_id: PaneId,
+        events: &mut Vec<WinitEvent<Event>>,
+        event_loop: &EventLoopWindowTarget<Event>,
+        event_proxy: &EventLoopProxy<Event>,
+        clipboard: &mut Clipboard,
+        scheduler: &mut Scheduler,
+    ) {
+        let is_focused = pane_id == self.focused_pane();
+        let pane = match self.panes.iter_mut().find(|pane| pane.id == pane_id) {
+            Some(pane) => pane,
+            None => {
+                events.clear();
+                return;
+            },
+        };
+
+        // Only the focused pane controls the window's title and cursor.
+        let mut title_changed = false;
+        events.retain(|event| {
+            let event = match event {
+                WinitEvent::UserEvent(event) => event,
+                _ => return true,
+            };
+
+            match event.payload() {
+                EventType::Terminal(TerminalEvent::Title(title)) => {
+                    pane.title = Some(title.clone());
+                    title_changed = true;
+                    is_focused
+                },
+                EventType::Terminal(TerminalEvent::ResetTitle) => {
+                    pane.title = None;
+                    title_changed = true;
+                    is_focused
+                },
+                EventType::Terminal(
+                    TerminalEvent::CursorBlinkingChange
+                    | TerminalEvent::MouseCursorDirty
+                    | TerminalEvent::WorkingDirectory(_),
+                ) => is_focused,
+                _ => true,
+            }
+        });
+
+        // Update the titles in the tab bar.
+        if title_changed && self.tabs.len() > 1 {
+            self.dirty = true;
+        }
+
+        if events.is_empty() {
+            return;
+        }
+
+        let mut terminal = pane.terminal.lock();
 
         let context = ActionContext {
             cursor_blink_timed_out: &mut self.cursor_blink_timed_out,
             message_buffer: &mut self.message_buffer,
-            inline_search_state: &mut self.inline_search_state,
-            search_state: &mut self.search_state,
+            key_sequence: &mut self.key_sequence,
+            inline_search_state: &mut pane.inline_search_state,
+            search_state: &mut pane.search_state,
             modifiers: &mut self.modifiers,
-            notifier: &mut self.notifier,
+            notifier: &mut pane.notifier,
+            recording: &mut pane.recording,
             display: &mut self.display,
             mouse: &mut self.mouse,
             touch: &mut self.touch,
             dirty: &mut self.dirty,
             occluded: &mut self.occluded,
+            size_info: pane.size_info,
             terminal: &mut terminal,
             #[cfg(not(windows))]
-            master_fd: self.master_fd,
+            master_fd: pane.master_fd,
             #[cfg(not(windows))]
-            shell_pid: self.shell_pid,
+            shell_pid: pane.shell_pid,
             preserve_title: self.preserve_title,
             config: &self.config,
             event_proxy,
@@ -451,54 +702,432 @@ impl WindowContext {
         };
         let mut processor = input::Processor::new(context);
 
-        for event in self.event_queue.drain(..) {
+        for event in events.drain(..) {
             processor.handle_event(event);
         }
+    }
 
-        // Process DisplayUpdate events.
-        if self.display.pending_update.dirty {
-            Self::submit_display_update(
-                &mut terminal,
-                &mut self.display,
-                &mut self.notifier,
-                &self.message_buffer,
-                &mut self.search_state,
-                old_is_searching,
-                &self.config,
-            );
-            self.dirty = true;
+    /// Apply a pane command from the focused pane.
+    fn pane_command(&mut self, command: PaneCommand) {
+        let area = self.pane_area();
+        match command {
+            PaneCommand::Split(direction) => {
+                if let Err(err) = self.split_pane(direction) {
+                    error!("Unable to split pane: {}", err);
+                }
+            },
+            // Panes are removed once their terminal has shut down.
+            PaneCommand::Close => self.focused().terminal.lock().exit(),
+            PaneCommand::Focus(direction) => {
+                let tab = &self.tabs[self.active_tab];
+                if let Some(pane_id) = tab.layout.neighbor(area, tab.focused_pane, direction) {
+                    self.focus_pane(pane_id);
+                }
+            },
+            PaneCommand::Resize(direction) => {
+                let tab = &mut self.tabs[self.active_tab];
+                if tab.layout.resize(area, tab.focused_pane, direction) {
+                    self.resize_panes();
+                }
+            },
         }
 
-        if self.dirty || self.mouse.hint_highlight_dirty {
-            self.dirty |= self.display.update_highlighted_hints(
-                &terminal,
-                &self.config,
-                &self.mouse,
-                self.modifiers.state(),
-            );
-            self.mouse.hint_highlight_dirty = false;
+        self.display.damage_tracker.frame().mark_fully_damaged();
+        self.dirty = true;
+    }
+
+    /// Split the focused pane, starting a new terminal next to it.
+    fn split_pane(&mut self, direction: SplitDirection) -> Result<(), Box<dyn Error>> {
+        // Both panes and their divider require at least one cell.
+        if self.focused().rect.length(direction) < 3 {
+            return Ok(());
         }
 
-        // Don't call `request_redraw` when event is `RedrawRequested` since the `dirty` flag
-        // represents the current frame, but redraw is for the next frame.
-        if self.dirty
-            && self.display.window.has_frame
-            && !self.occluded
-            && !matches!(event, WinitEvent::WindowEvent { event: WindowEvent::RedrawRequested, .. })
+        let pane = self.create_pane()?;
+        let pane_id = pane.id;
+        self.panes.push(pane);
+
+        let tab = &mut self.tabs[self.active_tab];
+        tab.layout.split(tab.focused_pane, pane_id, direction);
+        self.resize_panes();
+        self.focus_pane(pane_id);
+
+        Ok(())
+    }
+
+    /// Start a new terminal in the working directory of the focused one.
+    fn create_pane(&self) -> Result<Pane, Box<dyn Error>> {
+        let focused = self.focused();
+
+        #[cfg(unix)]
+        let options = TerminalOptions {
+            working_directory: focused.working_directory(),
+            ..Default::default()
+        };
+        #[cfg(not(unix))]
+        let options = TerminalOptions::default();
+
+        let proxy = self.proxy.clone();
+        let pane = Pane::new(&self.config, &options, focused.size_info, proxy, self.id(), false)?;
+        pane.terminal.lock().set_color_scheme(self.color_scheme);
+
+        Ok(pane)
+    }
+
+    /// Remove a pane after its terminal has shut down.
+    ///
+    /// Tabs are closed together with their last pane. This will return `false` if the pane was
+    /// the last one in the window.
+    pub fn close_pane(&mut self, pane_id: PaneId) -> bool {
+        let index = match self.panes.iter().position(|pane| pane.id == pane_id) {
+            Some(index) => index,
+            None => return true,
+        };
+        let tab_index = match self.tabs.iter().position(|tab| tab.layout.contains(pane_id)) {
+            Some(tab_index) => tab_index,
+            None => return true,
+        };
+
+        let was_focused = pane_id == self.focused_pane();
+        let is_focused = was_focused && self.is_focused();
+
+        if self.tabs[tab_index].layout.remove(pane_id) {
+            // Pass keyboard focus on to the first remaining pane of the tab.
+            let area = self.pane_area();
+            let tab = &mut self.tabs[tab_index];
+            if tab.focused_pane == pane_id {
+                let panes = tab.layout.arrange(area).panes;
+                tab.focused_pane = panes.first().map_or(pane_id, |(pane_id, _)| *pane_id);
+            }
+        } else if self.tabs.len() > 1 {
+            self.tabs.remove(tab_index);
+            if tab_index < self.active_tab || self.active_tab == self.tabs.len() {
+                self.active_tab -= 1;
+            }
+        } else {
+            return false;
+        }
+
+        self.panes.remove(index);
+
+        if was_focused {
+            self.focused().set_focused(is_focused);
+            self.pane_focus_changed();
+        }
+
+        self.resize_panes();
+
+        self.display.damage_tracker.frame().mark_fully_damaged();
+        self.dirty = true;
+
+        true
+    }
+
+    /// Move keyboard focus to another pane of the active tab.
+    fn focus_pane(&mut self, pane_id: PaneId) {
+        if pane_id == self.focused_pane() {
+            return;
+        }
+
+        let is_focused = self.is_focused();
+        self.focused().set_focused(false);
+        self.tabs[self.active_tab].focused_pane = pane_id;
+        self.focused().set_focused(is_focused);
+
+        self.pane_focus_changed();
+    }
+
+    /// Update the window after keyboard focus moved to another pane.
+    fn pane_focus_changed(&mut self) {
+        let pane = &self.panes[self.focused_index()];
+
+        // Show the title of the focused pane.
+        let window_config = &self.config.window;
+        if !self.preserve_title
+            && (window_config.dynamic_title || window_config.title_template.is_some())
         {
-            self.display.window.request_redraw();
+            let terminal = pane.terminal.lock();
+            let title = window_config.title(terminal.title(), terminal.working_directory());
+            self.display.window.set_title(title);
+        }
+
+        // Hints are only highlighted in the focused pane.
+        self.display.highlighted_hint = None;
+        self.display.vi_highlighted_hint = None;
+        self.mouse.inside_text_area = pane.size_info.contains_point(self.mouse.x, self.mouse.y);
+        self.mouse.hint_highlight_dirty = true;
+
+        // Update the cursor blinking and search bar of the new pane.
+        let event = Event::new(TerminalEvent::CursorBlinkingChange.into(), None);
+        self.event_queue.push(event.into());
+        self.display.pending_update.dirty = true;
+
+        self.dirty = true;
+    }
+
+    /// Apply a tab command from the focused pane.
+    #[cfg(not(target_os = "macos"))]
+    fn tab_command(&mut self, command: TabCommand) {
+        let count = self.tabs.len();
+        match command {
+            TabCommand::Create => {
+                if let Err(err) = self.create_tab() {
+                    error!("Unable to create tab: {}", err);
+                }
+            },
+            TabCommand::Next => self.select_tab((self.active_tab + 1) % count),
+            TabCommand::Previous => self.select_tab((self.active_tab + count - 1) % count),
+            TabCommand::Select(index) => self.select_tab(index),
+            TabCommand::Last => self.select_tab(count - 1),
         }
     }
 
+    /// Open a new tab after the active one.
+    #[cfg(not(target_os = "macos"))]
+    fn create_tab(&mut self) -> Result<(), Box<dyn Error>> {
+        let pane = self.create_pane()?;
+        let index = self.active_tab + 1;
+        self.tabs.insert(index, Tab::new(pane.id));
+        self.panes.push(pane);
+
+        // Make room for the tab bar.
+        self.resize_panes();
+        self.select_tab(index);
+
+        Ok(())
+    }
+
+    /// Activate the tab at `index`.
+    fn select_tab(&mut self, index: usize) {
+        if index == self.active_tab || index >= self.tabs.len() {
+            return;
+        }
+
+        let is_focused = self.is_focused();
+        self.focused().set_focused(false);
+        self.active_tab = index;
+        self.focused().set_focused(is_focused);
+
+        self.pane_focus_changed();
+        self.display.damage_tracker.frame().mark_fully_damaged();
+    }
+
+    /// Move the tab at `from` to position `to`.
+    fn move_tab(&mut self, from: usize, to: usize) {
+        if from == to || from >= self.tabs.len() || to >= self.tabs.len() {
+            return;
+        }
+
+        let tab = self.tabs.remove(from);
+        self.tabs.insert(to, tab);
+
+        // Keep the moved tab active, since dragging selects it.
+        if self.active_tab == from {
+            self.active_tab = to;
+        } else if from < self.active_tab && self.active_tab <= to {
+            self.active_tab -= 1;
+        } else if to <= self.active_tab && self.active_tab < from {
+            self.active_tab += 1;
+        }
+
+        self.display.damage_tracker.frame().mark_fully_damaged();
+        self.dirty = true;
+    }
+
+    /// Titles of all tabs, if the tab bar is visible.
+    fn tab_bar(&self) -> Option<TabBar> {
+        if self.tabs.len() < 2 {
+            return None;
+        }
+
+        let titles = self
+            .tabs
+            .iter()
+            .map(|tab| {
+                let pane = self.panes.iter().find(|pane| pane.id == tab.focused_pane);
+                let title = pane.and_then(|pane| pane.title.clone());
+                title.unwrap_or_else(|| self.config.window.identity.title.clone())
+            })
+            .collect();
+
+        Some(TabBar { titles, active: self.active_tab })
+    }
+
+    /// Tab in the tab bar at a position in the window.
+    fn tab_at(&self, x: usize, y: usize) -> Option<usize> {
+        if self.tabs.len() < 2 {
+            return None;
+        }
+
+        let size_info = &self.display.size_info;
+        let (padding_x, padding_y) =
+            (size_info.padding_x() as usize, size_info.padding_y() as usize);
+        if x < padding_x || y < padding_y || y >= padding_y + size_info.cell_height() as usize {
+            return None;
+        }
+
+        let column = (x - padding_x) / size_info.cell_width() as usize;
+        tab::tab_at(self.tabs.len(), size_info.columns(), column)
+    }
+
+    /// Fit the panes of all tabs into their area of the window.
+    fn resize_panes(&mut self) {
+        let size_info = self.display.size_info;
+        let area = self.pane_area();
+        for (pane_id, rect) in self.tabs.iter().flat_map(|tab| tab.layout.arrange(area).panes) {
+            if let Some(pane) = self.panes.iter_mut().find(|pane| pane.id == pane_id) {
+                pane.resize(rect, size_info.pane(&rect));
+            }
+        }
+    }
+
+    /// Grid area shared by all panes of a tab.
+    fn pane_area(&self) -> PaneRect {
+        let size_info = &self.display.size_info;
+
+        // Reserve the first line for the tab bar.
+        let tab_bar_lines = usize::from(self.tabs.len() > 1);
+        let lines = size_info.screen_lines().saturating_sub(tab_bar_lines);
+
+        PaneRect::new(tab_bar_lines, 0, lines, size_info.columns())
+    }
+
+    /// Pane with keyboard focus.
+    fn focused(&self) -> &Pane {
+        &self.panes[self.focused_index()]
+    }
+
+    fn focused_index(&self) -> usize {
+        let focused_pane = self.focused_pane();
+        self.panes.iter().position(|pane| pane.id == focused_pane).unwrap_or_default()
+    }
+
+    fn focused_pane(&self) -> PaneId {
+        self.tabs[self.active_tab].focused_pane
+    }
+
     /// ID of this terminal context.
     pub fn id(&self) -> WindowId {
         self.display.window.id()
     }
 
+    /// Save the next frame as screenshot.
+    #[cfg(unix)]
+    pub fn screenshot(&mut self, request: ScreenshotRequest) {
+        self.display.screenshot(request);
+        self.dirty = true;
+    }
+
+    /// Check if the window has keyboard focus.
+    pub fn is_focused(&self) -> bool {
+        self.focused().terminal.lock().is_focused
+    }
+
+    /// Execute triggers for new output of a pane.
+    pub fn run_triggers(&mut self, pane_id: PaneId) {
+        if self.config.triggers.is_empty() {
+            return;
+        }
+
+        let pane = match self.panes.iter_mut().find(|pane| pane.id == pane_id) {
+            Some(pane) => pane,
+            None => return,
+        };
+
+        let terminal = pane.terminal.lock();
+        let triggers = &self.config.triggers;
+        let matches = trigger::evaluate(&terminal, &mut pane.trigger_state, triggers);
+        drop(terminal);
+
+        for TriggerMatch { index, text, mut captures } in matches {
+            let trigger = &self.config.triggers[index];
+            match &trigger.action {
+                TriggerAction::Action(TriggerInternalAction::Bell) => {
+                    let window_id = self.display.window.id();
+                    let proxy = EventProxy::new(self.proxy.clone(), window_id, pane_id);
+                    proxy.send_event(TerminalEvent::Bell.into());
+                },
+                TriggerAction::Action(TriggerInternalAction::Notify) => {
+                    let title = pane.title.as_deref().unwrap_or(&self.config.window.identity.title);
+                    let config = &self.config.notifications;
+                    match notification::command(config, title, &text, Urgency::Normal) {
+                        Some(program) => pane.spawn_daemon(program.program(), program.args()),
+                        None => warn!("Desktop notifications are not supported on this platform"),
+                    }
+                },
+                TriggerAction::Command(program) => {
+                    // Pass the capture groups, or the whole match without any groups.
+                    if captures.len() > 1 {
+                        captures.remove(0);
+                    } else if captures.is_empty() {
+                        captures.push(text);
+                    }
+
+                    let mut args = program.args().to_vec();
+                    args.append(&mut captures);
+                    pane.spawn_daemon(program.program(), &args);
+                },
+                TriggerAction::Respond(response) => {
+                    if pane.trigger_state.allow_response(index) {
+                        pane.notifier.notify(response.clone().into_bytes());
+                    }
+                },
+                TriggerAction::Highlight(_) => (),
+            }
+        }
+    }
+
+    /// Describe the window for IPC queries.
+    #[cfg(unix)]
+    pub fn ipc_info(&self) -> WindowInfo {
+        let size_info = &self.display.size_info;
+        let pane = self.focused();
+        WindowInfo {
+            id: self.id().into(),
+            title: self.display.window.title().to_owned(),
+            columns: size_info.columns(),
+            lines: size_info.screen_lines(),
+            width: size_info.width() as u32,
+            height: size_info.height() as u32,
+            focused: self.is_focused(),
+            cwd: pane.working_directory(),
+        }
+    }
+
+    /// Text between two lines for IPC queries.
+    ///
+    /// The lines default to the viewport and are clamped to the available lines.
+    #[cfg(unix)]
+    pub fn ipc_text(&self, start: Option<i32>, end: Option<i32>) -> String {
+        let terminal = self.focused().terminal.lock();
+
+        let topmost = terminal.topmost_line().0;
+        let bottommost = terminal.bottommost_line().0;
+        let viewport_start = -(terminal.grid().display_offset() as i32);
+        let viewport_end = viewport_start + terminal.screen_lines() as i32 - 1;
+
+        let start = start.unwrap_or(viewport_start).clamp(topmost, bottommost);
+        let end = end.unwrap_or(viewport_end).clamp(topmost, bottommost);
+        if start > end {
+            return String::new();
+        }
+
+        let start = Point::new(Line(start), Column(0));
+        let end = Point::new(Line(end), terminal.last_column());
+        terminal.bounds_to_string(start, end)
+    }
+
+    /// Names of the active terminal modes for IPC queries.
+    #[cfg(unix)]
+    pub fn ipc_mode(&self) -> Vec<String> {
+        let terminal = self.focused().terminal.lock();
+        terminal.mode().iter_names().map(|(name, _)| name.to_owned()).collect()
+    }
+
     /// Write the ref test results to the disk.
     pub fn write_ref_test_results(&self) {
         // Dump grid state.
-        let mut grid = self.terminal.lock().grid().clone();
+        let mut grid = self.focused().terminal.lock().grid().clone();
         grid.initialize_all();
         grid.truncate();
 
@@ -508,7 +1137,12 @@ impl WindowContext {
         let size = TermSize::new(size_info.columns(), size_info.screen_lines());
         let serialized_size = json::to_string(&size).expect("serialize size");
 
-        let serialized_config = format!("{{\"history_size\":{}}}", grid.history_size());
+        let serialized_config = format!(
+            "{{\"history_size\":{},\"cell_width\":{},\"cell_height\":{}}}",
+            grid.history_size(),
+            size_info.cell_width() as usize,
+            size_info.cell_height() as usize,
+        );
 
         File::create("./grid.json")
             .and_then(|mut f| f.write_all(serialized_grid.as_bytes()))
@@ -524,29 +1158,33 @@ impl WindowContext {
     }
 
     /// Submit the pending changes to the `Display`.
-    fn submit_display_update(
-        terminal: &mut Term<EventProxy>,
-        display: &mut Display,
-        notifier: &mut Notifier,
-        message_buffer: &MessageBuffer,
-        search_state: &mut SearchState,
-        old_is_searching: bool,
-        config: &UiConfig,
-    ) {
+    fn submit_display_update(&mut self, old_is_searching: bool) {
+        let index = self.focused_index();
+
         // Compute cursor positions before resize.
-        let num_lines = terminal.screen_lines();
-        let cursor_at_bottom = terminal.grid().cursor.point.line + 1 == num_lines;
-        let origin_at_bottom = if terminal.mode().contains(TermMode::VI) {
-            terminal.vi_mode_cursor.point.line == num_lines - 1
-        } else {
-            search_state.direction == Direction::Left
+        let (cursor_at_bottom, origin_at_bottom) = {
+            let pane = &self.panes[index];
+            let terminal = pane.terminal.lock();
+            let num_lines = terminal.screen_lines();
+            let cursor_at_bottom = terminal.grid().cursor.point.line + 1 == num_lines;
+            let origin_at_bottom = if terminal.mode().contains(TermMode::VI) {
+                terminal.vi_mode_cursor.point.line == num_lines - 1
+            } else {
+                pane.search_state.direction == Direction::Left
+            };
+            (cursor_at_bottom, origin_at_bottom)
         };
 
-        display.handle_update(terminal, notifier, message_buffer, search_state, config);
+        let search_state = &mut self.panes[index].search_state;
+        self.display.handle_update(&self.message_buffer, search_state, &self.config);
 
-        let new_is_searching = search_state.history_index.is_some();
+        self.resize_panes();
+
+        let pane = &self.panes[index];
+        let new_is_searching = pane.search_state.history_index.is_some();
         if !old_is_searching && new_is_searching {
             // Scroll on search start to make sure origin is visible with minimal viewport motion.
+            let mut terminal = pane.terminal.lock();
             let display_offset = terminal.grid().display_offset();
             if display_offset == 0 && cursor_at_bottom && !origin_at_bottom {
                 terminal.scroll_display(Scroll::Delta(1));
@@ -556,10 +1194,3 @@ impl WindowContext {
         }
     }
 }
-
-impl Drop for WindowContext {
-    fn drop(&mut self) {
-        // Shutdown the terminal's PTY.
-        let _ = self.notifier.0.send(Msg::Shutdown);
-    }
-}
@@ -6,6 +6,10 @@ use log::LevelFilter;
 use serde::Deserialize;
 use toml::Value;
 
+pub mod schema;
+
+pub use crate::schema::ConfigSchema;
+
 pub trait SerdeReplace {
     fn replace(&mut self, value: Value) -> Result<(), Box<dyn Error>>;
 }
@@ -0,0 +1,303 @@
+//! JSON Schema generation for configuration types.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use log::LevelFilter;
+pub use serde_json::{json, Map, Value};
+
+/// JSON Schema draft used for the generated schema.
+pub const SCHEMA_DIALECT: &str = "http://json-schema.org/draft-07/schema#";
+
+/// Describe a configuration type as JSON Schema.
+///
+/// Types without an accurate description can rely on the default implementation, which accepts
+/// any value.
+pub trait ConfigSchema {
+    /// JSON Schema for all valid values of this type.
+    fn schema() -> Value {
+        Value::Object(Map::new())
+    }
+
+    /// JSON representation of this value.
+    ///
+    /// This is used for a field's default and to print the effective configuration, values
+    /// without a representation are omitted.
+    fn schema_default(&self) -> Option<Value> {
+        None
+    }
+}
+
+/// Builder for the schema of a table with a fixed set of keys.
+#[derive(Default, Debug)]
+pub struct ObjectSchema {
+    properties: Map<String, Value>,
+}
+
+impl ObjectSchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a field and all its aliases to the table.
+    pub fn field(
+        &mut self,
+        names: &[&str],
+        mut schema: Value,
+        description: &str,
+        default: Option<Value>,
+        deprecation: Option<&str>,
+    ) {
+        if let Value::Object(schema) = &mut schema {
+            let description = match deprecation {
+                Some(deprecation) if description.is_empty() => deprecation.to_owned(),
+                Some(deprecation) => format!("{description}\n\n{deprecation}"),
+                None => description.to_owned(),
+            };
+
+            if !description.is_empty() {
+                schema.insert("description".into(), description.into());
+            }
+            if let Some(default) = default {
+                schema.insert("default".into(), default);
+            }
+            if deprecation.is_some() {
+                schema.insert("deprecated".into(), true.into());
+            }
+        }
+
+        for name in names {
+            self.properties.insert((*name).into(), schema.clone());
+        }
+    }
+
+    /// Merge the fields of a flattened table.
+    pub fn flatten(&mut self, schema: Value) {
+        if let Some(Value::Object(properties)) = schema.get("properties") {
+            self.properties.extend(properties.clone());
+        }
+    }
+
+    /// Create the schema for the table.
+    pub fn build(self, description: &str) -> Value {
+        let mut schema = json!({
+            "type": "object",
+            "properties": self.properties,
+            "additionalProperties": false,
+        });
+
+        if !description.is_empty() {
+            schema["description"] = description.into();
+        }
+
+        schema
+    }
+}
+
+/// Schema for a string enumeration.
+///
+/// Since variants are matched case-insensitively, every variant is accepted regardless of its
+/// case.
+pub fn enum_schema(variants: &[&str]) -> Value {
+    let mut pattern = String::from("^(?:");
+    for (i, variant) in variants.iter().enumerate() {
+        if i != 0 {
+            pattern.push('|');
+        }
+
+        for c in variant.chars() {
+            if c.is_ascii_alphabetic() {
+                pattern.push('[');
+                pattern.push(c.to_ascii_uppercase());
+                pattern.push(c.to_ascii_lowercase());
+                pattern.push(']');
+            } else if c.is_ascii_digit() || c == '_' {
+                pattern.push(c);
+            } else {
+                pattern.push('\\');
+                pattern.push(c);
+            }
+        }
+    }
+    pattern.push_str(")$");
+
+    json!({ "type": "string", "pattern": pattern, "examples": variants })
+}
+
+macro_rules! impl_schema_primitive {
+    ($($ty:ty => $schema:tt),*$(,)*) => {
+        $(
+            impl ConfigSchema for $ty {
+                fn schema() -> Value {
+                    json!($schema)
+                }
+
+                fn schema_default(&self) -> Option<Value> {
+                    Some((*self).into())
+                }
+            }
+        )*
+    };
+}
+
+// Bounds of 64-bit integers are omitted, since most consumers cannot represent them accurately.
+#[rustfmt::skip]
+impl_schema_primitive!(
+    u8 => { "type": "integer", "minimum": u8::MIN, "maximum": u8::MAX },
+    u16 => { "type": "integer", "minimum": u16::MIN, "maximum": u16::MAX },
+    u32 => { "type": "integer", "minimum": u32::MIN, "maximum": u32::MAX },
+    u64 => { "type": "integer", "minimum": 0 },
+    usize => { "type": "integer", "minimum": 0 },
+    i8 => { "type": "integer", "minimum": i8::MIN, "maximum": i8::MAX },
+    i16 => { "type": "integer", "minimum": i16::MIN, "maximum": i16::MAX },
+    i32 => { "type": "integer", "minimum": i32::MIN, "maximum": i32::MAX },
+    i64 => { "type": "integer" },
+    isize => { "type": "integer" },
+    f64 => { "type": "number" },
+    bool => { "type": "boolean" },
+);
+
+impl ConfigSchema for f32 {
+    fn schema() -> Value {
+        json!({ "type": "number" })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        // Use the shortest representation, to avoid exposing the rounding error of the widening.
+        self.to_string().parse::<f64>().ok().filter(|value| value.is_finite()).map(Value::from)
+    }
+}
+
+impl ConfigSchema for char {
+    fn schema() -> Value {
+        json!({ "type": "string", "minLength": 1, "maxLength": 1 })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        Some(self.to_string().into())
+    }
+}
+
+impl ConfigSchema for String {
+    fn schema() -> Value {
+        json!({ "type": "string" })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        Some(self.as_str().into())
+    }
+}
+
+impl ConfigSchema for PathBuf {
+    fn schema() -> Value {
+        json!({ "type": "string" })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        self.to_str().map(Value::from)
+    }
+}
+
+impl ConfigSchema for LevelFilter {
+    fn schema() -> Value {
+        enum_schema(&["Off", "Error", "Warn", "Info", "Debug", "Trace"])
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        Some(self.to_string().into())
+    }
+}
+
+impl<T: ConfigSchema> ConfigSchema for Vec<T> {
+    fn schema() -> Value {
+        json!({ "type": "array", "items": T::schema() })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        self.iter().map(T::schema_default).collect::<Option<Vec<_>>>().map(Value::from)
+    }
+}
+
+impl<T: ConfigSchema> ConfigSchema for Option<T> {
+    fn schema() -> Value {
+        json!({ "anyOf": [T::schema(), enum_schema(&["None"])] })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        self.as_ref().and_then(T::schema_default)
+    }
+}
+
+impl<T: ConfigSchema> ConfigSchema for HashMap<String, T> {
+    fn schema() -> Value {
+        json!({ "type": "object", "additionalProperties": T::schema() })
+    }
+
+    fn schema_default(&self) -> Option<Value> {
+        let mut map = Map::new();
+        for (key, value) in self {
+            map.insert(key.clone(), value.schema_default()?);
+        }
+        Some(Value::Object(map))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn object_schema() {
+        let mut object = ObjectSchema::new();
+        object.field(&["size", "font_size"], u8::schema(), "Font size.", Some(3.into()), None);
+        object.field(&["gone"], bool::schema(), "", None, Some("gone has been removed"));
+
+        assert_eq!(
+            object.build("Font configuration."),
+            json!({
+                "type": "object",
+                "description": "Font configuration.",
+                "additionalProperties": false,
+                "properties": {
+                    "size": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "maximum": 255,
+                        "description": "Font size.",
+                        "default": 3,
+                    },
+                    "font_size": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "maximum": 255,
+                        "description": "Font size.",
+                        "default": 3,
+                    },
+                    "gone": {
+                        "type": "boolean",
+                        "description": "gone has been removed",
+                        "deprecated": true,
+                    },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn case_insensitive_enum() {
+        assert_eq!(
+            enum_schema(&["OnlyCopy", "F1"]),
+            json!({
+                "type": "string",
+                "pattern": "^(?:[Oo][Nn][Ll][Yy][Cc][Oo][Pp][Yy]|[Ff]1)$",
+                "examples": ["OnlyCopy", "F1"],
+            })
+        );
+    }
+
+    #[test]
+    fn float_default() {
+        assert_eq!(0.8f32.schema_default(), Some(json!(0.8)));
+        assert_eq!(f32::NAN.schema_default(), None);
+    }
+}
@@ -4,7 +4,7 @@ use quote::{format_ident, quote};
 use syn::meta::ParseNestedMeta;
 use syn::{DataEnum, Generics, Ident};
 
-use crate::serde_replace;
+use crate::{config_schema, serde_replace};
 
 pub fn derive_deserialize(ident: Ident, generics: Generics, data_enum: DataEnum) -> TokenStream {
     let visitor = format_ident!("{}Visitor", ident);
@@ -12,6 +12,7 @@ pub fn derive_deserialize(ident: Ident, generics: Generics, data_enum: DataEnum)
     // Create match arm streams and get a list with all available values.
     let mut match_arms_stream = TokenStream2::new();
     let mut available_values = String::from("one of ");
+    let mut variants = Vec::new();
     for variant in data_enum.variants.iter().filter(|variant| {
         // Skip deserialization for `#[config(skip)]` fields.
         variant.attrs.iter().all(|attr| {
@@ -26,6 +27,7 @@ pub fn derive_deserialize(ident: Ident, generics: Generics, data_enum: DataEnum)
         })
     }) {
         let variant_ident = &variant.ident;
+        variants.push(variant_ident);
         let variant_str = variant_ident.to_string();
         available_values = format!("{available_values}`{variant_str}`, ");
 
@@ -72,6 +74,9 @@ pub fn derive_deserialize(ident: Ident, generics: Generics, data_enum: DataEnum)
         }
     };
 
+    // Automatically implement [`alacritty_config::ConfigSchema`].
+    tokens.extend(config_schema::derive_enum(&ident, &variants));
+
     // Automatically implement [`alacritty_config::SerdeReplace`].
     tokens.extend(serde_replace::derive_direct(ident, generics));
 
@@ -3,9 +3,9 @@ use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::{Error, Field, Generics, Ident, Type};
+use syn::{Attribute, Error, Field, Generics, Ident, Type};
 
-use crate::{serde_replace, Attr, GenericsStreams, MULTIPLE_FLATTEN_ERROR};
+use crate::{config_schema, serde_replace, Attr, GenericsStreams, MULTIPLE_FLATTEN_ERROR};
 
 /// Use this crate's name as log target.
 const LOG_TARGET: &str = env!("CARGO_PKG_NAME");
@@ -13,6 +13,7 @@ const LOG_TARGET: &str = env!("CARGO_PKG_NAME");
 pub fn derive_deserialize<T>(
     ident: Ident,
     generics: Generics,
+    attrs: &[Attribute],
     fields: Punctuated<Field, T>,
 ) -> TokenStream {
     // Create all necessary tokens for the implementation.
@@ -75,6 +76,9 @@ pub fn derive_deserialize<T>(
         }
     };
 
+    // Automatically implement [`alacritty_config::ConfigSchema`].
+    tokens.extend(config_schema::derive_struct(&ident, &generics, attrs, &fields, true));
+
     // Automatically implement [`alacritty_config::SerdeReplace`].
     tokens.extend(serde_replace::derive_recursive(ident, generics, fields));
 
@@ -12,7 +12,7 @@ pub fn derive(input: TokenStream) -> TokenStream {
 
     match input.data {
         Data::Struct(DataStruct { fields: Fields::Named(fields), .. }) => {
-            de_struct::derive_deserialize(input.ident, input.generics, fields.named)
+            de_struct::derive_deserialize(input.ident, input.generics, &input.attrs, fields.named)
         },
         Data::Enum(data_enum) => {
             de_enum::derive_deserialize(input.ident, input.generics, data_enum)
@@ -0,0 +1,261 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{
+    parse_macro_input, Attribute, Data, DataStruct, DeriveInput, Error, Expr, ExprLit, Field,
+    Fields, GenericParam, Generics, Ident, Lit, Type, TypeParam,
+};
+
+use crate::{Attr, GenericsStreams};
+
+/// Error if the derive was used on an unsupported type.
+const UNSUPPORTED_ERROR: &str = "ConfigSchema must be used on a struct with fields";
+
+/// Implement [`alacritty_config::ConfigSchema`] for a struct deserialized through serde.
+///
+/// Since these structs are not required to implement [`Default`], no defaults are included in
+/// their schema.
+pub fn derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match input.data {
+        Data::Struct(DataStruct { fields: Fields::Named(fields), .. }) => {
+            derive_struct(&input.ident, &input.generics, &input.attrs, &fields.named, false).into()
+        },
+        _ => Error::new(input.ident.span(), UNSUPPORTED_ERROR).to_compile_error().into(),
+    }
+}
+
+/// Implement [`alacritty_config::ConfigSchema`] for a struct.
+///
+/// When `defaults` is set, the struct's [`Default`] implementation is used to document the
+/// default of each field.
+pub fn derive_struct<T>(
+    ident: &Ident,
+    generics: &Generics,
+    attrs: &[Attribute],
+    fields: &Punctuated<Field, T>,
+    defaults: bool,
+) -> TokenStream2 {
+    let GenericsStreams { unconstrained, .. } = crate::generics_streams(&generics.params);
+    let description = description(attrs);
+
+    // Constrain generics to types which can be described.
+    let bound = if defaults {
+        quote!(Default + alacritty_config::ConfigSchema)
+    } else {
+        quote!(alacritty_config::ConfigSchema)
+    };
+    let mut constrained = TokenStream2::new();
+    for generic in &generics.params {
+        if let GenericParam::Type(TypeParam { ident, .. }) = generic {
+            constrained.extend(quote! { #ident : #bound, });
+        }
+    }
+
+    let mut fields_stream = TokenStream2::new();
+    let mut defaults_stream = TokenStream2::new();
+    for field in fields.iter() {
+        fields_stream.extend(field_schema(field, defaults));
+        defaults_stream.extend(field_default(field, defaults));
+    }
+
+    let default_stream = if defaults {
+        quote! {
+            #[allow(unused_variables)]
+            let default = Self::default();
+        }
+    } else {
+        TokenStream2::new()
+    };
+
+    quote! {
+        impl <#constrained> alacritty_config::ConfigSchema for #ident <#unconstrained> {
+            fn schema() -> alacritty_config::schema::Value {
+                #default_stream
+
+                let mut schema = alacritty_config::schema::ObjectSchema::new();
+                #fields_stream
+                schema.build(#description)
+            }
+
+            fn schema_default(&self) -> Option<alacritty_config::schema::Value> {
+                #default_stream
+
+                let mut object = alacritty_config::schema::Map::new();
+                #defaults_stream
+                Some(alacritty_config::schema::Value::Object(object))
+            }
+        }
+    }
+}
+
+/// Implement [`alacritty_config::ConfigSchema`] for an enum with the deserializable `variants`.
+pub fn derive_enum(ident: &Ident, variants: &[&Ident]) -> TokenStream2 {
+    let literals = variants.iter().map(|variant| variant.to_string());
+    let default_literals = literals.clone();
+
+    quote! {
+        impl alacritty_config::ConfigSchema for #ident {
+            fn schema() -> alacritty_config::schema::Value {
+                alacritty_config::schema::enum_schema(&[#(#literals),*])
+            }
+
+            fn schema_default(&self) -> Option<alacritty_config::schema::Value> {
+                #[allow(unreachable_patterns)]
+                match self {
+                    #(Self::#variants => Some(#default_literals.into()),)*
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+/// Create the schema stream for a single field.
+fn field_schema(field: &Field, defaults: bool) -> TokenStream2 {
+    let ident = field.ident.as_ref().expect("unreachable tuple struct");
+    let ty = &field.ty;
+    let literal = ident.to_string();
+    let description = description(&field.attrs);
+    let mut literals = vec![literal.clone()];
+    let mut deprecation = quote!(None);
+
+    // Iterate over all #[config(...)] and #[serde(...)] attributes.
+    for attr in field.attrs.iter().filter(|attr| is_field_attr(attr)) {
+        let parsed = match attr.parse_args::<Attr>() {
+            Ok(parsed) => parsed,
+            Err(_) => continue,
+        };
+
+        match parsed.ident.as_str() {
+            // Skipped fields cannot be configured.
+            "skip" => return TokenStream2::new(),
+            "flatten" => {
+                return quote! {
+                    schema.flatten(<#ty as alacritty_config::ConfigSchema>::schema());
+                };
+            },
+            "deprecated" | "removed" => {
+                let mut message = format!("{} has been {}", literal, parsed.ident);
+                if let Some(warning) = parsed.param {
+                    message = format!("{}; {}", message, warning.value());
+                }
+                deprecation = quote!(Some(#message));
+            },
+            "alias" => {
+                if let Some(alias) = parsed.param {
+                    literals.push(alias.value());
+                }
+            },
+            _ => (),
+        }
+    }
+
+    let default = if defaults {
+        quote!(alacritty_config::ConfigSchema::schema_default(&default.#ident))
+    } else {
+        quote!(None)
+    };
+
+    quote! {
+        schema.field(
+            &[#(#literals),*],
+            <#ty as alacritty_config::ConfigSchema>::schema(),
+            #description,
+            #default,
+            #deprecation,
+        );
+    }
+}
+
+/// Create the stream inserting a single field's value into the `object` of its table.
+///
+/// With `defaults`, deprecated fields are only included when they differ from their default, and
+/// `Option`s which are unset despite having a default are written as `"None"`.
+fn field_default(field: &Field, defaults: bool) -> TokenStream2 {
+    let ident = field.ident.as_ref().expect("unreachable tuple struct");
+    let literal = ident.to_string();
+
+    for attr in field.attrs.iter().filter(|attr| is_field_attr(attr)) {
+        let parsed = match attr.parse_args::<Attr>() {
+            Ok(parsed) => parsed,
+            Err(_) => continue,
+        };
+
+        match parsed.ident.as_str() {
+            "skip" | "removed" => return TokenStream2::new(),
+            "flatten" => {
+                return quote! {
+                    if let Some(alacritty_config::schema::Value::Object(fields)) =
+                        alacritty_config::ConfigSchema::schema_default(&self.#ident)
+                    {
+                        object.extend(fields);
+                    }
+                };
+            },
+            "deprecated" if defaults => {
+                return quote! {
+                    let value = alacritty_config::ConfigSchema::schema_default(&self.#ident);
+                    if value != alacritty_config::ConfigSchema::schema_default(&default.#ident) {
+                        if let Some(value) = value {
+                            object.insert(#literal.into(), value);
+                        }
+                    }
+                };
+            },
+            _ => (),
+        }
+    }
+
+    let is_option = match &field.ty {
+        Type::Path(type_path) => {
+            type_path.path.segments.iter().last().is_some_and(|s| s.ident == "Option")
+        },
+        _ => false,
+    };
+    let unset_stream = if defaults && is_option {
+        quote! {
+            else if self.#ident.is_none() && default.#ident.is_some() {
+                object.insert(#literal.into(), "None".into());
+            }
+        }
+    } else {
+        TokenStream2::new()
+    };
+
+    quote! {
+        if let Some(value) = alacritty_config::ConfigSchema::schema_default(&self.#ident) {
+            object.insert(#literal.into(), value);
+        } #unset_stream
+    }
+}
+
+/// Check if an attribute can change a field's schema.
+///
+/// Serde attributes are supported alongside `#[config(...)]`, since `skip`, `flatten` and
+/// `alias` share their meaning.
+fn is_field_attr(attr: &Attribute) -> bool {
+    attr.path().is_ident("config") || attr.path().is_ident("serde")
+}
+
+/// Get the first paragraph of the documentation comments.
+fn description(attrs: &[Attribute]) -> String {
+    let mut lines = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("doc")) {
+        let line = match attr.meta.require_name_value().map(|meta| &meta.value) {
+            Ok(Expr::Lit(ExprLit { lit: Lit::Str(line), .. })) => line.value(),
+            _ => continue,
+        };
+
+        let line = line.trim();
+        if !line.is_empty() {
+            lines.push(line.to_owned());
+        } else if !lines.is_empty() {
+            break;
+        }
+    }
+
+    lines.join(" ")
+}
@@ -9,6 +9,7 @@ use syn::punctuated::Punctuated;
 use syn::{GenericParam, Ident, LitStr, Token, TypeParam};
 
 mod config_deserialize;
+mod config_schema;
 mod serde_replace;
 
 /// Error message when attempting to flatten multiple fields.
@@ -20,6 +21,11 @@ pub fn derive_config_deserialize(input: TokenStream) -> TokenStream {
     config_deserialize::derive(input)
 }
 
+#[proc_macro_derive(ConfigSchema, attributes(config))]
+pub fn derive_config_schema(input: TokenStream) -> TokenStream {
+    config_schema::derive(input)
+}
+
 #[proc_macro_derive(SerdeReplace)]
 pub fn derive_serde_replace(input: TokenStream) -> TokenStream {
     serde_replace::derive(input)
@@ -3,8 +3,8 @@ use std::sync::{Arc, Mutex};
 use log::{Level, Log, Metadata, Record};
 use serde::Deserialize;
 
-use alacritty_config::SerdeReplace as _;
-use alacritty_config_derive::{ConfigDeserialize, SerdeReplace};
+use alacritty_config::{ConfigSchema, SerdeReplace as _};
+use alacritty_config_derive::{ConfigDeserialize, ConfigSchema, SerdeReplace};
 
 #[derive(ConfigDeserialize, Debug, PartialEq, Eq)]
 enum TestEnum {
@@ -21,8 +21,12 @@ impl Default for TestEnum {
     }
 }
 
+/// Test configuration.
+///
+/// Only the first paragraph is used as description.
 #[derive(ConfigDeserialize)]
 struct Test {
+    /// First field.
     #[config(alias = "field1_alias")]
     #[config(deprecated = "use field2 instead")]
     field1: usize,
@@ -78,9 +82,24 @@ struct Test3 {
     flatty: usize,
 }
 
+/// Serde configuration.
+#[derive(ConfigSchema, Deserialize)]
+struct SerdeTest {
+    #[serde(flatten)]
+    flatty: Test3,
+    /// Enabled field.
+    #[serde(default)]
+    enabled: bool,
+    optional: Option<usize>,
+    #[serde(skip)]
+    skipped: usize,
+}
+
 #[derive(SerdeReplace, Deserialize, Default, PartialEq, Eq, Debug)]
 struct NewType(usize);
 
+impl ConfigSchema for NewType {}
+
 #[test]
 fn config_deserialize() {
     let logger = unsafe {
@@ -242,3 +261,80 @@ fn replace_flatten_using_alias() {
 
     assert_eq!(test.flatten.flatty, 7);
 }
+
+#[test]
+fn schema() {
+    let schema = Test::schema();
+
+    assert_eq!(schema["type"], "object");
+    assert_eq!(schema["description"], "Test configuration.");
+    assert_eq!(schema["additionalProperties"], false);
+
+    let properties = &schema["properties"];
+    assert_eq!(properties["field1"]["type"], "integer");
+    assert_eq!(properties["field1"]["default"], 13);
+    assert_eq!(properties["field1"]["deprecated"], true);
+    assert_eq!(
+        properties["field1"]["description"],
+        "First field.\n\nfield1 has been deprecated; use field2 instead"
+    );
+    assert_eq!(properties["field1_alias"], properties["field1"]);
+    assert_eq!(properties["gone"]["description"], "gone has been removed; it's gone");
+    assert_eq!(properties["field3"]["anyOf"][0]["maximum"], 255);
+    assert_eq!(properties["field3"]["default"], 23);
+
+    // Flattened fields are part of the parent.
+    assert_eq!(properties["flatty"]["type"], "integer");
+    assert_eq!(properties["flatty_alias"]["type"], "integer");
+
+    // Skipped fields and variants are excluded.
+    let nesting = &properties["nesting"]["properties"];
+    assert!(nesting.get("field3").is_none());
+    assert_eq!(nesting["aliased"]["type"], "integer");
+    assert_eq!(
+        properties["enom_small"]["pattern"],
+        "^(?:[Oo][Nn][Ee]|[Tt][Ww][Oo]|[Tt][Hh][Rr][Ee][Ee])$"
+    );
+    assert!(properties["enom_small"].get("default").is_none());
+}
+
+#[test]
+fn schema_default() {
+    let test = Test {
+        field2: String::from("changed"),
+        field3: None,
+        enom_big: TestEnum::Two,
+        ..Test::default()
+    };
+
+    assert_eq!(
+        test.schema_default(),
+        Some(serde_json::json!({
+            "field2": "changed",
+            "field3": "None",
+            "nesting": { "field1": 0, "field4": 0 },
+            "flatty": 0,
+            "enom_big": "Two",
+            "multiple_alias_field": 0,
+        }))
+    );
+}
+
+#[test]
+fn schema_derive() {
+    let schema = SerdeTest::schema();
+
+    assert_eq!(schema["description"], "Serde configuration.");
+
+    let properties = &schema["properties"];
+    assert_eq!(properties["flatty"]["type"], "integer");
+    assert_eq!(properties["flatty_alias"]["type"], "integer");
+    assert_eq!(properties["enabled"]["description"], "Enabled field.");
+    assert!(properties["enabled"].get("default").is_none());
+    assert!(properties["optional"].get("anyOf").is_some());
+    assert!(properties.get("skipped").is_none());
+
+    let test: SerdeTest = toml::from_str("flatty = 3\nenabled = true\nskipped = 1").unwrap();
+    assert_eq!(test.skipped, 0);
+    assert_eq!(test.schema_default(), Some(serde_json::json!({ "flatty": 3, "enabled": true })));
+}
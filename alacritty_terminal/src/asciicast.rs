@@ -0,0 +1,309 @@
+//! Terminal session recording in the asciicast v2 format.
+//!
+//! See <https://docs.asciinema.org/manual/asciicast/v2/> for the format specification.
+
+use std::fmt::{self, Debug, Formatter, Write as _};
+use std::io::{self, Write};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::{env, str};
+
+use crate::event::WindowSize;
+
+/// Version of the asciicast format written by the [`Recorder`].
+pub const VERSION: u32 = 2;
+
+/// Maximum time events are buffered before they are written.
+pub const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Environment variables stored in the recording header.
+const HEADER_ENV: [&str; 2] = ["SHELL", "TERM"];
+
+/// Writer for asciicast recordings.
+///
+/// The header is written on creation, every following call appends a single event. Events are
+/// buffered and only written once [`Self::flush_deadline`] has been reached, or when calling
+/// [`Self::flush`].
+pub struct Recorder {
+    writer: Box<dyn Write + Send>,
+
+    /// Time the recording was started at.
+    start: Instant,
+
+    /// Time of the oldest event which has not been flushed yet.
+    buffered_since: Option<Instant>,
+
+    /// Whether input events should be recorded.
+    record_input: bool,
+
+    /// Trailing bytes of an incomplete UTF-8 sequence in the output.
+    pending_output: Vec<u8>,
+
+    /// Trailing bytes of an incomplete UTF-8 sequence in the input.
+    pending_input: Vec<u8>,
+}
+
+impl Recorder {
+    /// Start a new recording.
+    pub fn new(
+        mut writer: Box<dyn Write + Send>,
+        size: WindowSize,
+        record_input: bool,
+    ) -> io::Result<Self> {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |t| t.as_secs());
+
+        let (width, height) = (size.num_cols, size.num_lines);
+        let mut header = format!("{{\"version\":{VERSION},\"width\":{width},\"height\":{height},");
+        header.push_str(&format!("\"timestamp\":{timestamp},\"env\":{{"));
+        let vars = HEADER_ENV.iter().filter_map(|key| Some((key, env::var(key).ok()?)));
+        for (i, (key, value)) in vars.enumerate() {
+            if i > 0 {
+                header.push(',');
+            }
+            escape(&mut header, key);
+            header.push(':');
+            escape(&mut header, &value);
+        }
+        header.push_str("}}\n");
+
+        writer.write_all(header.as_bytes())?;
+        writer.flush()?;
+
+        Ok(Self {
+            writer,
+            record_input,
+            start: Instant::now(),
+            buffered_since: None,
+            pending_output: Vec::new(),
+            pending_input: Vec::new(),
+        })
+    }
+
+    /// Record bytes written by the child process.
+    pub fn output(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let text = decode(&mut self.pending_output, bytes);
+        self.write_event("o", &text)
+    }
+
+    /// Record bytes sent to the child process.
+    ///
+    /// This is ignored unless input recording was enabled.
+    pub fn input(&mut self, bytes: &[u8]) -> io::Result<()> {
+        if !self.record_input {
+            return Ok(());
+        }
+
+        let text = decode(&mut self.pending_input, bytes);
+        self.write_event("i", &text)
+    }
+
+    /// Record a change of the terminal dimensions.
+    pub fn resize(&mut self, size: WindowSize) -> io::Result<()> {
+        self.write_event("r", &format!("{}x{}", size.num_cols, size.num_lines))
+    }
+
+    /// Time at which the buffered events should be written.
+    ///
+    /// Returns `None` if there are no buffered events.
+    pub fn flush_deadline(&self) -> Option<Instant> {
+        self.buffered_since.map(|buffered_since| buffered_since + FLUSH_INTERVAL)
+    }
+
+    /// Write all buffered events.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.buffered_since = None;
+        self.writer.flush()
+    }
+
+    /// Append a single event line.
+    fn write_event(&mut self, code: &str, data: &str) -> io::Result<()> {
+        // Avoid empty events while waiting for the rest of a UTF-8 sequence.
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let time = self.start.elapsed().as_secs_f64();
+        let mut line = format!("[{time:.6},\"{code}\",");
+        escape(&mut line, data);
+        line.push_str("]\n");
+
+        self.writer.write_all(line.as_bytes())?;
+        self.buffered_since.get_or_insert_with(Instant::now);
+
+        Ok(())
+    }
+}
+
+impl Debug for Recorder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Recorder")
+            .field("start", &self.start)
+            .field("record_input", &self.record_input)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Decode bytes as UTF-8, keeping incomplete trailing sequences for the next call.
+///
+/// Invalid bytes are replaced with U+FFFD.
+fn decode(pending: &mut Vec<u8>, bytes: &[u8]) -> String {
+    pending.extend_from_slice(bytes);
+
+    let mut text = String::with_capacity(pending.len());
+    let mut rest = &pending[..];
+    loop {
+        match str::from_utf8(rest) {
+            Ok(valid) => {
+                text.push_str(valid);
+                rest = &[];
+                break;
+            },
+            Err(err) => {
+                let (valid, invalid) = rest.split_at(err.valid_up_to());
+                text.push_str(str::from_utf8(valid).unwrap_or_default());
+
+                match err.error_len() {
+                    Some(len) => {
+                        text.push(char::REPLACEMENT_CHARACTER);
+                        rest = &invalid[len..];
+                    },
+                    None => {
+                        rest = invalid;
+                        break;
+                    },
+                }
+            },
+        }
+    }
+
+    let consumed = pending.len() - rest.len();
+    pending.drain(..consumed);
+
+    text
+}
+
+/// Append text as a JSON string literal.
+fn escape(out: &mut String, text: &str) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() && (c as u32) < 0x80 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            },
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    /// Writer sharing its buffer with the test.
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Buffer {
+        fn lines(&self) -> Vec<String> {
+            let buffer = self.0.lock().unwrap();
+            String::from_utf8_lossy(&buffer).lines().map(String::from).collect()
+        }
+    }
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn size(num_cols: u16, num_lines: u16) -> WindowSize {
+        WindowSize { num_cols, num_lines, cell_width: 1, cell_height: 1 }
+    }
+
+    /// Strip the timestamp from an event line.
+    fn event(line: &str) -> &str {
+        &line[line.find(',').unwrap() + 1..line.len() - 1]
+    }
+
+    #[test]
+    fn header() {
+        let buffer = Buffer::default();
+        Recorder::new(Box::new(buffer.clone()), size(80, 24), false).unwrap();
+
+        let lines = buffer.lines();
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].starts_with("{\"version\":2,\"width\":80,\"height\":24,\"timestamp\":"));
+        assert!(lines[0].ends_with("}}"));
+    }
+
+    #[test]
+    fn events() {
+        let buffer = Buffer::default();
+        let mut recorder = Recorder::new(Box::new(buffer.clone()), size(80, 24), true).unwrap();
+
+        recorder.output(b"\x1b[1m\"a\"\\\r\n").unwrap();
+        recorder.input(b"ls\r").unwrap();
+        recorder.resize(size(100, 50)).unwrap();
+
+        let lines = buffer.lines();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(event(&lines[1]), "\"o\",\"\\u001b[1m\\\"a\\\"\\\\\\r\\n\"");
+        assert_eq!(event(&lines[2]), "\"i\",\"ls\\r\"");
+        assert_eq!(event(&lines[3]), "\"r\",\"100x50\"");
+    }
+
+    #[test]
+    fn flush_deadline() {
+        let buffer = Buffer::default();
+        let mut recorder = Recorder::new(Box::new(buffer.clone()), size(80, 24), false).unwrap();
+        assert_eq!(recorder.flush_deadline(), None);
+
+        let start = Instant::now();
+        recorder.output(b"a").unwrap();
+        recorder.output(b"b").unwrap();
+        let deadline = recorder.flush_deadline().unwrap();
+        assert!(deadline >= start + FLUSH_INTERVAL);
+        assert!(deadline <= Instant::now() + FLUSH_INTERVAL);
+
+        recorder.flush().unwrap();
+        assert_eq!(recorder.flush_deadline(), None);
+    }
+
+    #[test]
+    fn skip_input() {
+        let buffer = Buffer::default();
+        let mut recorder = Recorder::new(Box::new(buffer.clone()), size(80, 24), false).unwrap();
+
+        recorder.input(b"secret\r").unwrap();
+
+        assert_eq!(buffer.lines().len(), 1);
+    }
+
+    #[test]
+    fn split_utf8() {
+        let buffer = Buffer::default();
+        let mut recorder = Recorder::new(Box::new(buffer.clone()), size(80, 24), false).unwrap();
+
+        // Split `ä` across two reads.
+        recorder.output(b"a\xc3").unwrap();
+        recorder.output(b"\xa4").unwrap();
+        recorder.output(b"\xff").unwrap();
+
+        let lines = buffer.lines();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(event(&lines[1]), "\"o\",\"a\"");
+        assert_eq!(event(&lines[2]), "\"o\",\"ä\"");
+        assert_eq!(event(&lines[3]), "\"o\",\"\u{FFFD}\"");
+    }
+}
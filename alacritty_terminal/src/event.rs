@@ -2,6 +2,9 @@ use std::borrow::Cow;
 use std::fmt::{self, Debug, Formatter};
 use std::sync::Arc;
 
+use crate::grid::Dimensions;
+use crate::term::notification::Urgency;
+use crate::term::working_directory::WorkingDirectory;
 use crate::term::ClipboardType;
 use crate::vte::ansi::Rgb;
 
@@ -50,6 +53,12 @@ pub enum Event {
     /// Terminal bell ring.
     Bell,
 
+    /// Request to show a desktop notification.
+    Notification { title: String, body: String, urgency: Urgency },
+
+    /// Working directory change reported by the shell.
+    WorkingDirectory(WorkingDirectory),
+
     /// Shutdown request.
     Exit,
 
@@ -71,6 +80,10 @@ impl Debug for Event {
             Event::ResetTitle => write!(f, "ResetTitle"),
             Event::Wakeup => write!(f, "Wakeup"),
             Event::Bell => write!(f, "Bell"),
+            Event::Notification { title, body, urgency } => {
+                write!(f, "Notification({title}, {body}, {urgency:?})")
+            },
+            Event::WorkingDirectory(directory) => write!(f, "WorkingDirectory({directory:?})"),
             Event::Exit => write!(f, "Exit"),
             Event::ChildExit(code) => write!(f, "ChildExit({code})"),
         }
@@ -93,6 +106,23 @@ pub struct WindowSize {
     pub cell_height: u16,
 }
 
+impl Dimensions for WindowSize {
+    #[inline]
+    fn total_lines(&self) -> usize {
+        self.screen_lines()
+    }
+
+    #[inline]
+    fn screen_lines(&self) -> usize {
+        self.num_lines as usize
+    }
+
+    #[inline]
+    fn columns(&self) -> usize {
+        self.num_cols as usize
+    }
+}
+
 /// Types that are interested in when the display is resized.
 pub trait OnResize {
     fn on_resize(&mut self, window_size: WindowSize);
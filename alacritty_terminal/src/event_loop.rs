@@ -14,10 +14,11 @@ use std::time::Instant;
 use log::error;
 use polling::{Event as PollingEvent, Events, PollMode};
 
+use crate::asciicast::Recorder;
 use crate::event::{self, Event, EventListener, WindowSize};
+use crate::parser::Processor;
 use crate::sync::FairMutex;
 use crate::term::Term;
-use crate::vte::ansi;
 use crate::{thread, tty};
 
 /// Max bytes to read from the PTY before forced terminal synchronization.
@@ -37,6 +38,12 @@ pub enum Msg {
 
     /// Instruction to resize the PTY.
     Resize(WindowSize),
+
+    /// Start recording the session, replacing any active recording.
+    StartRecording(Recorder),
+
+    /// Stop the active session recording.
+    StopRecording,
 }
 
 /// The main event loop.
@@ -52,6 +59,7 @@ pub struct EventLoop<T: tty::EventedPty, U: EventListener> {
     event_proxy: U,
     hold: bool,
     ref_test: bool,
+    recorder: Option<Recorder>,
 }
 
 impl<T, U> EventLoop<T, U>
@@ -78,6 +86,7 @@ where
             event_proxy,
             hold,
             ref_test,
+            recorder: None,
         })
     }
 
@@ -91,8 +100,19 @@ where
     fn drain_recv_channel(&mut self, state: &mut State) -> bool {
         while let Some(msg) = self.rx.recv() {
             match msg {
-                Msg::Input(input) => state.write_list.push_back(input),
-                Msg::Resize(window_size) => self.pty.on_resize(window_size),
+                Msg::Input(input) => {
+                    record(&mut self.recorder, |recorder| recorder.input(&input));
+                    state.write_list.push_back(input);
+                },
+                Msg::Resize(window_size) => {
+                    record(&mut self.recorder, |recorder| recorder.resize(window_size));
+                    self.pty.on_resize(window_size);
+                },
+                Msg::StartRecording(recorder) => {
+                    self.stop_recording();
+                    self.recorder = Some(recorder);
+                },
+                Msg::StopRecording => self.stop_recording(),
                 Msg::Shutdown => return false,
             }
         }
@@ -100,6 +120,12 @@ where
         true
     }
 
+    /// Write all buffered events and stop the session recording.
+    fn stop_recording(&mut self) {
+        record(&mut self.recorder, Recorder::flush);
+        self.recorder = None;
+    }
+
     #[inline]
     fn pty_read<X>(
         &mut self,
@@ -114,7 +140,7 @@ where
         let mut processed = 0;
 
         // Reserve the next terminal lock for PTY reading.
-        let _terminal_lease = Some(self.terminal.lease());
+        let terminal_lease = Some(self.terminal.lease());
         let mut terminal = None;
 
         loop {
@@ -150,6 +176,11 @@ where
                 writer.write_all(&buf[..unprocessed]).unwrap();
             }
 
+            // Keep a copy of the bytes for the session recording.
+            if self.recorder.is_some() {
+                state.recording.extend_from_slice(&buf[..unprocessed]);
+            }
+
             // Parse the incoming bytes.
             for byte in &buf[..unprocessed] {
                 state.parser.advance(&mut **terminal, *byte);
@@ -164,6 +195,14 @@ where
             }
         }
 
+        // Append the bytes to the session recording once the terminal is unlocked.
+        drop(terminal);
+        drop(terminal_lease);
+        if !state.recording.is_empty() {
+            record(&mut self.recorder, |recorder| recorder.output(&state.recording));
+            state.recording.clear();
+        }
+
         // Queue terminal redraw unless all processed bytes were synchronized.
         if state.parser.sync_bytes_count() < processed && processed > 0 {
             self.event_proxy.send_event(Event::Wakeup);
@@ -227,10 +266,15 @@ where
             };
 
             'event_loop: loop {
-                // Wakeup the event loop when a synchronized update timeout was reached.
-                let handler = state.parser.sync_timeout();
-                let timeout =
-                    handler.sync_timeout().map(|st| st.saturating_duration_since(Instant::now()));
+                // Wakeup the event loop when a synchronized update timeout was reached, or
+                // buffered events of the session recording should be written.
+                let sync_timeout = state.parser.sync_timeout().sync_timeout();
+                let flush_deadline = self.recorder.as_ref().and_then(Recorder::flush_deadline);
+                let timeout = sync_timeout
+                    .into_iter()
+                    .chain(flush_deadline)
+                    .min()
+                    .map(|deadline| deadline.saturating_duration_since(Instant::now()));
 
                 events.clear();
                 if let Err(err) = self.poll.wait(&mut events, timeout) {
@@ -243,10 +287,17 @@ where
                     }
                 }
 
-                // Handle synchronized update timeout.
+                let now = Instant::now();
+                if flush_deadline.is_some_and(|deadline| deadline <= now) {
+                    record(&mut self.recorder, Recorder::flush);
+                }
+
                 if events.is_empty() && self.rx.peek().is_none() {
-                    state.parser.stop_sync(&mut *self.terminal.lock());
-                    self.event_proxy.send_event(Event::Wakeup);
+                    // Handle synchronized update timeout.
+                    if sync_timeout.is_some_and(|deadline| deadline <= now) {
+                        state.parser.stop_sync(&mut *self.terminal.lock());
+                        self.event_proxy.send_event(Event::Wakeup);
+                    }
                     continue;
                 }
 
@@ -320,6 +371,8 @@ where
                 }
             }
 
+            self.stop_recording();
+
             // The evented instances are not dropped here so deregister them explicitly.
             let _ = self.pty.deregister(&self.poll);
 
@@ -328,6 +381,17 @@ where
     }
 }
 
+/// Write an event to the session recording, stopping the recording on failure.
+fn record<F>(recorder: &mut Option<Recorder>, f: F)
+where
+    F: FnOnce(&mut Recorder) -> io::Result<()>,
+{
+    if let Some(err) = recorder.as_mut().and_then(|recorder| f(recorder).err()) {
+        error!("Stopping session recording: {}", err);
+        *recorder = None;
+    }
+}
+
 /// Helper type which tracks how much of a buffer has been written.
 struct Writing {
     source: Cow<'static, [u8]>,
@@ -405,7 +469,10 @@ impl EventLoopSender {
 pub struct State {
     write_list: VecDeque<Cow<'static, [u8]>>,
     writing: Option<Writing>,
-    parser: ansi::Processor,
+    parser: Processor,
+
+    /// Bytes read from the PTY which have not been recorded yet.
+    recording: Vec<u8>,
 }
 
 impl State {
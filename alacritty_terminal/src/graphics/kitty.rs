@@ -0,0 +1,409 @@
+//! Kitty graphics protocol.
+//!
+//! See <https://sw.kovidgoyal.net/kitty/graphics-protocol/> for the protocol specification.
+
+use std::fmt::{self, Display, Formatter};
+use std::io::Read;
+use std::str;
+
+use base64::engine::general_purpose::STANDARD as Base64;
+use base64::Engine;
+use flate2::read::ZlibDecoder;
+
+use crate::graphics::{GraphicData, MAX_STORAGE_SIZE};
+
+/// Action performed by a graphics command.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// Store an image (`a=t`).
+    #[default]
+    Transmit,
+
+    /// Store an image and display it (`a=T`).
+    TransmitAndPut,
+
+    /// Display a previously stored image (`a=p`).
+    Put,
+
+    /// Delete images or placements (`a=d`).
+    Delete,
+
+    /// Check if an image could be stored, without storing it (`a=q`).
+    Query,
+}
+
+/// Pixel format of the transmitted data.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    /// 24-bit RGB (`f=24`).
+    Rgb,
+
+    /// 32-bit RGBA (`f=32`).
+    #[default]
+    Rgba,
+
+    /// PNG image (`f=100`).
+    Png,
+}
+
+/// Error reported to the client.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// Control data or payload are invalid.
+    InvalidData,
+
+    /// Transmission medium is not supported.
+    UnsupportedMedium,
+
+    /// Referenced image does not exist.
+    NotFound,
+
+    /// Image exceeds the size limits.
+    TooLarge,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidData => write!(f, "EINVAL:invalid image data"),
+            Self::UnsupportedMedium => write!(f, "EBADF:unsupported transmission medium"),
+            Self::NotFound => write!(f, "ENOENT:image not found"),
+            Self::TooLarge => write!(f, "EFBIG:image too large"),
+        }
+    }
+}
+
+/// Graphics command (`APC G <control data> ; <payload> ST`).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Command {
+    /// Action to perform (`a`).
+    pub action: Action,
+
+    /// Suppression of responses (`q`), `1` suppresses `OK` and `2` also suppresses errors.
+    pub quiet: u8,
+
+    /// Pixel format of the payload (`f`).
+    pub format: Format,
+
+    /// Transmission medium (`t`), only direct transmission (`d`) is supported.
+    pub medium: u8,
+
+    /// Whether the payload is compressed with zlib (`o=z`).
+    pub compressed: bool,
+
+    /// Whether more chunks of the payload will follow (`m`).
+    pub more: bool,
+
+    /// Width of the transmitted image in pixels (`s`).
+    pub width: u32,
+
+    /// Height of the transmitted image in pixels (`v`).
+    pub height: u32,
+
+    /// Image ID (`i`).
+    pub image_id: u32,
+
+    /// Image number (`I`).
+    pub image_number: u32,
+
+    /// Placement ID (`p`).
+    pub placement_id: u32,
+
+    /// Horizontal start of the displayed region in pixels (`x`).
+    pub source_x: u32,
+
+    /// Vertical start of the displayed region in pixels (`y`).
+    pub source_y: u32,
+
+    /// Width of the displayed region in pixels (`w`).
+    pub source_width: u32,
+
+    /// Height of the displayed region in pixels (`h`).
+    pub source_height: u32,
+
+    /// Horizontal offset within the first cell in pixels (`X`).
+    pub offset_x: u32,
+
+    /// Vertical offset within the first cell in pixels (`Y`).
+    pub offset_y: u32,
+
+    /// Number of columns the image is scaled to (`c`).
+    pub columns: u32,
+
+    /// Number of lines the image is scaled to (`r`).
+    pub rows: u32,
+
+    /// Whether the cursor should stay in place after displaying the image (`C=1`).
+    pub fixed_cursor: bool,
+
+    /// Z-index of the placement (`z`).
+    pub z_index: i32,
+
+    /// Placements which are deleted (`d`).
+    pub delete: u8,
+
+    /// Base64 encoded payload.
+    pub payload: Vec<u8>,
+}
+
+impl Command {
+    /// Parse a graphics command, without the leading `G`.
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        let (control, payload) = match bytes.iter().position(|&b| b == b';') {
+            Some(index) => (&bytes[..index], &bytes[index + 1..]),
+            None => (bytes, &[][..]),
+        };
+
+        let mut command =
+            Self { medium: b'd', delete: b'a', payload: payload.to_vec(), ..Self::default() };
+
+        for pair in control.split(|&b| b == b',').filter(|pair| !pair.is_empty()) {
+            let (key, value) = match pair {
+                [key, b'=', value @ ..] if !value.is_empty() => (*key, value),
+                _ => return None,
+            };
+
+            match key {
+                b'a' => {
+                    command.action = match value {
+                        b"t" => Action::Transmit,
+                        b"T" => Action::TransmitAndPut,
+                        b"p" => Action::Put,
+                        b"d" => Action::Delete,
+                        b"q" => Action::Query,
+                        _ => return None,
+                    }
+                },
+                b'f' => {
+                    command.format = match value {
+                        b"24" => Format::Rgb,
+                        b"32" => Format::Rgba,
+                        b"100" => Format::Png,
+                        _ => return None,
+                    }
+                },
+                b'o' => command.compressed = value == b"z",
+                b't' => command.medium = single_char(value)?,
+                b'd' => command.delete = single_char(value)?,
+                b'q' => command.quiet = parse_number(value)?,
+                b'm' => command.more = parse_number::<u8>(value)? == 1,
+                b'C' => command.fixed_cursor = parse_number::<u8>(value)? == 1,
+                b'z' => command.z_index = parse_number(value)?,
+                b's' => command.width = parse_number(value)?,
+                b'v' => command.height = parse_number(value)?,
+                b'i' => command.image_id = parse_number(value)?,
+                b'I' => command.image_number = parse_number(value)?,
+                b'p' => command.placement_id = parse_number(value)?,
+                b'x' => command.source_x = parse_number(value)?,
+                b'y' => command.source_y = parse_number(value)?,
+                b'w' => command.source_width = parse_number(value)?,
+                b'h' => command.source_height = parse_number(value)?,
+                b'X' => command.offset_x = parse_number(value)?,
+                b'Y' => command.offset_y = parse_number(value)?,
+                b'c' => command.columns = parse_number(value)?,
+                b'r' => command.rows = parse_number(value)?,
+                // Ignore keys for unsupported features, like animations.
+                _ => (),
+            }
+        }
+
+        Some(command)
+    }
+
+    /// Response sent to the client after processing the command.
+    ///
+    /// Returns `None` if the client did not ask for a response.
+    pub fn response(&self, result: Result<(), Error>) -> Option<String> {
+        if self.image_id == 0 && self.image_number == 0 {
+            return None;
+        }
+
+        let message = match result {
+            Ok(()) if self.quiet == 0 => String::from("OK"),
+            Err(err) if self.quiet < 2 => err.to_string(),
+            _ => return None,
+        };
+
+        let mut response = format!("\x1b_Gi={}", self.image_id);
+        if self.image_number != 0 {
+            response.push_str(&format!(",I={}", self.image_number));
+        }
+        if self.placement_id != 0 {
+            response.push_str(&format!(",p={}", self.placement_id));
+        }
+        response.push_str(&format!(";{message}\x1b\\"));
+
+        Some(response)
+    }
+
+    /// Decode the transmitted image.
+    pub fn decode(&self) -> Result<GraphicData, Error> {
+        if self.medium != b'd' {
+            return Err(Error::UnsupportedMedium);
+        }
+
+        if self.payload.len() > MAX_STORAGE_SIZE {
+            return Err(Error::TooLarge);
+        }
+
+        // Ignore whitespace, since some clients wrap their payload.
+        let payload: Vec<u8> =
+            self.payload.iter().copied().filter(|b| !b.is_ascii_whitespace()).collect();
+        let mut data = Base64.decode(payload).map_err(|_| Error::InvalidData)?;
+
+        if self.compressed {
+            let mut decompressed = Vec::new();
+            ZlibDecoder::new(data.as_slice())
+                .take(MAX_STORAGE_SIZE as u64 + 1)
+                .read_to_end(&mut decompressed)
+                .map_err(|_| Error::InvalidData)?;
+
+            if decompressed.len() > MAX_STORAGE_SIZE {
+                return Err(Error::TooLarge);
+            }
+
+            data = decompressed;
+        }
+
+        let (width, height) = (self.width as usize, self.height as usize);
+        let pixels = match self.format {
+            Format::Png => return decode_png(&data),
+            Format::Rgba => data,
+            Format::Rgb => {
+                if data.len() != width * height * 3 {
+                    return Err(Error::InvalidData);
+                }
+
+                rgb_to_rgba(&data)
+            },
+        };
+
+        GraphicData::new(width, height, pixels).ok_or(Error::InvalidData)
+    }
+}
+
+/// Decode a PNG image into RGBA pixels.
+fn decode_png(data: &[u8]) -> Result<GraphicData, Error> {
+    let mut decoder = png::Decoder::new(data);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(|_| Error::InvalidData)?;
+
+    let (width, height) = reader.info().size();
+    if width as usize * height as usize * 4 > MAX_STORAGE_SIZE {
+        return Err(Error::TooLarge);
+    }
+
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).map_err(|_| Error::InvalidData)?;
+    buffer.truncate(info.buffer_size());
+
+    let pixels = match info.color_type {
+        png::ColorType::Rgba => buffer,
+        png::ColorType::Rgb => rgb_to_rgba(&buffer),
+        png::ColorType::GrayscaleAlpha => {
+            buffer.chunks_exact(2).flat_map(|gray| [gray[0], gray[0], gray[0], gray[1]]).collect()
+        },
+        png::ColorType::Grayscale => {
+            buffer.iter().flat_map(|&gray| [gray, gray, gray, u8::MAX]).collect()
+        },
+        png::ColorType::Indexed => return Err(Error::InvalidData),
+    };
+
+    GraphicData::new(width as usize, height as usize, pixels).ok_or(Error::InvalidData)
+}
+
+/// Add an opaque alpha channel to RGB pixels.
+fn rgb_to_rgba(rgb: &[u8]) -> Vec<u8> {
+    rgb.chunks_exact(3).flat_map(|rgb| [rgb[0], rgb[1], rgb[2], u8::MAX]).collect()
+}
+
+/// Parse a single character value.
+fn single_char(value: &[u8]) -> Option<u8> {
+    match value {
+        [c] => Some(*c),
+        _ => None,
+    }
+}
+
+/// Parse a numeric value.
+fn parse_number<N: str::FromStr>(value: &[u8]) -> Option<N> {
+    str::from_utf8(value).ok()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_command() {
+        let command = Command::parse(b"a=T,f=24,s=2,v=1,i=3,p=4,c=5,r=6,C=1,q=2,U=1;AAAA").unwrap();
+
+        assert_eq!(command, Command {
+            action: Action::TransmitAndPut,
+            quiet: 2,
+            format: Format::Rgb,
+            medium: b'd',
+            width: 2,
+            height: 1,
+            image_id: 3,
+            placement_id: 4,
+            columns: 5,
+            rows: 6,
+            fixed_cursor: true,
+            delete: b'a',
+            payload: b"AAAA".to_vec(),
+            ..Command::default()
+        });
+    }
+
+    #[test]
+    fn parse_invalid_command() {
+        assert_eq!(Command::parse(b"a=x"), None);
+        assert_eq!(Command::parse(b"i=-1"), None);
+        assert_eq!(Command::parse(b"i"), None);
+        assert_eq!(Command::parse(b"f=8;AAAA"), None);
+    }
+
+    #[test]
+    fn decode_rgb() {
+        let command = Command::parse(b"f=24,s=2,v=1;/wAAAP8A").unwrap();
+        let data = command.decode().unwrap();
+
+        assert_eq!((data.width, data.height), (2, 1));
+        assert_eq!(data.pixels, vec![255, 0, 0, 255, 0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn decode_compressed() {
+        // Zlib compressed RGBA pixels `[1, 2, 3, 4]`.
+        let command = Command::parse(b"s=1,v=1,o=z;eJxjZGJmAQAAGAAL").unwrap();
+        let data = command.decode().unwrap();
+
+        assert_eq!(data.pixels, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn decode_invalid_size() {
+        let command = Command::parse(b"f=32,s=2,v=2;AAAAAA==").unwrap();
+        assert_eq!(command.decode(), Err(Error::InvalidData));
+
+        let command = Command::parse(b"t=f,s=1,v=1;AAAAAA==").unwrap();
+        assert_eq!(command.decode(), Err(Error::UnsupportedMedium));
+    }
+
+    #[test]
+    fn response() {
+        let command = Command::parse(b"i=1,p=2").unwrap();
+        assert_eq!(command.response(Ok(())), Some(String::from("\x1b_Gi=1,p=2;OK\x1b\\")));
+
+        let command = Command::parse(b"i=1,q=1").unwrap();
+        assert_eq!(command.response(Ok(())), None);
+        assert_eq!(
+            command.response(Err(Error::NotFound)),
+            Some(String::from("\x1b_Gi=1;ENOENT:image not found\x1b\\"))
+        );
+
+        let command = Command::parse(b"a=T").unwrap();
+        assert_eq!(command.response(Err(Error::NotFound)), None);
+    }
+}
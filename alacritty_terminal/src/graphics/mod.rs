@@ -0,0 +1,296 @@
+//! Inline images displayed in the terminal grid.
+//!
+//! Decoded images are kept in the [`Graphics`] storage, while their placements are attached to
+//! the cells they cover. This way placements scroll with the grid content and are removed
+//! together with it.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+pub mod kitty;
+pub mod sixel;
+
+/// Maximum number of bytes used for storing decoded images.
+pub(crate) const MAX_STORAGE_SIZE: usize = 320 * 1024 * 1024;
+
+/// Maximum width or height of an image in pixels.
+pub(crate) const MAX_IMAGE_SIZE: usize = 10_000;
+
+/// Counter for unique graphic IDs.
+static GRAPHIC_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Unique identifier for a decoded image.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GraphicId(u64);
+
+impl GraphicId {
+    fn next() -> Self {
+        Self(GRAPHIC_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// Decoded image.
+#[derive(Debug, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GraphicData {
+    /// Unique identifier of the image.
+    #[cfg_attr(feature = "serde", serde(skip, default = "GraphicId::next"))]
+    pub id: GraphicId,
+
+    /// Width in pixels.
+    pub width: usize,
+
+    /// Height in pixels.
+    pub height: usize,
+
+    /// Pixels in RGBA format, starting at the top-left corner.
+    pub pixels: Vec<u8>,
+}
+
+impl GraphicData {
+    /// Create a new image from its RGBA pixels.
+    ///
+    /// Returns `None` if the dimensions are invalid or do not match the number of pixels.
+    pub fn new(width: usize, height: usize, pixels: Vec<u8>) -> Option<Self> {
+        if width == 0
+            || height == 0
+            || width > MAX_IMAGE_SIZE
+            || height > MAX_IMAGE_SIZE
+            || pixels.len() != width * height * 4
+        {
+            return None;
+        }
+
+        Some(Self { id: GraphicId::next(), width, height, pixels })
+    }
+}
+
+impl PartialEq for GraphicData {
+    /// Images are considered equal if they contain the same pixels, regardless of their ID.
+    fn eq(&self, other: &Self) -> bool {
+        self.width == other.width && self.height == other.height && self.pixels == other.pixels
+    }
+}
+
+/// Region of an image displayed in the grid.
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GraphicPlacement {
+    /// Image which is displayed.
+    pub graphic: Arc<GraphicData>,
+
+    /// Horizontal start of the displayed region in the image, in pixels.
+    pub source_x: usize,
+
+    /// Vertical start of the displayed region in the image, in pixels.
+    pub source_y: usize,
+
+    /// Width of the displayed region in the image, in pixels.
+    pub source_width: usize,
+
+    /// Height of the displayed region in the image, in pixels.
+    pub source_height: usize,
+
+    /// Horizontal offset from the top-left cell, in pixels.
+    pub offset_x: usize,
+
+    /// Vertical offset from the top-left cell, in pixels.
+    pub offset_y: usize,
+
+    /// Displayed width, in pixels.
+    pub width: usize,
+
+    /// Displayed height, in pixels.
+    pub height: usize,
+
+    /// Number of columns covered by the placement.
+    pub columns: usize,
+
+    /// Number of lines covered by the placement.
+    pub lines: usize,
+
+    /// Image ID assigned by the client.
+    pub image_id: u32,
+
+    /// Placement ID assigned by the client.
+    pub placement_id: u32,
+}
+
+/// Part of a placement covering a single cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GraphicCell {
+    /// Placement which covers the cell.
+    pub placement: Arc<GraphicPlacement>,
+
+    /// Column of the cell within the placement.
+    pub column: usize,
+
+    /// Line of the cell within the placement.
+    pub line: usize,
+}
+
+/// Lines of the grid covered by a placement.
+#[derive(Debug)]
+pub(crate) struct PlacedGraphic {
+    /// Placement, which is dropped once none of the cells display it anymore.
+    pub placement: Weak<GraphicPlacement>,
+
+    /// First line covered by the placement, including all lines scrolled off the screen.
+    pub line: usize,
+
+    /// Whether the placement was inserted into the alternate screen.
+    pub alt_screen: bool,
+}
+
+/// Image stored for later placement.
+#[derive(Debug)]
+struct StoredImage {
+    number: u32,
+    data: Arc<GraphicData>,
+}
+
+/// Storage for images transmitted by the client.
+#[derive(Debug, Default)]
+pub(crate) struct Graphics {
+    /// Images by their client-assigned ID.
+    images: HashMap<u32, StoredImage>,
+
+    /// Total size of all stored pixels.
+    size: usize,
+
+    /// Width of a cell in pixels.
+    pub cell_width: usize,
+
+    /// Height of a cell in pixels.
+    pub cell_height: usize,
+
+    /// Chunked kitty image transmission in progress.
+    pub transmission: Option<kitty::Command>,
+
+    /// Location of all placements in the grid.
+    pub placements: Vec<PlacedGraphic>,
+}
+
+impl Graphics {
+    /// Store an image.
+    ///
+    /// This replaces any previous image with the same ID and evicts the oldest images when the
+    /// storage limit is exceeded.
+    pub fn insert(&mut self, image_id: u32, number: u32, data: Arc<GraphicData>) {
+        self.remove(image_id);
+
+        self.size += data.pixels.len();
+        self.images.insert(image_id, StoredImage { number, data });
+
+        while self.size > MAX_STORAGE_SIZE {
+            let oldest = self.images.iter().min_by_key(|(_, image)| image.data.id);
+            match oldest.map(|(image_id, _)| *image_id) {
+                Some(image_id) => self.remove(image_id),
+                None => break,
+            }
+        }
+    }
+
+    /// Remove an image from the storage.
+    pub fn remove(&mut self, image_id: u32) {
+        if let Some(image) = self.images.remove(&image_id) {
+            self.size -= image.data.pixels.len();
+        }
+    }
+
+    /// Remove images which are not referenced by any placement.
+    pub fn remove_unused(&mut self, image_ids: impl IntoIterator<Item = u32>) {
+        for image_id in image_ids {
+            let unused = self.images.get(&image_id).is_some_and(|image| {
+                // The storage itself holds one reference.
+                Arc::strong_count(&image.data) == 1
+            });
+
+            if unused {
+                self.remove(image_id);
+            }
+        }
+    }
+
+    /// Get an image by its ID.
+    pub fn get(&self, image_id: u32) -> Option<&Arc<GraphicData>> {
+        self.images.get(&image_id).map(|image| &image.data)
+    }
+
+    /// Find the ID of the newest image with the specified number.
+    pub fn find_number(&self, number: u32) -> Option<u32> {
+        self.images
+            .iter()
+            .filter(|(_, image)| image.number == number)
+            .max_by_key(|(_, image)| image.data.id)
+            .map(|(image_id, _)| *image_id)
+    }
+
+    /// Find an image ID which is not used yet.
+    pub fn unused_id(&self) -> u32 {
+        (1..=u32::MAX).rev().find(|image_id| !self.images.contains_key(image_id)).unwrap_or(0)
+    }
+
+    /// Remove all images.
+    pub fn clear(&mut self) {
+        self.images.clear();
+        self.placements.clear();
+        self.transmission = None;
+        self.size = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(width: usize, height: usize) -> Arc<GraphicData> {
+        Arc::new(GraphicData::new(width, height, vec![0; width * height * 4]).unwrap())
+    }
+
+    #[test]
+    fn invalid_dimensions() {
+        assert!(GraphicData::new(2, 2, vec![0; 15]).is_none());
+        assert!(GraphicData::new(0, 0, Vec::new()).is_none());
+        let width = MAX_IMAGE_SIZE + 1;
+        assert!(GraphicData::new(width, 1, vec![0; width * 4]).is_none());
+    }
+
+    #[test]
+    fn storage_limit() {
+        let mut graphics = Graphics::default();
+
+        // Each image uses a third of the storage.
+        let side = ((MAX_STORAGE_SIZE / 12) as f64).sqrt() as usize;
+        for image_id in 1..=4 {
+            graphics.insert(image_id, 0, image(side, side));
+        }
+
+        assert!(graphics.get(1).is_none());
+        assert!(graphics.get(2).is_some());
+        assert!(graphics.get(4).is_some());
+    }
+
+    #[test]
+    fn remove_unused() {
+        let mut graphics = Graphics::default();
+
+        graphics.insert(1, 0, image(1, 1));
+        graphics.insert(2, 7, image(1, 1));
+        let placed = graphics.get(2).cloned();
+
+        graphics.remove_unused([1, 2]);
+        assert!(graphics.get(1).is_none());
+        assert_eq!(graphics.find_number(7), Some(2));
+
+        drop(placed);
+        graphics.remove_unused([2]);
+        assert_eq!(graphics.find_number(7), None);
+    }
+}
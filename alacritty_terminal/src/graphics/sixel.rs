@@ -0,0 +1,371 @@
+//! Sixel image decoding.
+//!
+//! See the VT330/VT340 programmer reference manual, chapter 14 "Sixel Graphics", for the format
+//! specification.
+
+use std::fmt::{self, Display, Formatter};
+
+use crate::graphics::{GraphicData, MAX_IMAGE_SIZE, MAX_STORAGE_SIZE};
+
+/// Private mode for sixel display mode (DECSDM).
+///
+/// When set, images are displayed at the top-left corner of the screen without moving the cursor.
+pub(crate) const DISPLAY_MODE: u16 = 80;
+
+/// Number of available color registers.
+const COLOR_REGISTERS: usize = 1024;
+
+/// Maximum number of parameters for a single sixel control function.
+const MAX_PARAMS: usize = 5;
+
+/// Default color registers of the VT340, in RGB percent.
+const DEFAULT_PALETTE: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [20, 20, 80],
+    [80, 13, 13],
+    [20, 80, 20],
+    [80, 20, 80],
+    [20, 80, 80],
+    [80, 80, 20],
+    [53, 53, 53],
+    [26, 26, 26],
+    [33, 33, 60],
+    [60, 26, 26],
+    [33, 60, 33],
+    [60, 33, 60],
+    [33, 60, 60],
+    [60, 60, 33],
+    [80, 80, 80],
+];
+
+/// Error while decoding a sixel image.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// Image does not contain any pixels.
+    Empty,
+
+    /// Image exceeds the size limits.
+    TooLarge,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "image is empty"),
+            Self::TooLarge => write!(f, "image too large"),
+        }
+    }
+}
+
+/// Decode a sixel image.
+///
+/// The `params` are the DCS parameters preceding the `q` final byte, while `data` is the sixel
+/// data following it.
+///
+/// The pixel aspect ratio is ignored and all pixels are treated as square.
+pub fn decode(params: &[u8], data: &[u8]) -> Result<GraphicData, Error> {
+    // Pixels which are not set use the background color, unless P2 is `1`.
+    let transparent = params.split(|&b| b == b';').nth(1) == Some(b"1");
+
+    let mut decoder = Decoder::new();
+    decoder.decode(data)?;
+    decoder.finish(transparent)
+}
+
+/// Sixel data decoder.
+struct Decoder {
+    /// Color registers in RGBA.
+    palette: Vec<[u8; 4]>,
+
+    /// Currently selected color register.
+    color: usize,
+
+    /// Pixels in RGBA, pixels which are not set are fully transparent.
+    canvas: Vec<[u8; 4]>,
+
+    /// Allocated canvas dimensions.
+    canvas_width: usize,
+    canvas_height: usize,
+
+    /// Image dimensions requested by raster attributes.
+    raster_width: usize,
+    raster_height: usize,
+
+    /// Image dimensions covered by sixels.
+    width: usize,
+    height: usize,
+
+    /// Position of the next sixel.
+    x: usize,
+    y: usize,
+}
+
+impl Decoder {
+    fn new() -> Self {
+        let mut palette = vec![[0, 0, 0, u8::MAX]; COLOR_REGISTERS];
+        for (register, [r, g, b]) in palette.iter_mut().zip(DEFAULT_PALETTE) {
+            *register = [percent(r as u32), percent(g as u32), percent(b as u32), u8::MAX];
+        }
+
+        Self {
+            palette,
+            color: 0,
+            canvas: Vec::new(),
+            canvas_width: 0,
+            canvas_height: 0,
+            raster_width: 0,
+            raster_height: 0,
+            width: 0,
+            height: 0,
+            x: 0,
+            y: 0,
+        }
+    }
+
+    /// Process all sixel data.
+    fn decode(&mut self, data: &[u8]) -> Result<(), Error> {
+        let mut index = 0;
+        while let Some(&byte) = data.get(index) {
+            index += 1;
+
+            match byte {
+                // Raster attributes.
+                b'"' => {
+                    let (params, len) = parse_params(&data[index..]);
+                    index += len;
+
+                    self.raster_width = (params[2] as usize).min(MAX_IMAGE_SIZE);
+                    self.raster_height = (params[3] as usize).min(MAX_IMAGE_SIZE);
+                    self.reserve(self.raster_width, self.raster_height)?;
+                },
+                // Color introducer.
+                b'#' => {
+                    let (params, len) = parse_params(&data[index..]);
+                    index += len;
+
+                    self.color = params[0] as usize % COLOR_REGISTERS;
+
+                    let rgb = match params[1] {
+                        1 => hls_to_rgb(params[2], params[3], params[4]),
+                        2 => [percent(params[2]), percent(params[3]), percent(params[4])],
+                        _ => continue,
+                    };
+                    self.palette[self.color] = [rgb[0], rgb[1], rgb[2], u8::MAX];
+                },
+                // Repeat introducer.
+                b'!' => {
+                    let (params, len) = parse_params(&data[index..]);
+                    index += len;
+
+                    if let Some(&sixel @ 0x3f..=0x7e) = data.get(index) {
+                        index += 1;
+
+                        let count = (params[0] as usize).clamp(1, MAX_IMAGE_SIZE);
+                        self.draw(sixel - 0x3f, count)?;
+                    }
+                },
+                // Graphics carriage return.
+                b'$' => self.x = 0,
+                // Graphics new line.
+                b'-' => {
+                    self.x = 0;
+                    self.y = (self.y + 6).min(MAX_IMAGE_SIZE);
+                },
+                sixel @ 0x3f..=0x7e => self.draw(sixel - 0x3f, 1)?,
+                _ => (),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Draw a sixel `count` times at the current position.
+    fn draw(&mut self, sixel: u8, count: usize) -> Result<(), Error> {
+        let start = self.x;
+        self.x = (self.x + count).min(MAX_IMAGE_SIZE);
+
+        if sixel == 0 || start >= self.x {
+            return Ok(());
+        }
+
+        // Pixels beyond the maximum image size are discarded.
+        let bottom = (self.y + 8 - sixel.leading_zeros() as usize).min(MAX_IMAGE_SIZE);
+        self.reserve(self.x, bottom)?;
+        self.width = self.width.max(self.x);
+        self.height = self.height.max(bottom);
+
+        let color = self.palette[self.color];
+        for y in self.y..bottom {
+            if sixel & (1 << (y - self.y)) == 0 {
+                continue;
+            }
+
+            let row = y * self.canvas_width;
+            self.canvas[row + start..row + self.x].fill(color);
+        }
+
+        Ok(())
+    }
+
+    /// Grow the canvas to fit an image of the specified size.
+    fn reserve(&mut self, width: usize, height: usize) -> Result<(), Error> {
+        if width <= self.canvas_width && height <= self.canvas_height {
+            return Ok(());
+        }
+
+        // Grow exponentially, to avoid copying the canvas for every sixel.
+        let mut canvas_width = width.max(self.canvas_width * 2).min(MAX_IMAGE_SIZE);
+        let mut canvas_height = height.max(self.canvas_height * 2).min(MAX_IMAGE_SIZE);
+        if canvas_width * canvas_height * 4 > MAX_STORAGE_SIZE {
+            canvas_width = width.max(self.canvas_width);
+            canvas_height = height.max(self.canvas_height);
+        }
+
+        if canvas_width * canvas_height * 4 > MAX_STORAGE_SIZE {
+            return Err(Error::TooLarge);
+        }
+
+        let mut canvas = vec![[0; 4]; canvas_width * canvas_height];
+        for y in 0..self.canvas_height {
+            let old_row = &self.canvas[y * self.canvas_width..(y + 1) * self.canvas_width];
+            let new_start = y * canvas_width;
+            canvas[new_start..new_start + self.canvas_width].copy_from_slice(old_row);
+        }
+
+        self.canvas = canvas;
+        self.canvas_width = canvas_width;
+        self.canvas_height = canvas_height;
+
+        Ok(())
+    }
+
+    /// Convert the canvas to an image.
+    fn finish(self, transparent: bool) -> Result<GraphicData, Error> {
+        let width = self.width.max(self.raster_width);
+        let height = self.height.max(self.raster_height);
+        if width == 0 || height == 0 {
+            return Err(Error::Empty);
+        }
+
+        let background = if transparent { [0; 4] } else { self.palette[0] };
+
+        let mut pixels = Vec::with_capacity(width * height * 4);
+        for row in self.canvas.chunks_exact(self.canvas_width).take(height) {
+            for &pixel in &row[..width] {
+                let pixel = if pixel[3] == 0 { background } else { pixel };
+                pixels.extend_from_slice(&pixel);
+            }
+        }
+
+        GraphicData::new(width, height, pixels).ok_or(Error::TooLarge)
+    }
+}
+
+/// Parse numeric parameters separated by `;`.
+///
+/// Returns the parameters, with omitted parameters set to zero, and the number of bytes consumed.
+fn parse_params(data: &[u8]) -> ([u32; MAX_PARAMS], usize) {
+    let mut params = [0u32; MAX_PARAMS];
+    let mut param = 0;
+
+    let mut len = 0;
+    for &byte in data {
+        match byte {
+            b'0'..=b'9' if param < MAX_PARAMS => {
+                let digit = u32::from(byte - b'0');
+                params[param] = params[param].saturating_mul(10).saturating_add(digit);
+            },
+            b'0'..=b'9' => (),
+            b';' => param += 1,
+            _ => break,
+        }
+        len += 1;
+    }
+
+    (params, len)
+}
+
+/// Convert a color component from percent to 8-bit.
+fn percent(value: u32) -> u8 {
+    (value.min(100) * 255 / 100) as u8
+}
+
+/// Convert a sixel HLS color to RGB.
+///
+/// Unlike most HLS representations, sixel hues start at blue instead of red.
+fn hls_to_rgb(hue: u32, lightness: u32, saturation: u32) -> [u8; 3] {
+    let hue = ((hue + 240) % 360) as f32;
+    let lightness = lightness.min(100) as f32 / 100.;
+    let saturation = saturation.min(100) as f32 / 100.;
+
+    let chroma = (1. - (2. * lightness - 1.).abs()) * saturation;
+    let x = chroma * (1. - ((hue / 60.) % 2. - 1.).abs());
+    let m = lightness - chroma / 2.;
+
+    let (r, g, b) = match hue as u32 / 60 {
+        0 => (chroma, x, 0.),
+        1 => (x, chroma, 0.),
+        2 => (0., chroma, x),
+        3 => (0., x, chroma),
+        4 => (x, 0., chroma),
+        _ => (chroma, 0., x),
+    };
+
+    [((r + m) * 255.).round() as u8, ((g + m) * 255.).round() as u8, ((b + m) * 255.).round() as u8]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+
+    fn pixel(graphic: &GraphicData, x: usize, y: usize) -> [u8; 4] {
+        let index = (y * graphic.width + x) * 4;
+        graphic.pixels[index..index + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn decode_colors() {
+        // Red top half and blue bottom half, using both RGB and HLS colors.
+        let graphic = decode(b"0;1", b"#1;2;100;0;0#2;1;0;50;100#1!2F$#2!2w").unwrap();
+
+        assert_eq!((graphic.width, graphic.height), (2, 6));
+        assert_eq!(pixel(&graphic, 1, 0), RED);
+        assert_eq!(pixel(&graphic, 0, 2), RED);
+        assert_eq!(pixel(&graphic, 0, 3), BLUE);
+        assert_eq!(pixel(&graphic, 1, 5), BLUE);
+    }
+
+    #[test]
+    fn decode_bands() {
+        // Second band is only two pixels high, with a transparent gap at the start.
+        let graphic = decode(b"0;1", b"#0;2;100;0;0~-?B").unwrap();
+
+        assert_eq!((graphic.width, graphic.height), (2, 8));
+        assert_eq!(pixel(&graphic, 0, 5), RED);
+        assert_eq!(pixel(&graphic, 1, 0), [0; 4]);
+        assert_eq!(pixel(&graphic, 0, 7), [0; 4]);
+        assert_eq!(pixel(&graphic, 1, 7), RED);
+    }
+
+    #[test]
+    fn decode_background() {
+        let data = b"\"1;1;3;2#0;2;0;0;100#1;2;100;0;0#1@";
+
+        let graphic = decode(b"", data).unwrap();
+        assert_eq!((graphic.width, graphic.height), (3, 2));
+        assert_eq!(pixel(&graphic, 0, 0), RED);
+        assert_eq!(pixel(&graphic, 2, 1), BLUE);
+
+        let graphic = decode(b"0;1;0", data).unwrap();
+        assert_eq!(pixel(&graphic, 2, 1), [0; 4]);
+    }
+
+    #[test]
+    fn decode_invalid() {
+        assert_eq!(decode(b"", b"#1;2;0;0;0??").err(), Some(Error::Empty));
+        assert_eq!(decode(b"", b"\"1;1;10000;10000").err(), Some(Error::TooLarge));
+    }
+}
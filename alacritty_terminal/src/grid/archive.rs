@@ -0,0 +1,929 @@
+//! Compressed storage for the oldest lines of the scrollback history.
+//!
+//! Once the regular scrollback history is full, lines leaving it are packed into runs of
+//! characters sharing the same attributes and grouped into blocks. Full blocks are compressed and
+//! can optionally be moved to a temporary file. Blocks are only decompressed while they are
+//! accessed.
+
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Formatter};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::ops::{Index, IndexMut};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::{env, mem, process};
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use log::error;
+use parking_lot::Mutex;
+
+use crate::grid::{GridCell, Row};
+use crate::index::Column;
+use crate::term::prompt::SemanticMark;
+
+/// Number of lines stored in each block.
+const BLOCK_SIZE: usize = 256;
+
+/// Maximum number of decompressed blocks kept after the cache is trimmed.
+const MAX_CACHED_BLOCKS: usize = 16;
+
+/// Number of unused bytes in the spill file before it is compacted.
+const MIN_COMPACTION_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Number of recent templates checked for reuse, before adding a new one.
+const TEMPLATE_LOOKBEHIND: usize = 32;
+
+type PackFn<T> = fn(&Row<T>, &mut Vec<u8>, &mut Vec<T>);
+type UnpackFn<T> = fn(&mut &[u8], &[T], usize) -> Row<T>;
+
+/// Cells which can be stored in the [`Archive`].
+pub trait ArchiveCell: GridCell + Clone + Default + PartialEq {
+    /// Character stored in the cell.
+    fn character(&self) -> char;
+
+    /// Attributes of the cell, with an empty character.
+    ///
+    /// Attributes which should not be archived can be dropped here.
+    fn attributes(&self) -> Self;
+
+    /// Copy of the cell attributes with a different character.
+    fn with_character(&self, c: char) -> Self;
+}
+
+/// Compressed lines beyond the regular scrollback history.
+///
+/// Lines are indexed from the newest line at `0` to the oldest line at `len - 1`.
+pub struct Archive<T> {
+    /// Blocks of lines, oldest first.
+    ///
+    /// All blocks except for the last one are full.
+    blocks: VecDeque<Block<T>>,
+
+    /// Number of lines at the start of the first block which were discarded.
+    discarded: usize,
+
+    /// Number of lines in the archive.
+    len: usize,
+
+    /// Maximum number of lines in the archive.
+    max_len: usize,
+
+    /// Number of cells in decompressed lines.
+    columns: usize,
+
+    /// Temporary file storing full blocks.
+    spill: Option<SpillFile>,
+
+    /// Sequence number of the first block.
+    first_block: u64,
+
+    /// Sequence numbers of decompressed blocks.
+    cached: Mutex<Vec<u64>>,
+
+    /// Counter for tracking when blocks were last accessed.
+    clock: AtomicU64,
+
+    pack: PackFn<T>,
+    unpack: UnpackFn<T>,
+}
+
+impl<T> Archive<T> {
+    pub fn new(columns: usize, max_len: usize) -> Self
+    where
+        T: ArchiveCell,
+    {
+        Self {
+            columns,
+            max_len,
+            blocks: Default::default(),
+            discarded: Default::default(),
+            len: Default::default(),
+            spill: Default::default(),
+            first_block: Default::default(),
+            cached: Default::default(),
+            clock: Default::default(),
+            pack: pack_row::<T>,
+            unpack: unpack_row::<T>,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Update the maximum number of lines, discarding the oldest lines if necessary.
+    pub fn set_max_len(&mut self, max_len: usize) {
+        self.max_len = max_len;
+        self.discard(self.len.saturating_sub(max_len));
+    }
+
+    /// Update the number of cells in decompressed lines.
+    ///
+    /// Archived lines are not reflowed, they are truncated or padded with empty cells instead.
+    pub fn set_columns(&mut self, columns: usize) {
+        if self.columns == columns {
+            return;
+        }
+
+        for index in 0..self.blocks.len() {
+            self.flush(index);
+        }
+        self.columns = columns;
+    }
+
+    /// Move full blocks to a temporary file, or back into memory.
+    pub fn set_spill(&mut self, spill: bool) {
+        if spill == self.spill.is_some() {
+            return;
+        }
+
+        if spill {
+            match SpillFile::new() {
+                Ok(file) => self.spill = Some(file),
+                Err(err) => {
+                    error!("Unable to create scrollback file: {err}");
+                    return;
+                },
+            }
+
+            for index in 0..self.blocks.len() {
+                self.store(index);
+            }
+        } else {
+            for index in 0..self.blocks.len() {
+                self.blocks[index].data = self.load(&self.blocks[index].data);
+            }
+
+            self.spill = None;
+        }
+    }
+
+    /// Remove all lines.
+    pub fn clear(&mut self) {
+        self.first_block += self.blocks.len() as u64;
+        self.blocks.clear();
+        self.cached.get_mut().clear();
+        self.discarded = 0;
+        self.len = 0;
+
+        if let Some(spill) = &mut self.spill {
+            spill.clear();
+        }
+    }
+
+    /// Add a new line, which is newer than all other lines in the archive.
+    pub fn push(&mut self, row: &Row<T>) {
+        if self.max_len == 0 {
+            return;
+        }
+
+        if !matches!(self.blocks.back(), Some(Block { data: Data::Open(_), .. })) {
+            self.blocks.push_back(Block::default());
+        }
+
+        // Lines can only be added to the packed data.
+        let index = self.blocks.len() - 1;
+        self.flush(index);
+
+        let block = &mut self.blocks[index];
+        block.push(self.pack, row);
+        if block.len == BLOCK_SIZE {
+            self.seal(index);
+        }
+
+        self.len += 1;
+        self.discard(self.len.saturating_sub(self.max_len));
+
+        self.trim_cache();
+    }
+
+    /// Drop the least recently used decompressed blocks exceeding the cache limit.
+    ///
+    /// Since lines are borrowed from the decompressed blocks, they can only be dropped with
+    /// exclusive access to the archive. Read-only access like searches can decompress any number
+    /// of blocks, so this should also be called once such an operation is done.
+    pub fn trim_cache(&mut self) {
+        let mut cached = mem::take(self.cached.get_mut());
+        cached.retain(|id| {
+            id.checked_sub(self.first_block).is_some_and(|index| {
+                self.blocks.get(index as usize).is_some_and(|block| block.rows.get().is_some())
+            })
+        });
+
+        if cached.len() > MAX_CACHED_BLOCKS {
+            let last_used = |id: &u64| {
+                let block = &self.blocks[(id - self.first_block) as usize];
+                block.last_used.load(Ordering::Relaxed)
+            };
+            cached.sort_unstable_by_key(|id| Reverse(last_used(id)));
+
+            for id in cached.drain(MAX_CACHED_BLOCKS..) {
+                self.flush((id - self.first_block) as usize);
+            }
+        }
+
+        *self.cached.get_mut() = cached;
+    }
+
+    /// Remove the oldest lines.
+    fn discard(&mut self, count: usize) {
+        self.len -= count;
+        self.discarded += count;
+
+        while self.blocks.front().is_some_and(|block| block.len <= self.discarded) {
+            let block = self.blocks.pop_front().unwrap();
+            self.discarded -= block.len;
+            self.first_block += 1;
+
+            if let (Data::Disk { len, .. }, Some(spill)) = (block.data, &mut self.spill) {
+                spill.free(len);
+            }
+        }
+    }
+
+    /// Compress a full block.
+    fn seal(&mut self, index: usize) {
+        let block = &mut self.blocks[index];
+        if let Data::Open(data) = &block.data {
+            block.data = Data::Memory(compress(data));
+        }
+
+        self.store(index);
+    }
+
+    /// Move a compressed block to the spill file.
+    fn store(&mut self, index: usize) {
+        let spill = match &mut self.spill {
+            Some(spill) => spill,
+            None => return,
+        };
+
+        if spill.unused() > spill.used.max(MIN_COMPACTION_SIZE) {
+            if let Err(err) = self.compact() {
+                error!("Unable to compact scrollback file: {err}");
+            }
+        }
+
+        let (block, spill) = match (&mut self.blocks[index], &mut self.spill) {
+            (block, Some(spill)) => (block, spill),
+            _ => return,
+        };
+
+        if let Data::Memory(data) = &block.data {
+            match spill.write(data) {
+                Ok(offset) => block.data = Data::Disk { offset, len: data.len() },
+                Err(err) => error!("Unable to write scrollback to disk: {err}"),
+            }
+        }
+    }
+
+    /// Remove unused space from the spill file, by copying all blocks to a new file.
+    fn compact(&mut self) -> io::Result<()> {
+        let spill = match &self.spill {
+            Some(spill) => spill,
+            None => return Ok(()),
+        };
+
+        let mut compacted = SpillFile::new()?;
+        let mut offsets = Vec::new();
+        for block in &self.blocks {
+            if let Data::Disk { offset, len } = block.data {
+                offsets.push(compacted.write(&spill.read(offset, len)?)?);
+            }
+        }
+
+        // Only update blocks once all of them were copied successfully.
+        let mut offsets = offsets.into_iter();
+        for block in &mut self.blocks {
+            if let Data::Disk { offset, .. } = &mut block.data {
+                *offset = offsets.next().unwrap_or_default();
+            }
+        }
+        self.spill = Some(compacted);
+
+        Ok(())
+    }
+
+    /// Drop the decompressed lines of a block, storing all modifications.
+    fn flush(&mut self, index: usize) {
+        let block = &mut self.blocks[index];
+        let rows = match block.rows.take() {
+            Some(rows) => rows,
+            None => return,
+        };
+
+        if !mem::take(&mut block.dirty) {
+            return;
+        }
+
+        if let (Data::Disk { len, .. }, Some(spill)) = (&block.data, &mut self.spill) {
+            spill.free(*len);
+        }
+
+        let sealed = !matches!(block.data, Data::Open(_));
+        *block = Block::default();
+        for row in &rows {
+            block.push(self.pack, row);
+        }
+
+        if sealed {
+            self.seal(index);
+        }
+    }
+
+    /// Decompressed lines of a block.
+    fn rows(&self, index: usize) -> &[Row<T>] {
+        let block = &self.blocks[index];
+        block.last_used.store(self.clock.fetch_add(1, Ordering::Relaxed), Ordering::Relaxed);
+
+        block.rows.get_or_init(|| {
+            self.cached.lock().push(self.first_block + index as u64);
+
+            let data = self.read(block);
+            let mut input = &data[..];
+            let mut rows: Vec<_> = (0..block.len)
+                .map(|_| (self.unpack)(&mut input, &block.templates, self.columns))
+                .collect();
+
+            for (line, column, mark) in &block.marks {
+                rows[*line].push_mark(*column, *mark);
+                rows[*line].clamp_marks();
+            }
+
+            rows
+        })
+    }
+
+    /// Packed data of a block.
+    ///
+    /// If the data cannot be read, the block's lines are restored as empty lines.
+    fn read<'a>(&self, block: &'a Block<T>) -> Cow<'a, [u8]> {
+        let data = match &block.data {
+            Data::Open(data) => return Cow::Borrowed(data),
+            Data::Memory(data) => decompress(data),
+            Data::Disk { offset, len } => match &self.spill {
+                Some(spill) => spill.read(*offset, *len).and_then(|data| decompress(&data)),
+                None => Err(io::ErrorKind::NotFound.into()),
+            },
+        };
+
+        match data {
+            Ok(data) => Cow::Owned(data),
+            Err(err) => {
+                error!("Unable to read archived scrollback: {err}");
+                Cow::Borrowed(&[])
+            },
+        }
+    }
+
+    /// Copy of a block's data, which is loaded into memory if it was stored on disk.
+    fn load(&self, data: &Data) -> Data {
+        match (data, &self.spill) {
+            (Data::Disk { offset, len }, Some(spill)) => match spill.read(*offset, *len) {
+                Ok(data) => Data::Memory(data.into()),
+                Err(err) => {
+                    error!("Unable to read archived scrollback: {err}");
+                    Data::Memory(Box::default())
+                },
+            },
+            (data, _) => data.clone(),
+        }
+    }
+
+    /// Block and line within the block for the line at `index`.
+    #[inline]
+    fn locate(&self, index: usize) -> (usize, usize) {
+        assert!(index < self.len, "archive index out of bounds: {index}/{}", self.len);
+
+        let position = self.discarded + self.len - 1 - index;
+        (position / BLOCK_SIZE, position % BLOCK_SIZE)
+    }
+}
+
+impl<T> Index<usize> for Archive<T> {
+    type Output = Row<T>;
+
+    #[inline]
+    fn index(&self, index: usize) -> &Row<T> {
+        let (block, line) = self.locate(index);
+        &self.rows(block)[line]
+    }
+}
+
+impl<T> IndexMut<usize> for Archive<T> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut Row<T> {
+        let (block, line) = self.locate(index);
+        self.rows(block);
+
+        let block = &mut self.blocks[block];
+        block.dirty = true;
+        &mut block.rows.get_mut().unwrap()[line]
+    }
+}
+
+impl<T: Clone> Clone for Archive<T> {
+    fn clone(&self) -> Self {
+        // The spill file can't be shared, so the clone keeps all blocks in memory.
+        let blocks = self
+            .blocks
+            .iter()
+            .map(|block| {
+                let mut clone = block.clone();
+                clone.data = self.load(&block.data);
+                clone
+            })
+            .collect();
+
+        Self {
+            blocks,
+            spill: None,
+            cached: Mutex::new(self.cached.lock().clone()),
+            clock: AtomicU64::new(self.clock.load(Ordering::Relaxed)),
+            discarded: self.discarded,
+            len: self.len,
+            max_len: self.max_len,
+            columns: self.columns,
+            first_block: self.first_block,
+            pack: self.pack,
+            unpack: self.unpack,
+        }
+    }
+}
+
+impl<T> Debug for Archive<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Archive")
+            .field("len", &self.len)
+            .field("max_len", &self.max_len)
+            .field("columns", &self.columns)
+            .field("blocks", &self.blocks.len())
+            .field("spill", &self.spill.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Group of archived lines.
+struct Block<T> {
+    /// Packed lines.
+    data: Data,
+
+    /// Cell attributes referenced by the packed lines.
+    templates: Vec<T>,
+
+    /// Shell integration marks, with the index of their line within the block.
+    marks: Vec<(usize, Column, SemanticMark)>,
+
+    /// Number of lines in the block.
+    len: usize,
+
+    /// Decompressed lines, oldest first.
+    rows: OnceLock<Vec<Row<T>>>,
+
+    /// Whether the decompressed lines were modified.
+    dirty: bool,
+
+    /// Time of the last access to the decompressed lines, according to [`Archive::clock`].
+    last_used: AtomicU64,
+}
+
+impl<T: Clone> Clone for Block<T> {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            templates: self.templates.clone(),
+            marks: self.marks.clone(),
+            len: self.len,
+            rows: self.rows.clone(),
+            dirty: self.dirty,
+            last_used: AtomicU64::new(self.last_used.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl<T> Default for Block<T> {
+    fn default() -> Self {
+        Self {
+            data: Data::Open(Vec::new()),
+            templates: Vec::new(),
+            marks: Vec::new(),
+            len: 0,
+            rows: OnceLock::new(),
+            dirty: false,
+            last_used: AtomicU64::new(0),
+        }
+    }
+}
+
+impl<T> Block<T> {
+    /// Append a line to an open block.
+    fn push(&mut self, pack: PackFn<T>, row: &Row<T>) {
+        debug_assert!(self.len < BLOCK_SIZE);
+
+        if let Data::Open(data) = &mut self.data {
+            pack(row, data, &mut self.templates);
+        }
+
+        let line = self.len;
+        self.marks.extend(row.marks().iter().map(|(column, mark)| (line, *column, *mark)));
+        self.len += 1;
+    }
+}
+
+/// Storage of a block's packed lines.
+#[derive(Clone)]
+enum Data {
+    /// Uncompressed data of a block which isn't full yet.
+    Open(Vec<u8>),
+
+    /// Compressed data in memory.
+    Memory(Box<[u8]>),
+
+    /// Compressed data in the spill file.
+    Disk { offset: u64, len: usize },
+}
+
+/// Temporary file storing compressed blocks.
+struct SpillFile {
+    file: File,
+
+    /// Size of the file.
+    len: u64,
+
+    /// Number of bytes still used by blocks.
+    used: u64,
+}
+
+impl SpillFile {
+    fn new() -> io::Result<Self> {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let count = COUNT.fetch_add(1, Ordering::Relaxed);
+        let path = env::temp_dir().join(format!("alacritty-scrollback-{}-{count}", process::id()));
+
+        let mut options = OpenOptions::new();
+        options.read(true).write(true).create_new(true);
+
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+        #[cfg(windows)]
+        {
+            /// Delete the file once its last handle is closed.
+            const FILE_FLAG_DELETE_ON_CLOSE: u32 = 0x0400_0000;
+            std::os::windows::fs::OpenOptionsExt::custom_flags(
+                &mut options,
+                FILE_FLAG_DELETE_ON_CLOSE,
+            );
+        }
+
+        let file = options.open(&path)?;
+
+        // Remove the file immediately, so it's cleaned up even if Alacritty crashes.
+        #[cfg(unix)]
+        std::fs::remove_file(&path)?;
+
+        Ok(Self { file, len: 0, used: 0 })
+    }
+
+    /// Append data to the file, returning its offset.
+    fn write(&mut self, data: &[u8]) -> io::Result<u64> {
+        let offset = self.len;
+        write_at(&self.file, data, offset)?;
+
+        self.len += data.len() as u64;
+        self.used += data.len() as u64;
+
+        Ok(offset)
+    }
+
+    fn read(&self, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+        let mut data = vec![0; len];
+        read_at(&self.file, &mut data, offset)?;
+        Ok(data)
+    }
+
+    /// Mark data as no longer used.
+    fn free(&mut self, len: usize) {
+        self.used -= len as u64;
+    }
+
+    /// Number of bytes in the file which are no longer used.
+    fn unused(&self) -> u64 {
+        self.len - self.used
+    }
+
+    fn clear(&mut self) {
+        if let Err(err) = self.file.set_len(0) {
+            error!("Unable to truncate scrollback file: {err}");
+        }
+
+        self.len = 0;
+        self.used = 0;
+    }
+}
+
+#[cfg(unix)]
+fn read_at(file: &File, buf: &mut [u8], offset: u64) -> io::Result<()> {
+    std::os::unix::fs::FileExt::read_exact_at(file, buf, offset)
+}
+
+#[cfg(unix)]
+fn write_at(file: &File, buf: &[u8], offset: u64) -> io::Result<()> {
+    std::os::unix::fs::FileExt::write_all_at(file, buf, offset)
+}
+
+#[cfg(windows)]
+fn read_at(file: &File, mut buf: &mut [u8], mut offset: u64) -> io::Result<()> {
+    use std::os::windows::fs::FileExt;
+
+    while !buf.is_empty() {
+        match file.seek_read(buf, offset)? {
+            0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+            read => {
+                buf = &mut buf[read..];
+                offset += read as u64;
+            },
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(windows)]
+fn write_at(file: &File, mut buf: &[u8], mut offset: u64) -> io::Result<()> {
+    use std::os::windows::fs::FileExt;
+
+    while !buf.is_empty() {
+        match file.seek_write(buf, offset)? {
+            0 => return Err(io::ErrorKind::WriteZero.into()),
+            written => {
+                buf = &buf[written..];
+                offset += written as u64;
+            },
+        }
+    }
+
+    Ok(())
+}
+
+fn compress(data: &[u8]) -> Box<[u8]> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::fast());
+
+    // Writing to a vector cannot fail.
+    let _ = encoder.write_all(data);
+    encoder.finish().unwrap_or_default().into_boxed_slice()
+}
+
+fn decompress(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    DeflateDecoder::new(data).read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
+
+/// Append the packed form of a line.
+///
+/// Lines are stored as their number of cells, followed by runs of characters which share the
+/// same attributes. Empty cells at the end of the line are not stored.
+pub(crate) fn pack_row<T: ArchiveCell>(row: &Row<T>, out: &mut Vec<u8>, templates: &mut Vec<T>) {
+    let empty = T::default();
+    let cells = &row[..];
+    let len = cells.iter().rposition(|cell| *cell != empty).map_or(0, |i| i + 1);
+    write_varint(out, len);
+
+    let mut cells = &cells[..len];
+    while let Some((first, rest)) = cells.split_first() {
+        let template = first.attributes();
+        let run = 1 + rest.iter().take_while(|cell| cell.attributes() == template).count();
+
+        let lookbehind = templates.len().saturating_sub(TEMPLATE_LOOKBEHIND);
+        let index = match templates[lookbehind..].iter().rposition(|t| *t == template) {
+            Some(index) => lookbehind + index,
+            None => {
+                templates.push(template);
+                templates.len() - 1
+            },
+        };
+
+        write_varint(out, index);
+        write_varint(out, run);
+        for cell in &cells[..run] {
+            write_varint(out, cell.character() as usize);
+        }
+
+        cells = &cells[run..];
+    }
+}
+
+/// Read the next line packed by [`pack_row`].
+pub(crate) fn unpack_row<T: ArchiveCell>(
+    input: &mut &[u8],
+    templates: &[T],
+    columns: usize,
+) -> Row<T> {
+    let mut row = Row::new(columns);
+
+    // Restore as much as possible from invalid data.
+    let _ = unpack_cells(input, templates, &mut row);
+
+    row
+}
+
+fn unpack_cells<T: ArchiveCell>(
+    input: &mut &[u8],
+    templates: &[T],
+    row: &mut Row<T>,
+) -> Option<()> {
+    let len = read_varint(input)?;
+
+    let mut column = 0;
+    while column < len {
+        let template = templates.get(read_varint(input)?)?;
+        let run = read_varint(input)?;
+
+        for _ in 0..run {
+            let c = char::from_u32(u32::try_from(read_varint(input)?).ok()?)?;
+            if column < row.len() {
+                row[Column(column)] = template.with_character(c);
+            }
+            column += 1;
+        }
+    }
+
+    Some(())
+}
+
+/// Append an unsigned LEB128 integer.
+fn write_varint(out: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Read an unsigned LEB128 integer.
+fn read_varint(input: &mut &[u8]) -> Option<usize> {
+    let mut value = 0;
+    for shift in (0..usize::BITS).step_by(7) {
+        let (byte, rest) = input.split_first()?;
+        *input = rest;
+
+        value |= ((byte & 0x7f) as usize) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::term::cell::{Cell, Flags};
+    use crate::vte::ansi::{Color, NamedColor};
+
+    fn row(text: &str, columns: usize) -> Row<Cell> {
+        let mut row = Row::<Cell>::new(columns);
+        for (i, c) in text.chars().enumerate() {
+            row[Column(i)].c = c;
+        }
+        row
+    }
+
+    fn text(row: &Row<Cell>) -> String {
+        row[..].iter().map(|cell| cell.c).collect::<String>().trim_end().into()
+    }
+
+    #[test]
+    fn pack_attributes() {
+        let mut original = row("ab cdé", 10);
+        original[Column(1)].flags = Flags::BOLD;
+        original[Column(3)].fg = Color::Named(NamedColor::Red);
+        original[Column(4)].fg = Color::Named(NamedColor::Red);
+        original[Column(4)].push_zerowidth('\u{301}');
+        original[Column(9)].bg = Color::Indexed(4);
+
+        let mut data = Vec::new();
+        let mut templates = Vec::new();
+        pack_row(&original, &mut data, &mut templates);
+
+        let restored = unpack_row(&mut &data[..], &templates, 10);
+        assert_eq!(restored, original);
+
+        // Templates are shared between runs with the same attributes.
+        assert_eq!(templates.len(), 5);
+    }
+
+    #[test]
+    fn resize_columns() {
+        let mut archive = Archive::new(6, 10);
+        archive.push(&row("abcdef", 6));
+
+        archive.set_columns(3);
+        assert_eq!(archive[0].len(), 3);
+        assert_eq!(text(&archive[0]), "abc");
+
+        archive.set_columns(8);
+        assert_eq!(archive[0].len(), 8);
+        assert_eq!(text(&archive[0]), "abcdef");
+    }
+
+    #[test]
+    fn discard_oldest() {
+        let mut archive = Archive::new(10, BLOCK_SIZE + 10);
+        for i in 0..BLOCK_SIZE * 3 {
+            archive.push(&row(&i.to_string(), 10));
+        }
+
+        assert_eq!(archive.len(), BLOCK_SIZE + 10);
+        assert_eq!(text(&archive[0]), (BLOCK_SIZE * 3 - 1).to_string());
+        assert_eq!(text(&archive[BLOCK_SIZE + 9]), (BLOCK_SIZE * 2 - 10).to_string());
+        assert_eq!(archive.blocks.len(), 2);
+    }
+
+    #[test]
+    fn modify_archived() {
+        let mut archive = Archive::new(10, BLOCK_SIZE * 2);
+        for i in 0..BLOCK_SIZE * 2 {
+            archive.push(&row(&i.to_string(), 10));
+        }
+
+        archive[BLOCK_SIZE + 1][Column(0)].c = 'x';
+        archive.set_columns(11);
+
+        assert_eq!(text(&archive[BLOCK_SIZE + 1]), "x54");
+        assert_eq!(text(&archive[BLOCK_SIZE]), "255");
+    }
+
+    #[test]
+    fn trim_least_recently_used() {
+        let mut archive = Archive::new(10, usize::MAX);
+        for i in 0..BLOCK_SIZE * (MAX_CACHED_BLOCKS + 4) {
+            archive.push(&row(&i.to_string(), 10));
+        }
+
+        // Read every block from newest to oldest, then the newest line again.
+        for index in (0..archive.len()).step_by(BLOCK_SIZE) {
+            assert!(!text(&archive[index]).is_empty());
+        }
+        assert_eq!(text(&archive[0]), (archive.len() - 1).to_string());
+        assert_eq!(archive.blocks.iter().filter(|block| block.rows.get().is_some()).count(), 20);
+
+        archive.trim_cache();
+
+        let cached: Vec<_> =
+            archive.blocks.iter().map(|block| block.rows.get().is_some()).collect();
+        assert_eq!(cached.iter().filter(|cached| **cached).count(), MAX_CACHED_BLOCKS);
+        assert!(cached[0]);
+        assert!(!cached[MAX_CACHED_BLOCKS]);
+        assert!(cached[MAX_CACHED_BLOCKS + 3]);
+    }
+
+    #[test]
+    fn spill() {
+        let mut archive = Archive::new(10, usize::MAX);
+        archive.set_spill(true);
+        for i in 0..BLOCK_SIZE * 2 + 1 {
+            archive.push(&row(&i.to_string(), 10));
+        }
+
+        assert!(matches!(archive.blocks[0].data, Data::Disk { .. }));
+        assert!(matches!(archive.blocks[2].data, Data::Open(_)));
+        assert_eq!(text(&archive[BLOCK_SIZE * 2]), "0");
+        assert_eq!(text(&archive[0]), (BLOCK_SIZE * 2).to_string());
+
+        let clone = archive.clone();
+        archive.set_spill(false);
+        assert!(matches!(archive.blocks[1].data, Data::Memory(_)));
+        assert_eq!(text(&archive[BLOCK_SIZE]), BLOCK_SIZE.to_string());
+        assert_eq!(text(&clone[BLOCK_SIZE]), BLOCK_SIZE.to_string());
+    }
+
+    #[test]
+    fn varint() {
+        let mut data = Vec::new();
+        for value in [0, 0x7f, 0x80, 0x10ffff, usize::MAX] {
+            write_varint(&mut data, value);
+        }
+
+        let mut input = &data[..];
+        for value in [0, 0x7f, 0x80, 0x10ffff, usize::MAX] {
+            assert_eq!(read_varint(&mut input), Some(value));
+        }
+        assert_eq!(read_varint(&mut input), None);
+    }
+}
@@ -10,12 +10,16 @@ use crate::index::{Column, Line, Point};
 use crate::term::cell::{Flags, ResetDiscriminant};
 use crate::vte::ansi::{CharsetIndex, StandardCharset};
 
+mod archive;
 pub mod resize;
 mod row;
 mod storage;
 #[cfg(test)]
 mod tests;
 
+use self::archive::Archive;
+pub use self::archive::ArchiveCell;
+pub(crate) use self::archive::{pack_row, unpack_row};
 pub use self::row::Row;
 use self::storage::Storage;
 
@@ -105,6 +109,9 @@ pub enum Scroll {
 ///                           ^
 ///                        columns
 /// ```
+///
+/// Once the scrollback history is full, lines leaving it are moved to a compressed archive above
+/// it, if one is enabled using [`Grid::update_archive`].
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Grid<T> {
@@ -135,6 +142,14 @@ pub struct Grid<T> {
 
     /// Maximum number of lines in history.
     max_scroll_limit: usize,
+
+    /// Compressed lines above the scrollback history.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    archive: Option<Archive<T>>,
+
+    /// Number of lines scrolled off the top of the screen.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    scrolled_lines: usize,
 }
 
 impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
@@ -147,19 +162,50 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
             cursor: Cursor::default(),
             lines,
             columns,
+            archive: None,
+            scrolled_lines: 0,
         }
     }
 
+    /// Create a grid from all its lines, starting with the bottommost line.
+    ///
+    /// All lines above the screen are part of the scrollback history.
+    pub(crate) fn from_rows(lines: usize, columns: usize, rows: Vec<Row<T>>) -> Grid<T> {
+        let mut grid = Grid::new(lines, columns, rows.len().saturating_sub(lines));
+        grid.raw.replace_inner(rows);
+        grid
+    }
+
     /// Update the size of the scrollback history.
     pub fn update_history(&mut self, history_size: usize) {
-        let current_history_size = self.history_size();
+        let current_history_size = self.raw_history_size();
         if current_history_size > history_size {
             self.raw.shrink_lines(current_history_size - history_size);
         }
-        self.display_offset = min(self.display_offset, history_size);
+        self.display_offset = min(self.display_offset, self.history_size());
         self.max_scroll_limit = history_size;
     }
 
+    /// Update the size of the compressed history above the scrollback history.
+    ///
+    /// Setting `max_lines` to `0` disables the archive. With `spill` enabled, the compressed
+    /// lines are stored in a temporary file instead of memory.
+    pub fn update_archive(&mut self, max_lines: usize, spill: bool)
+    where
+        T: ArchiveCell,
+    {
+        if max_lines == 0 {
+            self.archive = None;
+        } else {
+            let columns = self.columns;
+            let archive = self.archive.get_or_insert_with(|| Archive::new(columns, max_lines));
+            archive.set_max_len(max_lines);
+            archive.set_spill(spill);
+        }
+
+        self.display_offset = min(self.display_offset, self.history_size());
+    }
+
     pub fn scroll_display(&mut self, scroll: Scroll) {
         self.display_offset = match scroll {
             Scroll::Delta(count) => {
@@ -170,19 +216,32 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
             Scroll::Top => self.history_size(),
             Scroll::Bottom => 0,
         };
+
+        // Release archived lines which are no longer visible.
+        self.trim_archive_cache();
+    }
+
+    /// Release archived lines which were decompressed for reading.
+    ///
+    /// Read-only access to archived lines keeps them decompressed until the grid is modified or
+    /// this is called, so it should be called after operations like searching the history.
+    pub fn trim_archive_cache(&mut self) {
+        if let Some(archive) = &mut self.archive {
+            archive.trim_cache();
+        }
     }
 
     fn increase_scroll_limit(&mut self, count: usize) {
-        let count = min(count, self.max_scroll_limit - self.history_size());
+        let count = min(count, self.max_scroll_limit - self.raw_history_size());
         if count != 0 {
             self.raw.initialize(count, self.columns);
         }
     }
 
     fn decrease_scroll_limit(&mut self, count: usize) {
-        let count = min(count, self.history_size());
+        let count = min(count, self.raw_history_size());
         if count != 0 {
-            self.raw.shrink_lines(min(count, self.history_size()));
+            self.raw.shrink_lines(count);
             self.display_offset = min(self.display_offset, self.history_size());
         }
     }
@@ -265,13 +324,26 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
 
         // Update display offset when not pinned to active area.
         if self.display_offset != 0 {
-            self.display_offset = min(self.display_offset + positions, self.max_scroll_limit);
+            let max_archive = self.archive.as_ref().map_or(0, |archive| archive.max_len());
+            let max_offset = self.max_scroll_limit.saturating_add(max_archive);
+            self.display_offset = min(self.display_offset + positions, max_offset);
         }
 
         // Only rotate the entire history if the active region starts at the top.
         if region.start == 0 {
+            // Archive lines which will be rotated out of the scrollback history.
+            if let Some(archive) = &mut self.archive {
+                let history_size = self.raw.len() - self.lines;
+                let archived = positions.saturating_sub(self.max_scroll_limit - history_size);
+                let topmost_line = -(history_size as i32);
+                for line in (topmost_line..topmost_line + archived as i32).map(Line::from) {
+                    archive.push(&self.raw[line]);
+                }
+            }
+
             // Create scrollback for the new lines.
             self.increase_scroll_limit(positions);
+            self.scrolled_lines += positions;
 
             // Swap the lines fixed at the top to their target positions after rotation.
             //
@@ -340,6 +412,9 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
     {
         self.clear_history();
 
+        // Move all previous output above the screen.
+        self.scrolled_lines += self.lines;
+
         self.saved_cursor = Cursor::default();
         self.cursor = Cursor::default();
         self.display_offset = 0;
@@ -382,7 +457,12 @@ impl<T> Grid<T> {
     #[inline]
     pub fn clear_history(&mut self) {
         // Explicitly purge all lines from history.
-        self.raw.shrink_lines(self.history_size());
+        //
+        // Since the visible lines don't move, this does not change the scrolled lines.
+        self.raw.shrink_lines(self.raw_history_size());
+        if let Some(archive) = &mut self.archive {
+            archive.clear();
+        }
 
         // Reset display offset.
         self.display_offset = 0;
@@ -398,7 +478,7 @@ impl<T> Grid<T> {
         self.truncate();
 
         // Initialize everything with empty new lines.
-        self.raw.initialize(self.max_scroll_limit - self.history_size(), self.columns);
+        self.raw.initialize(self.max_scroll_limit - self.raw_history_size(), self.columns);
     }
 
     /// This is used only for truncating before saving ref-tests.
@@ -433,6 +513,28 @@ impl<T> Grid<T> {
         self.display_offset
     }
 
+    /// Total number of lines scrolled off the top of the screen.
+    ///
+    /// This can be used to track lines across scrolling, since the sum of the scrolled lines and
+    /// a visible line stays the same while it is scrolled into history. Resizing keeps this
+    /// stable for the cursor's line.
+    #[inline]
+    pub fn scrolled_lines(&self) -> usize {
+        self.scrolled_lines
+    }
+
+    /// Number of compressed lines at the top of the scrollback history.
+    #[inline]
+    pub fn archived_lines(&self) -> usize {
+        self.archive.as_ref().map_or(0, |archive| archive.len())
+    }
+
+    /// Number of scrollback lines which are not archived.
+    #[inline]
+    fn raw_history_size(&self) -> usize {
+        self.raw.len() - self.lines
+    }
+
     #[inline]
     pub fn cursor_cell(&mut self) -> &mut T {
         let point = self.cursor.point;
@@ -455,14 +557,26 @@ impl<T> Index<Line> for Grid<T> {
 
     #[inline]
     fn index(&self, index: Line) -> &Row<T> {
-        &self.raw[index]
+        let topmost_line = -(self.raw_history_size() as i32);
+        match &self.archive {
+            Some(archive) if index < topmost_line => {
+                &archive[(topmost_line - index.0 - 1) as usize]
+            },
+            _ => &self.raw[index],
+        }
     }
 }
 
 impl<T> IndexMut<Line> for Grid<T> {
     #[inline]
     fn index_mut(&mut self, index: Line) -> &mut Row<T> {
-        &mut self.raw[index]
+        let topmost_line = -(self.raw_history_size() as i32);
+        match &mut self.archive {
+            Some(archive) if index < topmost_line => {
+                &mut archive[(topmost_line - index.0 - 1) as usize]
+            },
+            _ => &mut self.raw[index],
+        }
     }
 }
 
@@ -521,7 +635,7 @@ pub trait Dimensions {
 impl<G> Dimensions for Grid<G> {
     #[inline]
     fn total_lines(&self) -> usize {
-        self.raw.len()
+        self.raw.len() + self.archived_lines()
     }
 
     #[inline]
@@ -19,6 +19,9 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
         // Use empty template cell for resetting cells due to resize.
         let template = mem::take(&mut self.cursor.template);
 
+        // Output position of the cursor's line, which is kept stable across the resize.
+        let cursor_line = self.scrolled_lines + self.cursor.point.line.0 as usize;
+
         match self.lines.cmp(&lines) {
             Ordering::Less => self.grow_lines(lines),
             Ordering::Greater => self.shrink_lines(lines),
@@ -31,6 +34,14 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
             Ordering::Equal => (),
         }
 
+        // Archived lines are only truncated or extended, without reflow.
+        if let Some(archive) = &mut self.archive {
+            archive.set_columns(columns);
+        }
+
+        // Lines moving between history and screen do not count as scrolled.
+        self.scrolled_lines = cursor_line.saturating_sub(self.cursor.point.line.0 as usize);
+
         // Restore template cell.
         self.cursor.template = template;
     }
@@ -51,7 +62,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
         self.raw.grow_visible_lines(target);
         self.lines = target;
 
-        let history_size = self.history_size();
+        let history_size = self.raw_history_size();
         let from_history = min(history_size, lines_added);
 
         // Move existing lines up for every line that couldn't be pulled from history.
@@ -147,6 +158,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
             let len = min(row.len(), num_wrapped);
 
             // Insert leading spacer when there's not enough room for reflowing wide char.
+            let row_len = row.len();
             let mut cells = if row[Column(len - 1)].flags().contains(Flags::WIDE_CHAR) {
                 num_wrapped -= 1;
 
@@ -162,7 +174,9 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
             };
 
             // Add removed cells to previous row and reflow content.
+            let marks = row.front_split_off_marks(row_len - row.len());
             last_row.append(&mut cells);
+            last_row.append_marks(marks, last_len);
 
             let cursor_buffer_line = self.lines - self.cursor.point.line.0 as usize - 1;
 
@@ -182,6 +196,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
                 let line_delta = self.cursor.point.line - target.line;
 
                 if line_delta != 0 && row.is_clear() {
+                    last_row.append_marks(row.take_marks(), last_row.len());
                     continue;
                 }
 
@@ -198,6 +213,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
                 }
 
                 // Don't push line into the new buffer.
+                last_row.append_marks(row.take_marks(), last_row.len());
                 continue;
             }
 
@@ -253,6 +269,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
 
         let mut new_raw = Vec::with_capacity(self.raw.len());
         let mut buffered: Option<Vec<T>> = None;
+        let mut buffered_marks = Vec::new();
 
         let mut rows = self.raw.take_all();
         for (i, mut row) in rows.drain(..).enumerate().rev() {
@@ -265,6 +282,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
                     self.cursor.point.column += buffered.len();
                 }
 
+                row.append_front_marks(mem::take(&mut buffered_marks), buffered.len());
                 row.append_front(buffered);
             }
 
@@ -280,6 +298,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
                             Vec::new()
                         } else {
                             // Since it fits, just push the existing line without any reflow.
+                            row.clamp_marks();
                             new_raw.push(row);
                             break;
                         }
@@ -287,6 +306,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
                 };
 
                 // Insert spacer if a wide char would be wrapped into the last column.
+                let mut wrap_start = columns;
                 if row.len() >= columns
                     && row[Column(columns - 1)].flags().contains(Flags::WIDE_CHAR)
                 {
@@ -295,13 +315,18 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
 
                     let wide_char = mem::replace(&mut row[Column(columns - 1)], spacer);
                     wrapped.insert(0, wide_char);
+                    wrap_start -= 1;
                 }
 
+                // Move marks together with the wrapped cells.
+                let marks = row.split_off_marks(wrap_start);
+
                 // Remove wide char spacer before shrinking.
                 let len = wrapped.len();
                 if len > 0 && wrapped[len - 1].flags().contains(Flags::LEADING_WIDE_CHAR_SPACER) {
                     if len == 1 {
                         row[Column(columns - 1)].flags_mut().insert(Flags::WRAPLINE);
+                        row.append_marks(marks, wrap_start);
                         new_raw.push(row);
                         break;
                     } else {
@@ -331,6 +356,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
 
                     // Add removed cells to start of next row.
                     buffered = Some(wrapped);
+                    buffered_marks = marks;
                     break;
                 } else {
                     // Reflow cursor if a line below it is deleted.
@@ -354,6 +380,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
                         wrapped.resize_with(columns, T::default);
                     }
                     row = Row::from_vec(wrapped, occ);
+                    row.append_marks(marks, 0);
 
                     if i < self.display_offset {
                         // Since we added a new line, rotate up the viewport.
@@ -365,7 +392,16 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
 
         // Reverse iterator and use it as the new grid storage.
         let mut reversed: Vec<Row<T>> = new_raw.drain(..).rev().collect();
-        reversed.truncate(self.max_scroll_limit + self.lines);
+
+        // Archive the oldest lines which no longer fit into the scrollback history.
+        let max_len = self.max_scroll_limit + self.lines;
+        if let Some(archive) = self.archive.as_mut().filter(|_| reversed.len() > max_len) {
+            for row in reversed[max_len..].iter().rev() {
+                archive.push(row);
+            }
+        }
+
+        reversed.truncate(max_len);
         self.raw.replace_inner(reversed);
 
         // Clamp display offset in case some lines went off.
@@ -10,6 +10,7 @@ use serde::{Deserialize, Serialize};
 use crate::grid::GridCell;
 use crate::index::Column;
 use crate::term::cell::ResetDiscriminant;
+use crate::term::prompt::SemanticMark;
 
 /// A row in the grid.
 #[derive(Default, Clone, Debug)]
@@ -22,6 +23,13 @@ pub struct Row<T> {
     /// This is the upper bound on the number of elements in the row, which have been modified
     /// since the last reset. All cells after this point are guaranteed to be equal.
     pub(crate) occ: usize,
+
+    /// Shell integration marks, sorted by column.
+    ///
+    /// Since only few rows have marks, they are boxed to keep rows without marks small.
+    #[allow(clippy::box_collection)]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    marks: Option<Box<Vec<(Column, SemanticMark)>>>,
 }
 
 impl<T: PartialEq> PartialEq for Row<T> {
@@ -52,7 +60,7 @@ impl<T: Clone + Default> Row<T> {
             inner.set_len(columns);
         }
 
-        Row { inner, occ: 0 }
+        Row { inner, occ: 0, marks: None }
     }
 
     /// Increase the number of columns in the row.
@@ -111,6 +119,7 @@ impl<T: Clone + Default> Row<T> {
         }
 
         self.occ = 0;
+        self.marks = None;
     }
 }
 
@@ -118,7 +127,7 @@ impl<T: Clone + Default> Row<T> {
 impl<T> Row<T> {
     #[inline]
     pub fn from_vec(vec: Vec<T>, occ: usize) -> Row<T> {
-        Row { inner: vec, occ }
+        Row { inner: vec, occ, marks: None }
     }
 
     #[inline]
@@ -171,6 +180,91 @@ impl<T> Row<T> {
         std::mem::swap(&mut split, &mut self.inner);
         split
     }
+
+    /// Shell integration marks in this row.
+    #[inline]
+    pub fn marks(&self) -> &[(Column, SemanticMark)] {
+        self.marks.as_deref().map_or(&[], Vec::as_slice)
+    }
+
+    /// Add a new shell integration mark.
+    ///
+    /// Marks are kept sorted by column, with marks at the same column in insertion order.
+    pub fn push_mark(&mut self, column: Column, mark: SemanticMark) {
+        let marks = self.marks.get_or_insert_with(Default::default);
+        let index = marks.partition_point(|(mark_column, _)| *mark_column <= column);
+        marks.insert(index, (column, mark));
+    }
+
+    /// Remove all marks from the row.
+    #[inline]
+    pub(crate) fn take_marks(&mut self) -> Vec<(Column, SemanticMark)> {
+        self.marks.take().map_or_else(Vec::new, |marks| *marks)
+    }
+
+    /// Replace all marks of the row.
+    #[inline]
+    fn set_marks(&mut self, marks: Vec<(Column, SemanticMark)>) {
+        self.marks = (!marks.is_empty()).then(|| Box::new(marks));
+    }
+
+    /// Remove all marks in front of `column`.
+    ///
+    /// The remaining marks are moved to the left by `column` cells.
+    pub(crate) fn front_split_off_marks(&mut self, column: usize) -> Vec<(Column, SemanticMark)> {
+        let mut marks = self.take_marks();
+        let index = marks.partition_point(|(mark_column, _)| mark_column.0 < column);
+        let mut remaining = marks.split_off(index);
+        for (mark_column, _) in &mut remaining {
+            *mark_column -= column;
+        }
+        self.set_marks(remaining);
+        marks
+    }
+
+    /// Remove all marks at or after `column`.
+    ///
+    /// The removed marks are moved to the left by `column` cells.
+    pub(crate) fn split_off_marks(&mut self, column: usize) -> Vec<(Column, SemanticMark)> {
+        let mut marks = self.take_marks();
+        let index = marks.partition_point(|(mark_column, _)| mark_column.0 < column);
+        let mut split = marks.split_off(index);
+        for (mark_column, _) in &mut split {
+            *mark_column -= column;
+        }
+        self.set_marks(marks);
+        split
+    }
+
+    /// Add marks after the existing ones, moving them to the right by `offset` cells.
+    pub(crate) fn append_marks(&mut self, marks: Vec<(Column, SemanticMark)>, offset: usize) {
+        let last_column = Column(self.inner.len().saturating_sub(1));
+        for (column, mark) in marks {
+            self.push_mark(min(column + offset, last_column), mark);
+        }
+    }
+
+    /// Add marks in front of the existing ones, moving the existing marks right by `offset` cells.
+    pub(crate) fn append_front_marks(
+        &mut self,
+        mut marks: Vec<(Column, SemanticMark)>,
+        offset: usize,
+    ) {
+        let mut existing = self.take_marks();
+        for (column, _) in &mut existing {
+            *column += offset;
+        }
+        marks.append(&mut existing);
+        self.set_marks(marks);
+    }
+
+    /// Clamp all marks to the row's width.
+    pub(crate) fn clamp_marks(&mut self) {
+        let last_column = Column(self.inner.len().saturating_sub(1));
+        for (column, _) in self.marks.iter_mut().flat_map(|marks| marks.iter_mut()) {
+            *column = min(*column, last_column);
+        }
+    }
 }
 
 impl<'a, T> IntoIterator for &'a Row<T> {
@@ -147,11 +147,12 @@ impl<T> Storage<T> {
     /// Exploits the known size of Row<T> to produce a slightly more efficient
     /// swap than going through slice::swap.
     ///
-    /// The default implementation from swap generates 8 movups and 4 movaps
-    /// instructions. This implementation achieves the swap in only 8 movups
+    /// The default implementation from swap generates additional movaps
+    /// instructions. This implementation achieves the swap using only movups
     /// instructions.
     pub fn swap(&mut self, a: Line, b: Line) {
-        debug_assert_eq!(mem::size_of::<Row<T>>(), mem::size_of::<usize>() * 4);
+        let words = mem::size_of::<Row<T>>() / mem::size_of::<usize>();
+        debug_assert_eq!(mem::size_of::<Row<T>>(), mem::size_of::<usize>() * words);
 
         let a = self.compute_index(a);
         let b = self.compute_index(b);
@@ -167,10 +168,10 @@ impl<T> Storage<T> {
             //
             // The optimizer unrolls this loop and vectorizes it.
             let mut tmp: MaybeUninit<usize>;
-            for i in 0..4 {
-                tmp = *a_ptr.offset(i);
-                *a_ptr.offset(i) = *b_ptr.offset(i);
-                *b_ptr.offset(i) = tmp;
+            for i in 0..words {
+                tmp = *a_ptr.add(i);
+                *a_ptr.add(i) = *b_ptr.add(i);
+                *b_ptr.add(i) = tmp;
             }
         }
     }
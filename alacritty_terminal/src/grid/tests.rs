@@ -3,6 +3,8 @@
 use super::*;
 
 use crate::term::cell::Cell;
+use crate::term::prompt::SemanticMark;
+use crate::vte::ansi::Color;
 
 impl GridCell for usize {
     fn is_empty(&self) -> bool {
@@ -54,6 +56,45 @@ fn scroll_up() {
     assert_eq!(grid[Line(9)].occ, 0);
 }
 
+// Only scrolling the top of the screen counts scrolled lines.
+#[test]
+fn scrolled_lines() {
+    let mut grid = Grid::<usize>::new(10, 1, 5);
+
+    grid.scroll_up::<usize>(&(Line(0)..Line(10)), 2);
+    grid.scroll_up::<usize>(&(Line(2)..Line(10)), 1);
+    grid.scroll_down::<usize>(&(Line(0)..Line(10)), 1);
+    assert_eq!(grid.scrolled_lines(), 2);
+
+    grid.scroll_up::<usize>(&(Line(0)..Line(10)), 8);
+    assert_eq!(grid.scrolled_lines(), 10);
+}
+
+// Resizing and clearing keep the output position of the cursor's line.
+#[test]
+fn scrolled_lines_resize_and_clear() {
+    let mut grid = Grid::<usize>::new(10, 1, 5);
+    grid.cursor.point.line = Line(9);
+    grid.scroll_up::<usize>(&(Line(0)..Line(10)), 3);
+    assert_eq!(grid.scrolled_lines(), 3);
+
+    // Lines pulled from history are no longer scrolled.
+    grid.resize::<usize>(true, 12, 1);
+    assert_eq!(grid.cursor.point.line, Line(11));
+    assert_eq!(grid.scrolled_lines(), 1);
+
+    grid.resize::<usize>(true, 8, 1);
+    assert_eq!(grid.cursor.point.line, Line(7));
+    assert_eq!(grid.scrolled_lines(), 5);
+
+    grid.clear_history();
+    assert_eq!(grid.scrolled_lines(), 5);
+
+    // Resetting moves everything above the screen.
+    grid.reset::<usize>();
+    assert_eq!(grid.scrolled_lines(), 13);
+}
+
 // Scroll down moves lines downward.
 #[test]
 fn scroll_down() {
@@ -348,6 +389,104 @@ fn shrink_reflow_disabled() {
     assert_eq!(grid[Line(0)][Column(1)], cell('2'));
 }
 
+#[test]
+fn shrink_reflow_marks() {
+    let mut grid = Grid::<Cell>::new(1, 5, 2);
+    for (i, c) in "$ abc".chars().enumerate() {
+        grid[Line(0)][Column(i)] = cell(c);
+    }
+    grid[Line(0)].push_mark(Column(0), SemanticMark::PromptStart);
+    grid[Line(0)].push_mark(Column(2), SemanticMark::CommandStart);
+
+    grid.resize(true, 1, 2);
+
+    assert_eq!(grid[Line(-2)].marks(), &[(Column(0), SemanticMark::PromptStart)]);
+    assert_eq!(grid[Line(-1)].marks(), &[(Column(0), SemanticMark::CommandStart)]);
+    assert!(grid[Line(0)].marks().is_empty());
+}
+
+#[test]
+fn grow_reflow_marks() {
+    let mut grid = Grid::<Cell>::new(2, 2, 0);
+    grid[Line(0)][Column(0)] = cell('$');
+    grid[Line(0)][Column(1)] = wrap_cell(' ');
+    grid[Line(1)][Column(0)] = cell('a');
+    grid[Line(0)].push_mark(Column(0), SemanticMark::PromptStart);
+    grid[Line(1)].push_mark(Column(0), SemanticMark::CommandStart);
+
+    grid.resize(true, 2, 3);
+
+    let expected =
+        [(Column(0), SemanticMark::PromptStart), (Column(2), SemanticMark::CommandStart)];
+    assert_eq!(grid[Line(0)].marks(), &expected);
+    assert!(grid[Line(1)].marks().is_empty());
+}
+
+#[test]
+fn marks_rotate_with_scroll() {
+    let mut grid = Grid::<Cell>::new(3, 2, 10);
+    grid[Line(2)].push_mark(Column(0), SemanticMark::PromptStart);
+
+    grid.scroll_up::<Color>(&(Line(0)..Line(3)), 2);
+
+    assert_eq!(grid[Line(0)].marks(), &[(Column(0), SemanticMark::PromptStart)]);
+    assert!(grid[Line(2)].marks().is_empty());
+}
+
+#[test]
+fn marks_are_boxed() {
+    // Rows without marks should only grow by a single pointer.
+    let row_size = std::mem::size_of::<Row<Cell>>();
+    assert_eq!(row_size, std::mem::size_of::<Vec<Cell>>() + 2 * std::mem::size_of::<usize>());
+
+    let mut row = Row::<Cell>::new(5);
+    row.push_mark(Column(3), SemanticMark::PromptStart);
+    assert_eq!(row.split_off_marks(2), vec![(Column(1), SemanticMark::PromptStart)]);
+    assert!(row.marks().is_empty());
+}
+
+#[test]
+fn scroll_up_archive() {
+    let mut grid = Grid::<Cell>::new(2, 1, 2);
+    grid.update_archive(3, false);
+
+    for i in 0..8 {
+        grid[Line(1)][Column(0)] = cell(char::from_digit(i, 10).unwrap());
+        grid.scroll_up::<Color>(&(Line(0)..Line(2)), 1);
+    }
+
+    // Two lines in history, three in the archive and the oldest lines discarded.
+    assert_eq!(grid.total_lines(), 7);
+    assert_eq!(grid.archived_lines(), 3);
+    let lines: String = (-5..2).map(|line| grid[Line(line)][Column(0)].c).collect();
+    assert_eq!(lines, "234567 ");
+
+    grid.scroll_display(Scroll::Top);
+    assert_eq!(grid.display_offset(), 5);
+
+    grid.clear_history();
+    assert_eq!(grid.total_lines(), 2);
+}
+
+#[test]
+fn shrink_reflow_archive() {
+    let mut grid = Grid::<Cell>::new(1, 4, 1);
+    grid.update_archive(10, false);
+    for (i, c) in "abcd".chars().enumerate() {
+        grid[Line(0)][Column(i)] = cell(c);
+    }
+
+    grid.resize(true, 1, 1);
+
+    // Wrapped lines exceeding the history are archived without further reflow.
+    assert_eq!(grid.archived_lines(), 2);
+    let lines: String = (-3..1).map(|line| grid[Line(line)][Column(0)].c).collect();
+    assert_eq!(lines, "abcd");
+
+    grid.resize(true, 1, 2);
+    assert_eq!(grid[Line(-3)][..], [wrap_cell('a'), cell(' ')]);
+}
+
 // https://github.com/rust-lang/rust-clippy/pull/6375
 #[allow(clippy::all)]
 fn cell(c: char) -> Cell {
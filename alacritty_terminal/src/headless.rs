@@ -0,0 +1,496 @@
+//! Terminal driver without a window.
+//!
+//! The [`Headless`] driver runs a child process in a PTY and feeds its output through the regular
+//! parser, which makes it possible to interact with terminal applications programmatically.
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use alacritty_terminal::headless::Headless;
+//! use alacritty_terminal::term::search::RegexSearch;
+//! use alacritty_terminal::term::Config;
+//! use alacritty_terminal::tty::{Options, Shell};
+//!
+//! let shell = Shell::new("vim".into(), Vec::new());
+//! let options = Options { shell: Some(shell), ..Default::default() };
+//! let mut headless = Headless::new(&options, Config::default(), 80, 24).unwrap();
+//!
+//! let mut regex = RegexSearch::new("~").unwrap();
+//! headless.expect(&mut regex, Duration::from_secs(5)).unwrap();
+//! headless.send_line(":q");
+//! ```
+
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::event::{Event, EventListener, Notify, OnResize, WindowSize};
+use crate::event_loop::{EventLoop, Msg, Notifier, State};
+use crate::grid::Dimensions;
+use crate::index::{Column, Direction, Line, Point, Side};
+use crate::sync::FairMutex;
+use crate::term::cell::{Cell, Flags};
+use crate::term::search::RegexSearch;
+use crate::term::{Config, Term};
+use crate::tty;
+
+/// Width of a cell reported to the child process, in pixels.
+const CELL_WIDTH: u16 = 10;
+
+/// Height of a cell reported to the child process, in pixels.
+const CELL_HEIGHT: u16 = 20;
+
+/// Errors from the headless driver.
+#[derive(Debug)]
+pub enum Error {
+    /// Unable to create the PTY or its event loop.
+    Io(io::Error),
+
+    /// The deadline passed without a match.
+    Timeout,
+
+    /// The child process exited without a match.
+    Exited,
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            Error::Timeout | Error::Exited => None,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "headless terminal I/O error: {err}"),
+            Error::Timeout => f.write_str("timed out waiting for a match"),
+            Error::Exited => f.write_str("child process exited before a match was found"),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(val: io::Error) -> Self {
+        Error::Io(val)
+    }
+}
+
+/// Event listener storing all terminal events in a queue.
+#[derive(Clone, Default)]
+pub struct EventQueue {
+    inner: Arc<(Mutex<VecDeque<Event>>, Condvar)>,
+}
+
+impl EventQueue {
+    /// Take all queued events.
+    fn drain(&self) -> VecDeque<Event> {
+        let (events, _) = &*self.inner;
+        std::mem::take(&mut *events.lock().unwrap())
+    }
+
+    /// Block until an event is queued or the deadline has passed.
+    fn wait(&self, deadline: Instant) {
+        let (events, condvar) = &*self.inner;
+        let events = events.lock().unwrap();
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        let _ = condvar.wait_timeout_while(events, timeout, |events| events.is_empty());
+    }
+}
+
+impl EventListener for EventQueue {
+    fn send_event(&self, event: Event) {
+        let (events, condvar) = &*self.inner;
+        events.lock().unwrap().push_back(event);
+        condvar.notify_all();
+    }
+}
+
+/// Visible terminal content at a point in time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Cells of all visible lines, starting at the top of the screen.
+    pub lines: Vec<Vec<Cell>>,
+
+    /// Position of the terminal cursor.
+    pub cursor: Point,
+}
+
+impl Display for Snapshot {
+    /// Write the visible text, without trailing whitespace in each line.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, line) in self.lines.iter().enumerate() {
+            let mut text = String::new();
+            let cells = line.iter().filter(|cell| {
+                !cell.flags.intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER)
+            });
+            for cell in cells {
+                text.push(cell.c);
+                text.extend(cell.zerowidth().into_iter().flatten());
+            }
+
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+            f.write_str(text.trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Terminal running a child process without a window.
+pub struct Headless {
+    terminal: Arc<FairMutex<Term<EventQueue>>>,
+    notifier: Notifier,
+    event_queue: EventQueue,
+    io_thread: Option<JoinHandle<(EventLoop<tty::Pty, EventQueue>, State)>>,
+
+    /// Events received from the terminal, excluding wakeups.
+    events: Vec<Event>,
+
+    /// Current terminal dimensions.
+    size: WindowSize,
+
+    /// Start of the next search.
+    ///
+    /// The line is offset by the lines scrolled off the screen, so it keeps pointing to the same
+    /// content while the terminal scrolls.
+    search_origin: (usize, Column),
+
+    /// Whether the child process has exited.
+    exited: bool,
+
+    /// Exit code reported by the child process.
+    exit_code: Option<i32>,
+}
+
+impl Headless {
+    /// Spawn a child process in a new terminal.
+    pub fn new(
+        pty_options: &tty::Options,
+        config: Config,
+        columns: u16,
+        screen_lines: u16,
+    ) -> Result<Self, Error> {
+        let size = WindowSize {
+            num_cols: columns,
+            num_lines: screen_lines,
+            cell_width: CELL_WIDTH,
+            cell_height: CELL_HEIGHT,
+        };
+
+        let event_queue = EventQueue::default();
+        let mut terminal = Term::new(config, &size, event_queue.clone());
+        terminal.set_cell_size(CELL_WIDTH as usize, CELL_HEIGHT as usize);
+        let terminal = Arc::new(FairMutex::new(terminal));
+
+        let pty = tty::new(pty_options, size, 0)?;
+        let event_loop =
+            EventLoop::new(terminal.clone(), event_queue.clone(), pty, pty_options.hold, false)?;
+        let notifier = Notifier(event_loop.channel());
+        let io_thread = Some(event_loop.spawn());
+
+        Ok(Self {
+            terminal,
+            notifier,
+            event_queue,
+            io_thread,
+            size,
+            events: Default::default(),
+            search_origin: Default::default(),
+            exited: Default::default(),
+            exit_code: Default::default(),
+        })
+    }
+
+    /// Terminal state, for inspection beyond the provided helpers.
+    pub fn terminal(&self) -> &Arc<FairMutex<Term<EventQueue>>> {
+        &self.terminal
+    }
+
+    /// Write bytes to the child process.
+    pub fn send<B: Into<Cow<'static, [u8]>>>(&self, input: B) {
+        self.notifier.notify(input);
+    }
+
+    /// Write a line of text to the child process, followed by a carriage return.
+    pub fn send_line(&self, text: &str) {
+        self.send(format!("{text}\r").into_bytes());
+    }
+
+    /// Change the terminal dimensions.
+    pub fn resize(&mut self, columns: u16, screen_lines: u16) {
+        self.size.num_cols = columns;
+        self.size.num_lines = screen_lines;
+
+        self.terminal.lock().resize(self.size);
+        self.notifier.on_resize(self.size);
+    }
+
+    /// Wait until text matching `regex` appears in the terminal.
+    ///
+    /// Matches are searched in the scrollback history and on the screen. Every successful call
+    /// only considers text after the previous match, so the same regex can be used to wait for
+    /// multiple occurrences of a pattern.
+    ///
+    /// Returns the matched text.
+    pub fn expect(&mut self, regex: &mut RegexSearch, timeout: Duration) -> Result<String, Error> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            self.process_events();
+
+            if let Some(text) = self.find_next(regex) {
+                return Ok(text);
+            } else if self.exited {
+                return Err(Error::Exited);
+            } else if Instant::now() >= deadline {
+                return Err(Error::Timeout);
+            }
+
+            self.event_queue.wait(deadline);
+        }
+    }
+
+    /// Wait until the child process has exited.
+    ///
+    /// Returns the exit code, if the child reported one.
+    pub fn wait_exit(&mut self, timeout: Duration) -> Result<Option<i32>, Error> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            self.process_events();
+
+            if self.exited {
+                return Ok(self.exit_code);
+            } else if Instant::now() >= deadline {
+                return Err(Error::Timeout);
+            }
+
+            self.event_queue.wait(deadline);
+        }
+    }
+
+    /// Visible terminal content.
+    pub fn snapshot(&mut self) -> Snapshot {
+        self.process_events();
+
+        let terminal = self.terminal.lock();
+        let grid = terminal.grid();
+        let lines =
+            (0..grid.screen_lines()).map(|line| grid[Line(line as i32)][..].to_vec()).collect();
+
+        Snapshot { lines, cursor: grid.cursor.point }
+    }
+
+    /// Visible terminal text, without trailing whitespace in each line.
+    pub fn screen_text(&mut self) -> String {
+        self.snapshot().to_string()
+    }
+
+    /// Take all events received from the terminal since the last call.
+    ///
+    /// [`Event::Wakeup`] is not stored, since it is sent after every update.
+    pub fn take_events(&mut self) -> Vec<Event> {
+        self.process_events();
+        std::mem::take(&mut self.events)
+    }
+
+    /// Handle queued terminal events.
+    ///
+    /// This answers requests which would otherwise be handled by the window.
+    fn process_events(&mut self) {
+        // Check the I/O thread before draining, to make sure all its events are processed.
+        if self.io_thread.as_ref().map_or(true, |io_thread| io_thread.is_finished()) {
+            self.exited = true;
+        }
+
+        for event in self.event_queue.drain() {
+            match &event {
+                Event::PtyWrite(text) => self.send(text.clone().into_bytes()),
+                Event::TextAreaSizeRequest(format) => self.send(format(self.size).into_bytes()),
+                Event::ColorRequest(index, format) => {
+                    let color = self.terminal.lock().colors()[*index];
+                    if let Some(color) = color {
+                        self.send(format(color).into_bytes());
+                    }
+                },
+                Event::ChildExit(code) => {
+                    self.exit_code = Some(*code);
+                    self.exited = true;
+                },
+                Event::Exit => self.exited = true,
+                Event::Wakeup => continue,
+                _ => (),
+            }
+
+            self.events.push(event);
+        }
+    }
+
+    /// Find the next match after the previous one.
+    fn find_next(&mut self, regex: &mut RegexSearch) -> Option<String> {
+        let terminal = self.terminal.lock();
+
+        // Convert the search origin to grid coordinates.
+        let scrolled_lines = terminal.grid().scrolled_lines() as i32;
+        let (line, column) = self.search_origin;
+        let mut origin = Point::new(Line(line as i32 - scrolled_lines), column);
+        if origin.line < terminal.topmost_line() {
+            // Lines were removed from the history since the last match.
+            origin = Point::new(terminal.topmost_line(), Column(0));
+        } else if origin.line > terminal.bottommost_line() {
+            // The last match ended at the bottom of the screen and nothing was written since.
+            return None;
+        }
+
+        let regex_match =
+            terminal.search_next(regex, origin, Direction::Right, Side::Left, None)?;
+
+        // Ignore matches wrapping around to the start of the history.
+        if *regex_match.start() < origin {
+            return None;
+        }
+
+        // Continue the next search after the end of this match.
+        let end = *regex_match.end();
+        let next = if end.column == terminal.last_column() {
+            Point::new(end.line + 1, Column(0))
+        } else {
+            Point::new(end.line, end.column + 1)
+        };
+        self.search_origin = ((next.line.0 + scrolled_lines) as usize, next.column);
+
+        Some(terminal.bounds_to_string(*regex_match.start(), *regex_match.end()))
+    }
+}
+
+impl Drop for Headless {
+    fn drop(&mut self) {
+        // Stop the event loop, which terminates the child process.
+        let _ = self.notifier.0.send(Msg::Shutdown);
+        if let Some(io_thread) = self.io_thread.take() {
+            let _ = io_thread.join();
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    use crate::tty::Shell;
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    fn spawn(script: &str) -> Headless {
+        spawn_with_config(script, Config::default())
+    }
+
+    fn spawn_with_config(script: &str, config: Config) -> Headless {
+        let shell = Shell::new("/bin/sh".into(), vec!["-c".into(), script.into()]);
+        let options = tty::Options { shell: Some(shell), ..Default::default() };
+        Headless::new(&options, config, 20, 5).unwrap()
+    }
+
+    #[test]
+    fn expect_output() {
+        let mut headless = spawn("printf 'one\\r\\ntwo\\r\\none'; sleep 10");
+
+        let mut regex = RegexSearch::new("one|two").unwrap();
+        assert_eq!(headless.expect(&mut regex, TIMEOUT).unwrap(), "one");
+        assert_eq!(headless.expect(&mut regex, TIMEOUT).unwrap(), "two");
+        assert_eq!(headless.expect(&mut regex, TIMEOUT).unwrap(), "one");
+
+        let timeout = Duration::from_millis(100);
+        assert!(matches!(headless.expect(&mut regex, timeout), Err(Error::Timeout)));
+    }
+
+    #[test]
+    fn expect_after_full_history() {
+        let config = Config { scrolling_history: 5, ..Config::default() };
+        let script =
+            "seq 1 20; printf 'first\\r\\n'; read line; printf 'x%d\\r\\n' 1 2 3 4 5 6; sleep 10";
+        let mut headless = spawn_with_config(script, config);
+
+        let mut regex = RegexSearch::new("first").unwrap();
+        headless.expect(&mut regex, TIMEOUT).unwrap();
+
+        // Scroll lines out of the full history before the next search.
+        headless.send_line("");
+
+        let mut regex = RegexSearch::new("x\\d").unwrap();
+        assert_eq!(headless.expect(&mut regex, TIMEOUT).unwrap(), "x1");
+        assert_eq!(headless.expect(&mut regex, TIMEOUT).unwrap(), "x2");
+    }
+
+    #[test]
+    fn expect_after_bottom_line() {
+        let config = Config { scrolling_history: 5, ..Config::default() };
+        let script = "seq 1 20; printf '%020d' 0; read line; printf 'after'; sleep 10";
+        let mut headless = spawn_with_config(script, config);
+
+        // Match the entire last line of the screen.
+        let mut regex = RegexSearch::new("0{20}").unwrap();
+        headless.expect(&mut regex, TIMEOUT).unwrap();
+        assert_eq!(headless.terminal().lock().grid().cursor.point.line, Line(4));
+
+        headless.send_line("");
+
+        let mut regex = RegexSearch::new("after").unwrap();
+        assert_eq!(headless.expect(&mut regex, TIMEOUT).unwrap(), "after");
+    }
+
+    #[test]
+    fn send_input() {
+        let mut headless = spawn("read line; printf \"got:$line\"; sleep 10");
+
+        headless.send_line("hello");
+
+        let mut regex = RegexSearch::new("got:\\w+").unwrap();
+        assert_eq!(headless.expect(&mut regex, TIMEOUT).unwrap(), "got:hello");
+    }
+
+    #[test]
+    fn snapshot() {
+        let mut headless = spawn("printf '\\033[1mbold\\033[0m\\r\\nplain'; sleep 10");
+
+        let mut regex = RegexSearch::new("plain").unwrap();
+        headless.expect(&mut regex, TIMEOUT).unwrap();
+
+        let snapshot = headless.snapshot();
+        assert_eq!(snapshot.to_string(), "bold\nplain\n\n\n");
+        assert!(snapshot.lines[0][0].flags.contains(Flags::BOLD));
+        assert!(!snapshot.lines[1][0].flags.contains(Flags::BOLD));
+        assert_eq!(snapshot.cursor, Point::new(Line(1), Column(5)));
+    }
+
+    #[test]
+    fn resize() {
+        let mut headless = spawn("read line; stty size; sleep 10");
+
+        headless.resize(30, 7);
+        headless.send_line("");
+
+        let mut regex = RegexSearch::new("7 30").unwrap();
+        headless.expect(&mut regex, TIMEOUT).unwrap();
+        assert_eq!(headless.terminal().lock().columns(), 30);
+    }
+
+    #[test]
+    fn child_exit() {
+        let mut headless = spawn("exit 3");
+
+        assert_eq!(headless.wait_exit(TIMEOUT).unwrap(), Some(3));
+
+        let mut regex = RegexSearch::new("never").unwrap();
+        assert!(matches!(headless.expect(&mut regex, TIMEOUT), Err(Error::Exited)));
+    }
+}
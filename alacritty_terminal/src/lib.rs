@@ -4,10 +4,14 @@
 #![deny(clippy::all, clippy::if_not_else, clippy::enum_glob_use)]
 #![cfg_attr(clippy, deny(warnings))]
 
+pub mod asciicast;
 pub mod event;
 pub mod event_loop;
+pub mod graphics;
 pub mod grid;
+pub mod headless;
 pub mod index;
+pub mod parser;
 pub mod selection;
 pub mod sync;
 pub mod term;
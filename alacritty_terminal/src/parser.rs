@@ -0,0 +1,872 @@
+//! Escape sequence parser extending the VTE parser.
+//!
+//! Some escape sequences are not supported by the VTE parser, but are still required to support
+//! features like shell integration or inline images. These sequences are intercepted before they
+//! reach the VTE parser and dispatched to the [`ExtendedHandler`] directly.
+
+use std::time::Duration;
+use std::{mem, str};
+
+use base64::engine::general_purpose::STANDARD as Base64;
+use base64::Engine;
+use log::debug;
+
+use crate::graphics::{kitty, sixel, GraphicData};
+use crate::term::notification::{Notification, Urgency};
+use crate::term::prompt::SemanticMark;
+use crate::term::working_directory::WorkingDirectory;
+use crate::vte::ansi::{self, Handler, NamedPrivateMode, StdSyncHandler, Timeout};
+
+/// Maximum number of bytes stored for an intercepted OSC.
+const MAX_SEQUENCE_LEN: usize = 4096;
+
+/// Maximum number of bytes stored for an APC or DCS.
+///
+/// This is significantly larger than the OSC limit, since these sequences are used to transmit
+/// images.
+const MAX_IMAGE_SEQUENCE_LEN: usize = 16 * 1024 * 1024;
+
+/// Maximum number of digits in an OSC identifier.
+const MAX_OSC_NUMBER_LEN: usize = 4;
+
+/// Maximum number of bytes in the parameters of an intercepted DCS.
+const MAX_DCS_PARAMS_LEN: usize = 16;
+
+/// OSC identifiers which are handled by this parser instead of VTE.
+const INTERCEPTED_OSCS: &[&[u8]] = &[b"7", b"9", b"99", b"133", b"777"];
+
+/// CSI sequences which are handled by this parser instead of VTE.
+const INTERCEPTED_CSIS: &[&[u8]] = &[COLOR_SCHEME_QUERY];
+
+/// Color scheme query (`CSI ? 996 n`).
+const COLOR_SCHEME_QUERY: &[u8] = b"?996n";
+
+/// Maximum time before a synchronized update is aborted.
+const SYNC_UPDATE_TIMEOUT: Duration = Duration::from_millis(150);
+
+/// Maximum number of bytes read in one synchronized update (2MiB).
+const SYNC_BUFFER_SIZE: usize = 0x20_0000;
+
+/// BSU CSI sequence for beginning or extending synchronized updates.
+const BSU_CSI: &[u8] = b"\x1b[?2026h";
+
+/// ESU CSI sequence for terminating synchronized updates.
+const ESU_CSI: &[u8] = b"\x1b[?2026l";
+
+/// Escape character.
+const ESC: u8 = 0x1b;
+
+/// Bell character.
+const BEL: u8 = 0x07;
+
+/// Cancel character.
+const CAN: u8 = 0x18;
+
+/// Substitute character.
+const SUB: u8 = 0x1a;
+
+/// Handler for escape sequences not supported by the VTE parser.
+pub trait ExtendedHandler: Handler {
+    /// OSC 133 semantic prompt mark.
+    fn semantic_mark(&mut self, _mark: SemanticMark) {}
+
+    /// Kitty graphics protocol command (`APC G`).
+    fn kitty_graphics(&mut self, _command: kitty::Command) {}
+
+    /// Decoded sixel image (`DCS q`).
+    fn sixel_graphics(&mut self, _graphic: GraphicData) {}
+
+    /// Desktop notification (`OSC 9`, `OSC 777` or `OSC 99`).
+    fn desktop_notification(&mut self, _notification: Notification) {}
+
+    /// Working directory reported by the shell (`OSC 7`).
+    fn set_working_directory(&mut self, _directory: WorkingDirectory) {}
+
+    /// Report the color scheme preference (`CSI ? 996 n`).
+    fn report_color_scheme(&mut self) {}
+}
+
+/// Parser state for intercepted escape sequences.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+enum State {
+    /// No escape sequence is being intercepted.
+    #[default]
+    Ground,
+
+    /// Escape character was received.
+    Escape,
+
+    /// OSC identifier is being received.
+    OscNumber,
+
+    /// CSI matching an intercepted sequence is being received.
+    Csi,
+
+    /// DCS parameters are being received.
+    DcsParams,
+
+    /// Payload of an intercepted string sequence is being received.
+    String(StringKind),
+
+    /// Escape was received inside an intercepted string sequence.
+    StringEscape(StringKind),
+}
+
+/// Type of an intercepted string sequence.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum StringKind {
+    /// Operating system command.
+    Osc,
+
+    /// Application program command.
+    Apc,
+
+    /// Device control string.
+    Dcs,
+}
+
+impl StringKind {
+    /// Maximum number of bytes stored for the sequence.
+    fn max_len(self) -> usize {
+        match self {
+            Self::Osc => MAX_SEQUENCE_LEN,
+            Self::Apc | Self::Dcs => MAX_IMAGE_SEQUENCE_LEN,
+        }
+    }
+}
+
+/// Escape sequence processor.
+///
+/// This wraps [`ansi::Processor`], intercepting all escape sequences handled by
+/// [`ExtendedHandler`].
+///
+/// Synchronized updates are buffered here instead of inside VTE, so intercepted sequences are
+/// processed in order with the rest of the update once it ends.
+#[derive(Default)]
+pub struct Processor {
+    inner: ansi::Processor,
+    state: State,
+    buffer: Vec<u8>,
+
+    /// Timeout of the active synchronized update.
+    sync_timeout: StdSyncHandler,
+
+    /// Bytes read during the synchronized update.
+    sync_buffer: Vec<u8>,
+
+    /// Whether the synchronized bytes are currently being processed.
+    sync_flushing: bool,
+
+    /// Kitty notification which is split across multiple sequences.
+    pending_notification: Option<PendingNotification>,
+}
+
+/// Partially received kitty notification.
+#[derive(Debug)]
+struct PendingNotification {
+    id: Vec<u8>,
+    notification: Notification,
+}
+
+impl Processor {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Synchronized update timeout.
+    #[inline]
+    pub fn sync_timeout(&self) -> &StdSyncHandler {
+        &self.sync_timeout
+    }
+
+    /// End a synchronized update.
+    pub fn stop_sync<H: ExtendedHandler>(&mut self, handler: &mut H) {
+        // Process all synchronized bytes.
+        self.sync_timeout.clear_timeout();
+        self.sync_flushing = true;
+        let buffer = mem::take(&mut self.sync_buffer);
+        for &byte in &buffer {
+            self.advance(handler, byte);
+        }
+        self.sync_flushing = false;
+
+        // Reuse the allocation for the next synchronized update.
+        self.sync_buffer = buffer;
+        self.sync_buffer.clear();
+
+        // Report that update ended, since we could end due to timeout.
+        handler.unset_private_mode(NamedPrivateMode::SyncUpdate.into());
+    }
+
+    /// Number of bytes in the synchronization buffer.
+    #[inline]
+    pub fn sync_bytes_count(&self) -> usize {
+        self.sync_buffer.len()
+    }
+
+    /// Process a new byte from the PTY.
+    #[inline]
+    pub fn advance<H: ExtendedHandler>(&mut self, handler: &mut H, byte: u8) {
+        if self.sync_timeout.pending_timeout() {
+            self.advance_sync(handler, byte);
+            return;
+        }
+
+        match self.state {
+            State::Ground => self.advance_ground(handler, byte),
+            State::Escape => self.advance_escape(handler, byte),
+            State::OscNumber => self.advance_osc_number(handler, byte),
+            State::Csi => self.advance_csi(handler, byte),
+            State::DcsParams => self.advance_dcs_params(handler, byte),
+            State::String(kind) => self.advance_string(handler, kind, byte),
+            State::StringEscape(kind) => self.advance_string_escape(handler, kind, byte),
+        }
+    }
+
+    #[inline]
+    fn advance_ground<H: Handler>(&mut self, handler: &mut H, byte: u8) {
+        self.inner.advance(handler, byte);
+
+        if byte == ESC {
+            self.state = State::Escape;
+        }
+
+        // Take over synchronized updates started by VTE.
+        if self.inner.sync_timeout().pending_timeout() {
+            self.start_sync(handler);
+        }
+    }
+
+    /// Begin buffering a synchronized update.
+    #[cold]
+    fn start_sync<H: Handler>(&mut self, handler: &mut H) {
+        // Stop VTE from buffering, its synchronization buffer is still empty at this point.
+        self.inner.stop_sync(handler);
+
+        // Synchronized bytes which are being processed can't extend the update.
+        if !self.sync_flushing {
+            handler.set_private_mode(NamedPrivateMode::SyncUpdate.into());
+            self.sync_timeout.set_timeout(SYNC_UPDATE_TIMEOUT);
+        }
+    }
+
+    /// Process a new byte during a synchronized update.
+    #[cold]
+    fn advance_sync<H: ExtendedHandler>(&mut self, handler: &mut H, byte: u8) {
+        self.sync_buffer.push(byte);
+
+        // Check for extension/termination of the synchronized update.
+        let len = self.sync_buffer.len();
+        let end = &self.sync_buffer[len.saturating_sub(BSU_CSI.len())..];
+        if end == BSU_CSI {
+            self.sync_timeout.set_timeout(SYNC_UPDATE_TIMEOUT);
+        } else if end == ESU_CSI || len >= SYNC_BUFFER_SIZE - 1 {
+            self.stop_sync(handler);
+        }
+    }
+
+    #[inline]
+    fn advance_escape<H: ExtendedHandler>(&mut self, handler: &mut H, byte: u8) {
+        self.buffer.clear();
+
+        match byte {
+            b']' => self.state = State::OscNumber,
+            b'[' => self.state = State::Csi,
+            b'_' => self.state = State::String(StringKind::Apc),
+            b'P' => self.state = State::DcsParams,
+            _ => {
+                self.state = State::Ground;
+                self.advance_ground(handler, byte);
+            },
+        }
+    }
+
+    #[cold]
+    fn advance_osc_number<H: ExtendedHandler>(&mut self, handler: &mut H, byte: u8) {
+        match byte {
+            b'0'..=b'9' if self.buffer.len() < MAX_OSC_NUMBER_LEN => self.buffer.push(byte),
+            b';' if INTERCEPTED_OSCS.contains(&self.buffer.as_slice()) => {
+                self.buffer.push(byte);
+                self.state = State::String(StringKind::Osc);
+            },
+            _ => self.restore_sequence(handler, b']', byte),
+        }
+    }
+
+    #[inline]
+    fn advance_csi<H: ExtendedHandler>(&mut self, handler: &mut H, byte: u8) {
+        // Most CSIs can't be intercepted, so hand them to VTE without buffering.
+        if self.buffer.is_empty() && INTERCEPTED_CSIS.iter().all(|csi| csi[0] != byte) {
+            self.restore_sequence(handler, b'[', byte);
+            return;
+        }
+
+        self.buffer.push(byte);
+
+        // Hand the sequence back to VTE as soon as it can't match an intercepted CSI.
+        match INTERCEPTED_CSIS.iter().find(|csi| csi.starts_with(&self.buffer)) {
+            Some(csi) if csi.len() == self.buffer.len() => {
+                self.state = State::Ground;
+                self.csi_dispatch(handler);
+            },
+            Some(_) => (),
+            None => {
+                self.buffer.pop();
+                self.restore_sequence(handler, b'[', byte);
+            },
+        }
+    }
+
+    #[cold]
+    fn advance_dcs_params<H: ExtendedHandler>(&mut self, handler: &mut H, byte: u8) {
+        match byte {
+            b'0'..=b'9' | b';' if self.buffer.len() < MAX_DCS_PARAMS_LEN => self.buffer.push(byte),
+            // Sixel images are the only DCS without intermediates we're interested in.
+            b'q' => {
+                self.buffer.push(byte);
+                self.state = State::String(StringKind::Dcs);
+            },
+            _ => self.restore_sequence(handler, b'P', byte),
+        }
+    }
+
+    /// Hand an escape sequence back to VTE, since we're not interested in it.
+    fn restore_sequence<H: ExtendedHandler>(&mut self, handler: &mut H, introducer: u8, byte: u8) {
+        self.inner.advance(handler, introducer);
+        for i in 0..self.buffer.len() {
+            self.inner.advance(handler, self.buffer[i]);
+        }
+
+        self.state = State::Ground;
+        self.advance_ground(handler, byte);
+    }
+
+    #[cold]
+    fn advance_string<H: ExtendedHandler>(&mut self, handler: &mut H, kind: StringKind, byte: u8) {
+        match byte {
+            // Only OSCs can be terminated by a bell.
+            BEL if kind == StringKind::Osc => {
+                self.state = State::Ground;
+                self.dispatch(handler, kind);
+            },
+            ESC => self.state = State::StringEscape(kind),
+            // Abort the sequence, letting VTE handle the control character.
+            CAN | SUB => {
+                self.state = State::Ground;
+                self.inner.advance(handler, byte);
+            },
+            _ if self.buffer.len() < kind.max_len() => self.buffer.push(byte),
+            _ => (),
+        }
+    }
+
+    #[cold]
+    fn advance_string_escape<H: ExtendedHandler>(
+        &mut self,
+        handler: &mut H,
+        kind: StringKind,
+        byte: u8,
+    ) {
+        self.state = State::Ground;
+        self.dispatch(handler, kind);
+
+        // Any escape other than the string terminator starts a new sequence.
+        if byte != b'\\' {
+            self.advance_ground(handler, ESC);
+            self.advance(handler, byte);
+        }
+    }
+
+    /// Terminate the escape already passed to VTE as a string terminator, which is ignored.
+    #[inline]
+    fn terminate_escape<H: Handler>(&mut self, handler: &mut H) {
+        self.inner.advance(handler, b'\\');
+    }
+
+    /// Dispatch an intercepted string sequence to the handler.
+    fn dispatch<H: ExtendedHandler>(&mut self, handler: &mut H, kind: StringKind) {
+        self.terminate_escape(handler);
+
+        if self.buffer.len() >= kind.max_len() {
+            debug!("[unhandled {:?}] sequence exceeds {} bytes", kind, kind.max_len());
+            return;
+        }
+
+        match kind {
+            StringKind::Osc => self.osc_dispatch(handler),
+            StringKind::Apc => self.apc_dispatch(handler),
+            StringKind::Dcs => self.dcs_dispatch(handler),
+        }
+    }
+
+    /// Dispatch an intercepted OSC to the handler.
+    fn osc_dispatch<H: ExtendedHandler>(&mut self, handler: &mut H) {
+        let (number, params) = split_param(&self.buffer);
+        match number {
+            b"133" => match parse_semantic_mark(params.split(|&b| b == b';')) {
+                Some(mark) => handler.semantic_mark(mark),
+                None => debug!("[unhandled osc] {:?}", String::from_utf8_lossy(&self.buffer)),
+            },
+            b"7" => match WorkingDirectory::from_uri(params) {
+                Some(directory) => handler.set_working_directory(directory),
+                None => debug!("[unhandled osc] {:?}", String::from_utf8_lossy(&self.buffer)),
+            },
+            b"9" => match parse_iterm_notification(params) {
+                Some(notification) => handler.desktop_notification(notification),
+                None => debug!("[unhandled osc] {:?}", String::from_utf8_lossy(&self.buffer)),
+            },
+            b"777" => match parse_urxvt_notification(params) {
+                Some(notification) => handler.desktop_notification(notification),
+                None => debug!("[unhandled osc] {:?}", String::from_utf8_lossy(&self.buffer)),
+            },
+            b"99" => {
+                let pending = &mut self.pending_notification;
+                if let Some(notification) = parse_kitty_notification(pending, params) {
+                    handler.desktop_notification(notification);
+                }
+            },
+            _ => debug!("[unhandled osc] {:?}", String::from_utf8_lossy(&self.buffer)),
+        }
+    }
+
+    /// Dispatch an intercepted CSI to the handler.
+    fn csi_dispatch<H: ExtendedHandler>(&mut self, handler: &mut H) {
+        self.terminate_escape(handler);
+
+        match self.buffer.as_slice() {
+            COLOR_SCHEME_QUERY => handler.report_color_scheme(),
+            _ => debug!("[unhandled csi] {:?}", String::from_utf8_lossy(&self.buffer)),
+        }
+    }
+
+    /// Dispatch an APC to the handler.
+    fn apc_dispatch<H: ExtendedHandler>(&mut self, handler: &mut H) {
+        match self.buffer.split_first() {
+            Some((b'G', command)) => match kitty::Command::parse(command) {
+                Some(command) => handler.kitty_graphics(command),
+                None => debug!("[unhandled apc] invalid graphics command"),
+            },
+            _ => debug!("[unhandled apc] {:?}", String::from_utf8_lossy(&self.buffer)),
+        }
+
+        // Release memory used for transmitting large images.
+        self.buffer.shrink_to(MAX_SEQUENCE_LEN);
+    }
+
+    /// Dispatch an intercepted DCS to the handler.
+    fn dcs_dispatch<H: ExtendedHandler>(&mut self, handler: &mut H) {
+        if let Some(end) = self.buffer.iter().position(|&b| b == b'q') {
+            let (params, data) = (&self.buffer[..end], &self.buffer[end + 1..]);
+            match sixel::decode(params, data) {
+                Ok(graphic) => handler.sixel_graphics(graphic),
+                Err(err) => debug!("[unhandled dcs] invalid sixel image: {}", err),
+            }
+        }
+
+        // Release memory used for transmitting large images.
+        self.buffer.shrink_to(MAX_SEQUENCE_LEN);
+    }
+}
+
+/// Parse the parameters of an OSC 133 sequence.
+fn parse_semantic_mark<'a>(mut params: impl Iterator<Item = &'a [u8]>) -> Option<SemanticMark> {
+    let mark = match params.next()? {
+        b"A" => SemanticMark::PromptStart,
+        b"B" => SemanticMark::CommandStart,
+        b"C" => SemanticMark::OutputStart,
+        b"D" => {
+            // Options are passed as `key=value` pairs, which are ignored.
+            let exit_code = params
+                .next()
+                .filter(|param| !param.contains(&b'='))
+                .and_then(|param| str::from_utf8(param).ok()?.parse().ok());
+            SemanticMark::CommandEnd(exit_code)
+        },
+        _ => return None,
+    };
+
+    Some(mark)
+}
+
+/// Split the first parameter from a sequence's parameters.
+fn split_param(params: &[u8]) -> (&[u8], &[u8]) {
+    match params.iter().position(|&b| b == b';') {
+        Some(index) => (&params[..index], &params[index + 1..]),
+        None => (params, &[]),
+    }
+}
+
+/// Parse the parameters of an iTerm2 notification (`OSC 9 ; body`).
+fn parse_iterm_notification(params: &[u8]) -> Option<Notification> {
+    // ConEmu uses OSC 9 with a numeric first parameter for unrelated features.
+    let (first, _) = split_param(params);
+    if params.is_empty() || first.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+
+    let body = String::from_utf8_lossy(params).into_owned();
+    Some(Notification { body, ..Default::default() })
+}
+
+/// Parse the parameters of an urxvt notification (`OSC 777 ; notify ; title ; body`).
+fn parse_urxvt_notification(params: &[u8]) -> Option<Notification> {
+    let (kind, params) = split_param(params);
+    if kind != b"notify" {
+        return None;
+    }
+
+    let (title, body) = split_param(params);
+    let title = String::from_utf8_lossy(title).into_owned();
+    let body = String::from_utf8_lossy(body).into_owned();
+    Some(Notification { title, body, ..Default::default() })
+}
+
+/// Parse the parameters of a kitty notification (`OSC 99 ; metadata ; payload`).
+///
+/// Notifications can be split across multiple sequences, so this only returns the notification
+/// once its last chunk was received.
+fn parse_kitty_notification(
+    pending: &mut Option<PendingNotification>,
+    params: &[u8],
+) -> Option<Notification> {
+    let (metadata, payload) = split_param(params);
+
+    let mut id: &[u8] = b"";
+    let mut done = true;
+    let mut base64 = false;
+    let mut payload_type: &[u8] = b"title";
+    let mut urgency = None;
+    for option in metadata.split(|&b| b == b':') {
+        let (key, value) = match option.iter().position(|&b| b == b'=') {
+            Some(index) => (&option[..index], &option[index + 1..]),
+            None => continue,
+        };
+
+        match key {
+            b"i" => id = value,
+            b"d" => done = value != b"0",
+            b"e" => base64 = value == b"1",
+            b"p" => payload_type = value,
+            b"u" => {
+                urgency = match value {
+                    b"0" => Some(Urgency::Low),
+                    b"1" => Some(Urgency::Normal),
+                    b"2" => Some(Urgency::Critical),
+                    _ => None,
+                }
+            },
+            _ => (),
+        }
+    }
+
+    // Queries, icons and buttons are not supported.
+    if payload_type != b"title" && payload_type != b"body" {
+        debug!("[unhandled osc] kitty notification payload {:?}", payload_type);
+        return None;
+    }
+
+    let text = if base64 {
+        match Base64.decode(payload) {
+            Ok(text) => String::from_utf8_lossy(&text).into_owned(),
+            Err(err) => {
+                debug!("[unhandled osc] invalid kitty notification payload: {}", err);
+                return None;
+            },
+        }
+    } else {
+        String::from_utf8_lossy(payload).into_owned()
+    };
+
+    // Continue the pending notification, unless a different one is started.
+    let mut notification = match pending.take() {
+        Some(pending) if pending.id == id => pending.notification,
+        _ => Notification::default(),
+    };
+
+    if payload_type == b"title" {
+        notification.title.push_str(&text);
+    } else {
+        notification.body.push_str(&text);
+    }
+    if let Some(urgency) = urgency {
+        notification.urgency = urgency;
+    }
+
+    // Limit the size of notifications split across many sequences.
+    if notification.title.len() + notification.body.len() > MAX_SEQUENCE_LEN {
+        debug!("[unhandled osc] kitty notification exceeds {} bytes", MAX_SEQUENCE_LEN);
+        return None;
+    }
+
+    if done {
+        Some(notification)
+    } else {
+        *pending = Some(PendingNotification { id: id.to_vec(), notification });
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::path::PathBuf;
+
+    #[derive(Default)]
+    struct MockHandler {
+        marks: Vec<SemanticMark>,
+        mark_positions: Vec<usize>,
+        graphics: Vec<kitty::Command>,
+        sixels: Vec<GraphicData>,
+        notifications: Vec<Notification>,
+        directories: Vec<WorkingDirectory>,
+        color_scheme_reports: usize,
+        chars: String,
+        bells: usize,
+        titles: Vec<Option<String>>,
+    }
+
+    impl Handler for MockHandler {
+        fn input(&mut self, c: char) {
+            self.chars.push(c);
+        }
+
+        fn bell(&mut self) {
+            self.bells += 1;
+        }
+
+        fn set_title(&mut self, title: Option<String>) {
+            self.titles.push(title);
+        }
+    }
+
+    impl ExtendedHandler for MockHandler {
+        fn semantic_mark(&mut self, mark: SemanticMark) {
+            self.marks.push(mark);
+            self.mark_positions.push(self.chars.len());
+        }
+
+        fn kitty_graphics(&mut self, command: kitty::Command) {
+            self.graphics.push(command);
+        }
+
+        fn sixel_graphics(&mut self, graphic: GraphicData) {
+            self.sixels.push(graphic);
+        }
+
+        fn desktop_notification(&mut self, notification: Notification) {
+            self.notifications.push(notification);
+        }
+
+        fn set_working_directory(&mut self, directory: WorkingDirectory) {
+            self.directories.push(directory);
+        }
+
+        fn report_color_scheme(&mut self) {
+            self.color_scheme_reports += 1;
+        }
+    }
+
+    fn parse(bytes: &[u8]) -> MockHandler {
+        let mut handler = MockHandler::default();
+        let mut parser = Processor::new();
+        for byte in bytes {
+            parser.advance(&mut handler, *byte);
+        }
+        handler
+    }
+
+    #[test]
+    fn semantic_marks() {
+        let handler = parse(b"\x1b]133;A\x07\x1b]133;B\x1b\\\x1b]133;C\x07\x1b]133;D;1\x07");
+
+        let expected = vec![
+            SemanticMark::PromptStart,
+            SemanticMark::CommandStart,
+            SemanticMark::OutputStart,
+            SemanticMark::CommandEnd(Some(1)),
+        ];
+        assert_eq!(handler.marks, expected);
+        assert_eq!(handler.bells, 0);
+    }
+
+    #[test]
+    fn semantic_mark_options() {
+        let handler = parse(b"\x1b]133;A;cl=m\x07\x1b]133;D;aid=1\x07\x1b]133;D\x07");
+
+        let expected = vec![
+            SemanticMark::PromptStart,
+            SemanticMark::CommandEnd(None),
+            SemanticMark::CommandEnd(None),
+        ];
+        assert_eq!(handler.marks, expected);
+    }
+
+    #[test]
+    fn passthrough_other_oscs() {
+        let handler = parse(b"\x1b]2;title\x07\x1b]1337;x\x07\x1b]13;x\x07\x07");
+
+        assert_eq!(handler.titles, vec![Some("title".into())]);
+        assert_eq!(handler.bells, 1);
+        assert!(handler.marks.is_empty());
+    }
+
+    #[test]
+    fn escape_terminates_intercepted_osc() {
+        let handler = parse(b"\x1b]133;A\x1b]2;title\x07");
+
+        assert_eq!(handler.marks, vec![SemanticMark::PromptStart]);
+        assert_eq!(handler.titles, vec![Some("title".into())]);
+    }
+
+    #[test]
+    fn cancel_intercepted_osc() {
+        let handler = parse(b"\x1b]133;A\x18\x07");
+
+        assert!(handler.marks.is_empty());
+        assert_eq!(handler.bells, 1);
+    }
+
+    #[test]
+    fn kitty_graphics() {
+        let handler = parse(b"\x1b_Ga=T,i=1;AAAA\x1b\\x\x1b_Gi=2;\x07\x1b\\");
+
+        let ids: Vec<_> = handler.graphics.iter().map(|command| command.image_id).collect();
+        assert_eq!(ids, vec![1, 2]);
+        assert_eq!(handler.graphics[0].payload, b"AAAA");
+        assert_eq!(handler.graphics[1].payload, b"\x07");
+        assert_eq!(handler.chars, "x");
+        assert_eq!(handler.bells, 0);
+    }
+
+    #[test]
+    fn ignore_other_apcs() {
+        let handler = parse(b"\x1b_hello\x1b\\x\x1b_G\x18y");
+
+        assert!(handler.graphics.is_empty());
+        assert_eq!(handler.chars, "xy");
+    }
+
+    #[test]
+    fn sixel_graphics() {
+        let handler = parse(b"\x1bP0;1q#0;2;100;0;0~~\x1b\\x\x1bPq!3N\x1b\\");
+
+        let sizes: Vec<_> =
+            handler.sixels.iter().map(|sixel| (sixel.width, sixel.height)).collect();
+        assert_eq!(sizes, vec![(2, 6), (3, 4)]);
+        assert_eq!(handler.chars, "x");
+    }
+
+    #[test]
+    fn passthrough_other_dcss() {
+        let handler = parse(b"\x1bP+q544e\x1b\\x\x1bP=1s\x1b\\y\x1bP1$r\x18z");
+
+        assert!(handler.sixels.is_empty());
+        assert_eq!(handler.chars, "xyz");
+    }
+
+    #[test]
+    fn iterm_and_urxvt_notifications() {
+        let handler = parse(b"\x1b]9;Done; 2 errors\x07\x1b]777;notify;Build;Done\x1b\\");
+
+        assert_eq!(handler.notifications, vec![
+            Notification { body: "Done; 2 errors".into(), ..Default::default() },
+            Notification { title: "Build".into(), body: "Done".into(), ..Default::default() },
+        ]);
+        assert_eq!(handler.bells, 0);
+    }
+
+    #[test]
+    fn ignore_conemu_osc9() {
+        let handler = parse(b"\x1b]9;4;1;50\x07\x1b]777;preexec\x07x");
+
+        assert!(handler.notifications.is_empty());
+        assert_eq!(handler.chars, "x");
+    }
+
+    #[test]
+    fn kitty_notifications() {
+        let sequences: [&[u8]; 3] = [
+            b"\x1b]99;;Hello\x1b\\",
+            b"\x1b]99;i=1:d=0:u=2;Build\x1b\\",
+            b"\x1b]99;i=1:p=body:e=1;RG9uZQ==\x07",
+        ];
+        let handler = parse(&sequences.concat());
+
+        assert_eq!(handler.notifications, vec![
+            Notification { title: "Hello".into(), ..Default::default() },
+            Notification { title: "Build".into(), body: "Done".into(), urgency: Urgency::Critical },
+        ]);
+    }
+
+    #[test]
+    fn working_directory() {
+        let handler = parse(b"\x1b]7;file://host/tmp\x07\x1b]7;invalid\x1b\\x");
+
+        let paths: Vec<_> = handler.directories.iter().map(|dir| dir.path.clone()).collect();
+        assert_eq!(paths, vec![PathBuf::from("/tmp")]);
+        assert_eq!(handler.chars, "x");
+    }
+
+    #[test]
+    fn color_scheme_query() {
+        let handler = parse(b"\x1b[?996n\x1b[?996h\x1b[?99nx\x1b[6n");
+
+        assert_eq!(handler.color_scheme_reports, 1);
+        assert_eq!(handler.chars, "x");
+    }
+
+    #[test]
+    fn text_after_color_scheme_query() {
+        let handler = parse(b"\x1b[?996nabc\x1b[?9xyz\x1b[1mdef");
+
+        assert_eq!(handler.color_scheme_reports, 1);
+        assert_eq!(handler.chars, "abcyzdef");
+    }
+
+    #[test]
+    fn synchronized_update_order() {
+        let mut handler = MockHandler::default();
+        let mut parser = Processor::new();
+        for byte in b"\x1b[?2026ha\x1b]133;A\x07b\x1b]133;B\x07" {
+            parser.advance(&mut handler, *byte);
+        }
+
+        // Nothing is processed before the update ends.
+        assert!(parser.sync_timeout().sync_timeout().is_some());
+        assert!(handler.marks.is_empty());
+        assert!(handler.chars.is_empty());
+
+        for byte in b"c\x1b[?2026ld" {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert!(parser.sync_timeout().sync_timeout().is_none());
+        assert_eq!(parser.sync_bytes_count(), 0);
+        let expected = vec![SemanticMark::PromptStart, SemanticMark::CommandStart];
+        assert_eq!(handler.marks, expected);
+        assert_eq!(handler.mark_positions, vec![1, 2]);
+        assert_eq!(handler.chars, "abcd");
+    }
+
+    #[test]
+    fn synchronized_update_timeout() {
+        let mut handler = MockHandler::default();
+        let mut parser = Processor::new();
+        for byte in b"\x1b[?2026h\x1b]133;A\x07a\x1b[?2026hb" {
+            parser.advance(&mut handler, *byte);
+        }
+        assert!(handler.chars.is_empty());
+
+        parser.stop_sync(&mut handler);
+
+        assert!(parser.sync_timeout().sync_timeout().is_none());
+        assert_eq!(handler.marks, vec![SemanticMark::PromptStart]);
+        assert_eq!(handler.chars, "ab");
+
+        // Processing continues without synchronization.
+        parser.advance(&mut handler, b'c');
+        assert_eq!(handler.chars, "abc");
+    }
+}
@@ -95,6 +95,7 @@ pub enum SelectionType {
     Block,
     Semantic,
     Lines,
+    CommandOutput,
 }
 
 /// Describes a region of a 2-dimensional area.
@@ -105,6 +106,10 @@ pub enum SelectionType {
 /// [`semantic`] mode expands the initial selection to the nearest semantic escape char in either
 /// direction. [`lines`] will always select entire lines.
 ///
+/// Additionally, the command output mode selects all lines between its two anchors, excluding the
+/// anchor lines themselves. Its anchors are the line where a command was submitted and the line
+/// where the shell requested input again.
+///
 /// Calls to [`update`] operate different based on the selection kind. The [`simple`] and [`block`]
 /// mode do nothing special, simply track points and sides. [`semantic`] will continue to expand
 /// out to semantic boundaries as the selection point changes. Similarly, [`lines`] will always
@@ -220,6 +225,10 @@ impl Selection {
                         && start.side == Side::Left
                         && end.side == Side::Right)
             },
+            SelectionType::CommandOutput => {
+                let (start, end) = (self.region.start.point.line, self.region.end.point.line);
+                (start - end).0.abs() < 2
+            },
             SelectionType::Semantic | SelectionType::Lines => false,
         }
     }
@@ -280,6 +289,16 @@ impl Selection {
             mem::swap(&mut start, &mut end);
         }
 
+        // Exclude the lines of the command output's anchors.
+        if self.ty == SelectionType::CommandOutput {
+            if self.is_empty() {
+                return None;
+            }
+
+            start.point = Point::new(start.point.line + 1, Column(0));
+            end.point = Point::new(end.point.line - 1, term.last_column());
+        }
+
         // Clamp selection to within grid boundaries.
         if end.point.line < term.topmost_line() {
             return None;
@@ -292,6 +311,9 @@ impl Selection {
             SelectionType::Block => self.range_block(start, end),
             SelectionType::Semantic => Some(Self::range_semantic(term, start.point, end.point)),
             SelectionType::Lines => Some(Self::range_lines(term, start.point, end.point)),
+            SelectionType::CommandOutput => {
+                Some(SelectionRange::new(start.point, end.point, false))
+            },
         }
     }
 
@@ -546,6 +568,28 @@ mod tests {
         });
     }
 
+    #[test]
+    fn command_output_selection() {
+        let size = (10, 5);
+        let start = Point::new(Line(9), Column(0));
+        let mut selection = Selection::new(SelectionType::CommandOutput, start, Side::Left);
+        selection.update(Point::new(Line(4), Column(0)), Side::Left);
+        selection = selection.rotate(&size, &(Line(0)..Line(size.0 as i32)), 4).unwrap();
+
+        assert_eq!(selection.to_range(&term(size.0, size.1)).unwrap(), SelectionRange {
+            start: Point::new(Line(1), Column(0)),
+            end: Point::new(Line(4), Column(4)),
+            is_block: false,
+        });
+
+        // Adjacent anchors do not contain any output.
+        let start = Point::new(Line(1), Column(0));
+        let mut selection = Selection::new(SelectionType::CommandOutput, start, Side::Left);
+        selection.update(Point::new(Line(2), Column(0)), Side::Left);
+        assert!(selection.is_empty());
+        assert_eq!(selection.to_range(&term(size.0, size.1)), None);
+    }
+
     #[test]
     fn simple_selection() {
         let size = (10, 5);
@@ -5,7 +5,8 @@ use bitflags::bitflags;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::grid::{self, GridCell};
+use crate::graphics::GraphicCell;
+use crate::grid::{self, ArchiveCell, GridCell};
 use crate::index::Column;
 use crate::vte::ansi::{Color, Hyperlink as VteHyperlink, NamedColor};
 
@@ -128,6 +129,8 @@ pub struct CellExtra {
     underline_color: Option<Color>,
 
     hyperlink: Option<Hyperlink>,
+
+    graphic: Option<GraphicCell>,
 }
 
 /// Content and attributes of a single cell in the terminal grid.
@@ -182,10 +185,9 @@ impl Cell {
     pub fn set_underline_color(&mut self, color: Option<Color>) {
         // If we reset color and we don't have zerowidth we should drop extra storage.
         if color.is_none()
-            && self
-                .extra
-                .as_ref()
-                .map_or(true, |extra| extra.zerowidth.is_empty() && extra.hyperlink.is_none())
+            && self.extra.as_ref().map_or(true, |extra| {
+                extra.zerowidth.is_empty() && extra.hyperlink.is_none() && extra.graphic.is_none()
+            })
         {
             self.extra = None;
         } else {
@@ -204,7 +206,9 @@ impl Cell {
     pub fn set_hyperlink(&mut self, hyperlink: Option<Hyperlink>) {
         let should_drop = hyperlink.is_none()
             && self.extra.as_ref().map_or(true, |extra| {
-                extra.zerowidth.is_empty() && extra.underline_color.is_none()
+                extra.zerowidth.is_empty()
+                    && extra.underline_color.is_none()
+                    && extra.graphic.is_none()
             });
 
         if should_drop {
@@ -220,6 +224,29 @@ impl Cell {
     pub fn hyperlink(&self) -> Option<Hyperlink> {
         self.extra.as_ref()?.hyperlink.clone()
     }
+
+    /// Set graphic.
+    pub fn set_graphic(&mut self, graphic: Option<GraphicCell>) {
+        let should_drop = graphic.is_none()
+            && self.extra.as_ref().map_or(true, |extra| {
+                extra.zerowidth.is_empty()
+                    && extra.underline_color.is_none()
+                    && extra.hyperlink.is_none()
+            });
+
+        if should_drop {
+            self.extra = None;
+        } else {
+            let extra = self.extra.get_or_insert(Default::default());
+            Arc::make_mut(extra).graphic = graphic;
+        }
+    }
+
+    /// Graphic stored in this cell.
+    #[inline]
+    pub fn graphic(&self) -> Option<&GraphicCell> {
+        self.extra.as_ref()?.graphic.as_ref()
+    }
 }
 
 impl GridCell for Cell {
@@ -236,7 +263,10 @@ impl GridCell for Cell {
                     | Flags::WIDE_CHAR_SPACER
                     | Flags::LEADING_WIDE_CHAR_SPACER,
             )
-            && self.extra.as_ref().map(|extra| extra.zerowidth.is_empty()) != Some(false)
+            && self
+                .extra
+                .as_ref()
+                .map_or(true, |extra| extra.zerowidth.is_empty() && extra.graphic.is_none())
     }
 
     #[inline]
@@ -255,6 +285,30 @@ impl GridCell for Cell {
     }
 }
 
+impl ArchiveCell for Cell {
+    #[inline]
+    fn character(&self) -> char {
+        self.c
+    }
+
+    #[inline]
+    fn attributes(&self) -> Self {
+        let mut attributes = Cell { c: ' ', extra: self.extra.clone(), ..*self };
+
+        // Images are not archived.
+        if attributes.graphic().is_some() {
+            attributes.set_graphic(None);
+        }
+
+        attributes
+    }
+
+    #[inline]
+    fn with_character(&self, c: char) -> Self {
+        Cell { c, extra: self.extra.clone(), ..*self }
+    }
+}
+
 impl From<Color> for Cell {
     #[inline]
     fn from(color: Color) -> Self {
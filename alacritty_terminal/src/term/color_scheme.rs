@@ -0,0 +1,31 @@
+//! Color scheme preference reports (DSR 996 and mode 2031).
+
+/// Private mode for unsolicited color scheme reports.
+pub(crate) const COLOR_SCHEME_UPDATES_MODE: u16 = 2031;
+
+/// Color scheme preference of the terminal.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum ColorScheme {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl ColorScheme {
+    /// The opposite color scheme.
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Dark => Self::Light,
+            Self::Light => Self::Dark,
+        }
+    }
+
+    /// Escape sequence reporting the color scheme (`CSI ? 997 ; Ps n`).
+    pub(crate) fn report(self) -> String {
+        let scheme = match self {
+            Self::Dark => 1,
+            Self::Light => 2,
+        };
+        format!("\x1b[?997;{scheme}n")
+    }
+}
@@ -0,0 +1,618 @@
+//! Export of terminal content with its formatting.
+
+use std::fmt::Write;
+
+use crate::grid::Dimensions;
+use crate::index::{Column, Line, Point};
+use crate::selection::{Selection, SelectionRange, SelectionType};
+use crate::term::cell::{Cell, Flags, Hyperlink};
+use crate::term::color::COUNT;
+use crate::term::Term;
+use crate::vte::ansi::{Color, NamedColor, Rgb};
+
+/// Flags affecting the appearance of a cell.
+const STYLE_FLAGS: Flags = Flags::INVERSE
+    .union(Flags::BOLD)
+    .union(Flags::ITALIC)
+    .union(Flags::DIM)
+    .union(Flags::HIDDEN)
+    .union(Flags::STRIKEOUT)
+    .union(Flags::ALL_UNDERLINES);
+
+/// Factor for deriving dim colors from the normal colors.
+const DIM_FACTOR: f32 = 0.66;
+
+/// URI schemes which are linked in HTML exports.
+///
+/// Hyperlinks are set by the program running in the terminal, so other schemes like
+/// `javascript:` are exported as plain text.
+const HTML_LINK_SCHEMES: [&str; 4] = ["http", "https", "file", "mailto"];
+
+/// Receiver for the cells of exported lines.
+pub(crate) trait CellWriter {
+    /// Write the content of a cell.
+    fn push_cell(&mut self, cell: &Cell);
+
+    /// End the current line.
+    fn push_newline(&mut self);
+
+    /// Check if no cell was written since the last newline.
+    fn line_ended(&self) -> bool;
+}
+
+impl CellWriter for String {
+    fn push_cell(&mut self, cell: &Cell) {
+        self.push(cell.c);
+        for c in cell.zerowidth().into_iter().flatten() {
+            self.push(*c);
+        }
+    }
+
+    fn push_newline(&mut self) {
+        self.push('\n');
+    }
+
+    fn line_ended(&self) -> bool {
+        self.ends_with('\n')
+    }
+}
+
+impl<T> Term<T> {
+    /// Convert range between two points to text with ANSI escape sequences.
+    pub fn bounds_to_ansi(&self, start: Point, end: Point) -> String {
+        let mut writer = AnsiWriter::default();
+        self.write_bounds(&mut writer, start, end);
+        writer.finish()
+    }
+
+    /// Convert range between two points to HTML.
+    ///
+    /// The `colors` are the fully resolved palette, indexed like [`Colors`].
+    ///
+    /// [`Colors`]: crate::term::color::Colors
+    pub fn bounds_to_html(&self, start: Point, end: Point, colors: &[Rgb; COUNT]) -> String {
+        let mut writer = HtmlWriter::new(colors);
+        self.write_bounds(&mut writer, start, end);
+        writer.finish()
+    }
+
+    /// Convert the active selection to text with ANSI escape sequences.
+    pub fn selection_to_ansi(&self) -> Option<String> {
+        let mut writer = AnsiWriter::default();
+        self.write_selection(&mut writer)?;
+        Some(writer.finish())
+    }
+
+    /// Convert the active selection to HTML.
+    ///
+    /// The `colors` are the fully resolved palette, indexed like [`Colors`].
+    ///
+    /// [`Colors`]: crate::term::color::Colors
+    pub fn selection_to_html(&self, colors: &[Rgb; COUNT]) -> Option<String> {
+        let mut writer = HtmlWriter::new(colors);
+        self.write_selection(&mut writer)?;
+        Some(writer.finish())
+    }
+
+    /// Write all cells between two points.
+    fn write_bounds<W: CellWriter>(&self, writer: &mut W, start: Point, end: Point) {
+        for line in (start.line.0..=end.line.0).map(Line::from) {
+            let start_col = if line == start.line { start.column } else { Column(0) };
+            let end_col = if line == end.line { end.column } else { self.last_column() };
+
+            self.write_line(writer, line, start_col..end_col, line == end.line);
+        }
+    }
+
+    /// Write all cells of the active selection.
+    fn write_selection<W: CellWriter>(&self, writer: &mut W) -> Option<()> {
+        let selection_range = self.selection.as_ref().and_then(|s| s.to_range(self))?;
+        let SelectionRange { start, end, .. } = selection_range;
+
+        match self.selection.as_ref() {
+            Some(Selection { ty: SelectionType::Block, .. }) => {
+                for line in (start.line.0..end.line.0).map(Line::from) {
+                    let cols = start.column..end.column;
+                    self.write_line(writer, line, cols, start.column.0 != 0);
+
+                    if !writer.line_ended() {
+                        writer.push_newline();
+                    }
+                }
+
+                self.write_line(writer, end.line, start.column..end.column, true);
+            },
+            _ => self.write_bounds(writer, start, end),
+        }
+
+        Some(())
+    }
+}
+
+/// Visual style shared by consecutive cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Style {
+    fg: Color,
+    bg: Color,
+    underline_color: Option<Color>,
+    flags: Flags,
+    hyperlink: Option<Hyperlink>,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            fg: Color::Named(NamedColor::Foreground),
+            bg: Color::Named(NamedColor::Background),
+            underline_color: None,
+            flags: Flags::empty(),
+            hyperlink: None,
+        }
+    }
+}
+
+impl Style {
+    fn new(cell: &Cell) -> Self {
+        Self {
+            fg: cell.fg,
+            bg: cell.bg,
+            underline_color: cell.underline_color(),
+            flags: cell.flags & STYLE_FLAGS,
+            hyperlink: cell.hyperlink(),
+        }
+    }
+
+    /// Check if the style only differs from the default in its hyperlink.
+    fn is_plain(&self) -> bool {
+        let default = Self::default();
+        self.fg == default.fg
+            && self.bg == default.bg
+            && self.underline_color.is_none()
+            && self.flags.is_empty()
+    }
+}
+
+/// Writer for text with ANSI escape sequences.
+#[derive(Default)]
+struct AnsiWriter {
+    text: String,
+    style: Style,
+    newlines: usize,
+}
+
+impl AnsiWriter {
+    fn finish(mut self) -> String {
+        self.set_style(Style::default());
+        self.text
+    }
+
+    fn set_style(&mut self, style: Style) {
+        if style == self.style {
+            return;
+        }
+
+        if style.hyperlink != self.style.hyperlink {
+            match &style.hyperlink {
+                Some(hyperlink) => {
+                    let _ =
+                        write!(self.text, "\x1b]8;id={};{}\x1b\\", hyperlink.id(), hyperlink.uri());
+                },
+                None => self.text.push_str("\x1b]8;;\x1b\\"),
+            }
+        }
+
+        if !style.is_plain() || !self.style.is_plain() {
+            self.text.push_str("\x1b[0");
+
+            for (flag, code) in [
+                (Flags::BOLD, "1"),
+                (Flags::DIM, "2"),
+                (Flags::ITALIC, "3"),
+                (Flags::UNDERLINE, "4"),
+                (Flags::DOUBLE_UNDERLINE, "4:2"),
+                (Flags::UNDERCURL, "4:3"),
+                (Flags::DOTTED_UNDERLINE, "4:4"),
+                (Flags::DASHED_UNDERLINE, "4:5"),
+                (Flags::INVERSE, "7"),
+                (Flags::HIDDEN, "8"),
+                (Flags::STRIKEOUT, "9"),
+            ] {
+                if style.flags.contains(flag) {
+                    self.text.push(';');
+                    self.text.push_str(code);
+                }
+            }
+
+            self.push_color(style.fg, 30, 90, 38);
+            self.push_color(style.bg, 40, 100, 48);
+            if let Some(color) = style.underline_color {
+                self.push_color(color, 0, 0, 58);
+            }
+
+            self.text.push('m');
+        }
+
+        self.style = style;
+    }
+
+    /// Write the SGR parameters for a color.
+    ///
+    /// Named colors use the `normal` and `bright` offsets, unless they are zero.
+    fn push_color(&mut self, color: Color, normal: u8, bright: u8, extended: u8) {
+        let _ = match color {
+            Color::Named(named) if (named as usize) < 8 && normal != 0 => {
+                write!(self.text, ";{}", normal + named as u8)
+            },
+            Color::Named(named) if (named as usize) < 16 && bright != 0 => {
+                write!(self.text, ";{}", bright + named as u8 - 8)
+            },
+            Color::Named(named) if (named as usize) < 16 => {
+                write!(self.text, ";{extended};5;{}", named as u8)
+            },
+            // Other named colors are the terminal's defaults.
+            Color::Named(_) => Ok(()),
+            Color::Indexed(index) => write!(self.text, ";{extended};5;{index}"),
+            Color::Spec(Rgb { r, g, b }) => write!(self.text, ";{extended};2;{r};{g};{b}"),
+        };
+    }
+}
+
+impl CellWriter for AnsiWriter {
+    fn push_cell(&mut self, cell: &Cell) {
+        // Reset the style before newlines, to avoid background colors bleeding into new lines.
+        if self.newlines > 0 {
+            self.set_style(Style::default());
+            for _ in 0..self.newlines {
+                self.text.push('\n');
+            }
+            self.newlines = 0;
+        }
+
+        self.set_style(Style::new(cell));
+        self.text.push_cell(cell);
+    }
+
+    fn push_newline(&mut self) {
+        self.newlines += 1;
+    }
+
+    fn line_ended(&self) -> bool {
+        self.newlines > 0
+    }
+}
+
+/// Writer for HTML, using inline styles.
+struct HtmlWriter<'a> {
+    text: String,
+    colors: &'a [Rgb; COUNT],
+    style: Style,
+    newlines: usize,
+}
+
+impl<'a> HtmlWriter<'a> {
+    fn new(colors: &'a [Rgb; COUNT]) -> Self {
+        let fg = colors[NamedColor::Foreground as usize];
+        let bg = colors[NamedColor::Background as usize];
+        let text = format!("<pre style=\"color:{fg};background-color:{bg};\">");
+
+        Self { text, colors, style: Style::default(), newlines: 0 }
+    }
+
+    fn finish(mut self) -> String {
+        self.set_style(Style::default());
+        self.text.push_str("</pre>");
+        self.text
+    }
+
+    fn set_style(&mut self, style: Style) {
+        if style == self.style {
+            return;
+        }
+
+        if !self.style.is_plain() {
+            self.text.push_str("</span>");
+        }
+
+        if style.hyperlink != self.style.hyperlink {
+            if self.style.hyperlink.is_some() {
+                self.text.push_str("</a>");
+            }
+
+            if let Some(hyperlink) = &style.hyperlink {
+                self.text.push_str("<a href=\"");
+                push_escaped(&mut self.text, hyperlink.uri());
+                self.text.push_str("\">");
+            }
+        }
+
+        if !style.is_plain() {
+            let css = self.css(&style);
+            let _ = write!(self.text, "<span style=\"{css}\">");
+        }
+
+        self.style = style;
+    }
+
+    /// Inline CSS for a style.
+    fn css(&self, style: &Style) -> String {
+        let mut fg = resolve_color(self.colors, style.fg, style.flags);
+        let mut bg = resolve_color(self.colors, style.bg, Flags::empty());
+        if style.flags.contains(Flags::INVERSE) {
+            (fg, bg) = (bg, fg);
+        }
+        if style.flags.contains(Flags::HIDDEN) {
+            fg = bg;
+        }
+
+        let mut css = format!("color:{fg};background-color:{bg};");
+
+        if style.flags.contains(Flags::BOLD) {
+            css.push_str("font-weight:bold;");
+        }
+
+        if style.flags.contains(Flags::ITALIC) {
+            css.push_str("font-style:italic;");
+        }
+
+        let underline = style.flags.intersects(Flags::ALL_UNDERLINES);
+        let strikeout = style.flags.contains(Flags::STRIKEOUT);
+        match (underline, strikeout) {
+            (true, true) => css.push_str("text-decoration-line:underline line-through;"),
+            (true, false) => css.push_str("text-decoration-line:underline;"),
+            (false, true) => css.push_str("text-decoration-line:line-through;"),
+            (false, false) => (),
+        }
+
+        let decoration_style = if style.flags.contains(Flags::DOUBLE_UNDERLINE) {
+            Some("double")
+        } else if style.flags.contains(Flags::UNDERCURL) {
+            Some("wavy")
+        } else if style.flags.contains(Flags::DOTTED_UNDERLINE) {
+            Some("dotted")
+        } else if style.flags.contains(Flags::DASHED_UNDERLINE) {
+            Some("dashed")
+        } else {
+            None
+        };
+        if let Some(decoration_style) = decoration_style {
+            let _ = write!(css, "text-decoration-style:{decoration_style};");
+        }
+
+        if let Some(color) = style.underline_color.filter(|_| underline) {
+            let color = resolve_color(self.colors, color, Flags::empty());
+            let _ = write!(css, "text-decoration-color:{color};");
+        }
+
+        css
+    }
+}
+
+impl<'a> CellWriter for HtmlWriter<'a> {
+    fn push_cell(&mut self, cell: &Cell) {
+        for _ in 0..self.newlines {
+            self.text.push('\n');
+        }
+        self.newlines = 0;
+
+        let mut style = Style::new(cell);
+        style.hyperlink = style.hyperlink.filter(|hyperlink| is_linkable(hyperlink.uri()));
+        self.set_style(style);
+
+        let mut text = String::new();
+        text.push_cell(cell);
+        push_escaped(&mut self.text, &text);
+    }
+
+    fn push_newline(&mut self) {
+        self.newlines += 1;
+    }
+
+    fn line_ended(&self) -> bool {
+        self.newlines > 0
+    }
+}
+
+/// Append text with HTML special characters escaped.
+fn push_escaped(html: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            c => html.push(c),
+        }
+    }
+}
+
+/// Check if a URI uses one of the schemes allowed in HTML links.
+fn is_linkable(uri: &str) -> bool {
+    uri.split_once(':').is_some_and(|(scheme, _)| {
+        HTML_LINK_SCHEMES.iter().any(|allowed| scheme.eq_ignore_ascii_case(allowed))
+    })
+}
+
+/// Convert a cell color to RGB.
+fn resolve_color(colors: &[Rgb; COUNT], color: Color, flags: Flags) -> Rgb {
+    if !flags.contains(Flags::DIM) {
+        return match color {
+            Color::Spec(rgb) => rgb,
+            Color::Named(named) => colors[named as usize],
+            Color::Indexed(index) => colors[index as usize],
+        };
+    }
+
+    // Match the dim colors used by the renderer.
+    match color {
+        Color::Spec(rgb) => rgb * DIM_FACTOR,
+        Color::Named(named) => colors[named.to_dim() as usize],
+        Color::Indexed(index @ 0..=7) => colors[NamedColor::DimBlack as usize + index as usize],
+        Color::Indexed(index @ 8..=15) => colors[index as usize - 8],
+        Color::Indexed(index) => colors[index as usize] * DIM_FACTOR,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::event::VoidListener;
+    use crate::index::Side;
+    use crate::term::test::TermSize;
+    use crate::term::Config;
+    use crate::vte::ansi::{Attr, Handler, Hyperlink as VteHyperlink};
+
+    fn term(text: &str) -> Term<VoidListener> {
+        let mut term = Term::new(Config::default(), &TermSize::new(10, 3), VoidListener);
+        for c in text.chars() {
+            match c {
+                '\n' => {
+                    term.linefeed();
+                    term.carriage_return();
+                },
+                c => term.input(c),
+            }
+        }
+        term
+    }
+
+    fn palette() -> [Rgb; COUNT] {
+        let mut colors = [Rgb { r: 0x80, g: 0x80, b: 0x80 }; COUNT];
+        colors[NamedColor::Foreground as usize] = Rgb { r: 0xd8, g: 0xd8, b: 0xd8 };
+        colors[NamedColor::Background as usize] = Rgb { r: 0x18, g: 0x18, b: 0x18 };
+        colors
+    }
+
+    fn bounds(term: &Term<VoidListener>) -> (Point, Point) {
+        (Point::new(Line(0), Column(0)), Point::new(term.bottommost_line(), term.last_column()))
+    }
+
+    #[test]
+    fn ansi_attributes() {
+        let mut term = term("a");
+        term.terminal_attribute(Attr::Bold);
+        term.terminal_attribute(Attr::Foreground(Color::Named(NamedColor::Red)));
+        term.input('b');
+        term.terminal_attribute(Attr::Background(Color::Spec(Rgb { r: 1, g: 2, b: 3 })));
+        term.input('c');
+        term.terminal_attribute(Attr::Reset);
+        term.input('d');
+
+        let (start, end) = bounds(&term);
+        assert_eq!(
+            term.bounds_to_ansi(start, end),
+            "a\x1b[0;1;31mb\x1b[0;1;31;48;2;1;2;3mc\x1b[0md"
+        );
+    }
+
+    #[test]
+    fn ansi_reset_before_newline() {
+        let mut term = term("");
+        term.terminal_attribute(Attr::Background(Color::Indexed(100)));
+        term.input('a');
+        term.linefeed();
+        term.carriage_return();
+        term.input('b');
+
+        let (start, end) = bounds(&term);
+        let ansi = term.bounds_to_ansi(start, end);
+        assert_eq!(ansi, "\x1b[0;48;5;100ma\x1b[0m\n\x1b[0;48;5;100mb\x1b[0m");
+    }
+
+    #[test]
+    fn ansi_hyperlink() {
+        let mut term = term("");
+        let hyperlink = VteHyperlink { id: Some(String::from("1")), uri: String::from("file:///") };
+        term.set_hyperlink(Some(hyperlink));
+        term.input('a');
+        term.set_hyperlink(None);
+        term.input('b');
+
+        let (start, end) = bounds(&term);
+        let ansi = term.bounds_to_ansi(start, end);
+        assert_eq!(ansi, "\x1b]8;id=1;file:///\x1b\\a\x1b]8;;\x1b\\b");
+    }
+
+    #[test]
+    fn html_escaping() {
+        let term = term("<a & b>");
+
+        let (start, end) = bounds(&term);
+        let html = term.bounds_to_html(start, end, &palette());
+        assert_eq!(
+            html,
+            "<pre style=\"color:#d8d8d8;background-color:#181818;\">&lt;a &amp; b&gt;</pre>"
+        );
+    }
+
+    #[test]
+    fn html_palette() {
+        let mut term = term("");
+        term.terminal_attribute(Attr::Foreground(Color::Named(NamedColor::Green)));
+        term.terminal_attribute(Attr::Underline);
+        term.input('a');
+
+        let mut colors = palette();
+        colors[NamedColor::Green as usize] = Rgb { r: 0, g: 255, b: 0 };
+
+        let (start, end) = bounds(&term);
+        let html = term.bounds_to_html(start, end, &colors);
+        assert_eq!(
+            html,
+            "<pre style=\"color:#d8d8d8;background-color:#181818;\"><span \
+             style=\"color:#00ff00;background-color:#181818;text-decoration-line:underline;\">a</\
+             span></pre>"
+        );
+    }
+
+    #[test]
+    fn block_selection() {
+        let mut term = term("abc\ndef\nghi");
+
+        let mut selection =
+            Selection::new(SelectionType::Block, Point::new(Line(0), Column(1)), Side::Left);
+        selection.update(Point::new(Line(1), Column(1)), Side::Right);
+        term.selection = Some(selection);
+
+        assert_eq!(term.selection_to_ansi().as_deref(), Some("b\ne"));
+    }
+
+    #[test]
+    fn html_dim() {
+        let mut term = term("");
+        term.terminal_attribute(Attr::Dim);
+        term.terminal_attribute(Attr::Foreground(Color::Spec(Rgb { r: 200, g: 100, b: 0 })));
+        term.input('a');
+        term.terminal_attribute(Attr::Foreground(Color::Indexed(1)));
+        term.input('b');
+
+        let mut colors = palette();
+        colors[NamedColor::DimRed as usize] = Rgb { r: 0x11, g: 0x22, b: 0x33 };
+
+        let (start, end) = bounds(&term);
+        let html = term.bounds_to_html(start, end, &colors);
+        assert!(html.contains("<span style=\"color:#844200;background-color:#181818;\">a"));
+        assert!(html.contains("<span style=\"color:#112233;background-color:#181818;\">b"));
+    }
+
+    #[test]
+    fn html_hyperlink_schemes() {
+        let mut term = term("");
+        let uri = String::from("HTTPS://example.org");
+        term.set_hyperlink(Some(VteHyperlink { id: None, uri }));
+        term.input('a');
+        let uri = String::from("javascript:alert(1)");
+        term.set_hyperlink(Some(VteHyperlink { id: None, uri }));
+        term.input('b');
+        term.set_hyperlink(None);
+
+        let (start, end) = bounds(&term);
+        let html = term.bounds_to_html(start, end, &palette());
+        assert_eq!(
+            html,
+            "<pre style=\"color:#d8d8d8;background-color:#181818;\"><a \
+             href=\"HTTPS://example.org\">a</a>b</pre>"
+        );
+    }
+}
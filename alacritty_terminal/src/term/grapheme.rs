@@ -0,0 +1,447 @@
+//! Grapheme cluster segmentation for the grapheme clustering mode (DECSET 2027).
+//!
+//! This implements the extended grapheme cluster boundary rules of UAX #29, except for the Indic
+//! conjunct rule GB9c. Since text is received one character at a time, boundaries are only ever
+//! checked between the last cluster and the next character.
+
+use std::cmp::Ordering;
+
+/// Private mode for grapheme clustering.
+pub(crate) const GRAPHEME_CLUSTERING_MODE: u16 = 2027;
+
+/// Zero width joiner.
+const ZWJ: char = '\u{200d}';
+
+/// Variation selector requesting text presentation.
+const VS15: char = '\u{fe0e}';
+
+/// Variation selector requesting emoji presentation.
+const VS16: char = '\u{fe0f}';
+
+/// First precomposed Hangul syllable.
+const HANGUL_SYLLABLE_START: u32 = 0xac00;
+
+/// Last precomposed Hangul syllable.
+const HANGUL_SYLLABLE_END: u32 = 0xd7a3;
+
+/// Number of trailing consonants each leading consonant and vowel combination can have.
+const HANGUL_TRAILING_COUNT: u32 = 28;
+
+/// `Grapheme_Cluster_Break` property values.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum GraphemeBreak {
+    Control,
+    Extend,
+    Zwj,
+    RegionalIndicator,
+    Prepend,
+    SpacingMark,
+    L,
+    V,
+    T,
+    Lv,
+    Lvt,
+    Other,
+}
+
+/// Check if a character continues the grapheme cluster of a cell.
+///
+/// The cluster consists of the cell's character followed by its zerowidth characters.
+pub fn extends_cluster(base: char, zerowidth: &[char], c: char) -> bool {
+    use GraphemeBreak::{
+        Control, Extend, Lv, Lvt, Prepend, RegionalIndicator, SpacingMark, Zwj, L, T, V,
+    };
+
+    let last = zerowidth.last().copied().unwrap_or(base);
+    match (grapheme_break(last), grapheme_break(c)) {
+        // GB4, GB5: Break around controls.
+        (Control, _) | (_, Control) => false,
+        // GB6, GB7, GB8: Hangul syllable sequences.
+        (L, L | V | Lv | Lvt) | (Lv | V, V | T) | (Lvt | T, T) => true,
+        // GB9, GB9a: Extending characters and spacing marks.
+        (_, Extend | Zwj | SpacingMark) => true,
+        // GB9b: Prepended concatenation marks.
+        (Prepend, _) => true,
+        // GB11: Emoji ZWJ sequences.
+        (Zwj, _) if is_extended_pictographic(c) => {
+            let cluster = [base].into_iter().chain(zerowidth.iter().copied()).rev();
+            let mut before_zwj = cluster.skip(1).skip_while(|c| grapheme_break(*c) == Extend);
+            before_zwj.next().is_some_and(is_extended_pictographic)
+        },
+        // GB12, GB13: Flags consist of a pair of regional indicators.
+        (RegionalIndicator, RegionalIndicator) => {
+            let cluster = [base].into_iter().chain(zerowidth.iter().copied()).rev();
+            let regional_indicators =
+                cluster.take_while(|c| grapheme_break(*c) == RegionalIndicator).count();
+            regional_indicators % 2 == 1
+        },
+        // GB999: Break everywhere else.
+        _ => false,
+    }
+}
+
+/// Check if a character extending a grapheme cluster forces emoji presentation.
+///
+/// Clusters with emoji presentation always occupy two cells.
+pub fn forces_emoji_presentation(c: char) -> bool {
+    c == VS16 || is_emoji_modifier(c) || is_regional_indicator(c) || is_extended_pictographic(c)
+}
+
+/// Check if a character extending a grapheme cluster forces text presentation.
+///
+/// This only applies to a single pictograph, which occupies one cell with text presentation.
+pub fn forces_text_presentation(base: char, zerowidth: &[char], c: char) -> bool {
+    c == VS15 && zerowidth.is_empty() && is_extended_pictographic(base)
+}
+
+/// Emoji skin tone modifiers.
+fn is_emoji_modifier(c: char) -> bool {
+    ('\u{1f3fb}'..='\u{1f3ff}').contains(&c)
+}
+
+/// Regional indicator symbols, used for flags.
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1f1e6}'..='\u{1f1ff}').contains(&c)
+}
+
+/// `Grapheme_Cluster_Break` property of a character.
+fn grapheme_break(c: char) -> GraphemeBreak {
+    match c {
+        ZWJ => return GraphemeBreak::Zwj,
+        c if is_regional_indicator(c) => return GraphemeBreak::RegionalIndicator,
+        _ => (),
+    }
+
+    // Precomposed Hangul syllables alternate between LV and LVT.
+    let c = c as u32;
+    if (HANGUL_SYLLABLE_START..=HANGUL_SYLLABLE_END).contains(&c) {
+        return if (c - HANGUL_SYLLABLE_START) % HANGUL_TRAILING_COUNT == 0 {
+            GraphemeBreak::Lv
+        } else {
+            GraphemeBreak::Lvt
+        };
+    }
+
+    GRAPHEME_BREAK
+        .binary_search_by(|&(start, end, _)| range_ordering(start, end, c))
+        .map_or(GraphemeBreak::Other, |index| GRAPHEME_BREAK[index].2)
+}
+
+/// Characters with the `Extended_Pictographic` property.
+fn is_extended_pictographic(c: char) -> bool {
+    let c = c as u32;
+    EXTENDED_PICTOGRAPHIC.binary_search_by(|&(start, end)| range_ordering(start, end, c)).is_ok()
+}
+
+/// Compare an inclusive range of codepoints to a codepoint.
+fn range_ordering(start: u32, end: u32, c: u32) -> Ordering {
+    if end < c {
+        Ordering::Less
+    } else if start > c {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
+    }
+}
+
+// The following tables are generated from the Unicode 16.0.0 character database.
+
+/// Ranges of the `Grapheme_Cluster_Break` property, except for `CR`, `LF`, `ZWJ`,
+/// `Regional_Indicator` and the precomposed Hangul syllables.
+#[rustfmt::skip]
+const GRAPHEME_BREAK: &[(u32, u32, GraphemeBreak)] = {
+    use GraphemeBreak::{Control, Extend, Prepend, SpacingMark, L, T, V};
+    &[
+        (0x0000, 0x0009, Control), (0x000b, 0x000c, Control), (0x000e, 0x001f, Control),
+        (0x007f, 0x009f, Control), (0x00ad, 0x00ad, Control), (0x0300, 0x036f, Extend),
+        (0x0483, 0x0489, Extend), (0x0591, 0x05bd, Extend), (0x05bf, 0x05bf, Extend),
+        (0x05c1, 0x05c2, Extend), (0x05c4, 0x05c5, Extend), (0x05c7, 0x05c7, Extend),
+        (0x0600, 0x0605, Prepend), (0x0610, 0x061a, Extend), (0x061c, 0x061c, Control),
+        (0x064b, 0x065f, Extend), (0x0670, 0x0670, Extend), (0x06d6, 0x06dc, Extend),
+        (0x06dd, 0x06dd, Prepend), (0x06df, 0x06e4, Extend), (0x06e7, 0x06e8, Extend),
+        (0x06ea, 0x06ed, Extend), (0x070f, 0x070f, Prepend), (0x0711, 0x0711, Extend),
+        (0x0730, 0x074a, Extend), (0x07a6, 0x07b0, Extend), (0x07eb, 0x07f3, Extend),
+        (0x07fd, 0x07fd, Extend), (0x0816, 0x0819, Extend), (0x081b, 0x0823, Extend),
+        (0x0825, 0x0827, Extend), (0x0829, 0x082d, Extend), (0x0859, 0x085b, Extend),
+        (0x0890, 0x0891, Prepend), (0x0897, 0x089f, Extend), (0x08ca, 0x08e1, Extend),
+        (0x08e2, 0x08e2, Prepend), (0x08e3, 0x0902, Extend), (0x0903, 0x0903, SpacingMark),
+        (0x093a, 0x093a, Extend), (0x093b, 0x093b, SpacingMark), (0x093c, 0x093c, Extend),
+        (0x093e, 0x0940, SpacingMark), (0x0941, 0x0948, Extend), (0x0949, 0x094c, SpacingMark),
+        (0x094d, 0x094d, Extend), (0x094e, 0x094f, SpacingMark), (0x0951, 0x0957, Extend),
+        (0x0962, 0x0963, Extend), (0x0981, 0x0981, Extend), (0x0982, 0x0983, SpacingMark),
+        (0x09bc, 0x09bc, Extend), (0x09be, 0x09be, Extend), (0x09bf, 0x09c0, SpacingMark),
+        (0x09c1, 0x09c4, Extend), (0x09c7, 0x09c8, SpacingMark), (0x09cb, 0x09cc, SpacingMark),
+        (0x09cd, 0x09cd, Extend), (0x09d7, 0x09d7, Extend), (0x09e2, 0x09e3, Extend),
+        (0x09fe, 0x09fe, Extend), (0x0a01, 0x0a02, Extend), (0x0a03, 0x0a03, SpacingMark),
+        (0x0a3c, 0x0a3c, Extend), (0x0a3e, 0x0a40, SpacingMark), (0x0a41, 0x0a42, Extend),
+        (0x0a47, 0x0a48, Extend), (0x0a4b, 0x0a4d, Extend), (0x0a51, 0x0a51, Extend),
+        (0x0a70, 0x0a71, Extend), (0x0a75, 0x0a75, Extend), (0x0a81, 0x0a82, Extend),
+        (0x0a83, 0x0a83, SpacingMark), (0x0abc, 0x0abc, Extend), (0x0abe, 0x0ac0, SpacingMark),
+        (0x0ac1, 0x0ac5, Extend), (0x0ac7, 0x0ac8, Extend), (0x0ac9, 0x0ac9, SpacingMark),
+        (0x0acb, 0x0acc, SpacingMark), (0x0acd, 0x0acd, Extend), (0x0ae2, 0x0ae3, Extend),
+        (0x0afa, 0x0aff, Extend), (0x0b01, 0x0b01, Extend), (0x0b02, 0x0b03, SpacingMark),
+        (0x0b3c, 0x0b3c, Extend), (0x0b3e, 0x0b3f, Extend), (0x0b40, 0x0b40, SpacingMark),
+        (0x0b41, 0x0b44, Extend), (0x0b47, 0x0b48, SpacingMark), (0x0b4b, 0x0b4c, SpacingMark),
+        (0x0b4d, 0x0b4d, Extend), (0x0b55, 0x0b57, Extend), (0x0b62, 0x0b63, Extend),
+        (0x0b82, 0x0b82, Extend), (0x0bbe, 0x0bbe, Extend), (0x0bbf, 0x0bbf, SpacingMark),
+        (0x0bc0, 0x0bc0, Extend), (0x0bc1, 0x0bc2, SpacingMark), (0x0bc6, 0x0bc8, SpacingMark),
+        (0x0bca, 0x0bcc, SpacingMark), (0x0bcd, 0x0bcd, Extend), (0x0bd7, 0x0bd7, Extend),
+        (0x0c00, 0x0c00, Extend), (0x0c01, 0x0c03, SpacingMark), (0x0c04, 0x0c04, Extend),
+        (0x0c3c, 0x0c3c, Extend), (0x0c3e, 0x0c40, Extend), (0x0c41, 0x0c44, SpacingMark),
+        (0x0c46, 0x0c48, Extend), (0x0c4a, 0x0c4d, Extend), (0x0c55, 0x0c56, Extend),
+        (0x0c62, 0x0c63, Extend), (0x0c81, 0x0c81, Extend), (0x0c82, 0x0c83, SpacingMark),
+        (0x0cbc, 0x0cbc, Extend), (0x0cbe, 0x0cbe, SpacingMark), (0x0cbf, 0x0cc0, Extend),
+        (0x0cc1, 0x0cc1, SpacingMark), (0x0cc2, 0x0cc2, Extend), (0x0cc3, 0x0cc4, SpacingMark),
+        (0x0cc6, 0x0cc8, Extend), (0x0cca, 0x0ccd, Extend), (0x0cd5, 0x0cd6, Extend),
+        (0x0ce2, 0x0ce3, Extend), (0x0cf3, 0x0cf3, SpacingMark), (0x0d00, 0x0d01, Extend),
+        (0x0d02, 0x0d03, SpacingMark), (0x0d3b, 0x0d3c, Extend), (0x0d3e, 0x0d3e, Extend),
+        (0x0d3f, 0x0d40, SpacingMark), (0x0d41, 0x0d44, Extend), (0x0d46, 0x0d48, SpacingMark),
+        (0x0d4a, 0x0d4c, SpacingMark), (0x0d4d, 0x0d4d, Extend), (0x0d4e, 0x0d4e, Prepend),
+        (0x0d57, 0x0d57, Extend), (0x0d62, 0x0d63, Extend), (0x0d81, 0x0d81, Extend),
+        (0x0d82, 0x0d83, SpacingMark), (0x0dca, 0x0dca, Extend), (0x0dcf, 0x0dcf, Extend),
+        (0x0dd0, 0x0dd1, SpacingMark), (0x0dd2, 0x0dd4, Extend), (0x0dd6, 0x0dd6, Extend),
+        (0x0dd8, 0x0dde, SpacingMark), (0x0ddf, 0x0ddf, Extend), (0x0df2, 0x0df3, SpacingMark),
+        (0x0e31, 0x0e31, Extend), (0x0e33, 0x0e33, SpacingMark), (0x0e34, 0x0e3a, Extend),
+        (0x0e47, 0x0e4e, Extend), (0x0eb1, 0x0eb1, Extend), (0x0eb3, 0x0eb3, SpacingMark),
+        (0x0eb4, 0x0ebc, Extend), (0x0ec8, 0x0ece, Extend), (0x0f18, 0x0f19, Extend),
+        (0x0f35, 0x0f35, Extend), (0x0f37, 0x0f37, Extend), (0x0f39, 0x0f39, Extend),
+        (0x0f3e, 0x0f3f, SpacingMark), (0x0f71, 0x0f7e, Extend), (0x0f7f, 0x0f7f, SpacingMark),
+        (0x0f80, 0x0f84, Extend), (0x0f86, 0x0f87, Extend), (0x0f8d, 0x0f97, Extend),
+        (0x0f99, 0x0fbc, Extend), (0x0fc6, 0x0fc6, Extend), (0x102d, 0x1030, Extend),
+        (0x1031, 0x1031, SpacingMark), (0x1032, 0x1037, Extend), (0x1039, 0x103a, Extend),
+        (0x103b, 0x103c, SpacingMark), (0x103d, 0x103e, Extend), (0x1056, 0x1057, SpacingMark),
+        (0x1058, 0x1059, Extend), (0x105e, 0x1060, Extend), (0x1071, 0x1074, Extend),
+        (0x1082, 0x1082, Extend), (0x1084, 0x1084, SpacingMark), (0x1085, 0x1086, Extend),
+        (0x108d, 0x108d, Extend), (0x109d, 0x109d, Extend), (0x1100, 0x115f, L),
+        (0x1160, 0x11a7, V), (0x11a8, 0x11ff, T), (0x135d, 0x135f, Extend),
+        (0x1712, 0x1715, Extend), (0x1732, 0x1734, Extend), (0x1752, 0x1753, Extend),
+        (0x1772, 0x1773, Extend), (0x17b4, 0x17b5, Extend), (0x17b6, 0x17b6, SpacingMark),
+        (0x17b7, 0x17bd, Extend), (0x17be, 0x17c5, SpacingMark), (0x17c6, 0x17c6, Extend),
+        (0x17c7, 0x17c8, SpacingMark), (0x17c9, 0x17d3, Extend), (0x17dd, 0x17dd, Extend),
+        (0x180b, 0x180d, Extend), (0x180e, 0x180e, Control), (0x180f, 0x180f, Extend),
+        (0x1885, 0x1886, Extend), (0x18a9, 0x18a9, Extend), (0x1920, 0x1922, Extend),
+        (0x1923, 0x1926, SpacingMark), (0x1927, 0x1928, Extend), (0x1929, 0x192b, SpacingMark),
+        (0x1930, 0x1931, SpacingMark), (0x1932, 0x1932, Extend), (0x1933, 0x1938, SpacingMark),
+        (0x1939, 0x193b, Extend), (0x1a17, 0x1a18, Extend), (0x1a19, 0x1a1a, SpacingMark),
+        (0x1a1b, 0x1a1b, Extend), (0x1a55, 0x1a55, SpacingMark), (0x1a56, 0x1a56, Extend),
+        (0x1a57, 0x1a57, SpacingMark), (0x1a58, 0x1a5e, Extend), (0x1a60, 0x1a60, Extend),
+        (0x1a62, 0x1a62, Extend), (0x1a65, 0x1a6c, Extend), (0x1a6d, 0x1a72, SpacingMark),
+        (0x1a73, 0x1a7c, Extend), (0x1a7f, 0x1a7f, Extend), (0x1ab0, 0x1ace, Extend),
+        (0x1b00, 0x1b03, Extend), (0x1b04, 0x1b04, SpacingMark), (0x1b34, 0x1b3d, Extend),
+        (0x1b3e, 0x1b41, SpacingMark), (0x1b42, 0x1b44, Extend), (0x1b6b, 0x1b73, Extend),
+        (0x1b80, 0x1b81, Extend), (0x1b82, 0x1b82, SpacingMark), (0x1ba1, 0x1ba1, SpacingMark),
+        (0x1ba2, 0x1ba5, Extend), (0x1ba6, 0x1ba7, SpacingMark), (0x1ba8, 0x1bad, Extend),
+        (0x1be6, 0x1be6, Extend), (0x1be7, 0x1be7, SpacingMark), (0x1be8, 0x1be9, Extend),
+        (0x1bea, 0x1bec, SpacingMark), (0x1bed, 0x1bed, Extend), (0x1bee, 0x1bee, SpacingMark),
+        (0x1bef, 0x1bf3, Extend), (0x1c24, 0x1c2b, SpacingMark), (0x1c2c, 0x1c33, Extend),
+        (0x1c34, 0x1c35, SpacingMark), (0x1c36, 0x1c37, Extend), (0x1cd0, 0x1cd2, Extend),
+        (0x1cd4, 0x1ce0, Extend), (0x1ce1, 0x1ce1, SpacingMark), (0x1ce2, 0x1ce8, Extend),
+        (0x1ced, 0x1ced, Extend), (0x1cf4, 0x1cf4, Extend), (0x1cf7, 0x1cf7, SpacingMark),
+        (0x1cf8, 0x1cf9, Extend), (0x1dc0, 0x1dff, Extend), (0x200b, 0x200b, Control),
+        (0x200c, 0x200c, Extend), (0x200e, 0x200f, Control), (0x2028, 0x202e, Control),
+        (0x2060, 0x206f, Control), (0x20d0, 0x20f0, Extend), (0x2cef, 0x2cf1, Extend),
+        (0x2d7f, 0x2d7f, Extend), (0x2de0, 0x2dff, Extend), (0x302a, 0x302f, Extend),
+        (0x3099, 0x309a, Extend), (0xa66f, 0xa672, Extend), (0xa674, 0xa67d, Extend),
+        (0xa69e, 0xa69f, Extend), (0xa6f0, 0xa6f1, Extend), (0xa802, 0xa802, Extend),
+        (0xa806, 0xa806, Extend), (0xa80b, 0xa80b, Extend), (0xa823, 0xa824, SpacingMark),
+        (0xa825, 0xa826, Extend), (0xa827, 0xa827, SpacingMark), (0xa82c, 0xa82c, Extend),
+        (0xa880, 0xa881, SpacingMark), (0xa8b4, 0xa8c3, SpacingMark), (0xa8c4, 0xa8c5, Extend),
+        (0xa8e0, 0xa8f1, Extend), (0xa8ff, 0xa8ff, Extend), (0xa926, 0xa92d, Extend),
+        (0xa947, 0xa951, Extend), (0xa952, 0xa952, SpacingMark), (0xa953, 0xa953, Extend),
+        (0xa960, 0xa97c, L), (0xa980, 0xa982, Extend), (0xa983, 0xa983, SpacingMark),
+        (0xa9b3, 0xa9b3, Extend), (0xa9b4, 0xa9b5, SpacingMark), (0xa9b6, 0xa9b9, Extend),
+        (0xa9ba, 0xa9bb, SpacingMark), (0xa9bc, 0xa9bd, Extend), (0xa9be, 0xa9bf, SpacingMark),
+        (0xa9c0, 0xa9c0, Extend), (0xa9e5, 0xa9e5, Extend), (0xaa29, 0xaa2e, Extend),
+        (0xaa2f, 0xaa30, SpacingMark), (0xaa31, 0xaa32, Extend), (0xaa33, 0xaa34, SpacingMark),
+        (0xaa35, 0xaa36, Extend), (0xaa43, 0xaa43, Extend), (0xaa4c, 0xaa4c, Extend),
+        (0xaa4d, 0xaa4d, SpacingMark), (0xaa7c, 0xaa7c, Extend), (0xaab0, 0xaab0, Extend),
+        (0xaab2, 0xaab4, Extend), (0xaab7, 0xaab8, Extend), (0xaabe, 0xaabf, Extend),
+        (0xaac1, 0xaac1, Extend), (0xaaeb, 0xaaeb, SpacingMark), (0xaaec, 0xaaed, Extend),
+        (0xaaee, 0xaaef, SpacingMark), (0xaaf5, 0xaaf5, SpacingMark), (0xaaf6, 0xaaf6, Extend),
+        (0xabe3, 0xabe4, SpacingMark), (0xabe5, 0xabe5, Extend), (0xabe6, 0xabe7, SpacingMark),
+        (0xabe8, 0xabe8, Extend), (0xabe9, 0xabea, SpacingMark), (0xabec, 0xabec, SpacingMark),
+        (0xabed, 0xabed, Extend), (0xd7b0, 0xd7c6, V), (0xd7cb, 0xd7fb, T),
+        (0xfb1e, 0xfb1e, Extend), (0xfe00, 0xfe0f, Extend), (0xfe20, 0xfe2f, Extend),
+        (0xfeff, 0xfeff, Control), (0xff9e, 0xff9f, Extend), (0xfff0, 0xfffb, Control),
+        (0x101fd, 0x101fd, Extend), (0x102e0, 0x102e0, Extend), (0x10376, 0x1037a, Extend),
+        (0x10a01, 0x10a03, Extend), (0x10a05, 0x10a06, Extend), (0x10a0c, 0x10a0f, Extend),
+        (0x10a38, 0x10a3a, Extend), (0x10a3f, 0x10a3f, Extend), (0x10ae5, 0x10ae6, Extend),
+        (0x10d24, 0x10d27, Extend), (0x10d69, 0x10d6d, Extend), (0x10eab, 0x10eac, Extend),
+        (0x10efc, 0x10eff, Extend), (0x10f46, 0x10f50, Extend), (0x10f82, 0x10f85, Extend),
+        (0x11000, 0x11000, SpacingMark), (0x11001, 0x11001, Extend),
+        (0x11002, 0x11002, SpacingMark), (0x11038, 0x11046, Extend), (0x11070, 0x11070, Extend),
+        (0x11073, 0x11074, Extend), (0x1107f, 0x11081, Extend), (0x11082, 0x11082, SpacingMark),
+        (0x110b0, 0x110b2, SpacingMark), (0x110b3, 0x110b6, Extend),
+        (0x110b7, 0x110b8, SpacingMark), (0x110b9, 0x110ba, Extend), (0x110bd, 0x110bd, Prepend),
+        (0x110c2, 0x110c2, Extend), (0x110cd, 0x110cd, Prepend), (0x11100, 0x11102, Extend),
+        (0x11127, 0x1112b, Extend), (0x1112c, 0x1112c, SpacingMark), (0x1112d, 0x11134, Extend),
+        (0x11145, 0x11146, SpacingMark), (0x11173, 0x11173, Extend), (0x11180, 0x11181, Extend),
+        (0x11182, 0x11182, SpacingMark), (0x111b3, 0x111b5, SpacingMark),
+        (0x111b6, 0x111be, Extend), (0x111bf, 0x111bf, SpacingMark), (0x111c0, 0x111c0, Extend),
+        (0x111c2, 0x111c3, Prepend), (0x111c9, 0x111cc, Extend), (0x111ce, 0x111ce, SpacingMark),
+        (0x111cf, 0x111cf, Extend), (0x1122c, 0x1122e, SpacingMark), (0x1122f, 0x11231, Extend),
+        (0x11232, 0x11233, SpacingMark), (0x11234, 0x11237, Extend), (0x1123e, 0x1123e, Extend),
+        (0x11241, 0x11241, Extend), (0x112df, 0x112df, Extend), (0x112e0, 0x112e2, SpacingMark),
+        (0x112e3, 0x112ea, Extend), (0x11300, 0x11301, Extend), (0x11302, 0x11303, SpacingMark),
+        (0x1133b, 0x1133c, Extend), (0x1133e, 0x1133e, Extend), (0x1133f, 0x1133f, SpacingMark),
+        (0x11340, 0x11340, Extend), (0x11341, 0x11344, SpacingMark),
+        (0x11347, 0x11348, SpacingMark), (0x1134b, 0x1134c, SpacingMark),
+        (0x1134d, 0x1134d, Extend), (0x11357, 0x11357, Extend), (0x11362, 0x11363, SpacingMark),
+        (0x11366, 0x1136c, Extend), (0x11370, 0x11374, Extend), (0x113b8, 0x113b8, Extend),
+        (0x113b9, 0x113ba, SpacingMark), (0x113bb, 0x113c0, Extend), (0x113c2, 0x113c2, Extend),
+        (0x113c5, 0x113c5, Extend), (0x113c7, 0x113c9, Extend), (0x113ca, 0x113ca, SpacingMark),
+        (0x113cc, 0x113cd, SpacingMark), (0x113ce, 0x113d0, Extend), (0x113d1, 0x113d1, Prepend),
+        (0x113d2, 0x113d2, Extend), (0x113e1, 0x113e2, Extend), (0x11435, 0x11437, SpacingMark),
+        (0x11438, 0x1143f, Extend), (0x11440, 0x11441, SpacingMark), (0x11442, 0x11444, Extend),
+        (0x11445, 0x11445, SpacingMark), (0x11446, 0x11446, Extend), (0x1145e, 0x1145e, Extend),
+        (0x114b0, 0x114b0, Extend), (0x114b1, 0x114b2, SpacingMark), (0x114b3, 0x114b8, Extend),
+        (0x114b9, 0x114b9, SpacingMark), (0x114ba, 0x114ba, Extend),
+        (0x114bb, 0x114bc, SpacingMark), (0x114bd, 0x114bd, Extend),
+        (0x114be, 0x114be, SpacingMark), (0x114bf, 0x114c0, Extend),
+        (0x114c1, 0x114c1, SpacingMark), (0x114c2, 0x114c3, Extend), (0x115af, 0x115af, Extend),
+        (0x115b0, 0x115b1, SpacingMark), (0x115b2, 0x115b5, Extend),
+        (0x115b8, 0x115bb, SpacingMark), (0x115bc, 0x115bd, Extend),
+        (0x115be, 0x115be, SpacingMark), (0x115bf, 0x115c0, Extend), (0x115dc, 0x115dd, Extend),
+        (0x11630, 0x11632, SpacingMark), (0x11633, 0x1163a, Extend),
+        (0x1163b, 0x1163c, SpacingMark), (0x1163d, 0x1163d, Extend),
+        (0x1163e, 0x1163e, SpacingMark), (0x1163f, 0x11640, Extend), (0x116ab, 0x116ab, Extend),
+        (0x116ac, 0x116ac, SpacingMark), (0x116ad, 0x116ad, Extend),
+        (0x116ae, 0x116af, SpacingMark), (0x116b0, 0x116b7, Extend), (0x1171d, 0x1171d, Extend),
+        (0x1171e, 0x1171e, SpacingMark), (0x1171f, 0x1171f, Extend), (0x11722, 0x11725, Extend),
+        (0x11726, 0x11726, SpacingMark), (0x11727, 0x1172b, Extend),
+        (0x1182c, 0x1182e, SpacingMark), (0x1182f, 0x11837, Extend),
+        (0x11838, 0x11838, SpacingMark), (0x11839, 0x1183a, Extend), (0x11930, 0x11930, Extend),
+        (0x11931, 0x11935, SpacingMark), (0x11937, 0x11938, SpacingMark),
+        (0x1193b, 0x1193e, Extend), (0x1193f, 0x1193f, Prepend), (0x11940, 0x11940, SpacingMark),
+        (0x11941, 0x11941, Prepend), (0x11942, 0x11942, SpacingMark), (0x11943, 0x11943, Extend),
+        (0x119d1, 0x119d3, SpacingMark), (0x119d4, 0x119d7, Extend), (0x119da, 0x119db, Extend),
+        (0x119dc, 0x119df, SpacingMark), (0x119e0, 0x119e0, Extend),
+        (0x119e4, 0x119e4, SpacingMark), (0x11a01, 0x11a0a, Extend), (0x11a33, 0x11a38, Extend),
+        (0x11a39, 0x11a39, SpacingMark), (0x11a3a, 0x11a3a, Prepend), (0x11a3b, 0x11a3e, Extend),
+        (0x11a47, 0x11a47, Extend), (0x11a51, 0x11a56, Extend), (0x11a57, 0x11a58, SpacingMark),
+        (0x11a59, 0x11a5b, Extend), (0x11a84, 0x11a89, Prepend), (0x11a8a, 0x11a96, Extend),
+        (0x11a97, 0x11a97, SpacingMark), (0x11a98, 0x11a99, Extend),
+        (0x11c2f, 0x11c2f, SpacingMark), (0x11c30, 0x11c36, Extend), (0x11c38, 0x11c3d, Extend),
+        (0x11c3e, 0x11c3e, SpacingMark), (0x11c3f, 0x11c3f, Extend), (0x11c92, 0x11ca7, Extend),
+        (0x11ca9, 0x11ca9, SpacingMark), (0x11caa, 0x11cb0, Extend),
+        (0x11cb1, 0x11cb1, SpacingMark), (0x11cb2, 0x11cb3, Extend),
+        (0x11cb4, 0x11cb4, SpacingMark), (0x11cb5, 0x11cb6, Extend), (0x11d31, 0x11d36, Extend),
+        (0x11d3a, 0x11d3a, Extend), (0x11d3c, 0x11d3d, Extend), (0x11d3f, 0x11d45, Extend),
+        (0x11d46, 0x11d46, Prepend), (0x11d47, 0x11d47, Extend), (0x11d8a, 0x11d8e, SpacingMark),
+        (0x11d90, 0x11d91, Extend), (0x11d93, 0x11d94, SpacingMark), (0x11d95, 0x11d95, Extend),
+        (0x11d96, 0x11d96, SpacingMark), (0x11d97, 0x11d97, Extend), (0x11ef3, 0x11ef4, Extend),
+        (0x11ef5, 0x11ef6, SpacingMark), (0x11f00, 0x11f01, Extend), (0x11f02, 0x11f02, Prepend),
+        (0x11f03, 0x11f03, SpacingMark), (0x11f34, 0x11f35, SpacingMark),
+        (0x11f36, 0x11f3a, Extend), (0x11f3e, 0x11f3f, SpacingMark), (0x11f40, 0x11f42, Extend),
+        (0x11f5a, 0x11f5a, Extend), (0x13430, 0x1343f, Control), (0x13440, 0x13440, Extend),
+        (0x13447, 0x13455, Extend), (0x1611e, 0x16129, Extend), (0x1612a, 0x1612c, SpacingMark),
+        (0x1612d, 0x1612f, Extend), (0x16af0, 0x16af4, Extend), (0x16b30, 0x16b36, Extend),
+        (0x16d63, 0x16d63, V), (0x16d67, 0x16d6a, V), (0x16f4f, 0x16f4f, Extend),
+        (0x16f51, 0x16f87, SpacingMark), (0x16f8f, 0x16f92, Extend), (0x16fe4, 0x16fe4, Extend),
+        (0x16ff0, 0x16ff1, Extend), (0x1bc9d, 0x1bc9e, Extend), (0x1bca0, 0x1bca3, Control),
+        (0x1cf00, 0x1cf2d, Extend), (0x1cf30, 0x1cf46, Extend), (0x1d165, 0x1d169, Extend),
+        (0x1d16d, 0x1d172, Extend), (0x1d173, 0x1d17a, Control), (0x1d17b, 0x1d182, Extend),
+        (0x1d185, 0x1d18b, Extend), (0x1d1aa, 0x1d1ad, Extend), (0x1d242, 0x1d244, Extend),
+        (0x1da00, 0x1da36, Extend), (0x1da3b, 0x1da6c, Extend), (0x1da75, 0x1da75, Extend),
+        (0x1da84, 0x1da84, Extend), (0x1da9b, 0x1da9f, Extend), (0x1daa1, 0x1daaf, Extend),
+        (0x1e000, 0x1e006, Extend), (0x1e008, 0x1e018, Extend), (0x1e01b, 0x1e021, Extend),
+        (0x1e023, 0x1e024, Extend), (0x1e026, 0x1e02a, Extend), (0x1e08f, 0x1e08f, Extend),
+        (0x1e130, 0x1e136, Extend), (0x1e2ae, 0x1e2ae, Extend), (0x1e2ec, 0x1e2ef, Extend),
+        (0x1e4ec, 0x1e4ef, Extend), (0x1e5ee, 0x1e5ef, Extend), (0x1e8d0, 0x1e8d6, Extend),
+        (0x1e944, 0x1e94a, Extend), (0x1f3fb, 0x1f3ff, Extend), (0xe0000, 0xe001f, Control),
+        (0xe0020, 0xe007f, Extend), (0xe0080, 0xe00ff, Control), (0xe0100, 0xe01ef, Extend),
+        (0xe01f0, 0xe0fff, Control),
+    ]
+};
+
+/// Ranges of the `Extended_Pictographic` property.
+#[rustfmt::skip]
+const EXTENDED_PICTOGRAPHIC: &[(u32, u32)] = &[
+    (0x00a9, 0x00a9), (0x00ae, 0x00ae), (0x203c, 0x203c), (0x2049, 0x2049), (0x2122, 0x2122),
+    (0x2139, 0x2139), (0x2194, 0x2199), (0x21a9, 0x21aa), (0x231a, 0x231b), (0x2328, 0x2328),
+    (0x2388, 0x2388), (0x23cf, 0x23cf), (0x23e9, 0x23f3), (0x23f8, 0x23fa), (0x24c2, 0x24c2),
+    (0x25aa, 0x25ab), (0x25b6, 0x25b6), (0x25c0, 0x25c0), (0x25fb, 0x25fe), (0x2600, 0x2605),
+    (0x2607, 0x2612), (0x2614, 0x2685), (0x2690, 0x2705), (0x2708, 0x2712), (0x2714, 0x2714),
+    (0x2716, 0x2716), (0x271d, 0x271d), (0x2721, 0x2721), (0x2728, 0x2728), (0x2733, 0x2734),
+    (0x2744, 0x2744), (0x2747, 0x2747), (0x274c, 0x274c), (0x274e, 0x274e), (0x2753, 0x2755),
+    (0x2757, 0x2757), (0x2763, 0x2767), (0x2795, 0x2797), (0x27a1, 0x27a1), (0x27b0, 0x27b0),
+    (0x27bf, 0x27bf), (0x2934, 0x2935), (0x2b05, 0x2b07), (0x2b1b, 0x2b1c), (0x2b50, 0x2b50),
+    (0x2b55, 0x2b55), (0x3030, 0x3030), (0x303d, 0x303d), (0x3297, 0x3297), (0x3299, 0x3299),
+    (0x1f000, 0x1f0ff), (0x1f10d, 0x1f10f), (0x1f12f, 0x1f12f), (0x1f16c, 0x1f171),
+    (0x1f17e, 0x1f17f), (0x1f18e, 0x1f18e), (0x1f191, 0x1f19a), (0x1f1ad, 0x1f1e5),
+    (0x1f201, 0x1f20f), (0x1f21a, 0x1f21a), (0x1f22f, 0x1f22f), (0x1f232, 0x1f23a),
+    (0x1f23c, 0x1f23f), (0x1f249, 0x1f3fa), (0x1f400, 0x1f53d), (0x1f546, 0x1f64f),
+    (0x1f680, 0x1f6ff), (0x1f774, 0x1f77f), (0x1f7d5, 0x1f7ff), (0x1f80c, 0x1f80f),
+    (0x1f848, 0x1f84f), (0x1f85a, 0x1f85f), (0x1f888, 0x1f88f), (0x1f8ae, 0x1f8ff),
+    (0x1f90c, 0x1f93a), (0x1f93c, 0x1f945), (0x1f947, 0x1faff), (0x1fc00, 0x1fffd),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zwj_sequences() {
+        // Man, ZWJ, woman.
+        assert!(extends_cluster('\u{1f468}', &[], ZWJ));
+        assert!(extends_cluster('\u{1f468}', &[ZWJ], '\u{1f469}'));
+        assert!(!extends_cluster('\u{1f468}', &[], '\u{1f469}'));
+
+        // Only pictographs are joined.
+        assert!(!extends_cluster('\u{1f468}', &[ZWJ], 'a'));
+
+        // The joiner must follow a pictograph, optionally with extending characters.
+        assert!(extends_cluster('\u{1f44d}', &['\u{1f3fd}', ZWJ], '\u{1f469}'));
+        assert!(!extends_cluster('a', &[ZWJ], '\u{1f469}'));
+    }
+
+    #[test]
+    fn hangul_syllables() {
+        let (l, v, t) = ('\u{1100}', '\u{1161}', '\u{11a8}');
+        assert!(extends_cluster(l, &[], l));
+        assert!(extends_cluster(l, &[], v));
+        assert!(extends_cluster(l, &[v], t));
+        assert!(!extends_cluster(l, &[v, t], v));
+
+        // Precomposed LV and LVT syllables.
+        assert!(extends_cluster('\u{ac00}', &[], v));
+        assert!(extends_cluster('\u{ac00}', &[], t));
+        assert!(!extends_cluster('\u{ac01}', &[], v));
+        assert!(extends_cluster('\u{ac01}', &[], t));
+        assert!(!extends_cluster(t, &[], l));
+    }
+
+    #[test]
+    fn spacing_marks_and_prepend() {
+        // Devanagari KA with the spacing vowel sign AA.
+        assert!(extends_cluster('\u{915}', &[], '\u{93e}'));
+
+        // Arabic number sign prepended to a digit.
+        assert!(extends_cluster('\u{600}', &[], '1'));
+        assert!(!extends_cluster('1', &[], '\u{600}'));
+    }
+
+    #[test]
+    fn regional_indicator_pairs() {
+        let (u, s) = ('\u{1f1fa}', '\u{1f1f8}');
+        assert!(extends_cluster(u, &[], s));
+        assert!(!extends_cluster(u, &[s], u));
+        assert!(!extends_cluster('a', &[], u));
+    }
+
+    #[test]
+    fn modifiers_and_variation_selectors() {
+        assert!(extends_cluster('\u{1f44d}', &[], '\u{1f3fd}'));
+        assert!(extends_cluster('\u{2764}', &[], VS16));
+        assert!(extends_cluster('e', &[], '\u{301}'));
+        assert!(!extends_cluster('e', &[], 'e'));
+
+        assert!(forces_emoji_presentation(VS16));
+        assert!(!forces_emoji_presentation('\u{301}'));
+
+        assert!(extends_cluster('\u{231a}', &[], VS15));
+        assert!(forces_text_presentation('\u{231a}', &[], VS15));
+        assert!(!forces_text_presentation('\u{231a}', &[VS16], VS15));
+        assert!(!forces_text_presentation('a', &[], VS15));
+    }
+}
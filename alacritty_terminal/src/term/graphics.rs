@@ -0,0 +1,613 @@
+//! Display of inline images.
+
+use std::sync::Arc;
+use std::{mem, ptr};
+
+use log::debug;
+
+use crate::event::{Event, EventListener};
+use crate::graphics::kitty::{self, Action, Command};
+use crate::graphics::{
+    GraphicCell, GraphicData, GraphicPlacement, PlacedGraphic, MAX_IMAGE_SIZE, MAX_STORAGE_SIZE,
+};
+use crate::grid::Dimensions;
+use crate::index::{Column, Line, Point};
+use crate::term::{Term, TermMode};
+use crate::vte::ansi::Handler;
+
+impl<T> Term<T> {
+    /// Update the size of a cell in pixels.
+    ///
+    /// Images can only be displayed once the cell size is known.
+    pub fn set_cell_size(&mut self, cell_width: usize, cell_height: usize) {
+        self.graphics.cell_width = cell_width;
+        self.graphics.cell_height = cell_height;
+    }
+
+    /// Remove all placements matching `filter` from the grid.
+    ///
+    /// Only the lines which contained the placements when they were inserted are checked, unless
+    /// the placements have been moved since, like by a resize or scrolling region.
+    fn remove_placements<F>(&mut self, filter: F)
+    where
+        F: Fn(&GraphicPlacement) -> bool,
+    {
+        let alt_screen = self.mode.contains(TermMode::ALT_SCREEN);
+        let mut placements = mem::take(&mut self.graphics.placements);
+        let mut full_scan = false;
+
+        placements.retain(|placed| {
+            let matches = placed.alt_screen == alt_screen
+                && placed.placement.upgrade().is_some_and(|placement| filter(&placement));
+            if !matches {
+                return placed.placement.strong_count() > 0;
+            }
+
+            let placement = placed.placement.as_ptr();
+            for line in self.placement_lines(placed) {
+                for cell in &mut self.grid[line][..] {
+                    if cell.graphic().is_some_and(|graphic| ptr::eq(&*graphic.placement, placement))
+                    {
+                        cell.set_graphic(None);
+                    }
+                }
+            }
+
+            // Fall back to checking every line if the placement moved.
+            full_scan |= placed.placement.strong_count() > 0;
+
+            false
+        });
+
+        self.graphics.placements = placements;
+
+        if full_scan {
+            // Archived lines never contain images.
+            let topmost_line = self.topmost_line() + self.grid.archived_lines();
+            for line in (topmost_line.0..=self.bottommost_line().0).map(Line) {
+                for cell in &mut self.grid[line][..] {
+                    if cell.graphic().is_some_and(|graphic| filter(&graphic.placement)) {
+                        cell.set_graphic(None);
+                    }
+                }
+            }
+        }
+
+        self.mark_fully_damaged();
+    }
+
+    /// Lines of the grid which were covered by a placement when it was inserted.
+    fn placement_lines(&self, placed: &PlacedGraphic) -> impl Iterator<Item = Line> {
+        let lines = placed.placement.upgrade().map_or(0, |placement| placement.lines);
+        let line = placed.line as i32 - self.grid.scrolled_lines() as i32;
+
+        // Archived lines never contain images.
+        let topmost_line = self.topmost_line() + self.grid.archived_lines();
+        let start = line.max(topmost_line.0);
+        let end = (line + lines as i32).min(self.bottommost_line().0 + 1);
+
+        (start..end).map(Line)
+    }
+
+    /// Placements covering any visible cell matching `filter`.
+    fn visible_placements<F>(&self, filter: F) -> Vec<Arc<GraphicPlacement>>
+    where
+        F: Fn(Point) -> bool,
+    {
+        let mut placements: Vec<Arc<GraphicPlacement>> = Vec::new();
+
+        for line in (0..self.screen_lines() as i32).map(Line) {
+            for (column, cell) in self.grid[line][..].iter().enumerate() {
+                let graphic = match cell.graphic() {
+                    Some(graphic) if filter(Point::new(line, Column(column))) => graphic,
+                    _ => continue,
+                };
+
+                if !placements.iter().any(|placement| Arc::ptr_eq(placement, &graphic.placement)) {
+                    placements.push(graphic.placement.clone());
+                }
+            }
+        }
+
+        placements
+    }
+}
+
+impl<T: EventListener> Term<T> {
+    /// Display an image at the cursor position.
+    ///
+    /// When `move_cursor` is `true`, the screen is scrolled as necessary to fit the image and the
+    /// cursor is placed after the image's last line. Otherwise the image is clipped at the bottom
+    /// of the screen.
+    pub(crate) fn insert_graphic(&mut self, placement: GraphicPlacement, move_cursor: bool) {
+        let placement = Arc::new(placement);
+        let template = self.grid.cursor.template.clone();
+        let start = self.grid.cursor.point;
+
+        self.graphics.placements.retain(|placed| placed.placement.strong_count() > 0);
+        self.graphics.placements.push(PlacedGraphic {
+            placement: Arc::downgrade(&placement),
+            line: self.grid.scrolled_lines() + start.line.0 as usize,
+            alt_screen: self.mode.contains(TermMode::ALT_SCREEN),
+        });
+
+        let columns = placement.columns.min(self.columns() - start.column.0);
+
+        for line in 0..placement.lines {
+            let point_line = if move_cursor {
+                if line > 0 {
+                    self.linefeed();
+                }
+                self.grid.cursor.point.line
+            } else if start.line + line <= self.bottommost_line() {
+                start.line + line
+            } else {
+                break;
+            };
+
+            let row = &mut self.grid[point_line];
+            for column in 0..columns {
+                let cell = &mut row[start.column + column];
+                *cell = template.clone();
+                cell.set_graphic(Some(GraphicCell { placement: placement.clone(), column, line }));
+            }
+
+            let last_column = start.column.0 + columns - 1;
+            self.damage.damage_line(point_line.0 as usize, start.column.0, last_column);
+        }
+
+        if move_cursor {
+            let column = start.column.0 + placement.columns;
+            self.grid.cursor.point.column = Column(column.min(self.columns() - 1));
+            self.grid.cursor.input_needs_wrap = column >= self.columns();
+        }
+    }
+
+    /// Display a sixel image.
+    ///
+    /// In sixel display mode (DECSDM), the image is displayed at the top-left corner of the
+    /// screen without moving the cursor. Otherwise it is displayed at the cursor position and the
+    /// cursor is moved to the start of the line below the image.
+    pub(super) fn insert_sixel(&mut self, graphic: GraphicData) {
+        let (cell_width, cell_height) = (self.graphics.cell_width, self.graphics.cell_height);
+        if cell_width == 0 || cell_height == 0 {
+            debug!("[sixel] Ignoring image, cell size is unknown");
+            return;
+        }
+
+        let (width, height) = (graphic.width, graphic.height);
+        let placement = GraphicPlacement {
+            graphic: Arc::new(graphic),
+            source_x: 0,
+            source_y: 0,
+            source_width: width,
+            source_height: height,
+            offset_x: 0,
+            offset_y: 0,
+            width,
+            height,
+            columns: (width + cell_width - 1) / cell_width,
+            lines: (height + cell_height - 1) / cell_height,
+            image_id: 0,
+            placement_id: 0,
+        };
+
+        if self.mode.contains(TermMode::SIXEL_DISPLAY) {
+            let cursor = self.grid.cursor.point;
+            self.grid.cursor.point = Point::new(Line(0), Column(0));
+            self.insert_graphic(placement, false);
+            self.grid.cursor.point = cursor;
+        } else {
+            self.insert_graphic(placement, true);
+            self.linefeed();
+            self.carriage_return();
+        }
+    }
+
+    /// Process a kitty graphics command.
+    pub(super) fn kitty_graphics_command(&mut self, mut command: Command) {
+        // Append chunks to the pending transmission.
+        if let Some(mut transmission) = self.graphics.transmission.take() {
+            // Stop accumulating oversized payloads, decoding will reject them.
+            if transmission.payload.len() <= MAX_STORAGE_SIZE {
+                transmission.payload.append(&mut command.payload);
+            }
+            transmission.more = command.more;
+            command = transmission;
+        }
+
+        let transmission =
+            matches!(command.action, Action::Transmit | Action::TransmitAndPut | Action::Query);
+        if command.more && transmission {
+            self.graphics.transmission = Some(command);
+            return;
+        }
+
+        let result = match command.action {
+            Action::Transmit | Action::TransmitAndPut => self.kitty_transmit(&mut command),
+            Action::Put => self.kitty_put(&mut command),
+            Action::Query => command.decode().map(drop),
+            Action::Delete => {
+                self.kitty_delete(&command);
+                return;
+            },
+        };
+
+        if let Err(err) = result {
+            debug!("[kitty graphics] {}", err);
+        }
+
+        if let Some(response) = command.response(result) {
+            self.event_proxy.send_event(Event::PtyWrite(response));
+        }
+    }
+
+    /// Store a transmitted image.
+    fn kitty_transmit(&mut self, command: &mut Command) -> Result<(), kitty::Error> {
+        let graphic = Arc::new(command.decode()?);
+        command.payload = Vec::new();
+
+        // Assign an ID to images which are only identified by their number.
+        if command.image_id == 0 && command.image_number != 0 {
+            command.image_id = self.graphics.unused_id();
+        }
+
+        if command.image_id != 0 {
+            self.graphics.insert(command.image_id, command.image_number, graphic.clone());
+        }
+
+        if command.action == Action::TransmitAndPut {
+            self.kitty_place(command, graphic)?;
+        }
+
+        Ok(())
+    }
+
+    /// Display a stored image.
+    fn kitty_put(&mut self, command: &mut Command) -> Result<(), kitty::Error> {
+        if command.image_id == 0 && command.image_number != 0 {
+            let image_id = self.graphics.find_number(command.image_number);
+            command.image_id = image_id.ok_or(kitty::Error::NotFound)?;
+        }
+
+        let graphic = self.graphics.get(command.image_id).ok_or(kitty::Error::NotFound)?;
+        self.kitty_place(command, graphic.clone())
+    }
+
+    /// Display an image at the cursor position.
+    fn kitty_place(
+        &mut self,
+        command: &Command,
+        graphic: Arc<GraphicData>,
+    ) -> Result<(), kitty::Error> {
+        let (cell_width, cell_height) = (self.graphics.cell_width, self.graphics.cell_height);
+        if cell_width == 0 || cell_height == 0 {
+            return Err(kitty::Error::InvalidData);
+        }
+
+        // Clip the displayed region to the image.
+        let source_x = (command.source_x as usize).min(graphic.width);
+        let source_y = (command.source_y as usize).min(graphic.height);
+        let mut source_width = graphic.width - source_x;
+        if command.source_width != 0 {
+            source_width = source_width.min(command.source_width as usize);
+        }
+        let mut source_height = graphic.height - source_y;
+        if command.source_height != 0 {
+            source_height = source_height.min(command.source_height as usize);
+        }
+        if source_width == 0 || source_height == 0 {
+            return Err(kitty::Error::InvalidData);
+        }
+
+        // Scale the image to the requested cells, keeping its aspect ratio if only one is set.
+        let (width, height) = match (command.columns as usize, command.rows as usize) {
+            (0, 0) => (source_width, source_height),
+            (columns, 0) => {
+                let width = columns * cell_width;
+                (width, source_height * width / source_width)
+            },
+            (0, rows) => {
+                let height = rows * cell_height;
+                (source_width * height / source_height, height)
+            },
+            (columns, rows) => (columns * cell_width, rows * cell_height),
+        };
+        if width == 0 || height == 0 {
+            return Err(kitty::Error::InvalidData);
+        } else if width > MAX_IMAGE_SIZE || height > MAX_IMAGE_SIZE {
+            return Err(kitty::Error::TooLarge);
+        }
+
+        let offset_x = (command.offset_x as usize).min(cell_width - 1);
+        let offset_y = (command.offset_y as usize).min(cell_height - 1);
+
+        // Replace the previous placement with the same ID.
+        if command.image_id != 0 && command.placement_id != 0 {
+            self.remove_placements(|placement| {
+                placement.image_id == command.image_id
+                    && placement.placement_id == command.placement_id
+            });
+        }
+
+        let placement = GraphicPlacement {
+            graphic,
+            source_x,
+            source_y,
+            source_width,
+            source_height,
+            offset_x,
+            offset_y,
+            width,
+            height,
+            columns: (offset_x + width + cell_width - 1) / cell_width,
+            lines: (offset_y + height + cell_height - 1) / cell_height,
+            image_id: command.image_id,
+            placement_id: command.placement_id,
+        };
+        self.insert_graphic(placement, !command.fixed_cursor);
+
+        Ok(())
+    }
+
+    /// Delete images and their placements.
+    ///
+    /// Lowercase targets only remove placements, while uppercase targets also free the image data
+    /// once it is no longer displayed.
+    fn kitty_delete(&mut self, command: &Command) {
+        let free = command.delete.is_ascii_uppercase();
+
+        let image_ids: Vec<u32> = match command.delete.to_ascii_lowercase() {
+            target @ (b'i' | b'n') => {
+                let image_id = match target {
+                    b'n' => self.graphics.find_number(command.image_number),
+                    _ => Some(command.image_id),
+                };
+
+                let image_id = match image_id {
+                    Some(image_id) if image_id != 0 => image_id,
+                    _ => return,
+                };
+
+                self.remove_placements(|placement| {
+                    placement.image_id == image_id
+                        && (command.placement_id == 0
+                            || placement.placement_id == command.placement_id)
+                });
+
+                vec![image_id]
+            },
+            target @ (b'a' | b'c' | b'p' | b'x' | b'y') => {
+                let cursor = self.grid.cursor.point;
+                let column = Column((command.source_x as usize).saturating_sub(1));
+                let line = Line((command.source_y as i32).saturating_sub(1));
+                let placements = self.visible_placements(|point| match target {
+                    b'c' => point == cursor,
+                    b'p' => point == Point::new(line, column),
+                    b'x' => point.column == column,
+                    b'y' => point.line == line,
+                    _ => true,
+                });
+
+                // Only keep the addresses, so removed placements are dropped.
+                let image_ids = placements.iter().map(|placement| placement.image_id).collect();
+                let placements: Vec<_> =
+                    placements.into_iter().map(|placement| Arc::as_ptr(&placement)).collect();
+
+                self.remove_placements(|placement| {
+                    placements.iter().any(|removed| ptr::eq(*removed, placement))
+                });
+
+                image_ids
+            },
+            target => {
+                debug!("[unhandled kitty graphics] delete target {:?}", target as char);
+                return;
+            },
+        };
+
+        if free {
+            self.graphics.remove_unused(image_ids);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::event::VoidListener;
+    use crate::parser::Processor;
+    use crate::term::test::TermSize;
+    use crate::term::Config;
+
+    fn term() -> Term<VoidListener> {
+        let size = TermSize::new(10, 5);
+        let mut term = Term::new(Config::default(), &size, VoidListener);
+        term.set_cell_size(2, 4);
+        term
+    }
+
+    fn parse(term: &mut Term<VoidListener>, bytes: &[u8]) {
+        let mut parser = Processor::new();
+        for byte in bytes {
+            parser.advance(term, *byte);
+        }
+    }
+
+    /// Placement ID of the graphic at a point.
+    fn placement_at(term: &Term<VoidListener>, line: i32, column: usize) -> Option<u32> {
+        let cell = &term.grid()[Line(line)][Column(column)];
+        cell.graphic().map(|graphic| graphic.placement.placement_id)
+    }
+
+    #[test]
+    fn transmit_and_put() {
+        let mut term = term();
+        term.input('a');
+
+        // 3x5 red RGB image, covering 2x2 cells.
+        let pixels = "/wAA".repeat(15);
+        parse(&mut term, format!("\x1b_Ga=T,f=24,s=3,v=5,i=1,p=2;{pixels}\x1b\\").as_bytes());
+
+        for (line, column) in [(0, 1), (0, 2), (1, 1), (1, 2)] {
+            assert_eq!(placement_at(&term, line, column), Some(2));
+        }
+        assert_eq!(placement_at(&term, 0, 0), None);
+        assert_eq!(placement_at(&term, 0, 3), None);
+        assert_eq!(placement_at(&term, 2, 1), None);
+        assert_eq!(term.grid().cursor.point, Point::new(Line(1), Column(3)));
+
+        let graphic = term.grid()[Line(1)][Column(2)].graphic().unwrap();
+        assert_eq!((graphic.column, graphic.line), (1, 1));
+        assert_eq!((graphic.placement.width, graphic.placement.height), (3, 5));
+        assert_eq!(graphic.placement.graphic.pixels[..4], [255, 0, 0, 255]);
+
+        // Placements scroll with the grid.
+        for _ in 0..4 {
+            term.newline();
+        }
+        assert_eq!(placement_at(&term, -1, 1), Some(2));
+        assert_eq!(placement_at(&term, 0, 1), Some(2));
+
+        // Placements are removed with the content.
+        term.clear_screen(crate::vte::ansi::ClearMode::All);
+        assert_eq!(placement_at(&term, -1, 1), Some(2));
+        assert_eq!(placement_at(&term, 0, 1), None);
+    }
+
+    #[test]
+    fn chunked_transmission() {
+        let mut term = term();
+
+        let pixels = "/wAAAA==";
+        parse(&mut term, b"\x1b_Ga=t,s=1,v=1,i=5,m=1;/wAA\x1b\\");
+        assert!(term.graphics.get(5).is_none());
+        parse(&mut term, b"\x1b_Gm=0;AA==\x1b\\");
+        assert!(term.graphics.get(5).is_some());
+
+        // Put the image scaled to three columns without moving the cursor.
+        parse(&mut term, b"\x1b_Ga=p,i=5,c=3,C=1\x1b\\");
+        let graphic = term.grid()[Line(0)][Column(2)].graphic().unwrap();
+        assert_eq!((graphic.placement.width, graphic.placement.height), (6, 6));
+        assert_eq!(placement_at(&term, 1, 2), Some(0));
+        assert_eq!(term.grid().cursor.point, Point::new(Line(0), Column(0)));
+
+        parse(&mut term, format!("\x1b_Ga=T,s=1,v=1,i=6;{pixels}\x1b\\").as_bytes());
+        assert!(term.graphics.get(6).is_some());
+    }
+
+    #[test]
+    fn replace_placement() {
+        let mut term = term();
+
+        parse(&mut term, b"\x1b_Ga=t,s=1,v=1,i=1;/wAAAA==\x1b\\");
+        parse(&mut term, b"\x1b_Ga=p,i=1,p=1\x1b\\");
+        parse(&mut term, b"\x1b_Ga=p,i=1,p=1\x1b\\");
+        parse(&mut term, b"\x1b_Ga=p,i=1,p=2\x1b\\");
+
+        assert_eq!(placement_at(&term, 0, 0), None);
+        assert_eq!(placement_at(&term, 0, 1), Some(1));
+        assert_eq!(placement_at(&term, 0, 2), Some(2));
+    }
+
+    #[test]
+    fn replace_placement_in_history() {
+        let mut term = term();
+
+        parse(&mut term, b"\x1b_Ga=t,s=1,v=1,i=1;/wAAAA==\x1b\\");
+        parse(&mut term, b"\x1b_Ga=p,i=1,p=1,C=1\x1b\\");
+        term.grid_mut().cursor.point.column = Column(1);
+        parse(&mut term, b"\x1b_Ga=p,i=1,p=2,C=1\x1b\\");
+        term.grid_mut().cursor.point.column = Column(0);
+        for _ in 0..20 {
+            term.newline();
+        }
+        assert_eq!(placement_at(&term, -16, 0), Some(1));
+        assert_eq!(placement_at(&term, -16, 1), Some(2));
+
+        parse(&mut term, b"\x1b_Ga=p,i=1,p=1\x1b\\");
+        parse(&mut term, b"\x1b_Ga=d,d=i,i=1,p=2\x1b\\");
+        assert_eq!(placement_at(&term, -16, 0), None);
+        assert_eq!(placement_at(&term, -16, 1), None);
+        assert_eq!(placement_at(&term, 4, 0), Some(1));
+
+        // Removed placements are no longer tracked.
+        assert_eq!(term.graphics.placements.len(), 1);
+    }
+
+    #[test]
+    fn replace_moved_placement() {
+        let mut term = term();
+
+        term.grid_mut().cursor.point = Point::new(Line(2), Column(0));
+        parse(&mut term, b"\x1b_Ga=t,s=1,v=1,i=1;/wAAAA==\x1b\\");
+        parse(&mut term, b"\x1b_Ga=p,i=1,p=1,C=1\x1b\\");
+
+        // Scrolling regions move placements without changing their tracked position.
+        term.grid_mut().scroll_up(&(Line(1)..Line(5)), 1);
+        assert_eq!(placement_at(&term, 1, 0), Some(1));
+
+        parse(&mut term, b"\x1b_Ga=d,d=i,i=1\x1b\\");
+        assert_eq!(placement_at(&term, 1, 0), None);
+        assert!(term.graphics.placements.is_empty());
+    }
+
+    #[test]
+    fn delete() {
+        let mut term = term();
+
+        parse(&mut term, b"\x1b_Ga=T,s=1,v=1,i=1,p=1;/wAAAA==\x1b\\");
+        parse(&mut term, b"\x1b_Ga=T,s=1,v=1,i=2,p=1;/wAAAA==\x1b\\");
+        parse(&mut term, b"\x1b_Ga=p,i=2,p=2\x1b\\");
+
+        // Delete placement at cursor.
+        term.grid_mut().cursor.point.column = Column(1);
+        parse(&mut term, b"\x1b_Ga=d,d=c\x1b\\");
+        assert_eq!(placement_at(&term, 0, 0), Some(1));
+        assert_eq!(placement_at(&term, 0, 1), None);
+        assert_eq!(placement_at(&term, 0, 2), Some(2));
+
+        // Delete image and free its data.
+        parse(&mut term, b"\x1b_Ga=d,d=I,i=2\x1b\\");
+        assert_eq!(placement_at(&term, 0, 2), None);
+        assert!(term.graphics.get(2).is_none());
+
+        // Delete all placements, keeping the image data.
+        parse(&mut term, b"\x1b_Ga=d\x1b\\");
+        assert_eq!(placement_at(&term, 0, 0), None);
+        assert!(term.graphics.get(1).is_some());
+
+        term.reset_state();
+        assert!(term.graphics.get(1).is_none());
+    }
+
+    #[test]
+    fn sixel_cursor_movement() {
+        let mut term = term();
+        term.grid_mut().cursor.point = Point::new(Line(3), Column(1));
+
+        // 3x6 image, covering 2x2 cells.
+        parse(&mut term, b"\x1bPq#1;2;100;0;0!3~\x1b\\");
+
+        for (line, column) in [(2, 1), (2, 2), (3, 1), (3, 2)] {
+            assert_eq!(placement_at(&term, line, column), Some(0));
+        }
+        assert_eq!(placement_at(&term, 1, 1), None);
+        assert_eq!(placement_at(&term, 4, 1), None);
+        assert_eq!(term.grid().cursor.point, Point::new(Line(4), Column(0)));
+    }
+
+    #[test]
+    fn sixel_display_mode() {
+        let mut term = term();
+        term.grid_mut().cursor.point = Point::new(Line(3), Column(4));
+
+        parse(&mut term, b"\x1b[?80h\x1bPq!3~\x1b\\");
+
+        assert_eq!(placement_at(&term, 0, 0), Some(0));
+        assert_eq!(placement_at(&term, 1, 1), Some(0));
+        assert_eq!(placement_at(&term, 3, 4), None);
+        assert_eq!(term.grid().cursor.point, Point::new(Line(3), Column(4)));
+    }
+}
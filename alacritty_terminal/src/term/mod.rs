@@ -10,15 +10,23 @@ use serde::{Deserialize, Serialize};
 use base64::engine::general_purpose::STANDARD as Base64;
 use base64::Engine;
 use bitflags::bitflags;
-use log::{debug, trace};
+use log::{debug, error, trace};
 use unicode_width::UnicodeWidthChar;
 
 use crate::event::{Event, EventListener};
+use crate::graphics::{kitty, sixel, GraphicData, Graphics};
 use crate::grid::{Dimensions, Grid, GridIterator, Scroll};
 use crate::index::{self, Boundary, Column, Direction, Line, Point, Side};
+use crate::parser::ExtendedHandler;
 use crate::selection::{Selection, SelectionRange, SelectionType};
 use crate::term::cell::{Cell, Flags, LineLength};
 use crate::term::color::Colors;
+use crate::term::color_scheme::{ColorScheme, COLOR_SCHEME_UPDATES_MODE};
+use crate::term::export::CellWriter;
+use crate::term::notification::Notification;
+use crate::term::prompt::{CommandBlocks, SemanticMark};
+use crate::term::search::RegexSearch;
+use crate::term::working_directory::WorkingDirectory;
 use crate::vi_mode::{ViModeCursor, ViMotion};
 use crate::vte::ansi::{
     self, Attr, CharsetIndex, Color, CursorShape, CursorStyle, Handler, Hyperlink, KeyboardModes,
@@ -28,7 +36,15 @@ use crate::vte::ansi::{
 
 pub mod cell;
 pub mod color;
+pub mod color_scheme;
+pub mod export;
+pub mod grapheme;
+mod graphics;
+pub mod notification;
+pub mod persist;
+pub mod prompt;
 pub mod search;
+pub mod working_directory;
 
 /// Minimum number of columns.
 ///
@@ -78,6 +94,9 @@ bitflags! {
         const REPORT_ALTERNATE_KEYS   = 0b0001_0000_0000_0000_0000_0000;
         const REPORT_ALL_KEYS_AS_ESC  = 0b0010_0000_0000_0000_0000_0000;
         const REPORT_ASSOCIATED_TEXT  = 0b0100_0000_0000_0000_0000_0000;
+        const SIXEL_DISPLAY           = 0b1000_0000_0000_0000_0000_0000;
+        const GRAPHEME_CLUSTERING     = 0b0001_0000_0000_0000_0000_0000_0000;
+        const COLOR_SCHEME_UPDATES    = 0b0010_0000_0000_0000_0000_0000_0000;
         const KITTY_KEYBOARD_PROTOCOL = Self::DISAMBIGUATE_ESC_CODES.bits()
                                       | Self::REPORT_EVENT_TYPES.bits()
                                       | Self::REPORT_ALTERNATE_KEYS.bits()
@@ -327,6 +346,18 @@ pub struct Term<T> {
 
     /// Config directly for the terminal.
     config: Config,
+
+    /// Compiled regex for locating shell prompts.
+    prompt_regex: Option<RegexSearch>,
+
+    /// Images transmitted by the client.
+    graphics: Graphics,
+
+    /// Working directory reported by the shell.
+    working_directory: Option<WorkingDirectory>,
+
+    /// Color scheme reported to applications.
+    color_scheme: ColorScheme,
 }
 
 /// Configuration options for the [`Term`].
@@ -335,6 +366,12 @@ pub struct Config {
     /// The maximum amount of scrolling history.
     pub scrolling_history: usize,
 
+    /// The maximum amount of compressed history kept beyond the scrolling history.
+    pub scrolling_archive: usize,
+
+    /// Store the compressed history in a temporary file instead of memory.
+    pub scrolling_archive_spill: bool,
+
     /// Default cursor style to reset the cursor to.
     pub default_cursor_style: CursorStyle,
 
@@ -351,17 +388,23 @@ pub struct Config {
 
     /// OSC52 support mode.
     pub osc52: Osc52,
+
+    /// Regex used to locate shell prompts.
+    pub prompt_regex: Option<String>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             scrolling_history: 10000,
+            scrolling_archive: Default::default(),
+            scrolling_archive_spill: Default::default(),
             semantic_escape_chars: SEMANTIC_ESCAPE_CHARS.to_owned(),
             default_cursor_style: Default::default(),
             vi_mode_cursor_style: Default::default(),
             kitty_keyboard: Default::default(),
             osc52: Default::default(),
+            prompt_regex: Default::default(),
         }
     }
 }
@@ -412,7 +455,8 @@ impl<T> Term<T> {
         let num_lines = dimensions.screen_lines();
 
         let history_size = options.scrolling_history;
-        let grid = Grid::new(num_lines, num_cols, history_size);
+        let mut grid = Grid::new(num_lines, num_cols, history_size);
+        grid.update_archive(options.scrolling_archive, options.scrolling_archive_spill);
         let alt = Grid::new(num_lines, num_cols, 0);
 
         let tabs = TabStops::new(grid.columns());
@@ -422,6 +466,8 @@ impl<T> Term<T> {
         // Initialize terminal damage, covering the entire terminal upon launch.
         let damage = TermDamageState::new(num_cols, num_lines);
 
+        let prompt_regex = prompt_regex(&options);
+
         Term {
             grid,
             inactive_grid: alt,
@@ -441,6 +487,10 @@ impl<T> Term<T> {
             selection: None,
             damage,
             config: options,
+            prompt_regex,
+            graphics: Default::default(),
+            working_directory: None,
+            color_scheme: Default::default(),
         }
     }
 
@@ -509,10 +559,17 @@ impl<T> Term<T> {
 
         self.event_proxy.send_event(title_event);
 
+        let (archive, spill) = (self.config.scrolling_archive, self.config.scrolling_archive_spill);
         if self.mode.contains(TermMode::ALT_SCREEN) {
             self.inactive_grid.update_history(self.config.scrolling_history);
+            self.inactive_grid.update_archive(archive, spill);
         } else {
             self.grid.update_history(self.config.scrolling_history);
+            self.grid.update_archive(archive, spill);
+        }
+
+        if self.config.prompt_regex != old_config.prompt_regex {
+            self.prompt_regex = prompt_regex(&self.config);
         }
 
         if self.config.kitty_keyboard != old_config.kitty_keyboard {
@@ -572,11 +629,22 @@ impl<T> Term<T> {
     fn line_to_string(
         &self,
         line: Line,
-        mut cols: Range<Column>,
+        cols: Range<Column>,
         include_wrapped_wide: bool,
     ) -> String {
         let mut text = String::new();
+        self.write_line(&mut text, line, cols, include_wrapped_wide);
+        text
+    }
 
+    /// Write the cells of a single line in the grid.
+    fn write_line<W: CellWriter>(
+        &self,
+        writer: &mut W,
+        line: Line,
+        mut cols: Range<Column>,
+        include_wrapped_wide: bool,
+    ) {
         let grid_line = &self.grid[line];
         let line_length = cmp::min(grid_line.line_length(), cols.end + 1);
 
@@ -603,13 +671,7 @@ impl<T> Term<T> {
             }
 
             if !cell.flags.intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER) {
-                // Push cells primary character.
-                text.push(cell.c);
-
-                // Push zero-width characters.
-                for c in cell.zerowidth().into_iter().flatten() {
-                    text.push(*c);
-                }
+                writer.push_cell(cell);
             }
         }
 
@@ -617,7 +679,7 @@ impl<T> Term<T> {
             && (line_length.0 == 0
                 || !self.grid[line][line_length - 1].flags.contains(Flags::WRAPLINE))
         {
-            text.push('\n');
+            writer.push_newline();
         }
 
         // If wide char is not part of the selection, but leading spacer is, include it.
@@ -626,10 +688,8 @@ impl<T> Term<T> {
             && grid_line[line_length - 1].flags.contains(Flags::LEADING_WIDE_CHAR_SPACER)
             && include_wrapped_wide
         {
-            text.push(self.grid[line - 1i32][Column(0)].c);
+            writer.push_cell(&self.grid[line - 1i32][Column(0)]);
         }
-
-        text
     }
 
     /// Terminal content required for rendering.
@@ -651,6 +711,14 @@ impl<T> Term<T> {
         &mut self.grid
     }
 
+    /// Iterate over all shell commands in the active grid, from the oldest to the newest.
+    ///
+    /// Commands are delimited using the semantic prompt marks reported by the shell.
+    #[inline]
+    pub fn command_blocks(&self) -> CommandBlocks<'_> {
+        CommandBlocks::new(&self.grid)
+    }
+
     /// Resize terminal to new dimensions.
     pub fn resize<S: Dimensions>(&mut self, size: S) {
         let old_cols = self.columns();
@@ -948,6 +1016,42 @@ impl<T> Term<T> {
         &self.colors
     }
 
+    /// Window title set by the application.
+    #[inline]
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Working directory last reported by the shell.
+    #[inline]
+    pub fn working_directory(&self) -> Option<&WorkingDirectory> {
+        self.working_directory.as_ref()
+    }
+
+    /// Color scheme reported to applications.
+    #[inline]
+    pub fn color_scheme(&self) -> ColorScheme {
+        self.color_scheme
+    }
+
+    /// Change the color scheme reported to applications.
+    ///
+    /// Applications which enabled color scheme updates are notified about the change.
+    pub fn set_color_scheme(&mut self, color_scheme: ColorScheme)
+    where
+        T: EventListener,
+    {
+        if self.color_scheme == color_scheme {
+            return;
+        }
+
+        self.color_scheme = color_scheme;
+
+        if self.mode.contains(TermMode::COLOR_SCHEME_UPDATES) {
+            self.event_proxy.send_event(Event::PtyWrite(color_scheme.report()));
+        }
+    }
+
     /// Insert a linebreak at the current cursor position.
     #[inline]
     fn wrapline(&mut self)
@@ -974,6 +1078,107 @@ impl<T> Term<T> {
         self.damage_cursor();
     }
 
+    /// Add a character to the grapheme cluster before the cursor.
+    ///
+    /// Returns `false` if the character starts a new grapheme cluster instead.
+    fn extend_grapheme_cluster(&mut self, c: char) -> bool
+    where
+        T: EventListener,
+    {
+        let line = self.grid.cursor.point.line;
+        let mut column = self.grid.cursor.point.column;
+        if !self.grid.cursor.input_needs_wrap {
+            if column == 0 {
+                return false;
+            }
+            column -= 1;
+        }
+
+        if self.grid[line][column].flags.contains(Flags::WIDE_CHAR_SPACER) && column > 0 {
+            column -= 1;
+        }
+
+        let cell = &mut self.grid[line][column];
+        let zerowidth = cell.zerowidth().unwrap_or_default();
+        if !grapheme::extends_cluster(cell.c, zerowidth, c) {
+            return false;
+        }
+        let text_presentation = grapheme::forces_text_presentation(cell.c, zerowidth, c);
+        cell.push_zerowidth(c);
+
+        let wide = cell.flags.contains(Flags::WIDE_CHAR);
+        self.damage.damage_point(Point::new(line.0 as usize, column));
+
+        if !wide && grapheme::forces_emoji_presentation(c) {
+            // Emoji presentation always occupies two cells.
+            self.widen_cluster(column);
+        } else if wide && text_presentation && column + 1 < self.columns() {
+            // Pictographs with text presentation occupy a single cell.
+            self.narrow_cluster(column);
+        }
+
+        true
+    }
+
+    /// Turn the fullwidth grapheme cluster before the cursor into a narrow cluster.
+    fn narrow_cluster(&mut self, column: Column) {
+        let line = self.grid.cursor.point.line;
+        let spacer = column + 1;
+
+        self.grid[line][column].flags.remove(Flags::WIDE_CHAR);
+        self.grid[line][spacer].flags.remove(Flags::WIDE_CHAR_SPACER);
+        self.damage.damage_point(Point::new(line.0 as usize, spacer));
+
+        // Continue writing in the cell previously occupied by the spacer.
+        self.grid.cursor.point.column = spacer;
+        self.grid.cursor.input_needs_wrap = false;
+    }
+
+    /// Turn the narrow grapheme cluster before the cursor into a fullwidth cluster.
+    fn widen_cluster(&mut self, column: Column)
+    where
+        T: EventListener,
+    {
+        if !self.grid.cursor.input_needs_wrap {
+            // Write spacer to cell following the cluster.
+            self.grid.cursor.template.flags.insert(Flags::WIDE_CHAR_SPACER);
+            self.write_at_cursor(' ');
+            self.grid.cursor.template.flags.remove(Flags::WIDE_CHAR_SPACER);
+
+            let line = self.grid.cursor.point.line;
+            self.grid[line][column].flags.insert(Flags::WIDE_CHAR);
+        } else if self.mode.contains(TermMode::LINE_WRAP) {
+            // Move the cluster to the next line, since it does not fit into the last column.
+            let cell = self.grid.cursor_cell().clone();
+            self.grid.cursor.template.flags.insert(Flags::LEADING_WIDE_CHAR_SPACER);
+            self.write_at_cursor(' ');
+            self.grid.cursor.template.flags.remove(Flags::LEADING_WIDE_CHAR_SPACER);
+            self.wrapline();
+
+            self.grid.cursor.template.flags.insert(Flags::WIDE_CHAR);
+            self.write_at_cursor(cell.c);
+            self.grid.cursor.template.flags.remove(Flags::WIDE_CHAR);
+            let cursor_cell = self.grid.cursor_cell();
+            cursor_cell.extra = cell.extra;
+            cursor_cell.fg = cell.fg;
+            cursor_cell.bg = cell.bg;
+
+            self.grid.cursor.point.column += 1;
+            self.grid.cursor.template.flags.insert(Flags::WIDE_CHAR_SPACER);
+            self.write_at_cursor(' ');
+            self.grid.cursor.template.flags.remove(Flags::WIDE_CHAR_SPACER);
+        } else {
+            // Keep the cluster narrow when it cannot be wrapped.
+            return;
+        }
+
+        if self.grid.cursor.point.column + 1 < self.columns() {
+            self.grid.cursor.point.column += 1;
+        } else {
+            self.grid.cursor.input_needs_wrap = true;
+        }
+    }
+
     /// Write `c` to the cell at the cursor position.
     #[inline(always)]
     fn write_at_cursor(&mut self, c: char) {
@@ -1061,6 +1266,11 @@ impl<T: EventListener> Handler for Term<T> {
             None => return,
         };
 
+        // Attach characters continuing the previous grapheme cluster.
+        if self.mode.contains(TermMode::GRAPHEME_CLUSTERING) && self.extend_grapheme_cluster(c) {
+            return;
+        }
+
         // Handle zero-width characters.
         if width == 0 {
             // Get previous column.
@@ -1253,7 +1463,7 @@ impl<T: EventListener> Handler for Term<T> {
         match intermediate {
             None => {
                 trace!("Reporting primary device attributes");
-                let text = String::from("\x1b[?6c");
+                let text = String::from("\x1b[?62;4c");
                 self.event_proxy.send_event(Event::PtyWrite(text));
             },
             Some('>') => {
@@ -1817,6 +2027,7 @@ impl<T: EventListener> Handler for Term<T> {
         self.vi_mode_cursor = Default::default();
         self.keyboard_mode_stack = Default::default();
         self.inactive_keyboard_mode_stack = Default::default();
+        self.graphics.clear();
 
         // Preserve vi mode across resets.
         self.mode &= TermMode::VI;
@@ -1903,6 +2114,21 @@ impl<T: EventListener> Handler for Term<T> {
     fn set_private_mode(&mut self, mode: PrivateMode) {
         let mode = match mode {
             PrivateMode::Named(mode) => mode,
+            PrivateMode::Unknown(sixel::DISPLAY_MODE) => {
+                trace!("Setting sixel display mode");
+                self.mode.insert(TermMode::SIXEL_DISPLAY);
+                return;
+            },
+            PrivateMode::Unknown(grapheme::GRAPHEME_CLUSTERING_MODE) => {
+                trace!("Setting grapheme clustering mode");
+                self.mode.insert(TermMode::GRAPHEME_CLUSTERING);
+                return;
+            },
+            PrivateMode::Unknown(COLOR_SCHEME_UPDATES_MODE) => {
+                trace!("Setting color scheme updates mode");
+                self.mode.insert(TermMode::COLOR_SCHEME_UPDATES);
+                return;
+            },
             PrivateMode::Unknown(mode) => {
                 debug!("Ignoring unknown mode {} in set_private_mode", mode);
                 return;
@@ -1963,6 +2189,21 @@ impl<T: EventListener> Handler for Term<T> {
     fn unset_private_mode(&mut self, mode: PrivateMode) {
         let mode = match mode {
             PrivateMode::Named(mode) => mode,
+            PrivateMode::Unknown(sixel::DISPLAY_MODE) => {
+                trace!("Unsetting sixel display mode");
+                self.mode.remove(TermMode::SIXEL_DISPLAY);
+                return;
+            },
+            PrivateMode::Unknown(grapheme::GRAPHEME_CLUSTERING_MODE) => {
+                trace!("Unsetting grapheme clustering mode");
+                self.mode.remove(TermMode::GRAPHEME_CLUSTERING);
+                return;
+            },
+            PrivateMode::Unknown(COLOR_SCHEME_UPDATES_MODE) => {
+                trace!("Unsetting color scheme updates mode");
+                self.mode.remove(TermMode::COLOR_SCHEME_UPDATES);
+                return;
+            },
             PrivateMode::Unknown(mode) => {
                 debug!("Ignoring unknown mode {} in unset_private_mode", mode);
                 return;
@@ -2050,6 +2291,15 @@ impl<T: EventListener> Handler for Term<T> {
                 NamedPrivateMode::SyncUpdate => ModeState::Reset,
                 NamedPrivateMode::ColumnMode => ModeState::NotSupported,
             },
+            PrivateMode::Unknown(sixel::DISPLAY_MODE) => {
+                self.mode.contains(TermMode::SIXEL_DISPLAY).into()
+            },
+            PrivateMode::Unknown(grapheme::GRAPHEME_CLUSTERING_MODE) => {
+                self.mode.contains(TermMode::GRAPHEME_CLUSTERING).into()
+            },
+            PrivateMode::Unknown(COLOR_SCHEME_UPDATES_MODE) => {
+                self.mode.contains(TermMode::COLOR_SCHEME_UPDATES).into()
+            },
             PrivateMode::Unknown(_) => ModeState::NotSupported,
         };
 
@@ -2238,6 +2488,53 @@ impl<T: EventListener> Handler for Term<T> {
     }
 }
 
+impl<T: EventListener> ExtendedHandler for Term<T> {
+    #[inline]
+    fn semantic_mark(&mut self, mark: SemanticMark) {
+        trace!("Adding semantic mark {:?}", mark);
+
+        let point = self.grid.cursor.point;
+        self.grid[point.line].push_mark(point.column, mark);
+    }
+
+    #[inline]
+    fn kitty_graphics(&mut self, command: kitty::Command) {
+        trace!("Processing kitty graphics command: {:?}", command.action);
+        self.kitty_graphics_command(command);
+    }
+
+    #[inline]
+    fn sixel_graphics(&mut self, graphic: GraphicData) {
+        trace!("Inserting sixel image: {}x{}", graphic.width, graphic.height);
+        self.insert_sixel(graphic);
+    }
+
+    #[inline]
+    fn desktop_notification(&mut self, notification: Notification) {
+        trace!("Requesting desktop notification: {:?}", notification);
+        let Notification { title, body, urgency } = notification;
+        self.event_proxy.send_event(Event::Notification { title, body, urgency });
+    }
+
+    #[inline]
+    fn set_working_directory(&mut self, directory: WorkingDirectory) {
+        // Shells usually report their directory with every prompt.
+        if self.working_directory.as_ref() == Some(&directory) {
+            return;
+        }
+
+        trace!("Setting working directory to {:?}", directory);
+        self.working_directory = Some(directory.clone());
+        self.event_proxy.send_event(Event::WorkingDirectory(directory));
+    }
+
+    #[inline]
+    fn report_color_scheme(&mut self) {
+        trace!("Reporting color scheme {:?}", self.color_scheme);
+        self.event_proxy.send_event(Event::PtyWrite(self.color_scheme.report()));
+    }
+}
+
 /// The state of the [`Mode`] and [`PrivateMode`].
 #[repr(u8)]
 #[derive(Debug, Clone, Copy)]
@@ -2281,6 +2578,18 @@ fn version_number(mut version: &str) -> usize {
     version_number
 }
 
+/// Compile the prompt regex of the terminal config.
+fn prompt_regex(config: &Config) -> Option<RegexSearch> {
+    let regex = config.prompt_regex.as_ref()?;
+    match RegexSearch::new(regex) {
+        Ok(regex) => Some(regex),
+        Err(err) => {
+            error!("Invalid prompt regex {:?}: {}", regex, err);
+            None
+        },
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ClipboardType {
     Clipboard,
@@ -2479,13 +2788,16 @@ pub mod test {
 mod tests {
     use super::*;
 
+    use std::cell::RefCell;
     use std::mem;
+    use std::rc::Rc;
 
     use crate::event::VoidListener;
     use crate::grid::{Grid, Scroll};
     use crate::index::{Column, Point, Side};
     use crate::selection::{Selection, SelectionType};
     use crate::term::cell::{Cell, Flags};
+    use crate::term::prompt::CommandBlock;
     use crate::term::test::TermSize;
     use crate::vte::ansi::{self, CharsetIndex, Handler, StandardCharset};
 
@@ -2728,6 +3040,113 @@ mod tests {
         assert_eq!(term.grid()[cursor].c, '▒');
     }
 
+    #[test]
+    fn input_grapheme_clusters() {
+        let size = TermSize::new(20, 3);
+        let mut term = Term::new(Config::default(), &size, VoidListener);
+
+        // Without grapheme clustering, every emoji of the sequence occupies two cells.
+        "\u{1f468}\u{200d}\u{1f469}".chars().for_each(|c| term.input(c));
+        assert_eq!(term.grid.cursor.point.column, Column(4));
+
+        term.set_private_mode(PrivateMode::Unknown(grapheme::GRAPHEME_CLUSTERING_MODE));
+        term.carriage_return();
+        term.linefeed();
+
+        // Emoji ZWJ sequence, flag and emoji presentation selector.
+        "\u{1f468}\u{200d}\u{1f469}\u{1f1fa}\u{1f1f8}\u{2764}\u{fe0f}x"
+            .chars()
+            .for_each(|c| term.input(c));
+
+        let line = &term.grid[Line(1)];
+        assert_eq!(line[Column(0)].zerowidth(), Some(&['\u{200d}', '\u{1f469}'][..]));
+        assert_eq!(line[Column(2)].zerowidth(), Some(&['\u{1f1f8}'][..]));
+        assert_eq!(line[Column(4)].zerowidth(), Some(&['\u{fe0f}'][..]));
+        for column in [0, 2, 4] {
+            assert!(line[Column(column)].flags.contains(Flags::WIDE_CHAR));
+        }
+        assert_eq!(line[Column(6)].c, 'x');
+    }
+
+    #[test]
+    fn input_hangul_and_spacing_marks() {
+        let size = TermSize::new(20, 3);
+        let mut term = Term::new(Config::default(), &size, VoidListener);
+        term.set_private_mode(PrivateMode::Unknown(grapheme::GRAPHEME_CLUSTERING_MODE));
+
+        // Hangul syllable from conjoining jamo, followed by a Devanagari spacing vowel sign.
+        "\u{1100}\u{1100}\u{1161}\u{11a8}\u{915}\u{93e}".chars().for_each(|c| term.input(c));
+
+        let line = &term.grid[Line(0)];
+        assert_eq!(line[Column(0)].zerowidth(), Some(&['\u{1100}', '\u{1161}', '\u{11a8}'][..]));
+        assert!(line[Column(0)].flags.contains(Flags::WIDE_CHAR));
+        assert_eq!(line[Column(2)].c, '\u{915}');
+        assert_eq!(line[Column(2)].zerowidth(), Some(&['\u{93e}'][..]));
+        assert_eq!(term.grid.cursor.point.column, Column(3));
+    }
+
+    #[test]
+    fn narrow_grapheme_cluster_with_text_presentation() {
+        let size = TermSize::new(20, 3);
+        let mut term = Term::new(Config::default(), &size, VoidListener);
+        term.set_private_mode(PrivateMode::Unknown(grapheme::GRAPHEME_CLUSTERING_MODE));
+
+        "\u{231a}\u{fe0e}x".chars().for_each(|c| term.input(c));
+
+        let line = &term.grid[Line(0)];
+        assert_eq!(line[Column(0)].zerowidth(), Some(&['\u{fe0e}'][..]));
+        assert!(!line[Column(0)].flags.contains(Flags::WIDE_CHAR));
+        assert!(!line[Column(1)].flags.contains(Flags::WIDE_CHAR_SPACER));
+        assert_eq!(line[Column(1)].c, 'x');
+        assert_eq!(term.grid.cursor.point.column, Column(2));
+    }
+
+    #[test]
+    fn widen_grapheme_cluster_at_line_end() {
+        let size = TermSize::new(3, 3);
+        let mut term = Term::new(Config::default(), &size, VoidListener);
+        term.set_private_mode(PrivateMode::Unknown(grapheme::GRAPHEME_CLUSTERING_MODE));
+
+        "ab\u{2764}\u{fe0f}".chars().for_each(|c| term.input(c));
+
+        let leading_spacer = &term.grid[Line(0)][Column(2)];
+        assert!(leading_spacer.flags.contains(Flags::LEADING_WIDE_CHAR_SPACER));
+
+        let cell = &term.grid[Line(1)][Column(0)];
+        assert_eq!(cell.c, '\u{2764}');
+        assert_eq!(cell.zerowidth(), Some(&['\u{fe0f}'][..]));
+        assert!(cell.flags.contains(Flags::WIDE_CHAR));
+        assert!(term.grid[Line(1)][Column(1)].flags.contains(Flags::WIDE_CHAR_SPACER));
+        assert_eq!(term.grid.cursor.point, Point::new(Line(1), Column(2)));
+    }
+
+    #[test]
+    fn color_scheme_updates() {
+        #[derive(Default, Clone)]
+        struct Writes(Rc<RefCell<Vec<String>>>);
+
+        impl EventListener for Writes {
+            fn send_event(&self, event: Event) {
+                if let Event::PtyWrite(text) = event {
+                    self.0.borrow_mut().push(text);
+                }
+            }
+        }
+
+        let size = TermSize::new(5, 5);
+        let writes = Writes::default();
+        let mut term = Term::new(Config::default(), &size, writes.clone());
+
+        // Changes are only reported after enabling the mode.
+        term.set_color_scheme(ColorScheme::Light);
+        term.set_private_mode(PrivateMode::Unknown(COLOR_SCHEME_UPDATES_MODE));
+        term.set_color_scheme(ColorScheme::Light);
+        term.set_color_scheme(ColorScheme::Dark);
+        term.report_color_scheme();
+
+        assert_eq!(*writes.0.borrow(), vec!["\x1b[?997;1n", "\x1b[?997;1n"]);
+    }
+
     #[test]
     fn clearing_viewport_keeps_history_position() {
         let size = TermSize::new(10, 20);
@@ -3262,6 +3681,102 @@ mod tests {
         assert_eq!(term.title, None);
     }
 
+    #[test]
+    fn command_blocks() {
+        let size = TermSize::new(10, 5);
+        let mut term = Term::new(Config::default(), &size, VoidListener);
+
+        // First command with output and exit code.
+        term.semantic_mark(SemanticMark::PromptStart);
+        term.input('$');
+        term.input(' ');
+        term.semantic_mark(SemanticMark::CommandStart);
+        term.input('a');
+        term.carriage_return();
+        term.newline();
+        term.semantic_mark(SemanticMark::OutputStart);
+        term.input('x');
+        term.carriage_return();
+        term.newline();
+        term.semantic_mark(SemanticMark::CommandEnd(Some(1)));
+
+        // Second command still waiting for input.
+        term.semantic_mark(SemanticMark::PromptStart);
+        term.input('$');
+        term.semantic_mark(SemanticMark::CommandStart);
+
+        let blocks: Vec<_> = term.command_blocks().collect();
+        assert_eq!(blocks, vec![
+            CommandBlock {
+                prompt_start: Some(Point::new(Line(0), Column(0))),
+                command_start: Some(Point::new(Line(0), Column(2))),
+                output_start: Some(Point::new(Line(1), Column(0))),
+                command_end: Some(Point::new(Line(2), Column(0))),
+                exit_code: Some(1),
+            },
+            CommandBlock {
+                prompt_start: Some(Point::new(Line(2), Column(0))),
+                command_start: Some(Point::new(Line(2), Column(1))),
+                ..CommandBlock::default()
+            },
+        ]);
+
+        // Marks are moved into history and removed when the screen is cleared.
+        for _ in 0..5 {
+            term.newline();
+        }
+        assert_eq!(term.command_blocks().count(), 2);
+        assert_eq!(term.command_blocks().next().unwrap().start(), Point::new(Line(-3), Column(0)));
+
+        term.clear_screen(ansi::ClearMode::Saved);
+        assert_eq!(term.command_blocks().count(), 0);
+    }
+
+    #[test]
+    fn last_command_output() {
+        let size = TermSize::new(10, 10);
+        let mut term = Term::new(Config::default(), &size, VoidListener);
+
+        for command in ["a", "b", "c"] {
+            term.input('$');
+            term.mark_command_submitted();
+            term.carriage_return();
+            term.newline();
+            for _ in 0..2 {
+                term.input(command.chars().next().unwrap());
+                term.carriage_return();
+                term.newline();
+            }
+        }
+
+        term.input('$');
+
+        let selection = term.last_command_output().unwrap();
+        term.selection = Some(selection);
+        assert_eq!(term.selection_to_string(), Some(String::from("c\nc")));
+
+        // Selection is rotated into history with its anchors.
+        for _ in 0..5 {
+            term.newline();
+        }
+        let range = term.selection.as_ref().and_then(|selection| selection.to_range(&term));
+        assert_eq!(range.map(|range| range.start), Some(Point::new(Line(2), Column(0))));
+
+        // Last command without any output has an empty output.
+        term.mark_command_submitted();
+        term.carriage_return();
+        term.newline();
+        term.input('$');
+
+        let selection = term.last_command_output().unwrap();
+        assert!(selection.is_empty());
+        assert_eq!(selection.to_range(&term), None);
+
+        // Alternate screen has no commands.
+        term.set_private_mode(NamedPrivateMode::SwapScreenAndSetRestoreCursor.into());
+        assert_eq!(term.last_command_output(), None);
+    }
+
     #[test]
     fn parse_cargo_version() {
         assert!(version_number(env!("CARGO_PKG_VERSION")) >= 10_01);
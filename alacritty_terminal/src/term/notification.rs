@@ -0,0 +1,22 @@
+//! Desktop notifications requested by escape sequences (OSC 9, OSC 777 and OSC 99).
+
+/// Desktop notification.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Notification {
+    /// Notification summary, this is empty if the application did not specify one.
+    pub title: String,
+
+    /// Notification text.
+    pub body: String,
+
+    pub urgency: Urgency,
+}
+
+/// Urgency of a desktop notification.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Urgency {
+    Low,
+    #[default]
+    Normal,
+    Critical,
+}
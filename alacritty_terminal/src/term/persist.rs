@@ -0,0 +1,265 @@
+//! Transfer of terminal content between sessions.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::event::EventListener;
+use crate::grid::{pack_row, unpack_row, Dimensions, Grid, Row, Scroll};
+use crate::index::{Column, Line, Point};
+use crate::term::cell::Cell;
+use crate::term::prompt::SemanticMark;
+use crate::term::{Term, TermMode};
+use crate::vte::ansi::Handler;
+
+/// Primary screen content, including its scrollback history.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SavedGrid {
+    /// Content of the primary screen and its history.
+    pub grid: Grid<Cell>,
+
+    /// Position of the cursor when the grid was saved.
+    pub cursor: Point,
+}
+
+impl SavedGrid {
+    /// Convert the grid to its compact form for storage.
+    pub fn pack(&self) -> PackedGrid {
+        let mut lines = Vec::new();
+        let mut templates = Vec::new();
+        let mut marks = Vec::new();
+
+        let grid = &self.grid;
+        for (index, line) in (grid.topmost_line().0..=grid.bottommost_line().0).enumerate() {
+            let row = &grid[Line(line)];
+            pack_row(row, &mut lines, &mut templates);
+            marks.extend(row.marks().iter().map(|(column, mark)| (index, *column, *mark)));
+        }
+
+        PackedGrid {
+            columns: grid.columns(),
+            screen_lines: grid.screen_lines(),
+            total_lines: grid.total_lines(),
+            cursor: self.cursor,
+            templates,
+            marks,
+            lines,
+        }
+    }
+}
+
+/// Compact form of a [`SavedGrid`].
+///
+/// Lines are packed like the lines of the scrollback archive, so the attributes of all cells in a
+/// run of cells sharing them are only stored once.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PackedGrid {
+    /// Number of columns.
+    columns: usize,
+
+    /// Number of visible lines.
+    screen_lines: usize,
+
+    /// Number of lines, including the scrollback history.
+    total_lines: usize,
+
+    /// Position of the cursor when the grid was saved.
+    cursor: Point,
+
+    /// Attributes of the packed cells.
+    templates: Vec<Cell>,
+
+    /// Shell integration marks, with the index of their line.
+    marks: Vec<(usize, Column, SemanticMark)>,
+
+    /// Packed lines, starting with the topmost line.
+    lines: Vec<u8>,
+}
+
+impl PackedGrid {
+    /// Restore the saved grid.
+    ///
+    /// Returns `None` if the dimensions of the grid are invalid.
+    pub fn unpack(self) -> Option<SavedGrid> {
+        // Every packed line uses at least one byte.
+        if self.columns == 0
+            || self.columns > u16::MAX as usize
+            || self.screen_lines == 0
+            || self.total_lines < self.screen_lines
+            || self.total_lines > self.lines.len()
+        {
+            return None;
+        }
+
+        let mut input = &self.lines[..];
+        let mut rows: Vec<Row<Cell>> = (0..self.total_lines)
+            .map(|_| unpack_row(&mut input, &self.templates, self.columns))
+            .collect();
+
+        for (index, column, mark) in self.marks {
+            if let Some(row) = rows.get_mut(index) {
+                row.push_mark(column, mark);
+                row.clamp_marks();
+            }
+        }
+
+        // The grid storage starts with the bottommost line.
+        rows.reverse();
+        let grid = Grid::from_rows(self.screen_lines, self.columns, rows);
+
+        Some(SavedGrid { grid, cursor: self.cursor })
+    }
+}
+
+impl<T: EventListener> Term<T> {
+    /// Save the primary screen content, including its scrollback history.
+    ///
+    /// Images are not saved, cells covered by them are restored as blank cells. Archived lines
+    /// are not part of the saved grid.
+    pub fn save_grid(&self) -> SavedGrid {
+        let mut grid = if self.mode.contains(TermMode::ALT_SCREEN) {
+            self.inactive_grid.clone()
+        } else {
+            self.grid.clone()
+        };
+
+        grid.update_archive(0, false);
+
+        for line in (grid.topmost_line().0..=grid.bottommost_line().0).map(Line) {
+            for cell in grid[line][..].iter_mut().filter(|cell| cell.graphic().is_some()) {
+                cell.set_graphic(None);
+            }
+        }
+
+        let cursor = grid.cursor.point;
+        SavedGrid { grid, cursor }
+    }
+
+    /// Replace the primary screen content with a previously saved grid.
+    ///
+    /// The grid is reflowed to the current dimensions and the cursor is moved to the start of the
+    /// line below the saved cursor, to avoid overwriting the restored content.
+    pub fn restore_grid(&mut self, saved: SavedGrid) {
+        let SavedGrid { mut grid, cursor } = saved;
+
+        grid.cursor.point.line = Line(cursor.line.0.clamp(0, grid.bottommost_line().0));
+        grid.cursor.point.column = Column(cursor.column.0.min(grid.last_column().0));
+        grid.scroll_display(Scroll::Bottom);
+        grid.update_history(self.config.scrolling_history);
+        grid.update_archive(self.config.scrolling_archive, self.config.scrolling_archive_spill);
+        grid.resize(true, self.screen_lines(), self.columns());
+        grid.saved_cursor = grid.cursor.clone();
+
+        if self.mode.contains(TermMode::ALT_SCREEN) {
+            self.inactive_grid = grid;
+        } else {
+            self.grid = grid;
+
+            if self.grid.cursor.point.column > 0 {
+                self.linefeed();
+            }
+            self.carriage_return();
+        }
+
+        self.selection = None;
+        self.mark_fully_damaged();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::event::VoidListener;
+    use crate::term::cell::Flags;
+    use crate::term::test::TermSize;
+    use crate::term::Config;
+
+    fn term(columns: usize, lines: usize) -> Term<VoidListener> {
+        Term::new(Config::default(), &TermSize::new(columns, lines), VoidListener)
+    }
+
+    fn write(term: &mut Term<VoidListener>, text: &str) {
+        for c in text.chars() {
+            match c {
+                '\n' => {
+                    term.linefeed();
+                    term.carriage_return();
+                },
+                c => term.input(c),
+            }
+        }
+    }
+
+    #[test]
+    fn restore_history() {
+        let mut old = term(10, 3);
+        write(&mut old, "one\ntwo\nthree\nfour\n$ ");
+        let saved = old.save_grid();
+
+        let mut new = term(10, 3);
+        new.restore_grid(saved);
+
+        // The new prompt starts below the restored cursor line.
+        assert_eq!(new.grid().cursor.point, Point::new(Line(2), Column(0)));
+        assert_eq!(new.history_size(), 3);
+
+        let topmost = Point::new(new.topmost_line(), Column(0));
+        let bottommost = Point::new(new.bottommost_line(), new.last_column());
+        assert_eq!(new.bounds_to_string(topmost, bottommost), "one\ntwo\nthree\nfour\n$\n");
+    }
+
+    #[test]
+    fn restore_resized() {
+        let mut old = term(10, 5);
+        write(&mut old, "0123456789abcdef");
+        let saved = old.save_grid();
+
+        let mut new = term(5, 4);
+        new.restore_grid(saved);
+
+        let start = Point::new(new.topmost_line(), Column(0));
+        let end = Point::new(new.topmost_line() + 3, new.last_column());
+        assert_eq!(new.bounds_to_string(start, end), "0123456789abcdef");
+    }
+
+    #[test]
+    fn pack() {
+        let mut term = term(10, 3);
+        write(&mut term, "one\ntwo\nthree\nfour\n$ ");
+        term.grid_mut()[Line(0)][Column(0)].flags = Flags::BOLD;
+        term.grid_mut()[Line(-1)].push_mark(Column(0), SemanticMark::PromptStart);
+        let saved = term.save_grid();
+
+        let packed = saved.pack();
+        assert_eq!(packed.total_lines, 5);
+        assert_eq!(packed.marks, [(1, Column(0), SemanticMark::PromptStart)]);
+
+        let unpacked = packed.unpack().unwrap();
+        assert_eq!(unpacked.cursor, saved.cursor);
+        assert_eq!(unpacked.grid.total_lines(), saved.grid.total_lines());
+        for line in (saved.grid.topmost_line().0..=saved.grid.bottommost_line().0).map(Line) {
+            assert_eq!(unpacked.grid[line], saved.grid[line]);
+            assert_eq!(unpacked.grid[line].marks(), saved.grid[line].marks());
+        }
+    }
+
+    #[test]
+    fn unpack_invalid() {
+        let mut packed = term(10, 3).save_grid().pack();
+        packed.total_lines = 100;
+        assert!(packed.unpack().is_none());
+    }
+
+    #[test]
+    fn restore_empty() {
+        let saved = term(10, 3).save_grid();
+
+        let mut new = term(10, 3);
+        new.restore_grid(saved);
+
+        assert_eq!(new.grid().cursor.point, Point::new(Line(0), Column(0)));
+        assert_eq!(new.history_size(), 0);
+    }
+}
@@ -0,0 +1,275 @@
+//! Shell integration through semantic prompt marks (OSC 133).
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::grid::{Dimensions, Grid};
+use crate::index::{Column, Direction, Line, Point, Side};
+use crate::selection::{Selection, SelectionType};
+use crate::term::cell::Cell;
+use crate::term::{Term, TermMode};
+
+/// Semantic prompt mark.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SemanticMark {
+    /// Start of the prompt (`OSC 133 ; A`).
+    PromptStart,
+
+    /// End of the prompt and start of the user's command (`OSC 133 ; B`).
+    CommandStart,
+
+    /// Command was submitted and its output starts (`OSC 133 ; C`).
+    OutputStart,
+
+    /// Command has finished with an optional exit code (`OSC 133 ; D [; exit_code]`).
+    CommandEnd(Option<i32>),
+
+    /// Enter was sent to the shell.
+    ///
+    /// Unlike all other marks, this is not reported by the shell but tracked by the terminal
+    /// itself. So it is available even without shell integration.
+    CommandSubmitted,
+}
+
+impl SemanticMark {
+    /// Position of the mark within a command block.
+    ///
+    /// Returns `None` for marks which are not part of a command block.
+    fn stage(self) -> Option<u8> {
+        match self {
+            Self::PromptStart => Some(0),
+            Self::CommandStart => Some(1),
+            Self::OutputStart => Some(2),
+            Self::CommandEnd(_) => Some(3),
+            Self::CommandSubmitted => None,
+        }
+    }
+}
+
+/// Prompt, command and output of a single shell command.
+///
+/// All points are optional, since the marks which created them might have been cleared or rotated
+/// out of the scrollback history.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct CommandBlock {
+    /// Start of the prompt.
+    pub prompt_start: Option<Point>,
+
+    /// Start of the command input.
+    pub command_start: Option<Point>,
+
+    /// Start of the command output.
+    pub output_start: Option<Point>,
+
+    /// End of the command output.
+    pub command_end: Option<Point>,
+
+    /// Exit code reported for the command.
+    pub exit_code: Option<i32>,
+}
+
+impl CommandBlock {
+    /// First point of the block.
+    pub fn start(&self) -> Point {
+        self.prompt_start
+            .or(self.command_start)
+            .or(self.output_start)
+            .or(self.command_end)
+            .unwrap_or_default()
+    }
+
+    /// Check if the command has finished executing.
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.command_end.is_some()
+    }
+
+    /// Last stage which was already recorded.
+    fn stage(&self) -> Option<u8> {
+        if self.command_end.is_some() {
+            Some(3)
+        } else if self.output_start.is_some() {
+            Some(2)
+        } else if self.command_start.is_some() {
+            Some(1)
+        } else if self.prompt_start.is_some() {
+            Some(0)
+        } else {
+            None
+        }
+    }
+
+    /// Add a mark to the block.
+    ///
+    /// Returns `false` if the mark belongs to a new block.
+    fn insert(&mut self, point: Point, mark: SemanticMark) -> bool {
+        if mark.stage().is_none() || self.stage() >= mark.stage() {
+            return false;
+        }
+
+        match mark {
+            SemanticMark::PromptStart => self.prompt_start = Some(point),
+            SemanticMark::CommandStart => self.command_start = Some(point),
+            SemanticMark::OutputStart => self.output_start = Some(point),
+            SemanticMark::CommandEnd(exit_code) => {
+                self.command_end = Some(point);
+                self.exit_code = exit_code;
+            },
+            SemanticMark::CommandSubmitted => (),
+        }
+
+        true
+    }
+}
+
+/// Iterator over all command blocks in the grid, from the oldest to the newest.
+pub struct CommandBlocks<'a> {
+    grid: &'a Grid<Cell>,
+    block: Option<CommandBlock>,
+    line: Line,
+    index: usize,
+}
+
+impl<'a> CommandBlocks<'a> {
+    pub fn new(grid: &'a Grid<Cell>) -> Self {
+        Self { grid, line: grid.topmost_line(), block: None, index: 0 }
+    }
+}
+
+impl<'a> Iterator for CommandBlocks<'a> {
+    type Item = CommandBlock;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.line <= self.grid.bottommost_line() {
+            let (column, mark) = match self.grid[self.line].marks().get(self.index) {
+                Some(&mark) => mark,
+                None => {
+                    self.line += 1;
+                    self.index = 0;
+                    continue;
+                },
+            };
+            self.index += 1;
+
+            if mark.stage().is_none() {
+                continue;
+            }
+
+            let point = Point::new(self.line, column);
+            if self.block.as_mut().is_some_and(|block| block.insert(point, mark)) {
+                continue;
+            }
+
+            // Start a new block if the mark does not fit into the current one.
+            let mut block = CommandBlock::default();
+            block.insert(point, mark);
+            if let Some(block) = self.block.replace(block) {
+                return Some(block);
+            }
+        }
+
+        self.block.take()
+    }
+}
+
+impl<T> Term<T> {
+    /// Record that a command was submitted at the cursor position.
+    ///
+    /// This is ignored in the alternate screen, since there's no shell to submit commands to.
+    pub fn mark_command_submitted(&mut self) {
+        if self.mode.contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+
+        let point = self.grid.cursor.point;
+        self.grid[point.line].push_mark(point.column, SemanticMark::CommandSubmitted);
+    }
+
+    /// Selection containing the output of the last submitted command.
+    ///
+    /// The output spans all lines between the line where the command was submitted and the line
+    /// where the shell requested input again, which is the next prompt. If there is no such line
+    /// yet, the cursor line is used instead. The selection is empty if the command had no output.
+    pub fn last_command_output(&self) -> Option<Selection> {
+        if self.mode.contains(TermMode::ALT_SCREEN) {
+            return None;
+        }
+
+        let mut next_prompt = self.grid.cursor.point.line;
+        for line in (self.topmost_line().0..=next_prompt.0).rev().map(Line) {
+            let marks = self.grid[line].marks();
+
+            if marks.iter().any(|(_, mark)| *mark == SemanticMark::CommandSubmitted) {
+                let start = Point::new(line, Column(0));
+                let ty = SelectionType::CommandOutput;
+                let mut selection = Selection::new(ty, start, Side::Left);
+                selection.update(Point::new(next_prompt, Column(0)), Side::Left);
+                return Some(selection);
+            }
+
+            if marks.iter().any(|(_, mark)| *mark == SemanticMark::PromptStart) {
+                next_prompt = line;
+            }
+        }
+
+        None
+    }
+
+    /// Find the closest shell prompt in the specified direction.
+    ///
+    /// Prompts are located using the semantic prompt marks and the configured prompt regex. Only
+    /// prompts outside of the line containing `origin` are considered.
+    pub fn prompt_search(&mut self, origin: Point, direction: Direction) -> Option<Point> {
+        let marked = self.marked_prompt(origin.line, direction);
+        let matched = self.matched_prompt(origin.line, direction);
+
+        match (direction, marked, matched) {
+            (Direction::Left, Some(marked), Some(matched)) => Some(marked.max(matched)),
+            (Direction::Right, Some(marked), Some(matched)) => Some(marked.min(matched)),
+            (_, marked, matched) => marked.or(matched),
+        }
+    }
+
+    /// Find the closest prompt start mark in the specified direction.
+    fn marked_prompt(&self, line: Line, direction: Direction) -> Option<Point> {
+        let prompt_start = |line: Line| {
+            let marks = self.grid[line].marks();
+            let (column, _) = marks.iter().find(|(_, mark)| *mark == SemanticMark::PromptStart)?;
+            Some(Point::new(line, *column))
+        };
+
+        match direction {
+            Direction::Left => {
+                (self.topmost_line().0..line.0).rev().map(Line).find_map(prompt_start)
+            },
+            Direction::Right => {
+                (line.0 + 1..=self.bottommost_line().0).map(Line).find_map(prompt_start)
+            },
+        }
+    }
+
+    /// Find the closest prompt regex match in the specified direction.
+    fn matched_prompt(&mut self, line: Line, direction: Direction) -> Option<Point> {
+        let mut regex = self.prompt_regex.take()?;
+
+        let last_column = self.last_column();
+        let prompt = match direction {
+            Direction::Left if line > self.topmost_line() => {
+                let start = Point::new(line - 1, last_column);
+                let end = Point::new(self.topmost_line(), Column(0));
+                self.regex_search_left(&mut regex, start, end)
+            },
+            Direction::Right if line < self.bottommost_line() => {
+                let start = Point::new(line + 1, Column(0));
+                let end = Point::new(self.bottommost_line(), last_column);
+                self.regex_search_right(&mut regex, start, end)
+            },
+            _ => None,
+        };
+
+        self.prompt_regex = Some(regex);
+
+        prompt.map(|prompt| *prompt.start())
+    }
+}
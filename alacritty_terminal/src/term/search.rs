@@ -6,6 +6,7 @@ use std::ops::RangeInclusive;
 use log::{debug, warn};
 use regex_automata::hybrid::dfa::{Builder, Cache, Config, DFA};
 pub use regex_automata::hybrid::BuildError;
+use regex_automata::meta::Regex;
 use regex_automata::nfa::thompson::Config as ThompsonConfig;
 use regex_automata::util::syntax::Config as SyntaxConfig;
 use regex_automata::{Anchored, Input, MatchKind};
@@ -27,6 +28,7 @@ pub struct RegexSearch {
     left_rdfa: LazyDfa,
     right_rdfa: LazyDfa,
     right_fdfa: LazyDfa,
+    captures: Option<Regex>,
 }
 
 impl RegexSearch {
@@ -74,7 +76,64 @@ impl RegexSearch {
         let right_rdfa =
             LazyDfa::new(search, config, syntax_config, thompson_config, Direction::Left, true)?;
 
-        Ok(RegexSearch { left_fdfa, left_rdfa, right_fdfa, right_rdfa })
+        // Create regex for resolving capture groups of matches.
+        let captures = match Regex::builder().syntax(syntax_config).build(search) {
+            Ok(regex) => Some(regex),
+            Err(err) => {
+                warn!("Unable to build regex for capture groups: {err}");
+                None
+            },
+        };
+
+        Ok(RegexSearch { left_fdfa, left_rdfa, right_fdfa, right_rdfa, captures })
+    }
+
+    /// Get the capture groups of the regex for a match in the terminal.
+    ///
+    /// The regex is matched against the entire logical line, so assertions like `\b` see the
+    /// same text surrounding the match as the search which found it. The first group is the
+    /// entire match, groups which did not participate are empty.
+    pub fn captures<T>(&self, term: &Term<T>, regex_match: &Match) -> Vec<String> {
+        let regex = match &self.captures {
+            Some(regex) => regex,
+            None => return Vec::new(),
+        };
+
+        let (start, end) = (*regex_match.start(), *regex_match.end());
+        let line_start = term.line_search_left(start);
+        let line_end = term.line_search_right(end);
+
+        // Text of the logical line, split around the match.
+        let before = if start > line_start {
+            term.bounds_to_string(line_start, start.sub(term, Boundary::None, 1))
+        } else {
+            String::new()
+        };
+        let text = term.bounds_to_string(start, end);
+        let mut after_start = end.add(term, Boundary::None, 1);
+        if term.grid[after_start].flags.contains(Flags::WIDE_CHAR_SPACER) {
+            after_start = after_start.add(term, Boundary::None, 1);
+        }
+        let after = if end < line_end && after_start <= line_end {
+            term.bounds_to_string(after_start, line_end)
+        } else {
+            String::new()
+        };
+
+        let haystack = [before.as_str(), &text, &after].concat();
+        let span = before.len()..before.len() + text.len();
+        let input = Input::new(&haystack).span(span).anchored(Anchored::Yes);
+
+        let mut captures = regex.create_captures();
+        regex.captures(input, &mut captures);
+
+        (0..captures.group_len())
+            .map(|group| {
+                captures
+                    .get_group(group)
+                    .map_or_else(String::new, |span| haystack[span.range()].into())
+            })
+            .collect()
     }
 }
 
@@ -681,9 +740,11 @@ impl<'a, T> Iterator for RegexIter<'a, T> {
 mod tests {
     use super::*;
 
+    use crate::event::VoidListener;
     use crate::index::{Column, Line};
     use crate::term::test::{mock_term, TermSize};
     use crate::term::Config;
+    use crate::vte::ansi::Handler;
 
     #[test]
     fn regex_right() {
@@ -1101,6 +1162,30 @@ mod tests {
         assert!(RegexSearch::new("[0-9A-Za-z]{9999999}").is_err());
     }
 
+    #[test]
+    fn captures() {
+        let term = mock_term("Alacritty@TERM alacritty@");
+        let mut regex = RegexSearch::new(r"(\w+)@(\w+)?").unwrap();
+
+        let start = Point::new(Line(0), Column(0));
+        let end = Point::new(Line(0), Column(24));
+        let matches: Vec<_> =
+            RegexIter::new(start, end, Direction::Right, &term, &mut regex).collect();
+        assert_eq!(regex.captures(&term, &matches[0]), vec!["Alacritty@TERM", "Alacritty", "TERM"]);
+        assert_eq!(regex.captures(&term, &matches[1]), vec!["alacritty@", "alacritty", ""]);
+    }
+
+    #[test]
+    fn captures_with_line_context() {
+        let term = mock_term("foo");
+        let mut regex = RegexSearch::new(r"(?-u:\B)(o+)").unwrap();
+
+        let start = Point::new(Line(0), Column(0));
+        let end = Point::new(Line(0), Column(2));
+        let regex_match = term.regex_search_right(&mut regex, start, end).unwrap();
+        assert_eq!(regex.captures(&term, &regex_match), vec!["oo", "oo"]);
+    }
+
     #[test]
     fn runtime_cache_error() {
         let term = mock_term(&str::repeat("i", 9999));
@@ -1155,4 +1240,27 @@ mod tests {
         assert_eq!(start, Point::new(Line(1), Column(0)));
         assert_eq!(end, Point::new(Line(1), Column(2)));
     }
+
+    #[test]
+    fn archived_history() {
+        let config = Config { scrolling_history: 2, scrolling_archive: 1000, ..Config::default() };
+        let mut term = Term::new(config, &TermSize::new(10, 2), VoidListener);
+        for i in 0..600 {
+            for c in format!("line {i}").chars() {
+                term.input(c);
+            }
+            term.linefeed();
+            term.carriage_return();
+        }
+        assert_eq!(term.grid().archived_lines(), 597);
+
+        let mut regex = RegexSearch::new("line 42 ").unwrap();
+        let origin = Point::new(Line(1), Column(0));
+        let regex_match = term.search_next(&mut regex, origin, Direction::Left, Side::Left, None);
+
+        let start = Point::new(Line(-557), Column(0));
+        let end = Point::new(Line(-557), Column(7));
+        assert_eq!(regex_match, Some(start..=end));
+        assert_eq!(term.bounds_to_string(start, end), "line 42");
+    }
 }
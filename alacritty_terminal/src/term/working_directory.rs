@@ -0,0 +1,96 @@
+//! Working directory reported by the shell (OSC 7).
+
+#[cfg(unix)]
+use std::ffi::OsStr;
+#[cfg(unix)]
+use std::os::unix::ffi::OsStrExt;
+use std::path::PathBuf;
+
+/// Working directory reported by the shell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkingDirectory {
+    /// Host the shell is running on, this is empty when no host was reported.
+    pub host: String,
+
+    /// Absolute path of the working directory on the host.
+    pub path: PathBuf,
+}
+
+impl WorkingDirectory {
+    /// Parse a `file://host/path` URI.
+    pub fn from_uri(uri: &[u8]) -> Option<Self> {
+        let uri = uri.strip_prefix(b"file://")?;
+        let path_start = uri.iter().position(|&b| b == b'/')?;
+        let (host, path) = uri.split_at(path_start);
+
+        let host = String::from_utf8(percent_decode(host)).ok()?;
+        let path = percent_decode(path);
+
+        #[cfg(unix)]
+        let path = PathBuf::from(OsStr::from_bytes(&path));
+
+        // Drive letters are preceded by a slash, like `file:///C:/Users`.
+        #[cfg(not(unix))]
+        let path = {
+            let path = String::from_utf8(path).ok()?;
+            match path.strip_prefix('/') {
+                Some(stripped) if stripped.get(1..2) == Some(":") => PathBuf::from(stripped),
+                _ => PathBuf::from(path),
+            }
+        };
+
+        Some(Self { host, path })
+    }
+}
+
+/// Decode `%XX` escapes, leaving invalid escapes untouched.
+fn percent_decode(bytes: &[u8]) -> Vec<u8> {
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|hex| bytes[i] == b'%' && hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            },
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            },
+        }
+    }
+
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_uri() {
+        let directory = WorkingDirectory::from_uri(b"file://host/home/user/with%20space").unwrap();
+        assert_eq!(directory.host, "host");
+        assert_eq!(directory.path, PathBuf::from("/home/user/with space"));
+
+        let directory = WorkingDirectory::from_uri(b"file:///tmp/%zz").unwrap();
+        assert_eq!(directory.host, "");
+        assert_eq!(directory.path, PathBuf::from("/tmp/%zz"));
+
+        let directory = WorkingDirectory::from_uri(b"file:///tmp/%+1").unwrap();
+        assert_eq!(directory.path, PathBuf::from("/tmp/%+1"));
+    }
+
+    #[test]
+    fn reject_invalid_uri() {
+        assert_eq!(WorkingDirectory::from_uri(b"http://host/tmp"), None);
+        assert_eq!(WorkingDirectory::from_uri(b"file://host"), None);
+    }
+}
@@ -1,7 +1,9 @@
 use log::info;
+use std::collections::HashMap;
+use std::ffi::OsString;
 use std::io::Error;
 use std::os::windows::io::IntoRawHandle;
-use std::{mem, ptr};
+use std::{env, mem, ptr};
 
 use windows_sys::core::{HRESULT, PWSTR};
 use windows_sys::Win32::Foundation::{HANDLE, S_OK};
@@ -13,8 +15,8 @@ use windows_sys::{s, w};
 
 use windows_sys::Win32::System::Threading::{
     CreateProcessW, InitializeProcThreadAttributeList, UpdateProcThreadAttribute,
-    EXTENDED_STARTUPINFO_PRESENT, PROCESS_INFORMATION, PROC_THREAD_ATTRIBUTE_PSEUDOCONSOLE,
-    STARTF_USESTDHANDLES, STARTUPINFOEXW, STARTUPINFOW,
+    CREATE_UNICODE_ENVIRONMENT, EXTENDED_STARTUPINFO_PRESENT, PROCESS_INFORMATION,
+    PROC_THREAD_ATTRIBUTE_PSEUDOCONSOLE, STARTF_USESTDHANDLES, STARTUPINFOEXW, STARTUPINFOW,
 };
 
 use crate::event::{OnResize, WindowSize};
@@ -201,6 +203,17 @@ pub fn new(config: &Options, window_size: WindowSize) -> Option<Pty> {
     let cmdline = win32_string(&cmdline(config));
     let cwd = config.working_directory.as_ref().map(win32_string);
 
+    // Only pass an environment block when the inherited environment is extended.
+    let mut creation_flags = EXTENDED_STARTUPINFO_PRESENT;
+    let env_block = env_block(&config.env);
+    let env_block_ptr = match &env_block {
+        Some(env_block) => {
+            creation_flags |= CREATE_UNICODE_ENVIRONMENT;
+            env_block.as_ptr() as *mut std::ffi::c_void
+        },
+        None => ptr::null_mut(),
+    };
+
     let mut proc_info: PROCESS_INFORMATION = unsafe { mem::zeroed() };
     unsafe {
         success = CreateProcessW(
@@ -209,8 +222,8 @@ pub fn new(config: &Options, window_size: WindowSize) -> Option<Pty> {
             ptr::null_mut(),
             ptr::null_mut(),
             false as i32,
-            EXTENDED_STARTUPINFO_PRESENT,
-            ptr::null_mut(),
+            creation_flags,
+            env_block_ptr,
             cwd.as_ref().map_or_else(ptr::null, |s| s.as_ptr()),
             &mut startup_info_ex.StartupInfo as *mut STARTUPINFOW,
             &mut proc_info as *mut PROCESS_INFORMATION,
@@ -230,6 +243,32 @@ pub fn new(config: &Options, window_size: WindowSize) -> Option<Pty> {
     Some(Pty::new(conpty, conout, conin, child_watcher))
 }
 
+/// Create a Unicode environment block with the process environment and the `env` overrides.
+fn env_block(env: &HashMap<String, String>) -> Option<Vec<u16>> {
+    if env.is_empty() {
+        return None;
+    }
+
+    let mut vars: Vec<(OsString, OsString)> = env::vars_os()
+        .filter(|(key, _)| !env.keys().any(|name| key.eq_ignore_ascii_case(name)))
+        .collect();
+    vars.extend(env.iter().map(|(key, value)| (key.into(), value.into())));
+
+    // Variables must be sorted alphabetically, ignoring case.
+    vars.sort_by_key(|(key, _)| key.to_ascii_uppercase());
+
+    let mut block = Vec::new();
+    for (key, value) in vars {
+        let mut var = key;
+        var.push("=");
+        var.push(value);
+        block.extend(win32_string(&var));
+    }
+    block.push(0);
+
+    Some(block)
+}
+
 // Panic with the last os error as message.
 fn panic_shell_spawn() {
     panic!("Unable to spawn shell: {}", Error::last_os_error());
@@ -52,6 +52,10 @@ pub enum ViMotion {
     WordRightEnd,
     /// Move to opposing bracket.
     Bracket,
+    /// Move to the previous shell prompt.
+    PromptUp,
+    /// Move to the next shell prompt.
+    PromptDown,
 }
 
 /// Cursor tracking vi mode position.
@@ -153,6 +157,12 @@ impl ViModeCursor {
                 self.point = word(term, self.point, Direction::Right, Side::Right);
             },
             ViMotion::Bracket => self.point = term.bracket_search(self.point).unwrap_or(self.point),
+            ViMotion::PromptUp => {
+                self.point = term.prompt_search(self.point, Direction::Left).unwrap_or(self.point);
+            },
+            ViMotion::PromptDown => {
+                self.point = term.prompt_search(self.point, Direction::Right).unwrap_or(self.point);
+            },
         }
 
         term.scroll_to_point(self.point);
@@ -383,6 +393,7 @@ mod tests {
 
     use crate::event::VoidListener;
     use crate::index::{Column, Line};
+    use crate::term::prompt::SemanticMark;
     use crate::term::test::TermSize;
     use crate::term::{Config, Term};
     use crate::vte::ansi::Handler;
@@ -820,4 +831,38 @@ mod tests {
         cursor = cursor.scroll(&term, -20);
         assert_eq!(cursor.point, Point::new(Line(19), Column(0)));
     }
+
+    #[test]
+    fn motion_prompt() {
+        let config = Config { prompt_regex: Some("\\$ ".into()), ..Config::default() };
+        let mut term = Term::new(config, &TermSize::new(20, 20), VoidListener);
+
+        // Create prompts using both regex and semantic marks.
+        term.grid_mut()[Line(2)][Column(0)].c = '$';
+        term.grid_mut()[Line(5)].push_mark(Column(0), SemanticMark::PromptStart);
+        term.grid_mut()[Line(9)][Column(3)].c = '$';
+
+        let mut cursor = ViModeCursor::new(Point::new(Line(19), Column(5)));
+
+        cursor = cursor.motion(&mut term, ViMotion::PromptUp);
+        assert_eq!(cursor.point, Point::new(Line(9), Column(3)));
+
+        cursor = cursor.motion(&mut term, ViMotion::PromptUp);
+        assert_eq!(cursor.point, Point::new(Line(5), Column(0)));
+
+        cursor = cursor.motion(&mut term, ViMotion::PromptUp);
+        assert_eq!(cursor.point, Point::new(Line(2), Column(0)));
+
+        cursor = cursor.motion(&mut term, ViMotion::PromptUp);
+        assert_eq!(cursor.point, Point::new(Line(2), Column(0)));
+
+        cursor = cursor.motion(&mut term, ViMotion::PromptDown);
+        assert_eq!(cursor.point, Point::new(Line(5), Column(0)));
+
+        cursor = cursor.motion(&mut term, ViMotion::PromptDown);
+        assert_eq!(cursor.point, Point::new(Line(9), Column(3)));
+
+        cursor = cursor.motion(&mut term, ViMotion::PromptDown);
+        assert_eq!(cursor.point, Point::new(Line(9), Column(3)));
+    }
 }
@@ -9,10 +9,10 @@ use std::path::Path;
 use alacritty_terminal::event::{Event, EventListener};
 use alacritty_terminal::grid::{Dimensions, Grid};
 use alacritty_terminal::index::{Column, Line};
+use alacritty_terminal::parser::Processor;
 use alacritty_terminal::term::cell::Cell;
 use alacritty_terminal::term::test::TermSize;
 use alacritty_terminal::term::{Config, Term};
-use alacritty_terminal::vte::ansi;
 
 macro_rules! ref_tests {
     ($($name:ident)*) => {
@@ -72,6 +72,7 @@ ref_tests! {
     zsh_tab_completion
     erase_in_line
     scroll_in_region_up_preserves_history
+    sixel
 }
 
 fn read_u8<P>(path: P) -> Vec<u8>
@@ -87,6 +88,10 @@ where
 #[derive(Deserialize, Default)]
 struct RefConfig {
     history_size: u32,
+    #[serde(default)]
+    cell_width: usize,
+    #[serde(default)]
+    cell_height: usize,
 }
 
 #[derive(Copy, Clone)]
@@ -110,7 +115,8 @@ fn ref_test(dir: &Path) {
         Config { scrolling_history: ref_config.history_size as usize, ..Default::default() };
 
     let mut terminal = Term::new(options, &size, Mock);
-    let mut parser: ansi::Processor = ansi::Processor::new();
+    terminal.set_cell_size(ref_config.cell_width, ref_config.cell_height);
+    let mut parser = Processor::new();
 
     for byte in recording {
         parser.advance(&mut terminal, byte);